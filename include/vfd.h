@@ -0,0 +1,98 @@
+#ifndef VFD_H
+#define VFD_H
+
+/* Generated by cbindgen from src/capi.rs; do not edit. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * Returned by functions that succeeded.
+ */
+#define VFD_OK 0
+
+/**
+ * Returned by functions that failed; see [`vfd_last_error`].
+ */
+#define VFD_ERROR -1
+
+/**
+ * Returned by functions that stopped on a bug in the library; see
+ * [`vfd_last_error`]. The handle can still be closed.
+ */
+#define VFD_PANIC -2
+
+/**
+ * Horizontal placement of a line of text, passed to [`vfd_write_line`] as an `int`.
+ */
+typedef enum VfdAlign {
+  VFD_ALIGN_LEFT = 0,
+  VFD_ALIGN_CENTER = 1,
+  VFD_ALIGN_RIGHT = 2,
+} VfdAlign;
+
+/**
+ * An open display, created by [`vfd_open`] and freed by [`vfd_close`].
+ */
+typedef struct VfdHandle VfdHandle;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Open the display attached to `device`, e.g. `/dev/ttyUSB0` or `COM3`,
+ * which is `width` characters wide and `height` rows high.
+ *
+ * Returns NULL on failure.
+ *
+ * # Safety
+ *
+ * `device` must be a valid NUL-terminated string.
+ */
+struct VfdHandle *vfd_open(const char *device, uint8_t width, uint8_t height);
+
+/**
+ * Replace row `row` (counted from 0) with `text`, a UTF-8 string, placed as
+ * `align` says: one of the [`VfdAlign`] values.
+ *
+ * # Safety
+ *
+ * `vfd` must come from [`vfd_open`] and not be closed yet, and `text` must
+ * be a valid NUL-terminated string.
+ */
+int vfd_write_line(struct VfdHandle *vfd, uint8_t row, const char *text, int align);
+
+/**
+ * Blank the display and move the cursor to the top-left corner.
+ *
+ * # Safety
+ *
+ * `vfd` must come from [`vfd_open`] and not be closed yet.
+ */
+int vfd_clear(struct VfdHandle *vfd);
+
+/**
+ * Close the display and free the handle. Passing NULL does nothing.
+ *
+ * # Safety
+ *
+ * `vfd` must come from [`vfd_open`] and must not be used afterwards.
+ */
+void vfd_close(struct VfdHandle *vfd);
+
+/**
+ * Description of the last error on the calling thread, or NULL if there was none.
+ *
+ * The string stays valid until the next failing call on the same thread.
+ */
+const char *vfd_last_error(void);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* VFD_H */
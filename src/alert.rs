@@ -0,0 +1,159 @@
+use crate::codepage::TextMapping;
+use crate::display_state::DisplayState;
+use crate::error::VfdError;
+use crate::layout::Align;
+use crate::text::{self, WrapPolicy};
+use crate::vfd::Vfd;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// A message shown over everything else for a while, see [`Vfd::show_alert_with`](crate::Vfd::show_alert_with).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Alert {
+    /// Text of the alert; `\n` starts a new row, long lines are word wrapped.
+    pub text: String,
+    pub duration: Duration,
+    /// Flash the alert on and off every this long.
+    pub blink: Option<Duration>,
+    pub align: Align,
+}
+
+impl Alert {
+    /// A centered, steady alert showing `text` for `duration`.
+    pub fn new(text: &str, duration: Duration) -> Self {
+        Alert {
+            text: text.to_string(),
+            duration,
+            blink: None,
+            align: Align::Center,
+        }
+    }
+
+    pub fn blink(mut self, interval: Duration) -> Self {
+        self.blink = Some(interval);
+        self
+    }
+
+    pub fn align(mut self, align: Align) -> Self {
+        self.align = align;
+        self
+    }
+
+    /// Show the alert on a display shared with other threads, blocking until
+    /// it is over, like [`Vfd::show_alert_with`] but holding the lock on
+    /// `vfd` only while drawing.
+    ///
+    /// Marquees, widgets and animations on the same display keep running;
+    /// what they draw meanwhile is held back and sent once the alert is gone.
+    /// An alert already showing is waited for first.
+    ///
+    /// ```no_run
+    /// use std::sync::{Arc, Mutex};
+    /// use std::time::Duration;
+    /// use vfd_dsp_v9fb_over_serial::{Alert, ScrollDirection, Vfd};
+    ///
+    /// let vfd = Vfd::new("/dev/ttyUSB0", 20, 2).unwrap();
+    /// let marquee = vfd.marquee("Today's specials", 0, Duration::from_millis(300), ScrollDirection::Left);
+    /// let vfd = Arc::new(Mutex::new(vfd));
+    /// let _scrolling = marquee.spawn(Arc::clone(&vfd));
+    ///
+    /// let alert = Alert::new("Card declined", Duration::from_secs(3)).blink(Duration::from_millis(400));
+    /// alert.show(&vfd).unwrap();
+    /// ```
+    pub fn show(&self, vfd: &Mutex<Vfd>) -> Result<(), VfdError> {
+        let lock = || vfd.lock().unwrap_or_else(PoisonError::into_inner);
+        let (rows, dismisser) = loop {
+            let mut vfd = lock();
+            if !vfd.is_showing_alert() {
+                break (vfd.begin_alert(self)?, vfd.alert_dismisser());
+            }
+            drop(vfd);
+            thread::sleep(Duration::from_millis(20));
+        };
+        let shown = self.flash(&dismisser, |visible| lock().draw_alert(&rows, visible));
+        let restored = lock().end_alert();
+        shown?;
+        restored
+    }
+
+    // Call `draw` with whether the alert is to be seen, every blink interval,
+    // until its time is up or it is dismissed
+    pub(crate) fn flash<F>(&self, dismisser: &AlertDismisser, mut draw: F) -> Result<(), VfdError>
+    where
+        F: FnMut(bool) -> Result<(), VfdError>,
+    {
+        let started = Instant::now();
+        let mut visible = false;
+        while let Some(left) = self.duration.checked_sub(started.elapsed()) {
+            if left.is_zero() || dismisser.is_dismissed() {
+                break;
+            }
+            visible = !visible;
+            draw(visible)?;
+            let interval = self.blink.filter(|interval| !interval.is_zero());
+            let until = Instant::now() + interval.unwrap_or(left).min(left);
+            // In short steps, so that a dismissal is noticed soon
+            while let Some(wait) = until.checked_duration_since(Instant::now()) {
+                if wait.is_zero() || dismisser.is_dismissed() {
+                    break;
+                }
+                thread::sleep(wait.min(Duration::from_millis(20)));
+            }
+        }
+        Ok(())
+    }
+
+    // The alert as the display shows it, broken into at most `height` rows of `width` cells
+    pub(crate) fn rows(&self, mapping: &TextMapping, width: u8, height: u8) -> Vec<String> {
+        let width = width as usize;
+        self.text
+            .lines()
+            .flat_map(|line| text::wrap(&mapping.apply(line), width, width, WrapPolicy::WORD))
+            .take(height as usize)
+            .collect()
+    }
+}
+
+/// Ends the alert a [`Vfd`](crate::Vfd) is showing before its time is up, e.g.
+/// when a button is pressed. See [`Vfd::alert_dismisser`](crate::Vfd::alert_dismisser).
+///
+/// Dismissing while no alert is shown does nothing.
+#[derive(Debug, Clone, Default)]
+pub struct AlertDismisser {
+    dismissed: Arc<AtomicBool>,
+}
+
+impl AlertDismisser {
+    pub fn dismiss(&self) {
+        self.dismissed.store(true, Ordering::Relaxed);
+    }
+
+    pub(crate) fn is_dismissed(&self) -> bool {
+        self.dismissed.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn reset(&self) {
+        self.dismissed.store(false, Ordering::Relaxed);
+    }
+}
+
+// An alert showing over the display. Everything else sent meanwhile is held
+// back, to be sent after what the display showed before is put back.
+pub(crate) struct AlertOverlay {
+    pub(crate) under: DisplayState,
+    pub(crate) held: Vec<u8>,
+    // Set while output goes straight out anyway, such as the alert itself
+    pub(crate) passing: bool,
+}
+
+impl AlertOverlay {
+    pub(crate) fn new(under: DisplayState) -> Self {
+        AlertOverlay {
+            under,
+            held: Vec::new(),
+            passing: false,
+        }
+    }
+}
@@ -0,0 +1,205 @@
+use crate::display::VfdDisplay;
+use crate::error::VfdError;
+use crate::screen::Screen;
+use std::io;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+// Longest a playing animation waits before checking whether it was stopped
+const STOP_POLL: Duration = Duration::from_millis(50);
+
+/// One screenful of an animation and how long it stays up.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    pub rows: Vec<String>,
+    pub duration: Duration,
+}
+
+/// A sequence of frames played one after another, e.g. an attract loop on an idle display.
+///
+/// Frames are drawn through a [`Screen`], so only the cells that change
+/// between consecutive frames are sent.
+#[derive(Debug, Clone)]
+pub struct Animation {
+    frames: Vec<Frame>,
+    // `None` repeats forever
+    repeat: Option<u32>,
+}
+
+impl Default for Animation {
+    fn default() -> Self {
+        Animation::new()
+    }
+}
+
+impl Animation {
+    /// An empty animation that plays once.
+    pub fn new() -> Self {
+        Animation {
+            frames: Vec::new(),
+            repeat: Some(1),
+        }
+    }
+
+    /// Append a frame showing `rows`, top to bottom, for `duration`.
+    pub fn frame(mut self, rows: &[&str], duration: Duration) -> Self {
+        self.frames.push(Frame {
+            rows: rows.iter().map(|row| row.to_string()).collect(),
+            duration,
+        });
+        self
+    }
+
+    /// Append a frame showing what is composed on `screen`, for `duration`.
+    pub fn screen(mut self, screen: &Screen, duration: Duration) -> Self {
+        let (_, height) = screen.size();
+        self.frames.push(Frame {
+            rows: (0..height).map(|y| screen.frame().row(y)).collect(),
+            duration,
+        });
+        self
+    }
+
+    /// Play the sequence `times` times (default once).
+    pub fn repeat(mut self, times: u32) -> Self {
+        self.repeat = Some(times);
+        self
+    }
+
+    /// Play the sequence until stopped.
+    pub fn looped(mut self) -> Self {
+        self.repeat = None;
+        self
+    }
+
+    pub fn frames(&self) -> &[Frame] {
+        &self.frames
+    }
+
+    /// Play on the calling thread. Returns when the last repetition ends, so never for a looped animation.
+    pub fn play<D: VfdDisplay + ?Sized>(&self, display: &mut D) -> Result<(), VfdError> {
+        self.play_until(display, &AtomicBool::new(false))
+    }
+
+    /// Play on the calling thread until done or until `stop` is set.
+    pub fn play_until<D: VfdDisplay + ?Sized>(
+        &self,
+        display: &mut D,
+        stop: &AtomicBool,
+    ) -> Result<(), VfdError> {
+        let mut screen = Screen::for_display(display);
+        let stopped = || stop.load(Ordering::Relaxed);
+        self.run(&mut screen, &stopped, |screen| screen.present(display))
+    }
+
+    /// Play on the calling thread until done or until `limit` has passed,
+    /// which also ends a looped animation.
+    pub fn play_for<D: VfdDisplay + ?Sized>(
+        &self,
+        display: &mut D,
+        limit: Duration,
+    ) -> Result<(), VfdError> {
+        let deadline = Instant::now() + limit;
+        let mut screen = Screen::for_display(display);
+        let stopped = || Instant::now() >= deadline;
+        self.run(&mut screen, &stopped, |screen| screen.present(display))
+    }
+
+    /// Play on a background thread until finished or until the returned handle is stopped.
+    pub fn spawn<D>(self, display: Arc<Mutex<D>>) -> AnimationHandle
+    where
+        D: VfdDisplay + Send + 'static,
+    {
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread_stop = Arc::clone(&stop);
+        let thread = thread::spawn(move || {
+            let mut screen = {
+                let display = display.lock().unwrap_or_else(PoisonError::into_inner);
+                Screen::for_display(&*display)
+            };
+            let stopped = || thread_stop.load(Ordering::Relaxed);
+            self.run(&mut screen, &stopped, |screen| {
+                let mut display = display.lock().unwrap_or_else(PoisonError::into_inner);
+                screen.present(&mut *display)
+            })
+        });
+        AnimationHandle {
+            stop,
+            thread: Some(thread),
+        }
+    }
+
+    fn run<F>(
+        &self,
+        screen: &mut Screen,
+        stopped: &dyn Fn() -> bool,
+        mut present: F,
+    ) -> Result<(), VfdError>
+    where
+        F: FnMut(&mut Screen) -> Result<(), VfdError>,
+    {
+        if self.frames.is_empty() {
+            return Ok(());
+        }
+        let mut played = 0;
+        while self.repeat.is_none_or(|times| played < times) {
+            for frame in &self.frames {
+                if stopped() {
+                    return Ok(());
+                }
+                screen.clear();
+                for (y, row) in frame.rows.iter().enumerate() {
+                    screen.text(0, y as u8, row);
+                }
+                present(screen)?;
+
+                let shown = Instant::now();
+                while let Some(left) = frame.duration.checked_sub(shown.elapsed()) {
+                    if left.is_zero() || stopped() {
+                        break;
+                    }
+                    thread::sleep(left.min(STOP_POLL));
+                }
+            }
+            played += 1;
+        }
+        Ok(())
+    }
+}
+
+/// Control handle for an animation playing on a background thread.
+pub struct AnimationHandle {
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<Result<(), VfdError>>>,
+}
+
+impl AnimationHandle {
+    /// Whether the animation has played to the end or failed.
+    pub fn is_finished(&self) -> bool {
+        self.thread.as_ref().is_none_or(JoinHandle::is_finished)
+    }
+
+    /// Stop playing and wait for the thread to finish, returning any write error it hit.
+    pub fn stop(self) -> Result<(), VfdError> {
+        self.stop.store(true, Ordering::Relaxed);
+        self.join()
+    }
+
+    /// Wait for the animation to play to the end. Never returns for a looped animation.
+    pub fn join(mut self) -> Result<(), VfdError> {
+        match self.thread.take() {
+            Some(thread) => thread
+                .join()
+                .unwrap_or_else(|_| Err(io::Error::other("Animation thread panicked").into())),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for AnimationHandle {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
@@ -0,0 +1,63 @@
+use alloc::format;
+use alloc::string::String;
+use core::fmt;
+use core::str::FromStr;
+
+/// A fixed icon printed on the display glass, lit independently of the text.
+///
+/// Which icons exist depends on the model, see
+/// [`CommandSet::annunciators`](crate::CommandSet::annunciators).
+///
+/// ```
+/// use vfd_dsp_v9fb_over_serial::Annunciator;
+///
+/// assert_eq!("total".parse(), Ok(Annunciator::Total));
+/// assert_eq!("7".parse(), Ok(Annunciator::Numbered(7)));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Annunciator {
+    /// Σ, shown while items are being summed up.
+    Sum,
+    /// TOTAL, shown next to the amount due.
+    Total,
+    /// CHANGE, shown next to the change to give back.
+    Change,
+    /// The currency symbol in front of the amount.
+    Currency,
+    /// The icon at this position in the display's icon strip, counted from 1,
+    /// for models whose icons differ from the named ones.
+    Numbered(u8),
+}
+
+impl FromStr for Annunciator {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim().to_ascii_lowercase();
+        if let Ok(n) = s.parse() {
+            return Ok(Annunciator::Numbered(n));
+        }
+        match s.as_str() {
+            "sum" => Ok(Annunciator::Sum),
+            "total" => Ok(Annunciator::Total),
+            "change" => Ok(Annunciator::Change),
+            "currency" => Ok(Annunciator::Currency),
+            other => Err(format!(
+                "Unknown annunciator '{}'. Expected one of sum, total, change, currency or a position number.",
+                other
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Annunciator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Annunciator::Sum => write!(f, "sum"),
+            Annunciator::Total => write!(f, "total"),
+            Annunciator::Change => write!(f, "change"),
+            Annunciator::Currency => write!(f, "currency"),
+            Annunciator::Numbered(n) => write!(f, "{}", n),
+        }
+    }
+}
@@ -0,0 +1,404 @@
+//! Non-blocking driver for tokio applications (`tokio` feature).
+//!
+//! [`AsyncVfd`] mirrors the blocking [`Vfd`](crate::Vfd) API with `async fn`
+//! methods, so slow 9600 baud writes never stall the runtime.
+
+use crate::builder::VfdBuilder;
+use crate::capabilities::Capabilities;
+use crate::codepage::{CodePage, TextMapping, encode_text, prepare_text, unencodable};
+use crate::command_set::{BrightnessLevel, CommandSet, CursorStyle, DisplayMode};
+use crate::error::VfdError;
+use crate::geometry::Geometry;
+use crate::glyph::Glyph;
+use crate::layout::{self, Align};
+use crate::logging;
+use crate::position::Position;
+use crate::symbols::SymbolMap;
+use crate::text;
+use crate::throttle::Throttle;
+use crate::vfd::{TextFit, print_cell, text_fit, too_long};
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio_serial::SerialStream;
+
+/// Driver for a VFD customer display over a tokio serial stream.
+pub struct AsyncVfd {
+    port: SerialStream,
+    command_set: CommandSet,
+    capabilities: Capabilities,
+    brightness: BrightnessLevel,
+    code_page: CodePage,
+    display_mode: DisplayMode,
+    transliterate: bool,
+    symbols: SymbolMap,
+    throttle: Throttle,
+    width: u8,
+    height: u8,
+    cursor_x: u8,
+    cursor_y: u8,
+}
+
+/// Async driver for a Birch DSP-V9FB display, the default command set.
+pub type AsyncBirchVfd = AsyncVfd;
+
+impl AsyncVfd {
+    /// Open the serial device at 9600 8N1 and initialize the display.
+    ///
+    /// Must be called from within a tokio runtime.
+    pub async fn new(device_path: &str, width: u8, height: u8) -> Result<Self, VfdError> {
+        VfdBuilder::new(device_path, width, height)
+            .open_async()
+            .await
+    }
+
+    /// Like [`AsyncVfd::new`], for a display speaking the given command set.
+    pub async fn with_command_set(
+        device_path: &str,
+        width: u8,
+        height: u8,
+        command_set: CommandSet,
+    ) -> Result<Self, VfdError> {
+        VfdBuilder::new(device_path, width, height)
+            .command_set(command_set)
+            .open_async()
+            .await
+    }
+
+    /// Wrap an already opened stream. Nothing is sent to the display.
+    pub fn from_stream(port: SerialStream, command_set: CommandSet, width: u8, height: u8) -> Self {
+        AsyncVfd {
+            port,
+            command_set: command_set.clone(),
+            capabilities: Capabilities::new(command_set, Geometry::new(width, height)),
+            brightness: BrightnessLevel::Percent100,
+            code_page: CodePage::default(),
+            display_mode: DisplayMode::default(),
+            transliterate: true,
+            symbols: SymbolMap::default(),
+            throttle: Throttle::default(),
+            width,
+            height,
+            cursor_x: 0,
+            cursor_y: 0,
+        }
+    }
+
+    async fn send(&mut self, bytes: &[u8]) -> Result<(), VfdError> {
+        logging::debug!(bytes = %logging::Hex(bytes), "Sending {} bytes", bytes.len());
+        let Some(size) = self.throttle.chunk_size() else {
+            self.port.write_all(bytes).await?;
+            return Ok(());
+        };
+        for chunk in bytes.chunks(size) {
+            self.port.write_all(chunk).await?;
+            self.port.flush().await?;
+            tokio::time::sleep(self.throttle.pause_after(chunk.len())).await;
+        }
+        Ok(())
+    }
+
+    /// Send bytes to the display as they are, without tracking their effect
+    pub async fn send_raw(&mut self, bytes: &[u8]) -> Result<(), VfdError> {
+        self.send(bytes).await
+    }
+
+    /// Send the standard initialization command (ESC @ on Birch)
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+    pub async fn initialize(&mut self) -> Result<(), VfdError> {
+        let cmd = self.command_set.initialize();
+        self.send(&cmd).await?;
+        self.brightness = BrightnessLevel::Percent100;
+        self.code_page = CodePage::default();
+        self.display_mode = DisplayMode::default();
+        (self.cursor_x, self.cursor_y) = (0, 0);
+        Ok(())
+    }
+
+    /// Command set this display was opened with
+    pub fn command_set(&self) -> &CommandSet {
+        &self.command_set
+    }
+
+    /// Clear screen and return cursor to home (top-left)
+    pub async fn clear(&mut self) -> Result<(), VfdError> {
+        let cmd = self.command_set.clear();
+        self.send(&cmd).await?;
+        if let Err(e) = self.port.flush().await {
+            logging::warning!("Failed to flush Serial port after clear command: {}", e);
+        }
+        self.set_cursor(0, 0).await
+    }
+
+    /// Move cursor to column `x`, row `y`, counted from 0 at the top-left.
+    ///
+    /// Fails with [`VfdError::OutOfBounds`] for a cell off the display.
+    pub async fn set_cursor(&mut self, x: u8, y: u8) -> Result<(), VfdError> {
+        let Position { x, y } = Position::new(x, y).check(self.size())?;
+        let cmd = self.command_set.set_cursor(x, y, self.width);
+        self.send(&cmd).await?;
+        (self.cursor_x, self.cursor_y) = (x, y);
+        Ok(())
+    }
+
+    /// Show or hide the cursor
+    pub async fn set_cursor_visible(&mut self, visible: bool) -> Result<(), VfdError> {
+        let cmd = self.command_set.set_cursor_visible(visible);
+        self.send(&cmd).await
+    }
+
+    /// Change how the cursor is drawn, where the command set supports the style
+    pub async fn set_cursor_style(&mut self, style: CursorStyle) -> Result<(), VfdError> {
+        let cmd = self.command_set.set_cursor_style(style).ok_or_else(|| {
+            VfdError::NotSupported(format!(
+                "{:?} cursor on {:?} displays",
+                style, self.command_set
+            ))
+        })?;
+        self.send(&cmd).await
+    }
+
+    /// Turn the display on or off without losing its contents
+    pub async fn set_display_enabled(&mut self, enabled: bool) -> Result<(), VfdError> {
+        let cmd = self
+            .command_set
+            .set_display_enabled(enabled, self.brightness)
+            .ok_or_else(|| {
+                VfdError::NotSupported(format!(
+                    "Turning {:?} displays on and off",
+                    self.command_set
+                ))
+            })?;
+        self.send(&cmd).await?;
+        Ok(())
+    }
+
+    /// Blink the whole display every `interval`; `Duration::ZERO` stops blinking
+    pub async fn blink(&mut self, interval: Duration) -> Result<(), VfdError> {
+        let cmd = self.command_set.blink(interval).ok_or_else(|| {
+            VfdError::NotSupported(format!("Blinking {:?} displays", self.command_set))
+        })?;
+        self.send(&cmd).await?;
+        Ok(())
+    }
+
+    /// Change the display brightness
+    pub async fn set_brightness(&mut self, level: BrightnessLevel) -> Result<(), VfdError> {
+        if !self.capabilities.supports_brightness {
+            return Err(VfdError::NotSupported(
+                "Changing the brightness".to_string(),
+            ));
+        }
+        let cmd = self.command_set.set_brightness(level);
+        self.send(&cmd).await?;
+        self.brightness = level;
+        Ok(())
+    }
+
+    /// Last brightness level sent to the display
+    pub fn brightness(&self) -> BrightnessLevel {
+        self.brightness
+    }
+
+    pub fn get_cursor(&self) -> (u8, u8) {
+        (self.cursor_x, self.cursor_y)
+    }
+
+    /// Display geometry as (columns, rows)
+    pub fn size(&self) -> (u8, u8) {
+        (self.width, self.height)
+    }
+
+    /// What the display can do; by default everything its command set has commands for.
+    pub fn capabilities(&self) -> Capabilities {
+        self.capabilities
+    }
+
+    /// Narrow what the display is assumed to do, like [`Vfd::set_capabilities`](crate::Vfd::set_capabilities).
+    pub fn set_capabilities(&mut self, capabilities: Capabilities) -> Result<(), VfdError> {
+        let geometry = Geometry::new(self.width, self.height);
+        if capabilities.geometry != geometry {
+            return Err(VfdError::InvalidInput(format!(
+                "Capabilities are for a {} display, not {}",
+                capabilities.geometry, geometry
+            )));
+        }
+        self.capabilities = capabilities;
+        Ok(())
+    }
+
+    /// Select the character table used to render non-ASCII text
+    pub async fn set_code_page(&mut self, page: CodePage) -> Result<(), VfdError> {
+        let cmd = self.command_set.set_code_page(page).ok_or_else(|| {
+            VfdError::NotSupported(format!(
+                "Switching code pages on {:?} displays",
+                self.command_set
+            ))
+        })?;
+        self.send(&cmd).await?;
+        self.code_page = page;
+        Ok(())
+    }
+
+    /// Select how the display continues when text reaches the end of a line
+    pub async fn set_display_mode(&mut self, mode: DisplayMode) -> Result<(), VfdError> {
+        let cmd = self
+            .command_set
+            .set_display_mode(mode)
+            .filter(|_| self.capabilities.supports_display_mode(mode))
+            .ok_or_else(|| {
+                VfdError::NotSupported(format!(
+                    "{:?} mode on {:?} displays",
+                    mode, self.command_set
+                ))
+            })?;
+        self.send(&cmd).await?;
+        self.display_mode = mode;
+        Ok(())
+    }
+
+    /// Character table text is currently transcoded into
+    pub fn code_page(&self) -> CodePage {
+        self.code_page
+    }
+
+    /// Download a 5x7 bitmap into user-definable character `slot`.
+    pub async fn define_custom_char(&mut self, slot: u8, glyph: &Glyph) -> Result<(), VfdError> {
+        if !self.capabilities.supports_cgram {
+            return Err(VfdError::NotSupported("Custom characters".to_string()));
+        }
+        let code = self
+            .command_set
+            .custom_char_code(slot)
+            .filter(|_| self.capabilities.has_custom_char(slot))
+            .ok_or(VfdError::OutOfBounds {
+                what: "Custom character slot",
+                value: slot as usize,
+                max: (self.capabilities.max_custom_chars as usize).saturating_sub(1),
+            })?;
+        let cmd = self.command_set.define_custom_char(code, glyph);
+        self.send(&cmd).await
+    }
+
+    /// Replace characters missing from the code page with ASCII look-alikes,
+    /// or `?` where there is none (on by default). When off, writing such a
+    /// character fails with [`VfdError::Encoding`].
+    pub fn set_transliterate(&mut self, enabled: bool) {
+        self.transliterate = enabled;
+    }
+
+    /// Replacements for symbols missing from the code page ([`SymbolMap::default`] unless set)
+    pub fn set_symbols(&mut self, symbols: SymbolMap) {
+        self.symbols = symbols;
+    }
+
+    /// Pace every write for displays that drop bytes at full line speed (off by default)
+    pub fn set_throttle(&mut self, throttle: Throttle) {
+        self.throttle = throttle;
+    }
+
+    /// How text is mapped onto the cells with the current code page,
+    /// transliteration and symbols.
+    pub fn text_mapping(&self) -> TextMapping {
+        TextMapping::new(self.code_page, self.transliterate, self.symbols.clone())
+    }
+
+    // Text as it is sent and measured, the way `Vfd` prepares it
+    fn prepare(&self, text: &str) -> String {
+        prepare_text(text, self.code_page, self.transliterate, &self.symbols)
+    }
+
+    async fn write(&mut self, text: &str) -> Result<(), VfdError> {
+        if !self.transliterate
+            && let Some(c) = unencodable(text, self.code_page)
+        {
+            return Err(VfdError::Encoding(c.to_string()));
+        }
+        let bytes = encode_text(text, self.command_set.clone(), self.code_page);
+        self.send(&bytes).await?;
+        // Follow the cursor as the display moves it along
+        for _ in 0..text::display_width(text) {
+            let (x, y) = print_cell(
+                (self.cursor_x, self.cursor_y),
+                (self.width, self.height),
+                self.display_mode,
+            );
+            (self.cursor_x, self.cursor_y) = (x + 1, y);
+        }
+        Ok(())
+    }
+
+    /// Write a single line to the display
+    pub async fn writeln(&mut self, text: &str) -> Result<(), VfdError> {
+        let text = self.prepare(text);
+        self.write(&text).await
+    }
+
+    /// Write a single line to the display and truncate if necessary
+    pub async fn writeln_truncate(&mut self, text: &str) -> Result<(), VfdError> {
+        let text = self.prepare(text);
+        self.write_truncated(&text).await
+    }
+
+    async fn write_truncated(&mut self, text: &str) -> Result<(), VfdError> {
+        let space_available = (self.width - self.cursor_x) as usize;
+        self.write(text::truncate_to_width(text, space_available))
+            .await
+    }
+
+    async fn write_multi_line(&mut self, text: &str) -> Result<(), VfdError> {
+        let mut remaining = text;
+        while !remaining.is_empty() {
+            let (cursor_x, cursor_y) = self.get_cursor();
+            let (chunk, rest) = text::split_at_width(remaining, (self.width - cursor_x) as usize);
+            self.write(chunk.trim()).await?;
+            remaining = rest;
+            if !remaining.is_empty() {
+                self.set_cursor(0, cursor_y + 1).await?;
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn write_text(&mut self, text: &str) -> Result<(), VfdError> {
+        self.write_text_handler(text, false).await
+    }
+
+    /// Replace row `row` with `text`, aligned and padded to the full width.
+    pub async fn write_line(&mut self, row: u8, text: &str, align: Align) -> Result<(), VfdError> {
+        if row >= self.height {
+            return Err(VfdError::OutOfBounds {
+                what: "Row",
+                value: row as usize,
+                max: self.height as usize - 1,
+            });
+        }
+        let text = self.prepare(text);
+        let text = layout::align(&text, self.width as usize, align);
+        self.set_cursor(0, row).await?;
+        self.write(&text).await
+    }
+
+    pub async fn write_text_truncate(&mut self, text: &str) -> Result<(), VfdError> {
+        self.write_text_handler(text, true).await
+    }
+
+    async fn write_text_handler(&mut self, text: &str, truncate: bool) -> Result<(), VfdError> {
+        let text = &self.prepare(text);
+        match self.get_text_fit(text, truncate) {
+            TextFit::OneLine => self.write(text).await,
+            TextFit::OneLineTruncated => self.write_truncated(text).await,
+            TextFit::NeedsWrap => self.write_multi_line(text).await,
+            TextFit::TooLong => Err(too_long(text, self.size(), self.get_cursor())),
+        }
+    }
+
+    /// Determine if the text fits on the display from the current cursor position.
+    pub fn get_text_fit(&self, text: &str, truncate: bool) -> TextFit {
+        text_fit(
+            &self.prepare(text),
+            self.size(),
+            self.get_cursor(),
+            truncate,
+        )
+    }
+}
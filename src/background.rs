@@ -0,0 +1,163 @@
+use crate::codepage::TextMapping;
+use crate::command_set::BrightnessLevel;
+use crate::display::VfdDisplay;
+use crate::error::VfdError;
+use crate::vfd::Vfd;
+use std::io;
+use std::sync::mpsc::{self, Receiver, SyncSender};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread::{self, JoinHandle};
+
+type Job = Box<dyn FnOnce(&mut Vfd) -> Result<(), VfdError> + Send>;
+
+/// A display whose writes are performed by a dedicated writer thread.
+///
+/// Commands are queued and return immediately; once `capacity` commands are
+/// waiting, further calls block until the writer catches up. Errors hit by
+/// the writer are reported by the next call, or by [`BackgroundVfd::flush_sync`].
+pub struct BackgroundVfd {
+    sender: Option<SyncSender<Job>>,
+    thread: Option<JoinHandle<Vfd>>,
+    error: Arc<Mutex<Option<VfdError>>>,
+    width: u8,
+    height: u8,
+    cursor_x: u8,
+    cursor_y: u8,
+    mapping: TextMapping,
+}
+
+impl BackgroundVfd {
+    /// Move `vfd` to a writer thread with room for `capacity` queued commands.
+    pub fn new(vfd: Vfd, capacity: usize) -> Self {
+        let (width, height) = vfd.size();
+        let (cursor_x, cursor_y) = vfd.get_cursor();
+        let mapping = vfd.text_mapping();
+        let (sender, receiver) = mpsc::sync_channel(capacity);
+        let error = Arc::new(Mutex::new(None));
+        let thread_error = Arc::clone(&error);
+        let thread = thread::spawn(move || drain(vfd, receiver, thread_error));
+        BackgroundVfd {
+            sender: Some(sender),
+            thread: Some(thread),
+            error,
+            width,
+            height,
+            cursor_x,
+            cursor_y,
+            mapping,
+        }
+    }
+
+    /// Queue an arbitrary operation on the display.
+    pub fn execute<F>(&mut self, job: F) -> Result<(), VfdError>
+    where
+        F: FnOnce(&mut Vfd) -> Result<(), VfdError> + Send + 'static,
+    {
+        self.take_error()?;
+        let sender = self.sender.as_ref().ok_or_else(writer_gone)?;
+        sender.send(Box::new(job)).map_err(|_| writer_gone())
+    }
+
+    /// Block until every queued command has been written and flushed to the port.
+    ///
+    /// Returns the first error hit by the writer since the last report.
+    pub fn flush_sync(&mut self) -> Result<(), VfdError> {
+        let (done, wait) = mpsc::channel();
+        self.execute(move |vfd| {
+            let result = vfd.flush();
+            let _ = done.send(());
+            result
+        })?;
+        wait.recv().map_err(|_| writer_gone())?;
+        self.take_error()
+    }
+
+    /// Wait for the queue to drain and take back the display.
+    pub fn into_inner(mut self) -> Result<Vfd, VfdError> {
+        self.sender = None;
+        let vfd = self
+            .thread
+            .take()
+            .ok_or_else(writer_gone)?
+            .join()
+            .map_err(|_| writer_gone())?;
+        self.take_error()?;
+        Ok(vfd)
+    }
+
+    fn take_error(&self) -> Result<(), VfdError> {
+        match self
+            .error
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take()
+        {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+}
+
+// Run queued jobs until every sender is dropped, keeping the first error for the caller
+fn drain(mut vfd: Vfd, receiver: Receiver<Job>, error: Arc<Mutex<Option<VfdError>>>) -> Vfd {
+    for job in receiver {
+        if let Err(e) = job(&mut vfd) {
+            error
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .get_or_insert(e);
+        }
+    }
+    vfd
+}
+
+fn writer_gone() -> VfdError {
+    io::Error::new(io::ErrorKind::BrokenPipe, "Display writer thread stopped").into()
+}
+
+impl Drop for BackgroundVfd {
+    fn drop(&mut self) {
+        // Let the writer finish what is queued so nothing is cut off mid-command
+        self.sender = None;
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl VfdDisplay for BackgroundVfd {
+    fn size(&self) -> (u8, u8) {
+        (self.width, self.height)
+    }
+
+    fn clear(&mut self) -> Result<(), VfdError> {
+        self.execute(Vfd::clear)?;
+        self.cursor_x = 0;
+        self.cursor_y = 0;
+        Ok(())
+    }
+
+    fn set_cursor(&mut self, x: u8, y: u8) -> Result<(), VfdError> {
+        self.execute(move |vfd| vfd.set_cursor(x, y))?;
+        self.cursor_x = x.min(self.width);
+        self.cursor_y = y.min(self.height);
+        Ok(())
+    }
+
+    fn get_cursor(&self) -> (u8, u8) {
+        (self.cursor_x, self.cursor_y)
+    }
+
+    fn text_mapping(&self) -> TextMapping {
+        self.mapping.clone()
+    }
+
+    fn write_text(&mut self, text: &str) -> Result<(), VfdError> {
+        let text = text.to_string();
+        self.execute(move |vfd| vfd.write_text(&text))
+    }
+
+    fn set_brightness(&mut self, level: BrightnessLevel) -> Result<(), VfdError> {
+        self.execute(move |vfd| vfd.set_brightness(level))
+    }
+}
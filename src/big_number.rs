@@ -0,0 +1,145 @@
+use crate::error::VfdError;
+use crate::glyph::{Glyph, custom_char};
+use crate::text;
+
+// Building blocks of the default font, in slot order
+const UPPER_BAR: Glyph = [0b11111, 0b11111, 0, 0, 0, 0, 0];
+const LOWER_BAR: Glyph = [0, 0, 0, 0, 0, 0b11111, 0b11111];
+const BOTH_BARS: Glyph = [0b11111, 0b11111, 0, 0, 0, 0b11111, 0b11111];
+const FULL: Glyph = [0b11111; 7];
+
+/// Characters drawn several rows tall out of smaller pieces.
+///
+/// Each character is given as rows of text, top to bottom, which may refer to
+/// the font's custom glyphs through [`custom_char`]. Swap in your own font
+/// with [`BigFont::new`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BigFont {
+    glyphs: Vec<(u8, Glyph)>,
+    chars: Vec<(char, Vec<String>)>,
+    height: u8,
+    spacing: u8,
+}
+
+impl Default for BigFont {
+    fn default() -> Self {
+        BigFont::blocks(0)
+    }
+}
+
+impl BigFont {
+    /// A font whose characters are `height` rows tall, separated by `spacing` blank columns.
+    ///
+    /// `glyphs` are downloaded to the display before the font is first drawn.
+    pub fn new(glyphs: Vec<(u8, Glyph)>, height: u8, spacing: u8) -> Self {
+        BigFont {
+            glyphs,
+            chars: Vec::new(),
+            height,
+            spacing,
+        }
+    }
+
+    /// Define how `c` is drawn, one string per row.
+    pub fn char(mut self, c: char, rows: &[&str]) -> Self {
+        self.chars.retain(|(existing, _)| *existing != c);
+        self.chars
+            .push((c, rows.iter().map(|row| row.to_string()).collect()));
+        self
+    }
+
+    /// Two-row, three-column digits built from four custom glyphs stored from `first_slot`.
+    ///
+    /// Covers `0`-`9`, `:`, `-`, `.` and space.
+    pub fn blocks(first_slot: u8) -> Self {
+        let piece = |n: u8| custom_char(first_slot.saturating_add(n));
+        let (u, l, b, f) = (piece(0), piece(1), piece(2), piece(3));
+        let digit = |top: [char; 3], bottom: [char; 3]| {
+            [top.iter().collect::<String>(), bottom.iter().collect()]
+        };
+        let digits = [
+            ('0', digit([f, u, f], [f, l, f])),
+            ('1', digit([u, f, ' '], [l, f, l])),
+            ('2', digit([b, b, f], [f, l, l])),
+            ('3', digit([b, b, f], [l, l, f])),
+            ('4', digit([f, l, f], [' ', ' ', f])),
+            ('5', digit([f, b, b], [l, l, f])),
+            ('6', digit([f, b, b], [f, l, f])),
+            ('7', digit([u, u, f], [' ', ' ', f])),
+            ('8', digit([f, b, f], [f, l, f])),
+            ('9', digit([f, b, f], [l, l, f])),
+        ];
+        let mut font = BigFont::new(
+            vec![
+                (first_slot, UPPER_BAR),
+                (first_slot.saturating_add(1), LOWER_BAR),
+                (first_slot.saturating_add(2), BOTH_BARS),
+                (first_slot.saturating_add(3), FULL),
+            ],
+            2,
+            1,
+        );
+        for (c, [top, bottom]) in &digits {
+            font = font.char(*c, &[top, bottom]);
+        }
+        let colon = format!("{}", l);
+        font.char(':', &[&colon, &colon])
+            .char('-', &[&format!("{}{}", l, l), "  "])
+            .char('.', &[" ", &l.to_string()])
+            .char(' ', &["  ", "  "])
+    }
+
+    /// Custom glyphs the font needs on the display, as (slot, glyph) pairs.
+    pub fn glyphs(&self) -> &[(u8, Glyph)] {
+        &self.glyphs
+    }
+
+    /// Number of rows each character spans.
+    pub fn height(&self) -> u8 {
+        self.height
+    }
+
+    /// How many characters of `text` fit in `columns` when drawn in this font.
+    pub fn fitting_chars(&self, text: &str, columns: usize) -> usize {
+        let mut used = 0;
+        for (i, c) in text.chars().enumerate() {
+            let width = self
+                .chars
+                .iter()
+                .find(|(defined, _)| *defined == c)
+                .and_then(|(_, shape)| shape.iter().map(|row| text::display_width(row)).max())
+                .unwrap_or(0);
+            let spacing = if i > 0 { self.spacing as usize } else { 0 };
+            used += spacing + width;
+            if used > columns {
+                return i;
+            }
+        }
+        text.chars().count()
+    }
+
+    /// Rows of text, top to bottom, that draw `text` in this font.
+    pub fn render(&self, text: &str) -> Result<Vec<String>, VfdError> {
+        let mut rows = vec![String::new(); self.height as usize];
+        for (i, c) in text.chars().enumerate() {
+            let (_, shape) = self
+                .chars
+                .iter()
+                .find(|(defined, _)| *defined == c)
+                .ok_or_else(|| {
+                    VfdError::InvalidInput(format!("No big font character for {:?}", c))
+                })?;
+            let width = shape.iter().map(|row| text::display_width(row)).max();
+            for (y, row) in rows.iter_mut().enumerate() {
+                if i > 0 {
+                    row.push_str(&" ".repeat(self.spacing as usize));
+                }
+                let part = shape.get(y).map(String::as_str).unwrap_or("");
+                row.push_str(part);
+                let padding = width.unwrap_or(0) - text::display_width(part);
+                row.push_str(&" ".repeat(padding));
+            }
+        }
+        Ok(rows)
+    }
+}
@@ -0,0 +1,791 @@
+use clap::builder::BoolishValueParser;
+use clap::{Parser, Subcommand};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread::{self, sleep};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use vfd_dsp_v9fb_over_serial::config::{self, Config};
+use vfd_dsp_v9fb_over_serial::device_path;
+use vfd_dsp_v9fb_over_serial::script::Script;
+use vfd_dsp_v9fb_over_serial::sysmon::{self, Metric, SystemMonitor};
+use vfd_dsp_v9fb_over_serial::{
+    Align, Animation, Annunciator, BrightnessLevel, CodePage, CommandSet, CursorStyle, DisplayMode,
+    FitPolicy, FlowControl, Geometry, LogView, Overflow, Parity, ProgressStyle, Recording, Screen,
+    ScrollDirection, SerialProfile, Simulator, Throttle, Vfd, VfdError, Widget, WrapPolicy,
+    WriteOptions, discover, text,
+};
+
+/// Drive a VFD customer display from the command line.
+#[derive(Parser)]
+#[command(name = "vfd-ctl", version)]
+struct Cli {
+    /// Config file to use instead of searching the XDG config directories
+    #[arg(short, long, global = true)]
+    config: Option<PathBuf>,
+
+    /// Serial device the display is attached to, e.g. /dev/ttyUSB0, COM3, or rfc2217://host:port or tcp://host:port on a serial device server [default: /dev/ttyUSB0, COM3 on Windows]
+    #[arg(short, long, global = true)]
+    device: Option<String>,
+
+    /// Display width in characters [default: 20]
+    #[arg(long, global = true)]
+    width: Option<u8>,
+
+    /// Display height in rows [default: 2]
+    #[arg(long, global = true)]
+    height: Option<u8>,
+
+    /// Display size as COLUMNSxROWS, e.g. 16x2, 20x4 or 24x2
+    #[arg(long, global = true, conflicts_with_all = ["width", "height"])]
+    geometry: Option<Geometry>,
+
+    /// Serial baud rate [default: the command set's factory setting, 9600 or 19200]
+    #[arg(long, global = true)]
+    baud: Option<u32>,
+
+    /// Serial parity (none, even, odd) [default: none]
+    #[arg(long, global = true, value_parser = SerialProfile::parse_parity)]
+    parity: Option<Parity>,
+
+    /// Serial flow control (none, software, hardware) [default: the command set's factory setting]
+    #[arg(long, global = true, value_parser = SerialProfile::parse_flow_control)]
+    flow_control: Option<FlowControl>,
+
+    /// Level to drive DTR to after opening the port (on, off) [default: on]
+    #[arg(long, global = true, value_parser = BoolishValueParser::new())]
+    dtr: Option<bool>,
+
+    /// Level to drive RTS to after opening the port (on, off) [default: on]
+    #[arg(long, global = true, value_parser = BoolishValueParser::new())]
+    rts: Option<bool>,
+
+    /// Pace writes for displays that drop bytes: BYTES/s (e.g. 480/s) or BYTES/DELAY (e.g. 16/5ms)
+    #[arg(long, global = true)]
+    throttle: Option<Throttle>,
+
+    /// Try common baud rates and parities until the display answers an identify request
+    #[arg(long, global = true)]
+    auto_baud: bool,
+
+    /// Command set spoken by the display (birch, epson, noritake, futaba), or a protocol definition file (.toml) [default: birch]
+    #[arg(long, global = true, value_parser = config::parse_command_set)]
+    command_set: Option<CommandSet>,
+
+    /// Code page to transcode text into (pc437, katakana, pc850, pc858, pc862, pc866, wpc1252)
+    #[arg(long, global = true)]
+    code_page: Option<CodePage>,
+
+    /// Cursor style to select after opening (off, underline, block, blink)
+    #[arg(long, global = true)]
+    cursor: Option<CursorStyle>,
+
+    /// Append a hex dump of every byte sent to the display to this file
+    #[arg(long, global = true)]
+    tap: Option<PathBuf>,
+
+    /// Don't show the configured splash screen when initializing the display
+    #[arg(long, global = true)]
+    skip_splash: bool,
+
+    /// Leave the display alone and print the screen the command would leave on it, and every screen along the way for commands that keep running
+    #[arg(long, global = true, conflicts_with = "auto_baud")]
+    dry_run: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Write text at the cursor, or at --col/--row
+    Write {
+        #[arg(required_unless_present = "stdin")]
+        text: Option<String>,
+        /// Read lines from standard input instead, one per row
+        #[arg(long, conflicts_with = "text")]
+        stdin: bool,
+        /// With --stdin, keep reading and show the newest lines as they arrive
+        #[arg(long, requires = "stdin")]
+        follow: bool,
+        /// Column to start writing at
+        #[arg(long)]
+        col: Option<u8>,
+        /// Row to start writing at
+        #[arg(long)]
+        row: Option<u8>,
+        /// Clear the display first
+        #[arg(long)]
+        clear: bool,
+        /// Cut text at the end of the line instead of wrapping
+        #[arg(long)]
+        truncate: bool,
+        /// How long text is broken across rows (char, word, hyphenate, collapse)
+        #[arg(long, default_value = "char", conflicts_with = "truncate")]
+        wrap: WrapPolicy,
+        /// Trim each line, squeeze runs of spaces and drop blank lines before laying out the text (strict, compact)
+        #[arg(long, default_value = "strict")]
+        fit: FitPolicy,
+        /// What to do with text that does not fit (truncate, ellipsis, error, scroll)
+        #[arg(long, conflicts_with = "truncate")]
+        overflow: Option<Overflow>,
+        /// Character marking text cut by --overflow ellipsis (default … or >, depending on the code page)
+        #[arg(long)]
+        ellipsis: Option<char>,
+    },
+    /// Clear the display
+    Clear,
+    /// Move the cursor to column x, row y (counted from 0)
+    Cursor { x: u8, y: u8 },
+    /// Set brightness (20, 40, 60 or 100)
+    Brightness { level: BrightnessLevel },
+    /// Turn the display back on
+    On,
+    /// Turn the display off, keeping its contents
+    Off,
+    /// Blink the whole display (0 stops blinking)
+    Blink {
+        /// Milliseconds between blinks
+        interval: u64,
+    },
+    /// Light one of the icons printed on the display (sum, total, change, currency or a position)
+    Annunciator {
+        icon: Annunciator,
+        /// Darken the icon instead
+        #[arg(long)]
+        off: bool,
+    },
+    /// Draw a progress bar across a row
+    Progress {
+        /// Completed fraction, from 0.0 to 1.0
+        fraction: f32,
+        #[arg(long, default_value_t = 0)]
+        row: u8,
+        /// Bar style (ascii, shade, blocks)
+        #[arg(long, default_value = "ascii")]
+        style: ProgressStyle,
+    },
+    /// Draw a named template from the config file
+    Template {
+        name: String,
+        /// Placeholder values as KEY=VALUE
+        #[arg(value_parser = parse_key_value)]
+        values: Vec<(String, String)>,
+    },
+    /// Select what happens at the end of a line (overwrite, vertical, horizontal)
+    Mode { mode: DisplayMode },
+    /// Reset the display to its power-on state
+    Init,
+    /// Show what the display reports about itself
+    Info,
+    /// Show whether the display is ready for more data
+    Status,
+    /// Run the display's built-in self-test
+    SelfTest,
+    /// Print a line on a receipt printer chained behind the display
+    Print { text: String },
+    /// Scroll text across a row
+    Scroll {
+        text: String,
+        #[arg(long, default_value_t = 0)]
+        row: u8,
+        /// Milliseconds per step
+        #[arg(long, default_value_t = 300)]
+        speed: u64,
+        #[arg(long, default_value = "left")]
+        direction: ScrollDirection,
+        /// Stop after this many seconds (runs until interrupted if omitted)
+        #[arg(long)]
+        duration: Option<u64>,
+    },
+    /// Show host CPU, memory, load and network figures until interrupted
+    Sysmon {
+        /// Milliseconds between refreshes
+        #[arg(long, default_value_t = 1000)]
+        interval: u64,
+        /// Metrics for the next row, comma separated (cpu, mem, load, net); repeat per row
+        #[arg(long = "row", value_parser = sysmon::parse_row)]
+        rows: Vec<Vec<Metric>>,
+    },
+    /// Run a shell command repeatedly and show the first lines of its output
+    Watch {
+        /// Seconds between runs
+        #[arg(short = 'n', long, default_value_t = 2)]
+        interval: u64,
+        /// Command line, passed to `sh -c`
+        #[arg(required = true, last = true)]
+        command: Vec<String>,
+    },
+    /// Send a recorded byte stream (a --tap hex dump or raw bytes) to the display
+    Replay {
+        file: PathBuf,
+        /// Playback speed relative to the recording; 0 sends everything at once
+        #[arg(long, default_value_t = 1.0, value_parser = parse_speed)]
+        speed: f64,
+    },
+    /// Run a display script (see the script module docs for the format), or a
+    /// Rhai script if the file name ends in .rhai and the rhai feature is enabled
+    Run {
+        file: PathBuf,
+        /// Start over from the top until interrupted
+        #[arg(long = "loop")]
+        looped: bool,
+    },
+    /// Cycle through showcase animations to check a newly wired display
+    Demo {
+        /// Keep cycling until interrupted (attract mode)
+        #[arg(long = "loop")]
+        looped: bool,
+    },
+    /// List serial ports that may have a display attached
+    List {
+        /// Send an identify request to each port and report which ones answer
+        #[arg(long)]
+        probe: bool,
+    },
+    /// Print the text on a display driven by vfdd, asking it over its socket
+    Dump {
+        /// Socket vfdd listens on
+        #[arg(long, default_value = "/run/vfdd.sock")]
+        socket: PathBuf,
+    },
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let cli = Cli::parse();
+
+    let config = Config::discover(cli.config.as_deref())?.merge(Config {
+        device: cli.device,
+        width: cli.width.or(cli.geometry.map(|geometry| geometry.width)),
+        height: cli.height.or(cli.geometry.map(|geometry| geometry.height)),
+        baud_rate: cli.baud,
+        parity: cli.parity,
+        flow_control: cli.flow_control,
+        dtr: cli.dtr,
+        rts: cli.rts,
+        throttle: cli.throttle,
+        auto_baud: cli.auto_baud.then_some(true),
+        command_set: cli.command_set,
+        code_page: cli.code_page,
+        cursor: cli.cursor,
+        skip_splash: cli.skip_splash.then_some(true),
+        ..Config::default()
+    });
+
+    if let Command::List { probe } = cli.command {
+        return list_ports(probe.then(|| config.command_set.unwrap_or_default()));
+    }
+    if let Command::Dump { socket } = &cli.command {
+        return dump_daemon(socket);
+    }
+
+    let mut builder = config
+        .builder()
+        .auto_initialize(matches!(cli.command, Command::Init | Command::Demo { .. }));
+    if let Some(path) = &cli.tap {
+        builder = builder.tap(path);
+    }
+    // Stands in for the display on a dry run, drawing what would be sent
+    let simulator = cli.dry_run.then(|| {
+        Simulator::new(
+            config.command_set.clone().unwrap_or_default(),
+            config.width.unwrap_or(config::DEFAULT_WIDTH),
+            config.height.unwrap_or(config::DEFAULT_HEIGHT),
+        )
+    });
+    let mut vfd = if let Some(simulator) = &simulator {
+        builder.open_duplex(simulator.clone())?
+    } else if config.auto_baud == Some(true) {
+        let (vfd, profile) = builder.probe()?;
+        eprintln!("Display answered at {}", profile);
+        vfd
+    } else {
+        builder.open()?
+    };
+
+    let preview = simulator.map(Preview::new);
+    if let Some(preview) = &preview
+        && keeps_running(&cli.command)
+    {
+        preview.follow();
+    }
+
+    match cli.command {
+        Command::Write {
+            stdin: true,
+            follow,
+            truncate,
+            ..
+        } => {
+            if follow {
+                follow_stdin(&mut vfd, truncate)?;
+            } else {
+                write_stdin(&mut vfd, truncate)?;
+            }
+        }
+        Command::Write {
+            text,
+            col,
+            row,
+            clear,
+            truncate,
+            wrap,
+            fit,
+            overflow,
+            ellipsis,
+            ..
+        } => {
+            let text = text.unwrap_or_default();
+            vfd.set_wrap_policy(wrap);
+            if let Some(indicator) = ellipsis {
+                vfd.set_ellipsis(indicator);
+            }
+            if clear {
+                vfd.clear()?;
+            }
+            if col.is_some() || row.is_some() {
+                let (x, y) = vfd.get_cursor();
+                vfd.set_cursor(col.unwrap_or(x), row.unwrap_or(y))?;
+            }
+            if overflow.is_some() || fit != FitPolicy::Strict {
+                let overflow = overflow.unwrap_or(if truncate {
+                    Overflow::Truncate
+                } else {
+                    Overflow::Error
+                });
+                let options = WriteOptions::default()
+                    .wrap(wrap)
+                    .overflow(overflow)
+                    .fit(fit);
+                vfd.write_text_with(&text, options)?;
+            } else if truncate {
+                vfd.write_text_truncate(&text)?;
+            } else {
+                vfd.write_text(&text)?;
+            }
+        }
+        Command::Clear => vfd.clear()?,
+        Command::Cursor { x, y } => vfd.set_cursor(x, y)?,
+        Command::Brightness { level } => vfd.set_brightness(level)?,
+        Command::Progress {
+            fraction,
+            row,
+            style,
+        } => vfd.draw_progress_bar(row, fraction, style)?,
+        Command::Template { name, values } => {
+            let values: Vec<(&str, &str)> = values
+                .iter()
+                .map(|(key, value)| (key.as_str(), value.as_str()))
+                .collect();
+            vfd.render_template(&name, &values)?;
+        }
+        Command::Mode { mode } => vfd.set_display_mode(mode)?,
+        Command::On => vfd.set_display_enabled(true)?,
+        Command::Off => vfd.set_display_enabled(false)?,
+        Command::Blink { interval } => vfd.blink(Duration::from_millis(interval))?,
+        Command::Annunciator { icon, off } => vfd.set_annunciator(icon, !off)?,
+        // Already sent while opening
+        Command::Init => {}
+        Command::Info => {
+            let (width, height) = vfd.size();
+            println!(
+                "Device:      {}",
+                device_path::display_name(&config.device_path())
+            );
+            println!("Size:        {}x{}", width, height);
+            println!("Command set: {:?}", vfd.command_set());
+            let info = vfd.identify()?;
+            println!("Model ID:    0x{:02x}", info.model_id);
+            let id =
+                |id: Option<u8>| id.map_or("no answer".to_string(), |id| format!("0x{:02x}", id));
+            println!("Type ID:     {}", id(info.type_id));
+            println!("ROM version: {}", id(info.rom_version));
+        }
+        Command::Status => println!("{}", vfd.read_status()?),
+        Command::SelfTest => vfd.self_test()?,
+        Command::Print { text } => vfd.print(format!("{}\n", text).as_bytes())?,
+        Command::Scroll {
+            text,
+            row,
+            speed,
+            direction,
+            duration,
+        } => {
+            let mut marquee = vfd.marquee(&text, row, Duration::from_millis(speed), direction);
+            let started = Instant::now();
+            while duration.is_none_or(|secs| started.elapsed() < Duration::from_secs(secs)) {
+                marquee.tick(&mut vfd)?;
+                sleep(Duration::from_millis(20));
+            }
+        }
+        Command::Run { file, looped } => run_script(vfd, &file, looped)?,
+        Command::Sysmon { interval, rows } => {
+            let mut monitor = if rows.is_empty() {
+                SystemMonitor::default()
+            } else {
+                SystemMonitor::new(rows)
+            }
+            .refresh(Duration::from_millis(interval));
+            vfd.clear()?;
+            loop {
+                monitor.tick(&mut vfd)?;
+                sleep(Duration::from_millis(50));
+            }
+        }
+        Command::Watch { interval, command } => {
+            watch(&mut vfd, &command.join(" "), Duration::from_secs(interval))?
+        }
+        Command::Replay { file, speed } => {
+            let recording = Recording::load(&file)?;
+            let length = recording.replay_duration(speed);
+            println!(
+                "Replaying {} chunks over {:.1}s",
+                recording.chunks().len(),
+                length.as_secs_f64()
+            );
+            recording.replay(&mut vfd, speed)?;
+        }
+        Command::Demo { looped } => demo(&mut vfd, looped)?,
+        Command::List { .. } | Command::Dump { .. } => {
+            unreachable!("handled before opening the port")
+        }
+    }
+
+    if let Some(preview) = preview {
+        preview.show()?;
+    }
+    Ok(())
+}
+
+// Whether the command draws over time rather than once
+fn keeps_running(command: &Command) -> bool {
+    matches!(
+        command,
+        Command::Write { follow: true, .. }
+            | Command::Scroll { .. }
+            | Command::Sysmon { .. }
+            | Command::Watch { .. }
+            | Command::Replay { .. }
+            | Command::Run { .. }
+            | Command::Demo { .. }
+    )
+}
+
+// How often a dry run checks whether the screen changed
+const PREVIEW_INTERVAL: Duration = Duration::from_millis(50);
+
+// Prints the screen of a dry run, each time it changes
+#[derive(Clone)]
+struct Preview {
+    simulator: Simulator,
+    // Rows as last printed
+    printed: Arc<Mutex<Option<Vec<String>>>>,
+}
+
+impl Preview {
+    fn new(simulator: Simulator) -> Self {
+        Preview {
+            simulator,
+            printed: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    // Print the screen unless it is what was printed last
+    fn show(&self) -> io::Result<()> {
+        let mut printed = self.printed.lock().unwrap_or_else(PoisonError::into_inner);
+        let rows = self.simulator.rows();
+        if printed.as_ref() == Some(&rows) {
+            return Ok(());
+        }
+        let mut stdout = io::stdout().lock();
+        stdout.write_all(self.simulator.frame().ascii_art().as_bytes())?;
+        stdout.flush()?;
+        *printed = Some(rows);
+        Ok(())
+    }
+
+    // Keep printing the screen as it changes, for as long as the command runs
+    fn follow(&self) {
+        let preview = self.clone();
+        thread::spawn(move || {
+            while preview.show().is_ok() {
+                sleep(PREVIEW_INTERVAL);
+            }
+        });
+    }
+}
+
+fn parse_speed(arg: &str) -> Result<f64, String> {
+    match arg.parse::<f64>() {
+        Ok(speed) if speed.is_finite() && speed >= 0.0 => Ok(speed),
+        _ => Err(format!("Expected a speed of 0 or more, got '{}'", arg)),
+    }
+}
+
+fn parse_key_value(arg: &str) -> Result<(String, String), String> {
+    arg.split_once('=')
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .ok_or_else(|| format!("Expected KEY=VALUE, got '{}'", arg))
+}
+
+// Print the rows vfdd reports for its display
+#[cfg(unix)]
+fn dump_daemon(socket: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    use std::io::{BufRead, BufReader};
+    use std::os::unix::net::UnixStream;
+
+    let mut stream = UnixStream::connect(socket)
+        .map_err(|e| format!("Cannot connect to vfdd at {}: {}", socket.display(), e))?;
+    stream.write_all(b"dump\n")?;
+    let mut lines = BufReader::new(stream).lines();
+    let status = lines.next().transpose()?.unwrap_or_default();
+    let rows: usize = match status.strip_prefix("OK ") {
+        Some(count) => count.parse()?,
+        None => return Err(status.trim_start_matches("ERR ").to_string().into()),
+    };
+    for row in lines.take(rows) {
+        println!("{}", row?);
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn dump_daemon(_socket: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    Err("vfdd is only available on Unix".into())
+}
+
+fn list_ports(probe: Option<CommandSet>) -> Result<(), Box<dyn std::error::Error>> {
+    let candidates = discover(probe.clone())?;
+    if candidates.is_empty() {
+        println!("No serial ports found.");
+    }
+    for candidate in candidates {
+        let usb_id = candidate
+            .usb_id
+            .map(|(vid, pid)| format!(" [{:04x}:{:04x}]", vid, pid))
+            .unwrap_or_default();
+        let answer = match (&candidate.probe_response, &probe) {
+            (Some(_), _) => " - responded",
+            (None, Some(_)) => " - no response",
+            (None, None) => "",
+        };
+        println!(
+            "{}\t{}{}{}",
+            device_path::display_name(&candidate.path),
+            candidate.description,
+            usb_id,
+            answer
+        );
+    }
+    Ok(())
+}
+
+// Split input lines into display rows, wrapping long lines unless truncating
+fn rows_for(lines: &[String], width: u8, truncate: bool) -> Vec<String> {
+    let mut rows = Vec::new();
+    for line in lines {
+        let mut remaining = line.as_str();
+        loop {
+            let (mut row, mut rest) = text::split_at_width(remaining, width as usize);
+            // A row too narrow for the next character still takes it, so wrapping ends
+            if row.is_empty() && !rest.is_empty() {
+                let next = rest.chars().next().map_or(0, char::len_utf8);
+                (row, rest) = rest.split_at(next);
+            }
+            rows.push(row.to_string());
+            remaining = rest;
+            if truncate || remaining.is_empty() {
+                break;
+            }
+        }
+    }
+    rows
+}
+
+fn run_script(
+    mut vfd: Vfd,
+    file: &std::path::Path,
+    looped: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    #[cfg(feature = "rhai")]
+    if file.extension().is_some_and(|ext| ext == "rhai") {
+        use std::sync::{Arc, Mutex};
+        use vfd_dsp_v9fb_over_serial::scripting::ScriptEngine;
+
+        let engine = ScriptEngine::new(Arc::new(Mutex::new(vfd)));
+        loop {
+            engine.run_file(file)?;
+            if !looped {
+                return Ok(());
+            }
+        }
+    }
+
+    let script = Script::load(file)?;
+    loop {
+        script.run(&mut vfd)?;
+        if !looped {
+            return Ok(());
+        }
+    }
+}
+
+fn write_stdin(vfd: &mut Vfd, truncate: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let lines = io::stdin().lines().collect::<Result<Vec<_>, _>>()?;
+    let mut screen = Screen::for_display(vfd);
+    for (y, row) in rows_for(&lines, vfd.size().0, truncate)
+        .iter()
+        .take(screen.size().1 as usize)
+        .enumerate()
+    {
+        screen.line(y as u8, row, Align::Left);
+    }
+    screen.present(vfd)?;
+    Ok(())
+}
+
+fn follow_stdin(vfd: &mut Vfd, truncate: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let mut log = LogView::new();
+    vfd.clear()?;
+    for line in io::stdin().lines() {
+        for row in rows_for(&[line?], vfd.size().0, truncate) {
+            log.push_line(vfd, &row)?;
+        }
+    }
+    Ok(())
+}
+
+fn watch(
+    vfd: &mut Vfd,
+    command: &str,
+    interval: Duration,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut screen = Screen::for_display(vfd);
+    vfd.clear()?;
+    loop {
+        let started = Instant::now();
+        let output = process::Command::new("sh")
+            .arg("-c")
+            .arg(command)
+            .stderr(process::Stdio::inherit())
+            .output()?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let (_, height) = screen.size();
+        let mut lines = stdout.lines();
+        for y in 0..height {
+            let line = lines.next().unwrap_or("").replace('\t', " ");
+            screen.line(y, &line, Align::Left);
+        }
+        screen.present(vfd)?;
+        sleep(interval.saturating_sub(started.elapsed()));
+    }
+}
+
+fn demo(vfd: &mut Vfd, looped: bool) -> Result<(), Box<dyn std::error::Error>> {
+    println!("Device connected. Running demo, press Ctrl-C to stop...");
+    loop {
+        demo_greeting(vfd)?;
+        demo_marquee(vfd)?;
+        demo_clock(vfd)?;
+        demo_blink(vfd)?;
+        demo_brightness(vfd)?;
+        if !looped {
+            break;
+        }
+    }
+    vfd.clear()?;
+    vfd.write_line(0, "Demo finished", Align::Center)?;
+    Ok(())
+}
+
+fn demo_greeting(vfd: &mut Vfd) -> Result<(), Box<dyn std::error::Error>> {
+    println!("Greeting");
+    let step = Duration::from_millis(600);
+    Animation::new()
+        .frame(&["Epale!"], step)
+        .frame(&["Epale!", "    :)"], step)
+        .frame(&["Epale!", "    :) yuju!"], Duration::from_secs(2))
+        .frame(
+            &["Rust speaking serial", "   to a *VFD* :)"],
+            Duration::from_secs(2),
+        )
+        .play(vfd)?;
+    Ok(())
+}
+
+fn demo_marquee(vfd: &mut Vfd) -> Result<(), Box<dyn std::error::Error>> {
+    println!("Marquee");
+    vfd.clear()?;
+    vfd.write_line(0, "Marquee", Align::Center)?;
+    let (_, height) = vfd.size();
+    let mut marquee = vfd.marquee(
+        "Scrolling text on the bottom row, one cell at a time",
+        height - 1,
+        Duration::from_millis(150),
+        ScrollDirection::Left,
+    );
+    let started = Instant::now();
+    while started.elapsed() < Duration::from_secs(8) {
+        marquee.tick(vfd)?;
+        sleep(Duration::from_millis(20));
+    }
+    Ok(())
+}
+
+fn demo_clock(vfd: &mut Vfd) -> Result<(), Box<dyn std::error::Error>> {
+    println!("Clock");
+    vfd.clear()?;
+    vfd.write_line(0, "Clock (UTC)", Align::Center)?;
+    let (_, height) = vfd.size();
+    for _ in 0..5 {
+        let secs = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+        let time = format!(
+            "{:02}:{:02}:{:02}",
+            secs / 3600 % 24,
+            secs / 60 % 60,
+            secs % 60
+        );
+        vfd.write_line(height - 1, &time, Align::Center)?;
+        sleep(Duration::from_secs(1));
+    }
+    Ok(())
+}
+
+fn demo_blink(vfd: &mut Vfd) -> Result<(), Box<dyn std::error::Error>> {
+    println!("Blink");
+    vfd.clear()?;
+    vfd.write_line(0, "Blink", Align::Center)?;
+    match vfd.blink(Duration::from_millis(400)) {
+        Ok(()) => {
+            sleep(Duration::from_secs(3));
+            vfd.blink(Duration::ZERO)?;
+        }
+        Err(VfdError::NotSupported(what)) => println!("  skipped: {} is not supported", what),
+        Err(e) => return Err(e.into()),
+    }
+    Ok(())
+}
+
+fn demo_brightness(vfd: &mut Vfd) -> Result<(), Box<dyn std::error::Error>> {
+    println!("Brightness sweep");
+    vfd.clear()?;
+    let original = vfd.brightness();
+    let levels = [
+        BrightnessLevel::Percent20,
+        BrightnessLevel::Percent40,
+        BrightnessLevel::Percent60,
+        BrightnessLevel::Percent100,
+    ];
+    for level in levels.iter().chain(levels.iter().rev()) {
+        vfd.set_brightness(*level)?;
+        vfd.write_line(
+            0,
+            &format!("Brightness {}%", level.percent()),
+            Align::Center,
+        )?;
+        sleep(Duration::from_millis(700));
+    }
+    vfd.set_brightness(original)?;
+    Ok(())
+}
@@ -0,0 +1,437 @@
+use clap::Parser;
+use std::path::PathBuf;
+use vfd_dsp_v9fb_over_serial::{CommandSet, Parity, SerialProfile, config};
+
+/// Own the display's serial port and accept commands from other processes.
+#[derive(Parser)]
+#[command(name = "vfdd", version)]
+struct Cli {
+    /// Config file to use instead of searching the XDG config directories
+    #[arg(short, long)]
+    config: Option<PathBuf>,
+
+    /// Serial device the display is attached to, e.g. /dev/ttyUSB0, COM3, or rfc2217://host:port or tcp://host:port on a serial device server [default: /dev/ttyUSB0, COM3 on Windows]
+    #[arg(short, long)]
+    device: Option<String>,
+
+    /// Display width in characters [default: 20]
+    #[arg(long)]
+    width: Option<u8>,
+
+    /// Display height in rows [default: 2]
+    #[arg(long)]
+    height: Option<u8>,
+
+    /// Serial baud rate [default: the command set's factory setting, 9600 or 19200]
+    #[arg(long)]
+    baud: Option<u32>,
+
+    /// Serial parity (none, even, odd) [default: none]
+    #[arg(long, value_parser = SerialProfile::parse_parity)]
+    parity: Option<Parity>,
+
+    /// Try common baud rates and parities until the display answers an identify request
+    #[arg(long)]
+    auto_baud: bool,
+
+    /// Command set spoken by the display (birch, epson, noritake, futaba), or a protocol definition file (.toml) [default: birch]
+    #[arg(long, value_parser = config::parse_command_set)]
+    command_set: Option<CommandSet>,
+
+    /// Leave the display alone and print every screen the daemon would draw on it
+    #[arg(long, conflicts_with = "auto_baud")]
+    dry_run: bool,
+
+    /// Unix socket to listen on, unless systemd passes one in
+    #[arg(short, long, default_value = "/run/vfdd.sock")]
+    socket: PathBuf,
+
+    /// Message shown when the daemon is stopped [default: blank the display]
+    #[arg(long)]
+    offline_message: Option<String>,
+
+    /// Also show anything written to a named pipe, created if needed
+    #[arg(long, num_args = 0..=1, default_missing_value = "/run/vfd.fifo")]
+    fifo: Option<PathBuf>,
+
+    /// Also accept POST /display requests and serve Prometheus metrics at GET /metrics on this address (e.g. 0.0.0.0:8080)
+    #[cfg(feature = "http")]
+    #[arg(long)]
+    http: Option<String>,
+
+    /// Subscribe to display topics on this MQTT broker (host or host:port)
+    #[cfg(feature = "mqtt")]
+    #[arg(long)]
+    mqtt: Option<String>,
+
+    /// Prefix for the MQTT topics (<prefix>/line1, <prefix>/line2, <prefix>/command)
+    #[cfg(feature = "mqtt")]
+    #[arg(long, default_value = "vfd")]
+    mqtt_topic: String,
+
+    /// Show what this MPD server is playing (host or host:port)
+    #[cfg(feature = "mpd")]
+    #[arg(long)]
+    mpd: Option<String>,
+
+    /// Read buttons from this evdev device (e.g. /dev/input/event0) to step through the [[screens]] of the config file and dismiss alerts
+    #[cfg(all(feature = "input", target_os = "linux"))]
+    #[arg(long)]
+    buttons: Option<PathBuf>,
+
+    /// Key codes of the buttons and what they do
+    #[cfg(all(feature = "input", target_os = "linux"))]
+    #[arg(long, default_value = "106=next,105=previous,28=ack", value_parser = vfd_dsp_v9fb_over_serial::input::parse_bindings)]
+    button_keys: Bindings,
+
+    /// Read buttons from sysfs GPIO pins instead, e.g. 17=next,27=previous,22=ack
+    #[cfg(all(feature = "input", target_os = "linux"))]
+    #[arg(long, value_parser = vfd_dsp_v9fb_over_serial::input::parse_bindings, conflicts_with = "buttons")]
+    gpio_buttons: Option<Bindings>,
+
+    /// GPIO buttons read high when pressed, rather than low
+    #[cfg(all(feature = "input", target_os = "linux"))]
+    #[arg(long)]
+    gpio_active_high: bool,
+
+    /// Show entity states from this Home Assistant server (host or host:port), with the access token in $HOME_ASSISTANT_TOKEN
+    #[cfg(feature = "home-assistant")]
+    #[arg(long)]
+    home_assistant: Option<String>,
+
+    /// Template from the config file that shows the Home Assistant entities, named in its placeholders
+    #[cfg(feature = "home-assistant")]
+    #[arg(long, default_value = "home-assistant")]
+    home_assistant_template: String,
+
+    /// Show the current weather at this latitude,longitude in turns with other content
+    #[cfg(feature = "weather")]
+    #[arg(long, allow_hyphen_values = true)]
+    weather: Option<String>,
+
+    /// Where the weather comes from (open-meteo, openweathermap), with the OpenWeatherMap API key in $OPENWEATHERMAP_API_KEY
+    #[cfg(feature = "weather")]
+    #[arg(long, default_value = "open-meteo")]
+    weather_provider: vfd_dsp_v9fb_over_serial::weather::WeatherProvider,
+
+    /// Place name shown under the weather
+    #[cfg(feature = "weather")]
+    #[arg(long)]
+    weather_location: Option<String>,
+
+    /// Temperature units for the weather (metric, imperial)
+    #[cfg(feature = "weather")]
+    #[arg(long, default_value = "metric")]
+    weather_units: vfd_dsp_v9fb_over_serial::weather::Units,
+
+    /// Seconds from one turn of the weather on the display to the next
+    #[cfg(feature = "weather")]
+    #[arg(long, default_value_t = 60)]
+    weather_every: u64,
+
+    /// Seconds the weather stays up each turn
+    #[cfg(feature = "weather")]
+    #[arg(long, default_value_t = 10)]
+    weather_for: u64,
+
+    /// Seconds between fetches of the weather
+    #[cfg(feature = "weather")]
+    #[arg(long, default_value_t = 600)]
+    weather_refresh: u64,
+}
+
+// Key codes or pins and what pressing them does; not a Vec, so that clap takes the list as one value
+#[cfg(all(feature = "input", target_os = "linux"))]
+type Bindings = Vec<(u32, vfd_dsp_v9fb_over_serial::input::ButtonAction)>;
+
+// Read buttons on their own thread, logging why they stopped
+#[cfg(all(unix, feature = "input", target_os = "linux"))]
+fn spawn_buttons<B>(
+    vfd: std::sync::Arc<std::sync::Mutex<vfd_dsp_v9fb_over_serial::Vfd>>,
+    buttons: B,
+    screens: vfd_dsp_v9fb_over_serial::ScreenCycle,
+) where
+    B: vfd_dsp_v9fb_over_serial::input::Buttons + Send + 'static,
+{
+    std::thread::spawn(move || {
+        if let Err(e) = vfd_dsp_v9fb_over_serial::input::serve_buttons(vfd, buttons, screens) {
+            eprintln!("Warning: Button reader stopped: {}", e);
+        }
+    });
+}
+
+// Print the screen of a dry run each time it changes, for as long as the daemon runs
+#[cfg(unix)]
+fn print_screens(simulator: vfd_dsp_v9fb_over_serial::Simulator) {
+    std::thread::spawn(move || {
+        let mut printed = None;
+        loop {
+            let rows = simulator.rows();
+            if printed.as_ref() != Some(&rows) {
+                print!("{}", simulator.frame().ascii_art());
+                printed = Some(rows);
+            }
+            std::thread::sleep(std::time::Duration::from_millis(50));
+        }
+    });
+}
+
+// Logs the display going away and coming back, as writes then fail for every client
+#[cfg(unix)]
+struct LogConnection;
+
+#[cfg(unix)]
+impl vfd_dsp_v9fb_over_serial::VfdObserver for LogConnection {
+    fn on_disconnected(&self, error: &vfd_dsp_v9fb_over_serial::VfdError) {
+        eprintln!("Warning: Display disconnected: {}", error);
+    }
+
+    fn on_reconnected(&self) {
+        eprintln!("Display reconnected");
+    }
+}
+
+#[cfg(unix)]
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    use std::sync::{Arc, Mutex, PoisonError};
+    use vfd_dsp_v9fb_over_serial::config::Config;
+    use vfd_dsp_v9fb_over_serial::{Simulator, daemon};
+
+    let cli = Cli::parse();
+
+    let config = Config::discover(cli.config.as_deref())?.merge(Config {
+        device: cli.device.clone(),
+        width: cli.width,
+        height: cli.height,
+        baud_rate: cli.baud,
+        parity: cli.parity,
+        auto_baud: cli.auto_baud.then_some(true),
+        command_set: cli.command_set,
+        offline_message: cli.offline_message.clone(),
+        ..Config::default()
+    });
+
+    let builder = config.builder().observer(LogConnection);
+    // Stands in for the display on a dry run
+    let simulator = cli.dry_run.then(|| {
+        Simulator::new(
+            config.command_set.clone().unwrap_or_default(),
+            config.width.unwrap_or(config::DEFAULT_WIDTH),
+            config.height.unwrap_or(config::DEFAULT_HEIGHT),
+        )
+    });
+    let mut vfd = if let Some(simulator) = &simulator {
+        builder.open_duplex(simulator.clone())?
+    } else if config.auto_baud == Some(true) {
+        let (vfd, profile) = builder.probe()?;
+        println!("Display answered at {}", profile);
+        vfd
+    } else {
+        builder.open()?
+    };
+    if let Some(message) = &config.startup_message {
+        vfd.write_text(message)?;
+    }
+    let vfd = Arc::new(Mutex::new(vfd));
+
+    #[cfg(all(feature = "systemd", target_os = "linux"))]
+    let activated = vfd_dsp_v9fb_over_serial::systemd::listener()?;
+    #[cfg(not(all(feature = "systemd", target_os = "linux")))]
+    let activated = None;
+
+    // Before any other thread starts, so that they all leave the signals to it
+    {
+        let vfd = Arc::clone(&vfd);
+        let offline_message = config.offline_message.clone();
+        let socket = activated.is_none().then(|| cli.socket.clone());
+        let simulator = simulator.clone();
+        daemon::on_termination(move || {
+            #[cfg(all(feature = "systemd", target_os = "linux"))]
+            let _ = vfd_dsp_v9fb_over_serial::systemd::notify("STOPPING=1");
+            let mut vfd = vfd.lock().unwrap_or_else(PoisonError::into_inner);
+            let result = vfd.clear().and_then(|()| match &offline_message {
+                Some(message) => vfd.write_text(message),
+                None => Ok(()),
+            });
+            if let Err(e) = result.and_then(|()| vfd.flush()) {
+                eprintln!("Warning: Could not update the display on exit: {}", e);
+            }
+            if let Some(simulator) = &simulator {
+                print!("{}", simulator.frame().ascii_art());
+            }
+            if let Some(path) = socket {
+                let _ = std::fs::remove_file(path);
+            }
+        })?;
+    }
+
+    if let Some(simulator) = simulator {
+        print_screens(simulator);
+    }
+
+    #[cfg(feature = "http")]
+    if let Some(addr) = cli.http.clone() {
+        let vfd = Arc::clone(&vfd);
+        println!("Serving HTTP on {}", addr);
+        std::thread::spawn(move || {
+            if let Err(e) = vfd_dsp_v9fb_over_serial::http::serve_http(vfd, &addr) {
+                eprintln!("Warning: HTTP server stopped: {}", e);
+            }
+        });
+    }
+
+    #[cfg(feature = "mqtt")]
+    if let Some(broker) = cli.mqtt.clone() {
+        use vfd_dsp_v9fb_over_serial::mqtt::{self, MqttConfig};
+
+        let (host, port) = match broker.rsplit_once(':') {
+            Some((host, port)) => (host.to_string(), port.parse()?),
+            None => (broker, 1883),
+        };
+        let config = MqttConfig {
+            host,
+            port,
+            topic_prefix: cli.mqtt_topic.clone(),
+            ..MqttConfig::default()
+        };
+        let vfd = Arc::clone(&vfd);
+        println!(
+            "Subscribing to {}/# on {}:{}",
+            config.topic_prefix, config.host, config.port
+        );
+        std::thread::spawn(move || mqtt::serve_mqtt(vfd, &config));
+    }
+
+    #[cfg(feature = "mpd")]
+    if let Some(server) = cli.mpd.clone() {
+        use vfd_dsp_v9fb_over_serial::mpd::{self, MpdConfig};
+
+        let (host, port) = match server.rsplit_once(':') {
+            Some((host, port)) => (host.to_string(), port.parse()?),
+            None => (server, 6600),
+        };
+        let config = MpdConfig {
+            host,
+            port,
+            ..MpdConfig::default()
+        };
+        let vfd = Arc::clone(&vfd);
+        println!("Following MPD on {}:{}", config.host, config.port);
+        std::thread::spawn(move || mpd::serve_mpd(vfd, &config));
+    }
+
+    #[cfg(all(feature = "input", target_os = "linux"))]
+    {
+        use vfd_dsp_v9fb_over_serial::config::ScreenConfig;
+        use vfd_dsp_v9fb_over_serial::input::{EvdevButtons, GpioButtons};
+
+        let screens = ScreenConfig::cycle(config.screens.as_deref().unwrap_or_default());
+        if let Some(path) = cli.buttons.clone() {
+            let buttons = EvdevButtons::open(&path, &cli.button_keys)?;
+            println!("Reading buttons from {}", path.display());
+            spawn_buttons(Arc::clone(&vfd), buttons, screens);
+        } else if let Some(pins) = &cli.gpio_buttons {
+            let buttons = GpioButtons::open(pins)?.active_high(cli.gpio_active_high);
+            println!("Reading buttons from GPIO");
+            spawn_buttons(Arc::clone(&vfd), buttons, screens);
+        }
+    }
+
+    #[cfg(feature = "home-assistant")]
+    if let Some(server) = cli.home_assistant.clone() {
+        use vfd_dsp_v9fb_over_serial::home_assistant::{self, HomeAssistantConfig};
+
+        let (host, port) = match server.rsplit_once(':') {
+            Some((host, port)) => (host.to_string(), port.parse()?),
+            None => (server, 8123),
+        };
+        let template = config
+            .templates
+            .as_ref()
+            .and_then(|templates| templates.get(&cli.home_assistant_template))
+            .ok_or_else(|| {
+                format!(
+                    "No template '{}' in the config file",
+                    cli.home_assistant_template
+                )
+            })?;
+        let config = HomeAssistantConfig {
+            host,
+            port,
+            token: std::env::var("HOME_ASSISTANT_TOKEN")
+                .map_err(|_| "HOME_ASSISTANT_TOKEN must hold a Home Assistant access token")?,
+            template: template.clone(),
+            ..HomeAssistantConfig::default()
+        };
+        let vfd = Arc::clone(&vfd);
+        println!(
+            "Following Home Assistant on {}:{}",
+            config.host, config.port
+        );
+        std::thread::spawn(move || home_assistant::serve_home_assistant(vfd, &config));
+    }
+
+    #[cfg(feature = "weather")]
+    if let Some(coordinates) = cli.weather.clone() {
+        use std::time::Duration;
+        use vfd_dsp_v9fb_over_serial::weather::{self, WeatherConfig, WeatherProvider};
+
+        let (latitude, longitude) = coordinates
+            .split_once(',')
+            .ok_or("--weather takes latitude,longitude")?;
+        // Taken from the environment so that it does not show in the process list
+        let api_key = std::env::var("OPENWEATHERMAP_API_KEY").ok();
+        if cli.weather_provider == WeatherProvider::OpenWeatherMap && api_key.is_none() {
+            return Err("OPENWEATHERMAP_API_KEY must hold an OpenWeatherMap API key".into());
+        }
+        let config = WeatherConfig {
+            provider: cli.weather_provider,
+            api_key,
+            latitude: latitude.trim().parse()?,
+            longitude: longitude.trim().parse()?,
+            location: cli.weather_location.clone(),
+            units: cli.weather_units,
+            refresh_interval: Duration::from_secs(cli.weather_refresh),
+            show_every: Duration::from_secs(cli.weather_every),
+            show_for: Duration::from_secs(cli.weather_for),
+        };
+        let vfd = Arc::clone(&vfd);
+        println!(
+            "Showing the weather at {},{} from {}",
+            config.latitude, config.longitude, config.provider
+        );
+        std::thread::spawn(move || weather::serve_weather(vfd, &config));
+    }
+
+    if let Some(path) = cli.fifo.clone() {
+        let vfd = Arc::clone(&vfd);
+        println!("Reading {}", path.display());
+        std::thread::spawn(move || {
+            if let Err(e) = daemon::serve_fifo(vfd, &path) {
+                eprintln!("Warning: FIFO reader stopped: {}", e);
+            }
+        });
+    }
+
+    let listener = match activated {
+        Some(listener) => {
+            println!("Listening on the socket passed in by systemd");
+            listener
+        }
+        None => {
+            println!("Listening on {}", cli.socket.display());
+            daemon::bind_unix(&cli.socket)?
+        }
+    };
+    #[cfg(all(feature = "systemd", target_os = "linux"))]
+    vfd_dsp_v9fb_over_serial::systemd::notify("READY=1")?;
+    daemon::serve_listener(vfd, listener)?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn main() {
+    Cli::parse();
+    eprintln!("vfdd requires Unix domain sockets and is not available on this platform.");
+    std::process::exit(1);
+}
@@ -0,0 +1,590 @@
+#[cfg(feature = "tokio")]
+use crate::async_vfd::AsyncVfd;
+use crate::capabilities::Capabilities;
+use crate::codepage::CodePage;
+use crate::command_set::{BrightnessLevel, CommandSet, CursorStyle, DisplayMode, Peripheral};
+use crate::currency::Currency;
+use crate::error::VfdError;
+use crate::geometry::Geometry;
+use crate::number_format::{Locale, NumberFormat};
+use crate::observer::{SharedObserver, VfdObserver};
+use crate::on_drop::OnDrop;
+use crate::serial_profile::SerialProfile;
+use crate::splash::Splash;
+use crate::symbols::SymbolMap;
+use crate::tap::{Tap, TapSink};
+use crate::tcp_serial::{BridgeMode, TcpSerial};
+use crate::template::Templates;
+use crate::text::WrapPolicy;
+use crate::throttle::Throttle;
+use crate::transport::{Duplex, Transport, WriteOnly};
+use crate::vfd::Vfd;
+use serialport::{DataBits, FlowControl, Parity, SerialPort, SerialPortBuilder, StopBits};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+// How long a probed display gets to answer at each setting
+const PROBE_TIMEOUT: Duration = Duration::from_millis(300);
+
+// The port to send through, behind a tap if one is configured, and a handle
+// for its control lines when it is a local serial port
+pub(crate) type PortHalves = (Box<dyn Duplex>, Option<Box<dyn SerialPort>>);
+
+/// Serial and display settings used to open a [`Vfd`].
+///
+/// Defaults match the Birch DSP-V9FB factory settings: 9600 baud, 8N1,
+/// no flow control and a one second timeout. Other models can start from
+/// [`SerialProfile::for_command_set`] via [`VfdBuilder::profile`].
+#[derive(Debug, Clone)]
+pub struct VfdBuilder {
+    device_path: String,
+    width: u8,
+    height: u8,
+    command_set: CommandSet,
+    capabilities: Option<Capabilities>,
+    baud_rate: u32,
+    data_bits: DataBits,
+    parity: Parity,
+    stop_bits: StopBits,
+    flow_control: FlowControl,
+    dtr: Option<bool>,
+    rts: Option<bool>,
+    timeout: Duration,
+    brightness: Option<BrightnessLevel>,
+    code_page: Option<CodePage>,
+    cursor_style: Option<CursorStyle>,
+    display_mode: Option<DisplayMode>,
+    currency: Currency,
+    number_format: NumberFormat,
+    templates: Templates,
+    tap: Option<TapSink>,
+    auto_initialize: bool,
+    pass_through: bool,
+    transliterate: bool,
+    symbols: SymbolMap,
+    bidi: bool,
+    wrap_policy: WrapPolicy,
+    throttle: Throttle,
+    ellipsis: Option<char>,
+    reconnect_attempts: u32,
+    reconnect_delay: Duration,
+    on_drop: OnDrop,
+    observer: Option<SharedObserver>,
+    splash: Option<Splash>,
+    skip_splash: bool,
+}
+
+impl VfdBuilder {
+    /// Settings for the display on `device_path`: a serial device such as
+    /// `/dev/ttyUSB0` or `COM3`, or a port on a serial device server given as
+    /// `tcp://host:port` (raw) or `rfc2217://host:port` (see [`TcpSerial`]).
+    pub fn new(device_path: &str, width: u8, height: u8) -> Self {
+        VfdBuilder {
+            device_path: device_path.to_string(),
+            width,
+            height,
+            command_set: CommandSet::default(),
+            capabilities: None,
+            baud_rate: 9600,
+            data_bits: DataBits::Eight,
+            parity: Parity::None,
+            stop_bits: StopBits::One,
+            flow_control: FlowControl::None,
+            dtr: Some(true),
+            rts: Some(true),
+            timeout: Duration::from_millis(1000),
+            brightness: None,
+            code_page: None,
+            cursor_style: None,
+            display_mode: None,
+            currency: Currency::default(),
+            number_format: NumberFormat::default(),
+            templates: Templates::default(),
+            tap: None,
+            auto_initialize: true,
+            pass_through: false,
+            transliterate: true,
+            symbols: SymbolMap::default(),
+            bidi: false,
+            wrap_policy: WrapPolicy::default(),
+            throttle: Throttle::default(),
+            ellipsis: None,
+            reconnect_attempts: 0,
+            reconnect_delay: Duration::from_millis(500),
+            on_drop: OnDrop::default(),
+            observer: None,
+            splash: None,
+            skip_splash: false,
+        }
+    }
+
+    /// Display size, replacing the width and height given to [`VfdBuilder::new`].
+    pub fn geometry(mut self, geometry: Geometry) -> Self {
+        (self.width, self.height) = geometry.into();
+        self
+    }
+
+    pub fn command_set(mut self, command_set: CommandSet) -> Self {
+        self.command_set = command_set;
+        self
+    }
+
+    /// What the model can do, when it lacks some of its command set; see
+    /// [`Vfd::set_capabilities`]. Also sets the display size to theirs.
+    pub fn capabilities(mut self, capabilities: Capabilities) -> Self {
+        (self.width, self.height) = capabilities.geometry.into();
+        self.capabilities = Some(capabilities);
+        self
+    }
+
+    pub fn baud_rate(mut self, baud_rate: u32) -> Self {
+        self.baud_rate = baud_rate;
+        self
+    }
+
+    pub fn data_bits(mut self, data_bits: DataBits) -> Self {
+        self.data_bits = data_bits;
+        self
+    }
+
+    pub fn parity(mut self, parity: Parity) -> Self {
+        self.parity = parity;
+        self
+    }
+
+    pub fn stop_bits(mut self, stop_bits: StopBits) -> Self {
+        self.stop_bits = stop_bits;
+        self
+    }
+
+    /// [`FlowControl::Hardware`] for displays wired to hold writes back with CTS.
+    pub fn flow_control(mut self, flow_control: FlowControl) -> Self {
+        self.flow_control = flow_control;
+        self
+    }
+
+    /// Drive DTR to `level` once the port is open. Asserted by default, as
+    /// some displays stay blank until the host raises it.
+    pub fn dtr(mut self, level: bool) -> Self {
+        self.dtr = Some(level);
+        self
+    }
+
+    /// Drive RTS to `level` once the port is open. Asserted by default;
+    /// ignored with hardware flow control.
+    pub fn rts(mut self, level: bool) -> Self {
+        self.rts = Some(level);
+        self
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Use all the serial line settings of `profile`, e.g.
+    /// `SerialProfile::for_command_set(CommandSet::NoritakeCu)`.
+    pub fn profile(mut self, profile: SerialProfile) -> Self {
+        self.baud_rate = profile.baud_rate;
+        self.data_bits = profile.data_bits;
+        self.parity = profile.parity;
+        self.stop_bits = profile.stop_bits;
+        self.flow_control = profile.flow_control;
+        self.dtr = profile.dtr;
+        self.rts = profile.rts;
+        self
+    }
+
+    /// The serial line settings currently configured.
+    pub fn serial_profile(&self) -> SerialProfile {
+        SerialProfile {
+            baud_rate: self.baud_rate,
+            data_bits: self.data_bits,
+            parity: self.parity,
+            stop_bits: self.stop_bits,
+            flow_control: self.flow_control,
+            dtr: self.dtr,
+            rts: self.rts,
+        }
+    }
+
+    /// Brightness applied right after the port is opened.
+    pub fn brightness(mut self, level: BrightnessLevel) -> Self {
+        self.brightness = Some(level);
+        self
+    }
+
+    /// Code page selected right after the port is opened.
+    pub fn code_page(mut self, page: CodePage) -> Self {
+        self.code_page = Some(page);
+        self
+    }
+
+    /// Cursor style selected right after the port is opened, e.g. [`CursorStyle::Hidden`].
+    pub fn cursor_style(mut self, style: CursorStyle) -> Self {
+        self.cursor_style = Some(style);
+        self
+    }
+
+    /// Display mode selected right after the port is opened.
+    pub fn display_mode(mut self, mode: DisplayMode) -> Self {
+        self.display_mode = Some(mode);
+        self
+    }
+
+    /// Currency prices are formatted in (default US dollars).
+    pub fn currency(mut self, currency: Currency) -> Self {
+        self.currency = currency;
+        self
+    }
+
+    /// How plain numbers are written (default two decimals, no grouping), see [`Vfd::write_item_value`].
+    pub fn number_format(mut self, format: NumberFormat) -> Self {
+        self.number_format = format;
+        self
+    }
+
+    /// Write numbers and prices the way `locale` does, with its currency.
+    pub fn locale(self, locale: Locale) -> Self {
+        self.currency(locale.currency())
+            .number_format(locale.number_format())
+    }
+
+    /// Append a timestamped hex dump of every byte sent to the display to `path`.
+    pub fn tap(mut self, path: impl AsRef<Path>) -> Self {
+        self.tap = Some(TapSink::File(path.as_ref().to_path_buf()));
+        self
+    }
+
+    /// Hand every chunk of bytes sent to the display to `callback`, with the time it was sent.
+    pub fn tap_with<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(SystemTime, &[u8]) + Send + Sync + 'static,
+    {
+        self.tap = Some(TapSink::Callback(Arc::new(callback)));
+        self
+    }
+
+    /// Named screen layouts for [`Vfd::render_template`].
+    pub fn templates(mut self, templates: Templates) -> Self {
+        self.templates = templates;
+        self
+    }
+
+    /// Whether to send the initialization command on open (default `true`).
+    pub fn auto_initialize(mut self, auto_initialize: bool) -> Self {
+        self.auto_initialize = auto_initialize;
+        self
+    }
+
+    /// Whether the display sits on a pass-through chain in front of a printer (default `false`).
+    ///
+    /// When set, the display is selected on open and after reconnecting, before
+    /// anything else is sent.
+    pub fn pass_through(mut self, pass_through: bool) -> Self {
+        self.pass_through = pass_through;
+        self
+    }
+
+    /// Whether to replace characters missing from the code page with ASCII
+    /// look-alikes (default `true`), rather than fail with [`VfdError::Encoding`].
+    pub fn transliterate(mut self, transliterate: bool) -> Self {
+        self.transliterate = transliterate;
+        self
+    }
+
+    /// Replacements for symbols missing from the code page (default [`SymbolMap::default`]).
+    pub fn symbols(mut self, symbols: SymbolMap) -> Self {
+        self.symbols = symbols;
+        self
+    }
+
+    /// Whether to show Hebrew and Arabic text right to left (default `false`), see [`Vfd::set_bidi`].
+    pub fn bidi(mut self, bidi: bool) -> Self {
+        self.bidi = bidi;
+        self
+    }
+
+    /// How text running past the end of a row is broken (default by character).
+    pub fn wrap_policy(mut self, policy: WrapPolicy) -> Self {
+        self.wrap_policy = policy;
+        self
+    }
+
+    /// Pace writes for clone displays that drop bytes at full line speed.
+    pub fn throttle(mut self, throttle: Throttle) -> Self {
+        self.throttle = throttle;
+        self
+    }
+
+    /// Character marking text cut by [`Overflow::Ellipsis`](crate::Overflow::Ellipsis)
+    /// (default `…` where the code page has it, `>` otherwise).
+    pub fn ellipsis(mut self, indicator: char) -> Self {
+        self.ellipsis = Some(indicator);
+        self
+    }
+
+    /// Reopen the port up to `attempts` times when a write fails because the device went away (default 0, off).
+    ///
+    /// After reconnecting the display is re-initialized, its previous text
+    /// is redrawn and the failed write is retried.
+    pub fn auto_reconnect(mut self, attempts: u32) -> Self {
+        self.reconnect_attempts = attempts;
+        self
+    }
+
+    /// Pause before each reconnect attempt (default 500 ms).
+    pub fn reconnect_delay(mut self, delay: Duration) -> Self {
+        self.reconnect_delay = delay;
+        self
+    }
+
+    /// What to leave on the display when the opened [`Vfd`] is dropped, e.g.
+    /// [`OnDrop::Clear`] so stale totals don't stay up after the program exits.
+    pub fn on_drop(mut self, on_drop: OnDrop) -> Self {
+        self.on_drop = on_drop;
+        self
+    }
+
+    /// Report when the display is opened, fails a write, goes away or is reconnected.
+    pub fn observer<O: VfdObserver + 'static>(mut self, observer: O) -> Self {
+        self.observer = Some(SharedObserver(Arc::new(observer)));
+        self
+    }
+
+    /// Screen shown once the display is initialized on open, after the
+    /// brightness, code page, cursor style and display mode are applied.
+    ///
+    /// Opening waits for the splash to finish. Nothing is shown when
+    /// [`VfdBuilder::auto_initialize`] is off, or for displays opened asynchronously.
+    pub fn splash(mut self, splash: Splash) -> Self {
+        self.splash = Some(splash);
+        self
+    }
+
+    /// Leave out the configured splash, e.g. for scripts that open the display repeatedly.
+    pub fn skip_splash(mut self, skip: bool) -> Self {
+        self.skip_splash = skip;
+        self
+    }
+
+    fn port_builder(&self) -> SerialPortBuilder {
+        serialport::new(&self.device_path, self.baud_rate)
+            .data_bits(self.data_bits)
+            .flow_control(self.flow_control)
+            .parity(self.parity)
+            .stop_bits(self.stop_bits)
+            .timeout(self.timeout)
+    }
+
+    // Drive the modem control lines the way the settings ask
+    fn set_control_lines(&self, port: &mut dyn SerialPort) -> Result<(), VfdError> {
+        if let Some(level) = self.dtr {
+            port.write_data_terminal_ready(level)?;
+        }
+        if let Some(level) = self.rts
+            && self.flow_control != FlowControl::Hardware
+        {
+            port.write_request_to_send(level)?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn open_port(&self) -> Result<PortHalves, VfdError> {
+        if let Some((mode, address)) = BridgeMode::from_device(&self.device_path) {
+            let bridge = TcpSerial::connect(address, mode, &self.serial_profile(), self.timeout)?;
+            return Ok((self.tapped(bridge)?, None));
+        }
+        let mut port = self.port_builder().open()?;
+        self.set_control_lines(port.as_mut())?;
+        let control = port.try_clone()?;
+        Ok((self.tapped(port)?, Some(control)))
+    }
+
+    // Put the configured tap, if any, in front of `port`
+    fn tapped<T: Duplex + 'static>(&self, port: T) -> Result<Box<dyn Duplex>, VfdError> {
+        Ok(match &self.tap {
+            Some(sink) => Box::new(Tap::new(port, sink)?),
+            None => Box::new(port),
+        })
+    }
+
+    pub(crate) fn auto_initializes(&self) -> bool {
+        self.auto_initialize
+    }
+
+    pub(crate) fn passes_through(&self) -> bool {
+        self.pass_through
+    }
+
+    pub(crate) fn reconnect_policy(&self) -> (u32, Duration) {
+        (self.reconnect_attempts, self.reconnect_delay)
+    }
+
+    /// Open the serial port and return the configured display.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "open", skip_all, fields(device = %self.device_path))
+    )]
+    pub fn open(self) -> Result<Vfd, VfdError> {
+        let (port, control) = self.open_port()?;
+        let mut vfd = Vfd::from_port(port, self.command_set.clone(), self.width, self.height);
+        vfd.set_control_port(control);
+        vfd.set_settings(self.clone());
+        self.set_up(vfd)
+    }
+
+    /// Set up the display on `transport` instead of the device, e.g. a
+    /// [`NullTransport`](crate::NullTransport) to try out what would be sent.
+    ///
+    /// The serial settings are not used and the display cannot be
+    /// reconnected; everything else applies as for [`VfdBuilder::open`].
+    /// Requests answered by the display fail as for [`Vfd::from_transport`].
+    pub fn open_transport<T: Transport + 'static>(self, transport: T) -> Result<Vfd, VfdError> {
+        self.open_duplex(WriteOnly(transport))
+    }
+
+    /// Set up the display on `link` like [`VfdBuilder::open_transport`],
+    /// reading its answers back as for [`Vfd::from_duplex`].
+    pub fn open_duplex<T: Duplex + 'static>(self, link: T) -> Result<Vfd, VfdError> {
+        let port = self.tapped(link)?;
+        let vfd = Vfd::from_port(port, self.command_set.clone(), self.width, self.height);
+        self.set_up(vfd)
+    }
+
+    // Apply everything but the port settings to a newly opened display
+    fn set_up(self, mut vfd: Vfd) -> Result<Vfd, VfdError> {
+        if let Some(capabilities) = self.capabilities {
+            vfd.set_capabilities(capabilities)?;
+        }
+        if let Some(observer) = &self.observer {
+            vfd.set_observer(Arc::clone(&observer.0));
+        }
+        vfd.set_response_timeout(self.timeout);
+        vfd.set_transliterate(self.transliterate);
+        vfd.set_symbols(self.symbols.clone());
+        vfd.set_bidi(self.bidi);
+        vfd.set_wrap_policy(self.wrap_policy);
+        vfd.set_throttle(self.throttle);
+        if let Some(indicator) = self.ellipsis {
+            vfd.set_ellipsis(indicator);
+        }
+        vfd.set_currency(self.currency.clone());
+        vfd.set_number_format(self.number_format);
+        vfd.set_templates(self.templates.clone());
+        if self.pass_through {
+            vfd.select_peripheral(Peripheral::Display)?;
+        }
+        if self.auto_initialize {
+            vfd.initialize()?;
+        }
+        if let Some(level) = self.brightness {
+            vfd.set_brightness(level)?;
+        }
+        if let Some(page) = self.code_page {
+            vfd.set_code_page(page)?;
+        }
+        if let Some(style) = self.cursor_style {
+            vfd.set_cursor_style(style)?;
+        }
+        if let Some(mode) = self.display_mode {
+            vfd.set_display_mode(mode)?;
+        }
+        if let Some(splash) = &self.splash
+            && self.auto_initialize
+            && !self.skip_splash
+        {
+            splash.show(&mut vfd)?;
+        }
+        vfd.set_on_drop(self.on_drop);
+        vfd.notify_connected();
+        Ok(vfd)
+    }
+
+    /// Open the display with the first serial settings it answers an identify request on.
+    ///
+    /// The configured settings are tried first, then
+    /// [`SerialProfile::probe_order`] for the command set, waiting a short
+    /// while for an answer on each. Returns the opened display together with
+    /// the settings that worked, or [`VfdError::Timeout`] when none did. Needs
+    /// a command set with an identify request.
+    pub fn probe(self) -> Result<(Vfd, SerialProfile), VfdError> {
+        if self.command_set.identify_request().is_none() {
+            return Err(VfdError::NotSupported(format!(
+                "Probing serial settings of {:?} displays",
+                self.command_set
+            )));
+        }
+        let mut profiles = vec![self.serial_profile()];
+        for profile in SerialProfile::probe_order(self.command_set.clone()) {
+            if !profiles.contains(&profile) {
+                profiles.push(profile);
+            }
+        }
+
+        for profile in profiles {
+            let candidate = self.clone().profile(profile).timeout(PROBE_TIMEOUT);
+            let (port, control) = candidate.open_port()?;
+            let mut vfd = Vfd::from_port(port, self.command_set.clone(), self.width, self.height);
+            vfd.set_control_port(control);
+            vfd.set_response_timeout(PROBE_TIMEOUT);
+            match vfd.identify() {
+                Ok(_) => {
+                    // Release the port before opening it again with the full setup
+                    drop(vfd);
+                    return Ok((self.profile(profile).open()?, profile));
+                }
+                Err(VfdError::Timeout) => continue,
+                Err(e) => return Err(e),
+            }
+        }
+        Err(VfdError::Timeout)
+    }
+
+    /// Open the serial port as a tokio stream and return the configured display.
+    ///
+    /// Must be called from within a tokio runtime. Serial device servers are
+    /// not supported here.
+    #[cfg(feature = "tokio")]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "open", skip_all, fields(device = %self.device_path))
+    )]
+    pub async fn open_async(self) -> Result<AsyncVfd, VfdError> {
+        use tokio_serial::SerialPortBuilderExt;
+
+        if BridgeMode::from_device(&self.device_path).is_some() {
+            return Err(VfdError::NotSupported(
+                "Opening a serial device server asynchronously".to_string(),
+            ));
+        }
+
+        let mut port = self.port_builder().open_native_async()?;
+        self.set_control_lines(&mut port)?;
+
+        let mut vfd = AsyncVfd::from_stream(port, self.command_set, self.width, self.height);
+        if let Some(capabilities) = self.capabilities {
+            vfd.set_capabilities(capabilities)?;
+        }
+        vfd.set_transliterate(self.transliterate);
+        vfd.set_symbols(self.symbols.clone());
+        vfd.set_throttle(self.throttle);
+        if self.auto_initialize {
+            vfd.initialize().await?;
+        }
+        if let Some(level) = self.brightness {
+            vfd.set_brightness(level).await?;
+        }
+        if let Some(page) = self.code_page {
+            vfd.set_code_page(page).await?;
+        }
+        if let Some(style) = self.cursor_style {
+            vfd.set_cursor_style(style).await?;
+        }
+        if let Some(mode) = self.display_mode {
+            vfd.set_display_mode(mode).await?;
+        }
+        Ok(vfd)
+    }
+}
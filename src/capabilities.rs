@@ -0,0 +1,64 @@
+use crate::command_set::{CommandSet, DisplayMode};
+use crate::geometry::Geometry;
+
+/// What a particular display can do, checked by the drivers before they send
+/// a command, so that unsupported requests fail with `NotSupported` instead of
+/// being silently ignored by the hardware.
+///
+/// [`Capabilities::new`] gives what the command set allows; narrow it for
+/// models that lack some of it, e.g. a clone without user-definable characters.
+///
+/// ```
+/// use vfd_dsp_v9fb_over_serial::{Capabilities, CommandSet, Geometry};
+///
+/// let capabilities = Capabilities::new(CommandSet::Futaba, Geometry::SIZE_20X2);
+/// assert!(!capabilities.supports_hscroll);
+/// assert_eq!(capabilities.max_custom_chars, 16);
+///
+/// let clone = Capabilities {
+///     supports_cgram: false,
+///     max_custom_chars: 0,
+///     ..Capabilities::new(CommandSet::Birch, Geometry::SIZE_20X2)
+/// };
+/// assert!(!clone.supports_cgram);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Capabilities {
+    /// The brightness can be changed.
+    pub supports_brightness: bool,
+    /// Characters can be downloaded into character RAM.
+    pub supports_cgram: bool,
+    /// Number of user-definable character slots.
+    pub max_custom_chars: u8,
+    /// Lines can scroll sideways as text is added ([`DisplayMode::HorizontalScroll`]).
+    pub supports_hscroll: bool,
+    pub geometry: Geometry,
+}
+
+impl Capabilities {
+    /// Everything `command_set` has commands for, on a display of `geometry`.
+    pub fn new(command_set: CommandSet, geometry: Geometry) -> Self {
+        Capabilities {
+            supports_brightness: match &command_set {
+                CommandSet::Custom(protocol) => protocol.has_brightness(),
+                _ => true,
+            },
+            supports_cgram: command_set.max_custom_chars() > 0,
+            max_custom_chars: command_set.max_custom_chars(),
+            supports_hscroll: command_set
+                .set_display_mode(DisplayMode::HorizontalScroll)
+                .is_some(),
+            geometry,
+        }
+    }
+
+    /// Whether custom character `slot` exists.
+    pub fn has_custom_char(&self, slot: u8) -> bool {
+        self.supports_cgram && slot < self.max_custom_chars
+    }
+
+    /// Whether the display can be switched to `mode`.
+    pub fn supports_display_mode(&self, mode: DisplayMode) -> bool {
+        mode != DisplayMode::HorizontalScroll || self.supports_hscroll
+    }
+}
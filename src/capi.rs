@@ -0,0 +1,235 @@
+//! C interface for point-of-sale software written in C or C++ (`capi` feature).
+//!
+//! The declarations are in `include/vfd.h`, generated from this file alone with
+//! `cbindgen --config cbindgen.toml --output include/vfd.h src/capi.rs`.
+//! Build a library to link against with
+//! `cargo rustc --lib --release --features capi --crate-type cdylib` (or
+//! `staticlib`).
+//!
+//! ```c
+//! #include "vfd.h"
+//!
+//! VfdHandle *vfd = vfd_open("/dev/ttyUSB0", 20, 2);
+//! if (vfd == NULL) {
+//!     fprintf(stderr, "%s\n", vfd_last_error());
+//!     return 1;
+//! }
+//! vfd_clear(vfd);
+//! vfd_write_line(vfd, 0, "Total", VFD_ALIGN_LEFT);
+//! vfd_write_line(vfd, 1, "12.50", VFD_ALIGN_RIGHT);
+//! vfd_close(vfd);
+//! ```
+//!
+//! Functions returning `int` give `VFD_OK` (0) on success and a negative
+//! code on failure: `VFD_ERROR` (-1) when the call could not be carried out,
+//! `VFD_PANIC` (-2) when the library hit a bug. Either way
+//! [`vfd_last_error`] describes what went wrong. No panic unwinds into C.
+
+use crate::builder::VfdBuilder;
+use crate::error::VfdError;
+use crate::layout::Align;
+use crate::vfd::Vfd;
+use std::any::Any;
+use std::cell::RefCell;
+use std::ffi::{CStr, CString, c_char, c_int};
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+
+/// Returned by functions that succeeded.
+pub const VFD_OK: c_int = 0;
+/// Returned by functions that failed; see [`vfd_last_error`].
+pub const VFD_ERROR: c_int = -1;
+/// Returned by functions that stopped on a bug in the library; see
+/// [`vfd_last_error`]. The handle can still be closed.
+pub const VFD_PANIC: c_int = -2;
+
+thread_local! {
+    // Message of the last failed call on this thread
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// An open display, created by [`vfd_open`] and freed by [`vfd_close`].
+pub struct VfdHandle(Vfd);
+
+/// Horizontal placement of a line of text, passed to [`vfd_write_line`] as an `int`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VfdAlign {
+    Left = 0,
+    Center = 1,
+    Right = 2,
+}
+
+impl From<VfdAlign> for Align {
+    fn from(align: VfdAlign) -> Self {
+        match align {
+            VfdAlign::Left => Align::Left,
+            VfdAlign::Center => Align::Center,
+            VfdAlign::Right => Align::Right,
+        }
+    }
+}
+
+impl TryFrom<c_int> for VfdAlign {
+    type Error = VfdError;
+
+    fn try_from(value: c_int) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(VfdAlign::Left),
+            1 => Ok(VfdAlign::Center),
+            2 => Ok(VfdAlign::Right),
+            other => Err(VfdError::InvalidInput(format!(
+                "Invalid alignment {}. Expected VFD_ALIGN_LEFT, VFD_ALIGN_CENTER or VFD_ALIGN_RIGHT.",
+                other
+            ))),
+        }
+    }
+}
+
+/// Open the display attached to `device`, e.g. `/dev/ttyUSB0` or `COM3`,
+/// which is `width` characters wide and `height` rows high.
+///
+/// Returns NULL on failure.
+///
+/// # Safety
+///
+/// `device` must be a valid NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn vfd_open(device: *const c_char, width: u8, height: u8) -> *mut VfdHandle {
+    guard(ptr::null_mut(), || {
+        // SAFETY: the caller passes a valid string or NULL
+        let result = unsafe { c_str(device, "device") }
+            .and_then(|device| VfdBuilder::new(device, width, height).open());
+        match result {
+            Ok(vfd) => Box::into_raw(Box::new(VfdHandle(vfd))),
+            Err(e) => {
+                set_last_error(&e);
+                ptr::null_mut()
+            }
+        }
+    })
+}
+
+/// Replace row `row` (counted from 0) with `text`, a UTF-8 string, placed as
+/// `align` says: one of the [`VfdAlign`] values.
+///
+/// # Safety
+///
+/// `vfd` must come from [`vfd_open`] and not be closed yet, and `text` must
+/// be a valid NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn vfd_write_line(
+    vfd: *mut VfdHandle,
+    row: u8,
+    text: *const c_char,
+    align: c_int,
+) -> c_int {
+    guard(VFD_PANIC, || {
+        let result = VfdAlign::try_from(align).and_then(|align| {
+            // SAFETY: the caller passes a handle from vfd_open or NULL, and a valid string or NULL
+            let vfd = unsafe { handle(vfd) }?;
+            let text = unsafe { c_str(text, "text") }?;
+            vfd.write_line(row, text, align.into())
+        });
+        status(result)
+    })
+}
+
+/// Blank the display and move the cursor to the top-left corner.
+///
+/// # Safety
+///
+/// `vfd` must come from [`vfd_open`] and not be closed yet.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn vfd_clear(vfd: *mut VfdHandle) -> c_int {
+    guard(VFD_PANIC, || {
+        // SAFETY: the caller passes a handle from vfd_open or NULL
+        status(unsafe { handle(vfd) }.and_then(|vfd| vfd.clear()))
+    })
+}
+
+/// Close the display and free the handle. Passing NULL does nothing.
+///
+/// # Safety
+///
+/// `vfd` must come from [`vfd_open`] and must not be used afterwards.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn vfd_close(vfd: *mut VfdHandle) {
+    guard((), || {
+        if !vfd.is_null() {
+            // SAFETY: the caller hands back ownership of a handle from vfd_open
+            drop(unsafe { Box::from_raw(vfd) });
+        }
+    })
+}
+
+/// Description of the last error on the calling thread, or NULL if there was none.
+///
+/// The string stays valid until the next failing call on the same thread.
+#[unsafe(no_mangle)]
+pub extern "C" fn vfd_last_error() -> *const c_char {
+    guard(ptr::null(), || {
+        LAST_ERROR.with(|last| {
+            last.borrow()
+                .as_ref()
+                .map_or(ptr::null(), |message| message.as_ptr())
+        })
+    })
+}
+
+// Run the body of an entry point, answering `on_panic` instead of unwinding into C
+fn guard<T>(on_panic: T, f: impl FnOnce() -> T) -> T {
+    panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|payload| {
+        set_last_message(&format!("Panicked: {}", panic_message(&*payload)));
+        on_panic
+    })
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    match payload.downcast_ref::<&str>() {
+        Some(message) => message,
+        None => payload
+            .downcast_ref::<String>()
+            .map_or("unknown cause", String::as_str),
+    }
+}
+
+fn set_last_error(e: &VfdError) {
+    set_last_message(&e.to_string());
+}
+
+fn set_last_message(message: &str) {
+    // Interior NULs cannot come from our own messages, but drop them rather than lose the error
+    let message = CString::new(message.replace('\0', "")).unwrap_or_default();
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(message));
+}
+
+fn status(result: Result<(), VfdError>) -> c_int {
+    match result {
+        Ok(()) => VFD_OK,
+        Err(e) => {
+            set_last_error(&e);
+            VFD_ERROR
+        }
+    }
+}
+
+// SAFETY: `vfd` must be NULL or a live handle from vfd_open
+unsafe fn handle<'a>(vfd: *mut VfdHandle) -> Result<&'a mut Vfd, VfdError> {
+    // SAFETY: upheld by the caller
+    match unsafe { vfd.as_mut() } {
+        Some(handle) => Ok(&mut handle.0),
+        None => Err(VfdError::InvalidInput("Display handle is NULL".to_string())),
+    }
+}
+
+// SAFETY: `s` must be NULL or a valid NUL-terminated string outliving 'a
+unsafe fn c_str<'a>(s: *const c_char, what: &str) -> Result<&'a str, VfdError> {
+    if s.is_null() {
+        return Err(VfdError::InvalidInput(format!("{} is NULL", what)));
+    }
+    // SAFETY: upheld by the caller
+    unsafe { CStr::from_ptr(s) }
+        .to_str()
+        .map_err(|_| VfdError::InvalidInput(format!("{} is not valid UTF-8", what)))
+}
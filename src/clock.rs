@@ -0,0 +1,92 @@
+//! Clock widget (`clock` feature).
+
+use crate::display::VfdDisplay;
+use crate::error::VfdError;
+use crate::layout::Align;
+use crate::region::Region;
+use crate::widget::Widget;
+use chrono::Local;
+use chrono::format::{Item, StrftimeItems};
+use std::time::Duration;
+
+/// The local time, formatted with a strftime pattern, on one row or in a region.
+///
+/// The row is only rewritten when the formatted text changes, so ticking
+/// more often than the format's resolution costs nothing.
+#[derive(Debug, Clone)]
+pub struct ClockWidget {
+    items: Vec<Item<'static>>,
+    row: u8,
+    align: Align,
+    region: Option<Region>,
+    shown: Option<String>,
+}
+
+impl ClockWidget {
+    /// A clock drawn with `format` (e.g. `"%H:%M:%S"`) centered on the top row.
+    pub fn new(format: &str) -> Result<Self, VfdError> {
+        let items = StrftimeItems::new(format)
+            .parse_to_owned()
+            .map_err(|_| VfdError::InvalidInput(format!("Invalid time format '{}'", format)))?;
+        Ok(ClockWidget {
+            items,
+            row: 0,
+            align: Align::Center,
+            region: None,
+            shown: None,
+        })
+    }
+
+    /// Draw on row `row`.
+    pub fn row(mut self, row: u8) -> Self {
+        self.row = row;
+        self
+    }
+
+    pub fn align(mut self, align: Align) -> Self {
+        self.align = align;
+        self
+    }
+
+    /// Draw on the first row of `region` instead of a whole display row.
+    pub fn region(mut self, region: Region) -> Self {
+        self.region = Some(region);
+        self
+    }
+
+    /// The current time as the widget shows it.
+    pub fn text(&self) -> String {
+        Local::now()
+            .format_with_items(self.items.iter())
+            .to_string()
+    }
+
+    fn draw(&mut self, display: &mut dyn VfdDisplay, text: String) -> Result<(), VfdError> {
+        match &mut self.region {
+            Some(region) => region.write_line(display, 0, &text, self.align)?,
+            None => display.write_line(self.row, &text, self.align)?,
+        }
+        self.shown = Some(text);
+        Ok(())
+    }
+}
+
+impl Widget for ClockWidget {
+    fn tick(&mut self, display: &mut dyn VfdDisplay) -> Result<bool, VfdError> {
+        let text = self.text();
+        if self.shown.as_ref() == Some(&text) {
+            return Ok(false);
+        }
+        self.draw(display, text)?;
+        Ok(true)
+    }
+
+    fn render(&mut self, display: &mut dyn VfdDisplay) -> Result<(), VfdError> {
+        let text = self.text();
+        self.draw(display, text)
+    }
+
+    fn interval(&self) -> Duration {
+        Duration::from_secs(1)
+    }
+}
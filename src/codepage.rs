@@ -0,0 +1,246 @@
+use crate::command_set::CommandSet;
+use crate::glyph::custom_slot;
+use crate::symbols::SymbolMap;
+use crate::text;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::str::FromStr;
+
+/// Character code tables selectable on the display (ESC t n on Epson-compatible models).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CodePage {
+    /// USA / standard Europe, the power-on default.
+    #[default]
+    Pc437,
+    /// JIS X 0201 half-width Katakana.
+    ///
+    /// Full-width kana and Latin text, as typed by Japanese input methods, is
+    /// shown in half-width form, see [`text::half_width`](crate::text::half_width).
+    Katakana,
+    /// Multilingual Latin-1.
+    Pc850,
+    /// Multilingual Latin-1 with the euro sign.
+    Pc858,
+    /// Hebrew.
+    Pc862,
+    /// Cyrillic.
+    Pc866,
+    /// Windows Latin-1.
+    Wpc1252,
+}
+
+const PC437_HIGH: &str = "ÇüéâäàåçêëèïîìÄÅÉæÆôöòûùÿÖÜ¢£¥₧ƒáíóúñÑªº¿⌐¬½¼¡«»░▒▓│┤╡╢╖╕╣║╗╝╜╛┐└┴┬├─┼╞╟╚╔╩╦╠═╬╧╨╤╥╙╘╒╓╫╪┘┌█▄▌▐▀αßΓπΣσµτΦΘΩδ∞φε∩≡±≥≤⌠⌡÷≈°∙·√ⁿ²■\u{A0}";
+const PC850_HIGH: &str = "ÇüéâäàåçêëèïîìÄÅÉæÆôöòûùÿÖÜø£Ø×ƒáíóúñÑªº¿®¬½¼¡«»░▒▓│┤ÁÂÀ©╣║╗╝¢¥┐└┴┬├─┼ãÃ╚╔╩╦╠═╬¤ðÐÊËÈıÍÎÏ┘┌█▄¦Ì▀ÓßÔÒõÕµþÞÚÛÙýÝ¯´\u{AD}±‗¾¶§÷¸°¨·¹³²■\u{A0}";
+const PC858_HIGH: &str = "ÇüéâäàåçêëèïîìÄÅÉæÆôöòûùÿÖÜø£Ø×ƒáíóúñÑªº¿®¬½¼¡«»░▒▓│┤ÁÂÀ©╣║╗╝¢¥┐└┴┬├─┼ãÃ╚╔╩╦╠═╬¤ðÐÊËÈ€ÍÎÏ┘┌█▄¦Ì▀ÓßÔÒõÕµþÞÚÛÙýÝ¯´\u{AD}±‗¾¶§÷¸°¨·¹³²■\u{A0}";
+// PC437 with the Hebrew alphabet in place of the accented letters
+const PC862_HIGH: &str = "אבגדהוזחטיךכלםמןנסעףפץצקרשת¢£¥₧ƒáíóúñÑªº¿⌐¬½¼¡«»░▒▓│┤╡╢╖╕╣║╗╝╜╛┐└┴┬├─┼╞╟╚╔╩╦╠═╬╧╨╤╥╙╘╒╓╫╪┘┌█▄▌▐▀αßΓπΣσµτΦΘΩδ∞φε∩≡±≥≤⌠⌡÷≈°∙·√ⁿ²■\u{A0}";
+const PC866_HIGH: &str = "АБВГДЕЖЗИЙКЛМНОПРСТУФХЦЧШЩЪЫЬЭЮЯабвгдежзийклмноп░▒▓│┤╡╢╖╕╣║╗╝╜╛┐└┴┬├─┼╞╟╚╔╩╦╠═╬╧╨╤╥╙╘╒╓╫╪┘┌█▄▌▐▀рстуфхцчшщъыьэюяЁёЄєЇїЎў°∙·√№¤■\u{A0}";
+// Only 0x80-0x9F differ from Latin-1
+const WPC1252_80_9F: &str = "€\u{81}‚ƒ„…†‡ˆ‰Š‹Œ\u{8D}Ž\u{8F}\u{90}‘’“”•–—˜™š›œ\u{9D}žŸ";
+
+impl CodePage {
+    /// Table number used by the ESC t n command.
+    pub fn table_number(&self) -> u8 {
+        match self {
+            CodePage::Pc437 => 0,
+            CodePage::Katakana => 1,
+            CodePage::Pc850 => 2,
+            CodePage::Pc858 => 19,
+            CodePage::Pc862 => 15,
+            CodePage::Pc866 => 17,
+            CodePage::Wpc1252 => 16,
+        }
+    }
+
+    /// Code page selected by table number `n` of the ESC t n command, if it is one of ours.
+    pub fn from_table_number(n: u8) -> Option<CodePage> {
+        match n {
+            0 => Some(CodePage::Pc437),
+            1 => Some(CodePage::Katakana),
+            2 => Some(CodePage::Pc850),
+            19 => Some(CodePage::Pc858),
+            15 => Some(CodePage::Pc862),
+            17 => Some(CodePage::Pc866),
+            16 => Some(CodePage::Wpc1252),
+            _ => None,
+        }
+    }
+
+    /// Byte that renders `c` in this code page, if the table contains it.
+    pub fn encode_char(&self, c: char) -> Option<u8> {
+        if c.is_ascii() {
+            return Some(c as u8);
+        }
+        match self {
+            CodePage::Pc437 => find_high(PC437_HIGH, c),
+            CodePage::Pc850 => find_high(PC850_HIGH, c),
+            CodePage::Pc858 => find_high(PC858_HIGH, c),
+            CodePage::Pc862 => find_high(PC862_HIGH, c),
+            CodePage::Pc866 => find_high(PC866_HIGH, c),
+            CodePage::Wpc1252 => match c as u32 {
+                0xA0..=0xFF => Some(c as u8),
+                _ => find_high(WPC1252_80_9F, c),
+            },
+            // Half-width Katakana U+FF61..U+FF9F sit at 0xA1..0xDF
+            CodePage::Katakana => match c as u32 {
+                0xFF61..=0xFF9F => Some((c as u32 - 0xFF61 + 0xA1) as u8),
+                _ => None,
+            },
+        }
+    }
+
+    /// Encode `text`, replacing characters missing from the table with `?`.
+    pub fn encode(&self, text: &str) -> Vec<u8> {
+        text.chars()
+            .map(|c| self.encode_char(c).unwrap_or(b'?'))
+            .collect()
+    }
+
+    /// Character the display shows for `byte`, if the table defines one.
+    pub fn decode(&self, byte: u8) -> Option<char> {
+        if byte.is_ascii() {
+            return Some(byte as char);
+        }
+        let high = (byte - 0x80) as usize;
+        match self {
+            CodePage::Pc437 => PC437_HIGH.chars().nth(high),
+            CodePage::Pc850 => PC850_HIGH.chars().nth(high),
+            CodePage::Pc858 => PC858_HIGH.chars().nth(high),
+            CodePage::Pc862 => PC862_HIGH.chars().nth(high),
+            CodePage::Pc866 => PC866_HIGH.chars().nth(high),
+            CodePage::Wpc1252 => match byte {
+                0xA0..=0xFF => Some(byte as char),
+                _ => WPC1252_80_9F.chars().nth(high),
+            },
+            CodePage::Katakana => match byte {
+                0xA1..=0xDF => char::from_u32(byte as u32 - 0xA1 + 0xFF61),
+                _ => None,
+            },
+        }
+    }
+}
+
+// Position of `c` in a table describing the bytes from 0x80 upwards
+fn find_high(table: &str, c: char) -> Option<u8> {
+    table
+        .chars()
+        .position(|t| t == c)
+        .map(|index| 0x80 + index as u8)
+}
+
+impl FromStr for CodePage {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "pc437" | "cp437" | "437" => Ok(CodePage::Pc437),
+            "katakana" => Ok(CodePage::Katakana),
+            "pc850" | "cp850" | "850" => Ok(CodePage::Pc850),
+            "pc858" | "cp858" | "858" => Ok(CodePage::Pc858),
+            "pc862" | "cp862" | "862" => Ok(CodePage::Pc862),
+            "pc866" | "cp866" | "866" => Ok(CodePage::Pc866),
+            "wpc1252" | "cp1252" | "1252" => Ok(CodePage::Wpc1252),
+            other => Err(format!(
+                "Unknown code page '{}'. Expected one of pc437, katakana, pc850, pc858, pc862, pc866, wpc1252.",
+                other
+            )),
+        }
+    }
+}
+
+/// How a display turns text into the characters it shows, one per cell:
+/// symbols mapped through a [`SymbolMap`] and characters missing from the
+/// code page transliterated.
+///
+/// Measure text after applying the mapping, as `"ß"` may become `"ss"`.
+///
+/// ```
+/// use vfd_dsp_v9fb_over_serial::{CodePage, SymbolMap, TextMapping};
+///
+/// let mapping = TextMapping::new(CodePage::Wpc1252, true, SymbolMap::default());
+/// assert_eq!(mapping.apply("5 Ω"), "5 Ohm");
+/// assert_eq!(TextMapping::identity().apply("5 Ω"), "5 Ω");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextMapping {
+    code_page: Option<CodePage>,
+    transliterate: bool,
+    symbols: SymbolMap,
+}
+
+impl TextMapping {
+    pub fn new(code_page: CodePage, transliterate: bool, symbols: SymbolMap) -> Self {
+        TextMapping {
+            code_page: Some(code_page),
+            transliterate,
+            symbols,
+        }
+    }
+
+    /// Leaves text as it is, for displays that show any character.
+    pub fn identity() -> Self {
+        TextMapping {
+            code_page: None,
+            transliterate: false,
+            symbols: SymbolMap::empty(),
+        }
+    }
+
+    /// `text` as the display shows it.
+    pub fn apply(&self, text: &str) -> String {
+        match self.code_page {
+            Some(code_page) => prepare_text(text, code_page, self.transliterate, &self.symbols),
+            None => text.into(),
+        }
+    }
+}
+
+// Map symbols and apply transliteration so that what we measure is what gets sent
+pub(crate) fn prepare_text(
+    text: &str,
+    code_page: CodePage,
+    transliterate: bool,
+    symbols: &SymbolMap,
+) -> String {
+    let mut prepared = String::with_capacity(text.len());
+    for c in text.chars() {
+        if custom_slot(c).is_some() || code_page.encode_char(c).is_some() {
+            prepared.push(c);
+        } else if let Some(half) = text::half_width(c)
+            .filter(|half| half.chars().all(|h| code_page.encode_char(h).is_some()))
+        {
+            prepared.push_str(half);
+        } else if let Some(replacement) = symbols.resolve(c, code_page) {
+            prepared.push_str(replacement);
+        } else if !transliterate {
+            prepared.push(c);
+        } else if let Some(replacement) = text::transliterate(c) {
+            prepared.push_str(replacement);
+        } else if text::char_width(c) > 0 {
+            prepared.push(c);
+        }
+    }
+    prepared
+}
+
+// First character of `text` the code page cannot show, leaving out custom
+// glyph slots and zero-width characters
+#[cfg(feature = "std")]
+pub(crate) fn unencodable(text: &str, code_page: CodePage) -> Option<char> {
+    text.chars().find(|&c| {
+        text::char_width(c) > 0 && custom_slot(c).is_none() && code_page.encode_char(c).is_none()
+    })
+}
+
+// Transcode text into the active code page, mapping custom glyph slots
+pub(crate) fn encode_text(text: &str, command_set: CommandSet, code_page: CodePage) -> Vec<u8> {
+    text.chars()
+        .filter(|c| text::char_width(*c) > 0)
+        .map(|c| match custom_slot(c) {
+            Some(slot) => command_set.custom_char_code(slot),
+            None => code_page.encode_char(c),
+        })
+        .map(|byte| byte.unwrap_or(b'?'))
+        .collect()
+}
@@ -0,0 +1,135 @@
+use crate::command_set::{BrightnessLevel, CommandSet, DisplayMode};
+use crate::error::VfdError;
+use crate::glyph::Glyph;
+use alloc::vec;
+use alloc::vec::Vec;
+
+const ESC: u8 = 0x1B;
+const US: u8 = 0x1F;
+
+/// A single command from the Epson ESC/POS customer display command set.
+///
+/// Send it with [`Vfd::send_command`](crate::Vfd::send_command), which keeps
+/// the driver's idea of the cursor, brightness and screen contents in step
+/// where the command's effect is known. Positions are 0-indexed, like
+/// everywhere else in the crate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    /// ESC @: reset to the power-on state.
+    Initialize,
+    /// CLR: blank the screen and home the cursor.
+    Clear,
+    /// CAN: blank the cursor's row and move to its start.
+    ClearLine,
+    /// BS: one column left.
+    CursorLeft,
+    /// HT: one column right.
+    CursorRight,
+    /// US LF: one row up.
+    CursorUp,
+    /// LF: one row down.
+    CursorDown,
+    /// HOM: top-left cell.
+    CursorHome,
+    /// CR: start of the current row.
+    CursorLeftmost,
+    /// US CR: end of the current row.
+    CursorRightmost,
+    /// US B: start of the bottom row.
+    CursorBottom,
+    /// US $ x y: column `x`, row `y`.
+    MoveCursor { x: u8, y: u8 },
+    /// US C n: show or hide the cursor.
+    CursorVisible(bool),
+    /// US X n: brightness.
+    Brightness(BrightnessLevel),
+    /// US E n: blink every `n` × 50 ms; 0 stops blinking and 255 blanks the display.
+    Blink(u8),
+    /// US MD1 / MD2 / MD3: end-of-line behaviour.
+    DisplayMode(DisplayMode),
+    /// US r n: reversed characters on or off.
+    Reverse(bool),
+    /// ESC R n: international character set (0 = USA).
+    InternationalCharset(u8),
+    /// ESC t n: character code table.
+    CodeTable(u8),
+    /// ESC & 1 c c 5 d1..d5 followed by ESC % 1: download a glyph at character code `code`.
+    DefineChar { code: u8, glyph: Glyph },
+    /// ESC % n: use the downloaded characters or the built-in ones.
+    UserCharset(bool),
+    /// ESC ? n: forget the downloaded character at `code`.
+    CancelUserChar(u8),
+    /// US T h m: set the built-in clock.
+    SetTime { hour: u8, minute: u8 },
+    /// US U: show the built-in clock.
+    ShowTime,
+    /// US @: run the hardware self-test.
+    SelfTest,
+    /// US :: start or end a macro definition.
+    MacroDefinition,
+    /// US ^ n m: run the macro, waiting `interval` × 50 ms between steps and `pause` × 50 ms between runs.
+    ExecuteMacro { interval: u8, pause: u8 },
+    /// US # n m: turn annunciator `index` on or off.
+    Annunciator { index: u8, on: bool },
+    /// ESC = n: select the device that receives data (1 = printer, 2 = display).
+    SelectPeripheral(u8),
+    /// GS I n: ask the display to transmit an ID.
+    TransmitId(u8),
+    /// Any other sequence, sent as is.
+    Raw(Vec<u8>),
+}
+
+impl Command {
+    /// Bytes of the command.
+    ///
+    /// Fails with [`VfdError::InvalidInput`] for a [`Command::MoveCursor`] to
+    /// column or row 255, which has no 1-indexed form.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, VfdError> {
+        Ok(match self {
+            Command::Initialize => vec![ESC, b'@'],
+            Command::Clear => vec![0x0C],
+            Command::ClearLine => vec![0x18],
+            Command::CursorLeft => vec![0x08],
+            Command::CursorRight => vec![0x09],
+            Command::CursorUp => vec![US, 0x0A],
+            Command::CursorDown => vec![0x0A],
+            Command::CursorHome => vec![0x0B],
+            Command::CursorLeftmost => vec![0x0D],
+            Command::CursorRightmost => vec![US, 0x0D],
+            Command::CursorBottom => vec![US, b'B'],
+            Command::MoveCursor { x, y } => {
+                let one_based = |n: u8| {
+                    n.checked_add(1).ok_or_else(|| {
+                        VfdError::InvalidInput(format!("Cannot move the cursor to {}", n))
+                    })
+                };
+                vec![US, b'$', one_based(*x)?, one_based(*y)?]
+            }
+            Command::CursorVisible(visible) => vec![US, b'C', *visible as u8],
+            Command::Brightness(level) => CommandSet::EpsonDmD.set_brightness(*level),
+            Command::Blink(n) => vec![US, b'E', *n],
+            Command::DisplayMode(mode) => match mode {
+                DisplayMode::Overwrite => vec![US, 0x01],
+                DisplayMode::VerticalScroll => vec![US, 0x02],
+                DisplayMode::HorizontalScroll => vec![US, 0x03],
+            },
+            Command::Reverse(on) => vec![US, b'r', *on as u8],
+            Command::InternationalCharset(n) => vec![ESC, b'R', *n],
+            Command::CodeTable(n) => vec![ESC, b't', *n],
+            Command::DefineChar { code, glyph } => {
+                CommandSet::EpsonDmD.define_custom_char(*code, glyph)
+            }
+            Command::UserCharset(on) => vec![ESC, b'%', *on as u8],
+            Command::CancelUserChar(code) => vec![ESC, b'?', *code],
+            Command::SetTime { hour, minute } => vec![US, b'T', *hour, *minute],
+            Command::ShowTime => vec![US, b'U'],
+            Command::SelfTest => vec![US, b'@'],
+            Command::MacroDefinition => vec![US, b':'],
+            Command::ExecuteMacro { interval, pause } => vec![US, b'^', *interval, *pause],
+            Command::Annunciator { index, on } => vec![US, b'#', *on as u8, *index],
+            Command::SelectPeripheral(n) => vec![ESC, b'=', *n],
+            Command::TransmitId(n) => vec![0x1D, b'I', *n],
+            Command::Raw(bytes) => bytes.clone(),
+        })
+    }
+}
@@ -0,0 +1,595 @@
+use crate::annunciator::Annunciator;
+use crate::codepage::CodePage;
+use crate::glyph::Glyph;
+use crate::position::Position;
+use crate::protocol_definition::ProtocolDefinition;
+use alloc::format;
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::str::FromStr;
+use core::time::Duration;
+
+const CMD_CLEAR: u8 = 0x0C;
+const CMD_ESC: u8 = 0x1B;
+const CMD_US: u8 = 0x1F;
+
+/// Brightness (dimming) levels supported by the display.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BrightnessLevel {
+    Percent20,
+    Percent40,
+    Percent60,
+    Percent100,
+}
+
+impl BrightnessLevel {
+    /// Brightness as a percentage of full output.
+    pub fn percent(&self) -> u8 {
+        match self {
+            BrightnessLevel::Percent20 => 20,
+            BrightnessLevel::Percent40 => 40,
+            BrightnessLevel::Percent60 => 60,
+            BrightnessLevel::Percent100 => 100,
+        }
+    }
+}
+
+impl FromStr for BrightnessLevel {
+    type Err = String;
+
+    // Accepts "20", "40", "60" or "100", with or without a trailing '%'
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().trim_end_matches('%') {
+            "20" => Ok(BrightnessLevel::Percent20),
+            "40" => Ok(BrightnessLevel::Percent40),
+            "60" => Ok(BrightnessLevel::Percent60),
+            "100" => Ok(BrightnessLevel::Percent100),
+            other => Err(format!(
+                "Invalid brightness '{}'. Expected one of 20, 40, 60, 100.",
+                other
+            )),
+        }
+    }
+}
+
+/// Appearance of the cursor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CursorStyle {
+    Hidden,
+    Underline,
+    Block,
+    Blinking,
+}
+
+impl FromStr for CursorStyle {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "off" | "hidden" => Ok(CursorStyle::Hidden),
+            "underline" => Ok(CursorStyle::Underline),
+            "block" => Ok(CursorStyle::Block),
+            "blink" | "blinking" => Ok(CursorStyle::Blinking),
+            other => Err(format!(
+                "Unknown cursor style '{}'. Expected one of off, underline, block, blink.",
+                other
+            )),
+        }
+    }
+}
+
+/// A single-step cursor movement.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CursorMove {
+    /// To the top-left cell.
+    Home,
+    /// One column left.
+    Left,
+    /// One column right.
+    Right,
+    /// One row up.
+    Up,
+    /// One row down.
+    Down,
+    /// To the start of the current row.
+    CarriageReturn,
+    /// One row down, scrolling the display from the bottom row in vertical scroll mode.
+    LineFeed,
+}
+
+/// A device on a pass-through chain, where the display sits between the host and a receipt printer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Peripheral {
+    Printer,
+    Display,
+    /// Printer and display both receive what is sent.
+    Both,
+}
+
+impl FromStr for Peripheral {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "printer" => Ok(Peripheral::Printer),
+            "display" => Ok(Peripheral::Display),
+            "both" => Ok(Peripheral::Both),
+            other => Err(format!(
+                "Unknown peripheral '{}'. Expected one of printer, display, both.",
+                other
+            )),
+        }
+    }
+}
+
+/// What the display does when text reaches the end of a line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DisplayMode {
+    /// Continue at the start of the next line, wrapping from the last line to the first.
+    #[default]
+    Overwrite,
+    /// Continue on the next line, scrolling everything up at the end of the last line.
+    VerticalScroll,
+    /// Stay on the current line, scrolling it left as each character is added.
+    HorizontalScroll,
+}
+
+impl FromStr for DisplayMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "overwrite" => Ok(DisplayMode::Overwrite),
+            "vertical" | "vscroll" => Ok(DisplayMode::VerticalScroll),
+            "horizontal" | "hscroll" => Ok(DisplayMode::HorizontalScroll),
+            other => Err(format!(
+                "Unknown display mode '{}'. Expected one of overwrite, vertical, horizontal.",
+                other
+            )),
+        }
+    }
+}
+
+/// Family of control codes understood by the display.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub enum CommandSet {
+    /// Birch DSP-V9FB and other Epson-compatible pole displays.
+    #[default]
+    Birch,
+    /// Epson ESC/POS customer displays (DM-D series).
+    EpsonDmD,
+    /// Noritake CU series character modules (e.g. CU20025).
+    NoritakeCu,
+    /// Futaba M202 series character modules.
+    Futaba,
+    /// A display described by a protocol definition file.
+    Custom(Arc<ProtocolDefinition>),
+}
+
+impl FromStr for CommandSet {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "birch" => Ok(CommandSet::Birch),
+            "epson" | "epson-dmd" => Ok(CommandSet::EpsonDmD),
+            "noritake" | "noritake-cu" => Ok(CommandSet::NoritakeCu),
+            "futaba" => Ok(CommandSet::Futaba),
+            other => Err(format!(
+                "Unknown command set '{}'. Expected one of birch, epson, noritake, futaba.",
+                other
+            )),
+        }
+    }
+}
+
+impl CommandSet {
+    /// Bytes that reset the display to its power-on state.
+    pub fn initialize(&self) -> Vec<u8> {
+        match self {
+            // ESC @
+            CommandSet::Birch | CommandSet::EpsonDmD => vec![CMD_ESC, 0x40],
+            // ESC I
+            CommandSet::NoritakeCu => vec![CMD_ESC, 0x49],
+            // RST
+            CommandSet::Futaba => vec![0x1F],
+            CommandSet::Custom(protocol) => protocol.initialize().to_vec(),
+        }
+    }
+
+    /// Bytes that clear the screen and home the cursor.
+    pub fn clear(&self) -> Vec<u8> {
+        match self {
+            CommandSet::Birch | CommandSet::EpsonDmD => vec![CMD_CLEAR],
+            CommandSet::NoritakeCu | CommandSet::Futaba => vec![0x0E],
+            CommandSet::Custom(protocol) => protocol.clear().to_vec(),
+        }
+    }
+
+    /// Bytes that select a display mode, if the command set has it.
+    pub fn set_display_mode(&self, mode: DisplayMode) -> Option<Vec<u8>> {
+        match (self, mode) {
+            // US MD1, US MD2, US MD3
+            (CommandSet::Birch | CommandSet::EpsonDmD, DisplayMode::Overwrite) => {
+                Some(vec![CMD_US, 0x01])
+            }
+            (CommandSet::Birch | CommandSet::EpsonDmD, DisplayMode::VerticalScroll) => {
+                Some(vec![CMD_US, 0x02])
+            }
+            (CommandSet::Birch | CommandSet::EpsonDmD, DisplayMode::HorizontalScroll) => {
+                Some(vec![CMD_US, 0x03])
+            }
+            // DC1, DC2
+            (CommandSet::NoritakeCu | CommandSet::Futaba, DisplayMode::Overwrite) => {
+                Some(vec![0x11])
+            }
+            (CommandSet::NoritakeCu | CommandSet::Futaba, DisplayMode::VerticalScroll) => {
+                Some(vec![0x12])
+            }
+            (CommandSet::NoritakeCu | CommandSet::Futaba, DisplayMode::HorizontalScroll) => None,
+            (CommandSet::Custom(_), _) => None,
+        }
+    }
+
+    /// Bytes that blank the cursor's row and move the cursor to its start, if the command set has them.
+    pub fn clear_line(&self) -> Option<Vec<u8>> {
+        match self {
+            // CAN
+            CommandSet::Birch | CommandSet::EpsonDmD => Some(vec![0x18]),
+            CommandSet::NoritakeCu | CommandSet::Futaba => None,
+            CommandSet::Custom(protocol) => protocol.clear_line().map(<[u8]>::to_vec),
+        }
+    }
+
+    /// Bytes for a single cursor movement, if the command set has a command for it.
+    pub fn move_cursor(&self, movement: CursorMove) -> Option<Vec<u8>> {
+        match (self, movement) {
+            // Only absolute moves are defined
+            (CommandSet::Custom(_), _) => None,
+            // HOM
+            (
+                CommandSet::Birch | CommandSet::EpsonDmD | CommandSet::NoritakeCu,
+                CursorMove::Home,
+            ) => Some(vec![0x0B]),
+            (CommandSet::Futaba, CursorMove::Home) => None,
+            // BS, HT
+            (_, CursorMove::Left) => Some(vec![0x08]),
+            (_, CursorMove::Right) => Some(vec![0x09]),
+            // US LF
+            (CommandSet::Birch | CommandSet::EpsonDmD, CursorMove::Up) => Some(vec![CMD_US, 0x0A]),
+            (CommandSet::NoritakeCu | CommandSet::Futaba, CursorMove::Up) => None,
+            // LF, CR
+            (_, CursorMove::Down | CursorMove::LineFeed) => Some(vec![0x0A]),
+            (_, CursorMove::CarriageReturn) => Some(vec![0x0D]),
+        }
+    }
+
+    /// Bytes that move the cursor to column `x`, row `y` (0-indexed) on a display `width` cells wide.
+    pub fn set_cursor(&self, x: u8, y: u8, width: u8) -> Vec<u8> {
+        match self {
+            // US $ x y, 1-indexed
+            CommandSet::Birch | CommandSet::EpsonDmD => {
+                let (x, y) = Position::new(x, y).to_one_based();
+                vec![CMD_US, b'$', x, y]
+            }
+            // ESC H n, linear address
+            CommandSet::NoritakeCu => vec![CMD_ESC, 0x48, linear_address(x, y, width)],
+            // DP n, linear address
+            CommandSet::Futaba => vec![0x10, linear_address(x, y, width)],
+            CommandSet::Custom(protocol) => protocol.set_cursor(x, y, width),
+        }
+    }
+
+    /// Bytes that set the display brightness.
+    pub fn set_brightness(&self, level: BrightnessLevel) -> Vec<u8> {
+        match self {
+            // US X n, n = 1..=4
+            CommandSet::Birch | CommandSet::EpsonDmD => {
+                let n = match level {
+                    BrightnessLevel::Percent20 => 1,
+                    BrightnessLevel::Percent40 => 2,
+                    BrightnessLevel::Percent60 => 3,
+                    BrightnessLevel::Percent100 => 4,
+                };
+                vec![CMD_US, 0x58, n]
+            }
+            // ESC L n, luminance in quarters (25/50/75/100%)
+            CommandSet::NoritakeCu => {
+                let n = match level {
+                    BrightnessLevel::Percent20 => 0x00,
+                    BrightnessLevel::Percent40 => 0x40,
+                    BrightnessLevel::Percent60 => 0x80,
+                    BrightnessLevel::Percent100 => 0xC0,
+                };
+                vec![CMD_ESC, 0x4C, n]
+            }
+            // DIM n
+            CommandSet::Futaba => {
+                let n = match level {
+                    BrightnessLevel::Percent20 => 0x20,
+                    BrightnessLevel::Percent40 => 0x40,
+                    BrightnessLevel::Percent60 => 0x60,
+                    BrightnessLevel::Percent100 => 0xFF,
+                };
+                vec![0x04, n]
+            }
+            // Nothing when the definition has no brightness levels
+            CommandSet::Custom(protocol) => protocol
+                .set_brightness(level)
+                .map(<[u8]>::to_vec)
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Bytes that show or hide the cursor, using the display's native style when shown.
+    pub fn set_cursor_visible(&self, visible: bool) -> Vec<u8> {
+        match self {
+            // US C n
+            CommandSet::Birch | CommandSet::EpsonDmD => vec![CMD_US, b'C', visible as u8],
+            CommandSet::NoritakeCu | CommandSet::Futaba => {
+                if visible {
+                    vec![0x13]
+                } else {
+                    vec![0x14]
+                }
+            }
+            // Nothing when the definition leaves it out
+            CommandSet::Custom(protocol) => protocol
+                .set_cursor_visible(visible)
+                .map(<[u8]>::to_vec)
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Bytes that select a cursor style, if the command set has it.
+    pub fn set_cursor_style(&self, style: CursorStyle) -> Option<Vec<u8>> {
+        match (self, style) {
+            (CommandSet::Custom(protocol), CursorStyle::Hidden) => {
+                protocol.set_cursor_visible(false).map(<[u8]>::to_vec)
+            }
+            (_, CursorStyle::Hidden) => Some(self.set_cursor_visible(false)),
+            // The Epson cursor is always a blinking block
+            (CommandSet::Birch | CommandSet::EpsonDmD, CursorStyle::Blinking) => {
+                Some(self.set_cursor_visible(true))
+            }
+            (CommandSet::NoritakeCu | CommandSet::Futaba, CursorStyle::Underline) => {
+                Some(vec![0x13])
+            }
+            (CommandSet::NoritakeCu | CommandSet::Futaba, CursorStyle::Blinking) => {
+                Some(vec![0x15])
+            }
+            _ => None,
+        }
+    }
+
+    /// Bytes that turn the display on (restoring `level`) or off, if the command set can.
+    pub fn set_display_enabled(&self, enabled: bool, level: BrightnessLevel) -> Option<Vec<u8>> {
+        match self {
+            // US E n, n = 255 blanks the display and n = 0 shows it steadily
+            CommandSet::Birch | CommandSet::EpsonDmD => {
+                Some(vec![CMD_US, b'E', if enabled { 0 } else { 0xFF }])
+            }
+            // DIM 0 turns the tube off
+            CommandSet::Futaba if enabled => Some(self.set_brightness(level)),
+            CommandSet::Futaba => Some(vec![0x04, 0x00]),
+            CommandSet::NoritakeCu | CommandSet::Custom(_) => None,
+        }
+    }
+
+    /// Bytes that turn reverse video on or off for text written after them, if the command set has it.
+    pub fn set_reverse(&self, enabled: bool) -> Option<Vec<u8>> {
+        match self {
+            // US r n
+            CommandSet::NoritakeCu => Some(vec![CMD_US, b'r', enabled as u8]),
+            CommandSet::Birch
+            | CommandSet::EpsonDmD
+            | CommandSet::Futaba
+            | CommandSet::Custom(_) => None,
+        }
+    }
+
+    /// Bytes that turn underlining on or off for text written after them, if the command set has it.
+    ///
+    /// None of the supported command sets underline text; only the cursor can be an underline.
+    pub fn set_underline(&self, enabled: bool) -> Option<Vec<u8>> {
+        let _ = enabled;
+        None
+    }
+
+    /// Bytes that blink the whole display every `interval`, or stop blinking for a zero interval.
+    pub fn blink(&self, interval: Duration) -> Option<Vec<u8>> {
+        match self {
+            // US E n, toggling every n × 50 ms (1..=254)
+            CommandSet::Birch | CommandSet::EpsonDmD => {
+                let n = if interval.is_zero() {
+                    0
+                } else {
+                    (interval.as_millis() / 50).clamp(1, 254) as u8
+                };
+                Some(vec![CMD_US, b'E', n])
+            }
+            CommandSet::NoritakeCu | CommandSet::Futaba | CommandSet::Custom(_) => None,
+        }
+    }
+
+    /// The named icons on models speaking this command set; empty if they have none.
+    ///
+    /// Epson-style displays number their icons from the left, so icons missing
+    /// here can still be reached as [`Annunciator::Numbered`].
+    pub fn annunciators(&self) -> &'static [Annunciator] {
+        match self {
+            CommandSet::EpsonDmD => &[
+                Annunciator::Sum,
+                Annunciator::Total,
+                Annunciator::Change,
+                Annunciator::Currency,
+            ],
+            CommandSet::Birch
+            | CommandSet::NoritakeCu
+            | CommandSet::Futaba
+            | CommandSet::Custom(_) => &[],
+        }
+    }
+
+    /// Bytes that light or darken `icon`, if the model has it.
+    pub fn set_annunciator(&self, icon: Annunciator, on: bool) -> Option<Vec<u8>> {
+        let position = self.annunciator_position(icon)?;
+        match self {
+            // US # n m, n = 1 lights icon m
+            CommandSet::EpsonDmD => Some(vec![CMD_US, b'#', on as u8, position]),
+            CommandSet::Birch
+            | CommandSet::NoritakeCu
+            | CommandSet::Futaba
+            | CommandSet::Custom(_) => None,
+        }
+    }
+
+    // Place of `icon` in the icon strip, counted from 1
+    pub(crate) fn annunciator_position(&self, icon: Annunciator) -> Option<u8> {
+        match icon {
+            Annunciator::Numbered(0) => None,
+            Annunciator::Numbered(n) => Some(n),
+            named => {
+                let index = self.annunciators().iter().position(|&a| a == named)?;
+                Some(index as u8 + 1)
+            }
+        }
+    }
+
+    /// Number of user-definable character slots.
+    pub fn max_custom_chars(&self) -> u8 {
+        match self {
+            CommandSet::Birch | CommandSet::EpsonDmD => 32,
+            CommandSet::NoritakeCu | CommandSet::Futaba => 16,
+            CommandSet::Custom(_) => 0,
+        }
+    }
+
+    /// Character code a custom slot is stored at, if the slot exists.
+    pub fn custom_char_code(&self, slot: u8) -> Option<u8> {
+        if slot < self.max_custom_chars() {
+            Some(0xE0 + slot)
+        } else {
+            None
+        }
+    }
+
+    /// Bytes that download `glyph` into the character RAM at `code`.
+    pub fn define_custom_char(&self, code: u8, glyph: &Glyph) -> Vec<u8> {
+        match self {
+            // ESC & y c1 c2 x d1..d5, column-major with the top dot in the MSB,
+            // followed by ESC % 1 to select the user-defined set
+            CommandSet::Birch | CommandSet::EpsonDmD => {
+                let mut cmd = vec![CMD_ESC, b'&', 1, code, code, 5];
+                for col in 0..5 {
+                    let mut column = 0u8;
+                    for (row, bits) in glyph.iter().enumerate() {
+                        if bits & (0b10000 >> col) != 0 {
+                            column |= 0x80 >> row;
+                        }
+                    }
+                    cmd.push(column);
+                }
+                cmd.extend_from_slice(&[CMD_ESC, b'%', 1]);
+                cmd
+            }
+            // ESC C n p1..p5, 35 dots packed row-major, LSB first
+            CommandSet::NoritakeCu | CommandSet::Futaba => {
+                let mut packed = [0u8; 5];
+                for (row, bits) in glyph.iter().enumerate() {
+                    for col in 0..5 {
+                        if bits & (0b10000 >> col) != 0 {
+                            let dot = row * 5 + col;
+                            packed[dot / 8] |= 1 << (dot % 8);
+                        }
+                    }
+                }
+                let mut cmd = vec![CMD_ESC, b'C', code];
+                cmd.extend_from_slice(&packed);
+                cmd
+            }
+            // Never reached, as definitions have no custom character slots
+            CommandSet::Custom(_) => Vec::new(),
+        }
+    }
+
+    /// Bytes that select a character code table, if the command set can switch tables.
+    pub fn set_code_page(&self, page: CodePage) -> Option<Vec<u8>> {
+        match self {
+            // ESC t n
+            CommandSet::Birch | CommandSet::EpsonDmD | CommandSet::NoritakeCu => {
+                Some(vec![CMD_ESC, b't', page.table_number()])
+            }
+            CommandSet::Futaba => None,
+            CommandSet::Custom(protocol) => protocol.set_code_page(page).map(<[u8]>::to_vec),
+        }
+    }
+
+    /// Bytes that ask the display to identify itself, if the command set has such a request.
+    pub fn identify_request(&self) -> Option<Vec<u8>> {
+        match self {
+            // GS I 1, transmit model ID
+            CommandSet::Birch | CommandSet::EpsonDmD => Some(vec![0x1D, b'I', 1]),
+            CommandSet::NoritakeCu | CommandSet::Futaba | CommandSet::Custom(_) => None,
+        }
+    }
+
+    /// Bytes that ask for the device type ID, if the command set has such a request.
+    pub fn type_request(&self) -> Option<Vec<u8>> {
+        match self {
+            // GS I 2
+            CommandSet::Birch | CommandSet::EpsonDmD => Some(vec![0x1D, b'I', 2]),
+            CommandSet::NoritakeCu | CommandSet::Futaba | CommandSet::Custom(_) => None,
+        }
+    }
+
+    /// Bytes that ask for the ROM version ID, if the command set has such a request.
+    pub fn rom_version_request(&self) -> Option<Vec<u8>> {
+        match self {
+            // GS I 3
+            CommandSet::Birch | CommandSet::EpsonDmD => Some(vec![0x1D, b'I', 3]),
+            CommandSet::NoritakeCu | CommandSet::Futaba | CommandSet::Custom(_) => None,
+        }
+    }
+
+    /// Bytes that ask for the real-time status byte, if the command set has such a request.
+    pub fn status_request(&self) -> Option<Vec<u8>> {
+        match self {
+            // DLE EOT 1
+            CommandSet::Birch | CommandSet::EpsonDmD => Some(vec![0x10, 0x04, 1]),
+            CommandSet::NoritakeCu | CommandSet::Futaba | CommandSet::Custom(_) => None,
+        }
+    }
+
+    /// Bytes that choose which device on a pass-through chain receives what follows,
+    /// if the command set supports pass-through.
+    pub fn select_peripheral(&self, peripheral: Peripheral) -> Option<Vec<u8>> {
+        let n = match peripheral {
+            Peripheral::Printer => 1,
+            Peripheral::Display => 2,
+            Peripheral::Both => 3,
+        };
+        match self {
+            // ESC = n
+            CommandSet::Birch | CommandSet::EpsonDmD => Some(vec![CMD_ESC, b'=', n]),
+            CommandSet::NoritakeCu | CommandSet::Futaba | CommandSet::Custom(_) => None,
+        }
+    }
+
+    /// Bytes that run the built-in self-test, if the command set can start it.
+    pub fn self_test(&self) -> Option<Vec<u8>> {
+        match self {
+            // US @
+            CommandSet::Birch | CommandSet::EpsonDmD => Some(vec![CMD_US, b'@']),
+            CommandSet::NoritakeCu | CommandSet::Futaba | CommandSet::Custom(_) => None,
+        }
+    }
+}
+
+// Cell number for displays addressed row by row; one byte reaches at most 256 cells
+fn linear_address(x: u8, y: u8, width: u8) -> u8 {
+    u8::try_from(Position::new(x, y).to_index(width)).unwrap_or(u8::MAX)
+}
@@ -0,0 +1,477 @@
+//! `config.toml` support for the command line tools.
+//!
+//! ```toml
+//! device = "/dev/ttyUSB0"
+//! width = 20
+//! height = 2
+//! baud_rate = 9600
+//! parity = "none"
+//! flow_control = "none"
+//! dtr = true
+//! auto_baud = false
+//! throttle = "16/5ms"
+//! command_set = "birch"
+//! code_page = "pc850"
+//! bidi = false
+//! locale = "de-DE"
+//! brightness = 60
+//! cursor = "off"
+//! startup_message = "Welcome!"
+//! offline_message = "Closed"
+//! reconnect_attempts = 5
+//! pass_through = false
+//!
+//! [splash]
+//! text = "ACME\nGroceries"
+//! duration_ms = 2000
+//!
+//! [symbols]
+//! "✓" = "v"
+//! "↑" = ["\uE000", "^"]
+//!
+//! [templates.sale]
+//! fields = [{ y = 0, text = "{item}" }, { y = 1, text = "{price}", align = "right" }]
+//!
+//! [[screens]]
+//! name = "hours"
+//! text = "Mon-Fri 9-18\nSat 10-14"
+//! ```
+//!
+//! Every key is optional. Files are looked up in `$XDG_CONFIG_HOME/vfd/config.toml`
+//! (falling back to `~/.config`) and then in each of `$XDG_CONFIG_DIRS` (falling
+//! back to `/etc/xdg`). On Windows they are looked up in `%APPDATA%\vfd\config.toml`
+//! and then `%PROGRAMDATA%\vfd\config.toml`, and `device` takes a COM port
+//! name such as `"COM3"`. A display on a serial device server is given as
+//! `device = "rfc2217://host:port"` or `"tcp://host:port"`.
+//!
+//! `[symbols]` adds to the built-in [`SymbolMap`]: each symbol is shown as the
+//! first of its replacements the code page has, and `\uE000` onwards name the
+//! custom glyphs in the display's CGRAM (see [`custom_char`](crate::custom_char)).
+//!
+//! `command_set` may also name a protocol definition file such as
+//! `"/etc/vfd/acme.toml"`, for displays without a built-in command set (see
+//! [`ProtocolDefinition`]).
+
+use crate::animation::Animation;
+use crate::builder::VfdBuilder;
+use crate::codepage::CodePage;
+use crate::command_set::{BrightnessLevel, CommandSet, CursorStyle};
+use crate::device_path::{self, Platform};
+use crate::number_format::Locale;
+use crate::paginator::Paginator;
+use crate::protocol_definition::ProtocolDefinition;
+use crate::screen_cycle::ScreenCycle;
+use crate::serial_profile::SerialProfile;
+use crate::splash::Splash;
+use crate::symbols::SymbolMap;
+use crate::template::Templates;
+use crate::throttle::Throttle;
+use serde::{Deserialize, Deserializer};
+use serialport::{FlowControl, Parity};
+use std::collections::BTreeMap;
+use std::env;
+use std::fmt::Display;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// `/dev/ttyUSB0`, or `COM3` on Windows.
+pub const DEFAULT_DEVICE: &str = Platform::current().default_device();
+pub const DEFAULT_WIDTH: u8 = 20;
+pub const DEFAULT_HEIGHT: u8 = 2;
+
+/// Settings read from a config file and/or the command line.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub device: Option<String>,
+    pub width: Option<u8>,
+    pub height: Option<u8>,
+    /// Overrides the command set's factory baud rate, see [`SerialProfile::for_command_set`].
+    pub baud_rate: Option<u32>,
+    /// Overrides the command set's factory parity: `none`, `even` or `odd`.
+    #[serde(deserialize_with = "parse_parity")]
+    pub parity: Option<Parity>,
+    /// Overrides the command set's factory flow control: `none`, `software` or `hardware`.
+    #[serde(deserialize_with = "parse_flow_control")]
+    pub flow_control: Option<FlowControl>,
+    /// Level to drive DTR to after opening the port.
+    pub dtr: Option<bool>,
+    /// Level to drive RTS to after opening the port.
+    pub rts: Option<bool>,
+    /// Try common serial settings until the display answers, see [`VfdBuilder::probe`].
+    pub auto_baud: Option<bool>,
+    /// Write pacing, see [`Throttle`]: `"off"`, `"480/s"` or `"16/5ms"`.
+    #[serde(deserialize_with = "parse_value")]
+    pub throttle: Option<Throttle>,
+    /// `birch`, `epson`, `noritake`, `futaba` or the path of a protocol definition file.
+    #[serde(deserialize_with = "parse_command_set_value")]
+    pub command_set: Option<CommandSet>,
+    #[serde(deserialize_with = "parse_value")]
+    pub code_page: Option<CodePage>,
+    /// Show Hebrew and Arabic text right to left, see [`Vfd::set_bidi`](crate::Vfd::set_bidi).
+    pub bidi: Option<bool>,
+    /// How numbers and prices are written, such as `"de-DE"`, see [`Locale`].
+    #[serde(deserialize_with = "parse_value")]
+    pub locale: Option<Locale>,
+    #[serde(deserialize_with = "parse_value")]
+    pub brightness: Option<BrightnessLevel>,
+    #[serde(deserialize_with = "parse_value")]
+    pub cursor: Option<CursorStyle>,
+    pub startup_message: Option<String>,
+    /// Shown by the daemon when it is stopped; the display is blanked when unset.
+    pub offline_message: Option<String>,
+    pub reconnect_attempts: Option<u32>,
+    /// Whether the display sits in front of a receipt printer, see [`VfdBuilder::pass_through`].
+    pub pass_through: Option<bool>,
+    /// Replacements for symbols missing from the code page, on top of the
+    /// built-in ones of [`SymbolMap`].
+    #[serde(deserialize_with = "parse_symbols")]
+    pub symbols: Option<SymbolMap>,
+    /// Named screen layouts, see [`Templates`].
+    pub templates: Option<Templates>,
+    /// Shown when the display is initialized on open, see [`VfdBuilder::splash`].
+    pub splash: Option<SplashConfig>,
+    /// Leave out the splash, see [`VfdBuilder::skip_splash`].
+    pub skip_splash: Option<bool>,
+    /// Text screens the daemon's buttons step through, in order.
+    pub screens: Option<Vec<ScreenConfig>>,
+}
+
+/// The `[splash]` table.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SplashConfig {
+    /// Text centered on the display, with `\n` starting a new row.
+    pub text: String,
+    /// Animation frames, each a list of rows, played instead of `text` when given.
+    pub frames: Vec<Vec<String>>,
+    /// How long each frame stays up.
+    pub frame_ms: u64,
+    /// How long the splash stays up at most.
+    pub duration_ms: u64,
+}
+
+impl Default for SplashConfig {
+    fn default() -> Self {
+        SplashConfig {
+            text: String::new(),
+            frames: Vec::new(),
+            frame_ms: 500,
+            duration_ms: 2000,
+        }
+    }
+}
+
+/// One of the `[[screens]]`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ScreenConfig {
+    pub name: String,
+    /// Text with `\n` starting a new row, shown a page at a time when too long.
+    pub text: String,
+}
+
+impl ScreenConfig {
+    /// The screens as a [`ScreenCycle`], each shown by a [`Paginator`].
+    pub fn cycle(screens: &[ScreenConfig]) -> ScreenCycle {
+        screens.iter().fold(ScreenCycle::new(), |cycle, screen| {
+            cycle.screen(&screen.name, Paginator::new(&screen.text))
+        })
+    }
+}
+
+impl SplashConfig {
+    pub fn splash(&self) -> Splash {
+        let duration = Duration::from_millis(self.duration_ms);
+        if self.frames.is_empty() {
+            return Splash::Text {
+                text: self.text.clone(),
+                duration,
+            };
+        }
+        let frame_duration = Duration::from_millis(self.frame_ms);
+        let animation = self
+            .frames
+            .iter()
+            .fold(Animation::new(), |animation, rows| {
+                let rows: Vec<&str> = rows.iter().map(String::as_str).collect();
+                animation.frame(&rows, frame_duration)
+            });
+        Splash::Animation {
+            animation,
+            duration,
+        }
+    }
+}
+
+// Values such as `brightness = 60` and `brightness = "60%"` both go through FromStr
+fn parse_value<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr,
+    T::Err: Display,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Raw {
+        Text(String),
+        Number(i64),
+    }
+
+    let raw = match Raw::deserialize(deserializer)? {
+        Raw::Text(text) => text,
+        Raw::Number(number) => number.to_string(),
+    };
+    raw.parse().map(Some).map_err(serde::de::Error::custom)
+}
+
+/// Parse a command set name, or load the protocol definition file at `s`
+/// when it ends in `.toml`.
+pub fn parse_command_set(s: &str) -> Result<CommandSet, String> {
+    if !s.trim().to_ascii_lowercase().ends_with(".toml") {
+        return s.parse();
+    }
+    let protocol = ProtocolDefinition::load(Path::new(s.trim())).map_err(|e| e.to_string())?;
+    Ok(CommandSet::Custom(Arc::new(protocol)))
+}
+
+fn parse_command_set_value<'de, D>(deserializer: D) -> Result<Option<CommandSet>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let raw = String::deserialize(deserializer)?;
+    parse_command_set(&raw)
+        .map(Some)
+        .map_err(serde::de::Error::custom)
+}
+
+// Each symbol maps to one replacement or a list of them, nearest first
+fn parse_symbols<'de, D>(deserializer: D) -> Result<Option<SymbolMap>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Replacements {
+        One(String),
+        Many(Vec<String>),
+    }
+
+    let mut symbols = SymbolMap::empty();
+    for (key, replacements) in BTreeMap::<String, Replacements>::deserialize(deserializer)? {
+        let mut chars = key.chars();
+        let (Some(symbol), None) = (chars.next(), chars.next()) else {
+            return Err(serde::de::Error::custom(format!(
+                "Expected a single character as symbol, got '{}'",
+                key
+            )));
+        };
+        match replacements {
+            Replacements::One(replacement) => symbols.insert(symbol, [replacement]),
+            Replacements::Many(replacements) => symbols.insert(symbol, replacements),
+        }
+    }
+    Ok(Some(symbols))
+}
+
+fn parse_parity<'de, D>(deserializer: D) -> Result<Option<Parity>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let raw = String::deserialize(deserializer)?;
+    SerialProfile::parse_parity(&raw)
+        .map(Some)
+        .map_err(serde::de::Error::custom)
+}
+
+fn parse_flow_control<'de, D>(deserializer: D) -> Result<Option<FlowControl>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let raw = String::deserialize(deserializer)?;
+    SerialProfile::parse_flow_control(&raw)
+        .map(Some)
+        .map_err(serde::de::Error::custom)
+}
+
+// $XDG_CONFIG_HOME, then $XDG_CONFIG_DIRS
+fn xdg_search_paths() -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+    let config_home = env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")));
+    if let Some(dir) = config_home {
+        paths.push(dir.join("vfd").join("config.toml"));
+    }
+    let config_dirs = env::var("XDG_CONFIG_DIRS")
+        .ok()
+        .filter(|dirs| !dirs.is_empty())
+        .unwrap_or_else(|| "/etc/xdg".to_string());
+    for dir in config_dirs.split(':').filter(|dir| !dir.is_empty()) {
+        paths.push(Path::new(dir).join("vfd").join("config.toml"));
+    }
+    paths
+}
+
+// %APPDATA%, then %PROGRAMDATA%
+fn windows_search_paths() -> Vec<PathBuf> {
+    ["APPDATA", "PROGRAMDATA"]
+        .into_iter()
+        .filter_map(env::var_os)
+        .filter(|dir| !dir.is_empty())
+        .map(|dir| PathBuf::from(dir).join("vfd").join("config.toml"))
+        .collect()
+}
+
+impl Config {
+    /// Parse a config file.
+    pub fn load(path: &Path) -> Result<Config, io::Error> {
+        let contents = std::fs::read_to_string(path)?;
+        toml::from_str(&contents).map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Invalid config file {}: {}", path.display(), e),
+            )
+        })
+    }
+
+    /// Candidate config file locations, most specific first.
+    pub fn search_paths() -> Vec<PathBuf> {
+        match Platform::current() {
+            Platform::Unix => xdg_search_paths(),
+            Platform::Windows => windows_search_paths(),
+        }
+    }
+
+    /// Load `path` if given, otherwise the first config file found in the XDG directories.
+    ///
+    /// Returns an empty config when no file exists.
+    pub fn discover(path: Option<&Path>) -> Result<Config, io::Error> {
+        if let Some(path) = path {
+            return Config::load(path);
+        }
+        match Config::search_paths().iter().find(|path| path.is_file()) {
+            Some(path) => Config::load(path),
+            None => Ok(Config::default()),
+        }
+    }
+
+    /// Settings from `overrides` take precedence over the ones in `self`.
+    ///
+    /// Templates are combined by name, each template in `overrides` replacing
+    /// the one of the same name in `self` as a whole.
+    pub fn merge(self, overrides: Config) -> Config {
+        Config {
+            device: overrides.device.or(self.device),
+            width: overrides.width.or(self.width),
+            height: overrides.height.or(self.height),
+            baud_rate: overrides.baud_rate.or(self.baud_rate),
+            parity: overrides.parity.or(self.parity),
+            flow_control: overrides.flow_control.or(self.flow_control),
+            dtr: overrides.dtr.or(self.dtr),
+            rts: overrides.rts.or(self.rts),
+            auto_baud: overrides.auto_baud.or(self.auto_baud),
+            throttle: overrides.throttle.or(self.throttle),
+            command_set: overrides.command_set.or(self.command_set),
+            code_page: overrides.code_page.or(self.code_page),
+            bidi: overrides.bidi.or(self.bidi),
+            locale: overrides.locale.or(self.locale),
+            brightness: overrides.brightness.or(self.brightness),
+            cursor: overrides.cursor.or(self.cursor),
+            startup_message: overrides.startup_message.or(self.startup_message),
+            offline_message: overrides.offline_message.or(self.offline_message),
+            reconnect_attempts: overrides.reconnect_attempts.or(self.reconnect_attempts),
+            pass_through: overrides.pass_through.or(self.pass_through),
+            symbols: overrides.symbols.or(self.symbols),
+            templates: match (self.templates, overrides.templates) {
+                (Some(mut templates), Some(overrides)) => {
+                    templates.extend(overrides);
+                    Some(templates)
+                }
+                (templates, overrides) => overrides.or(templates),
+            },
+            splash: overrides.splash.or(self.splash),
+            skip_splash: overrides.skip_splash.or(self.skip_splash),
+            screens: overrides.screens.or(self.screens),
+        }
+    }
+
+    /// The configured device, or [`DEFAULT_DEVICE`], normalized for this
+    /// platform with [`device_path::normalize`].
+    pub fn device_path(&self) -> String {
+        device_path::normalize(
+            self.device.as_deref().unwrap_or(DEFAULT_DEVICE),
+            Platform::current(),
+        )
+    }
+
+    /// A builder for the configured display, using defaults for anything unset.
+    pub fn builder(&self) -> VfdBuilder {
+        let command_set = self.command_set.clone().unwrap_or_default();
+        let mut builder = VfdBuilder::new(
+            &self.device_path(),
+            self.width.unwrap_or(DEFAULT_WIDTH),
+            self.height.unwrap_or(DEFAULT_HEIGHT),
+        )
+        .command_set(command_set.clone())
+        .profile(SerialProfile::for_command_set(command_set));
+        if let Some(baud_rate) = self.baud_rate {
+            builder = builder.baud_rate(baud_rate);
+        }
+        if let Some(parity) = self.parity {
+            builder = builder.parity(parity);
+        }
+        if let Some(flow_control) = self.flow_control {
+            builder = builder.flow_control(flow_control);
+        }
+        if let Some(level) = self.dtr {
+            builder = builder.dtr(level);
+        }
+        if let Some(level) = self.rts {
+            builder = builder.rts(level);
+        }
+        if let Some(throttle) = self.throttle {
+            builder = builder.throttle(throttle);
+        }
+        if let Some(code_page) = self.code_page {
+            builder = builder.code_page(code_page);
+        }
+        if let Some(bidi) = self.bidi {
+            builder = builder.bidi(bidi);
+        }
+        if let Some(locale) = self.locale {
+            builder = builder.locale(locale);
+        }
+        if let Some(level) = self.brightness {
+            builder = builder.brightness(level);
+        }
+        if let Some(style) = self.cursor {
+            builder = builder.cursor_style(style);
+        }
+        if let Some(attempts) = self.reconnect_attempts {
+            builder = builder.auto_reconnect(attempts);
+        }
+        if let Some(pass_through) = self.pass_through {
+            builder = builder.pass_through(pass_through);
+        }
+        if let Some(symbols) = &self.symbols {
+            let mut map = SymbolMap::default();
+            map.extend(symbols.clone());
+            builder = builder.symbols(map);
+        }
+        if let Some(templates) = &self.templates {
+            builder = builder.templates(templates.clone());
+        }
+        if let Some(splash) = &self.splash {
+            builder = builder.splash(splash.splash());
+        }
+        if let Some(skip) = self.skip_splash {
+            builder = builder.skip_splash(skip);
+        }
+        builder
+    }
+}
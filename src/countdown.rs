@@ -0,0 +1,226 @@
+use crate::display::VfdDisplay;
+use crate::error::VfdError;
+use crate::layout::Align;
+use crate::region::Region;
+use crate::widget::Widget;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+/// How a [`Countdown`] shows the time left, rounded up to whole seconds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CountdownFormat {
+    /// `90`
+    Seconds,
+    /// `1:30`, counting minutes past 59 rather than showing hours
+    #[default]
+    MinutesSeconds,
+    /// `0:01:30`
+    HoursMinutesSeconds,
+}
+
+impl CountdownFormat {
+    /// `remaining` as this format shows it.
+    pub fn format(&self, remaining: Duration) -> String {
+        let seconds = remaining.as_millis().div_ceil(1000) as u64;
+        match self {
+            CountdownFormat::Seconds => seconds.to_string(),
+            CountdownFormat::MinutesSeconds => format!("{}:{:02}", seconds / 60, seconds % 60),
+            CountdownFormat::HoursMinutesSeconds => format!(
+                "{}:{:02}:{:02}",
+                seconds / 3600,
+                seconds / 60 % 60,
+                seconds % 60
+            ),
+        }
+    }
+}
+
+/// The time left until a deadline, counting down on the first row of a region,
+/// e.g. "Ready in 4:59" on a pickup counter.
+///
+/// Nothing is drawn until [`Countdown::start`] is called, and the row is only
+/// rewritten when the text changes. Once the time is up the finished text is
+/// shown and the [`on_complete`](Countdown::on_complete) callback runs, once.
+///
+/// ```
+/// use std::time::Duration;
+/// use vfd_dsp_v9fb_over_serial::widget::Widget;
+/// use vfd_dsp_v9fb_over_serial::{CommandSet, Countdown, Region, Simulator};
+///
+/// let simulator = Simulator::new(CommandSet::Birch, 20, 2);
+/// let mut vfd = simulator.vfd();
+/// let mut countdown = Countdown::new(Region::new(0, 1, 20, 1)).label("Ready in ");
+/// countdown.start(Duration::from_secs(300));
+/// countdown.tick(&mut vfd).unwrap();
+/// assert_eq!(simulator.row(1), "   Ready in 5:00    ");
+/// ```
+pub struct Countdown {
+    region: Region,
+    align: Align,
+    format: CountdownFormat,
+    label: String,
+    finished_text: Option<String>,
+    on_complete: Option<Box<dyn FnMut() + Send>>,
+    deadline: Option<Instant>,
+    completed: bool,
+    shown: Option<String>,
+}
+
+impl Countdown {
+    /// A stopped countdown drawn centered on the first row of `region`.
+    pub fn new(region: Region) -> Self {
+        Countdown {
+            region,
+            align: Align::Center,
+            format: CountdownFormat::default(),
+            label: String::new(),
+            finished_text: None,
+            on_complete: None,
+            deadline: None,
+            completed: false,
+            shown: None,
+        }
+    }
+
+    pub fn align(mut self, align: Align) -> Self {
+        self.align = align;
+        self
+    }
+
+    pub fn format(mut self, format: CountdownFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Text shown before the time, such as `"Ready in "`.
+    pub fn label(mut self, label: &str) -> Self {
+        self.label = label.to_string();
+        self
+    }
+
+    /// Text shown once the time is up instead of the label and a zero time.
+    pub fn finished_text(mut self, text: &str) -> Self {
+        self.finished_text = Some(text.to_string());
+        self
+    }
+
+    /// Called from [`Widget::tick`] when the time is up.
+    pub fn on_complete<F: FnMut() + Send + 'static>(mut self, callback: F) -> Self {
+        self.on_complete = Some(Box::new(callback));
+        self
+    }
+
+    /// Count down `duration` from now, restarting if already running.
+    pub fn start(&mut self, duration: Duration) {
+        self.start_at(duration, Instant::now());
+    }
+
+    /// Like [`Countdown::start`], counting from `now`, e.g. to follow a
+    /// simulated clock.
+    pub fn start_at(&mut self, duration: Duration, now: Instant) {
+        self.deadline = Some(now + duration);
+        self.completed = false;
+    }
+
+    /// Stop counting, leaving the display as it is.
+    pub fn stop(&mut self) {
+        self.deadline = None;
+    }
+
+    /// Whether the countdown was started and its time is not up yet.
+    pub fn is_running(&self) -> bool {
+        self.deadline
+            .is_some_and(|deadline| Instant::now() < deadline)
+    }
+
+    /// Whether the time ran out since the countdown was last started.
+    pub fn is_finished(&self) -> bool {
+        self.finished_at(Instant::now())
+    }
+
+    fn finished_at(&self, now: Instant) -> bool {
+        self.deadline.is_some_and(|deadline| now >= deadline)
+    }
+
+    /// Time left, zero when stopped or finished.
+    pub fn remaining(&self) -> Duration {
+        self.remaining_at(Instant::now())
+    }
+
+    fn remaining_at(&self, now: Instant) -> Duration {
+        self.deadline
+            .map(|deadline| deadline.saturating_duration_since(now))
+            .unwrap_or_default()
+    }
+
+    /// What the countdown shows now.
+    pub fn text(&self) -> String {
+        self.text_at(Instant::now())
+    }
+
+    fn text_at(&self, now: Instant) -> String {
+        match &self.finished_text {
+            Some(text) if self.finished_at(now) => text.clone(),
+            _ => format!(
+                "{}{}",
+                self.label,
+                self.format.format(self.remaining_at(now))
+            ),
+        }
+    }
+
+    /// Like [`Widget::tick`], taking `now` as the current time, e.g. to
+    /// follow a simulated clock.
+    pub fn tick_at(
+        &mut self,
+        display: &mut dyn VfdDisplay,
+        now: Instant,
+    ) -> Result<bool, VfdError> {
+        if self.deadline.is_none() {
+            return Ok(false);
+        }
+        let text = self.text_at(now);
+        let changed = self.shown.as_ref() != Some(&text);
+        if changed {
+            self.draw(display, text)?;
+        }
+        if self.finished_at(now) && !self.completed {
+            self.completed = true;
+            if let Some(callback) = &mut self.on_complete {
+                callback();
+            }
+        }
+        Ok(changed)
+    }
+
+    fn draw(&mut self, display: &mut dyn VfdDisplay, text: String) -> Result<(), VfdError> {
+        self.region.write_line(display, 0, &text, self.align)?;
+        self.shown = Some(text);
+        Ok(())
+    }
+}
+
+impl fmt::Debug for Countdown {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Countdown")
+            .field("region", &self.region)
+            .field("format", &self.format)
+            .field("remaining", &self.remaining())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Widget for Countdown {
+    fn tick(&mut self, display: &mut dyn VfdDisplay) -> Result<bool, VfdError> {
+        self.tick_at(display, Instant::now())
+    }
+
+    fn render(&mut self, display: &mut dyn VfdDisplay) -> Result<(), VfdError> {
+        let text = self.text();
+        self.draw(display, text)
+    }
+
+    fn interval(&self) -> Duration {
+        Duration::from_millis(100)
+    }
+}
@@ -0,0 +1,86 @@
+use crate::number_format::NumberFormat;
+
+/// How amounts of money are written on the display.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Currency {
+    /// Symbol or code, e.g. `$` or `€`.
+    pub symbol: String,
+    /// Put the symbol after the amount (`9,99 €`) instead of before it (`$9.99`).
+    pub symbol_after: bool,
+    /// Character between the whole and fractional parts.
+    pub decimal_separator: char,
+    /// Character between each group of three whole digits, if any (`1,234.50`).
+    pub grouping_separator: Option<char>,
+    /// Number of fractional digits, i.e. how many minor units make one major unit (10^decimals).
+    pub decimals: u8,
+}
+
+impl Default for Currency {
+    fn default() -> Self {
+        Currency::usd()
+    }
+}
+
+impl Currency {
+    /// US dollars: `$1234.50`.
+    pub fn usd() -> Self {
+        Currency {
+            symbol: "$".to_string(),
+            symbol_after: false,
+            decimal_separator: '.',
+            grouping_separator: None,
+            decimals: 2,
+        }
+    }
+
+    /// Euros with continental formatting: `1234,50 €`.
+    pub fn eur() -> Self {
+        Currency {
+            symbol: "€".to_string(),
+            symbol_after: true,
+            decimal_separator: ',',
+            grouping_separator: None,
+            decimals: 2,
+        }
+    }
+
+    /// Pounds sterling: `£1234.50`.
+    pub fn gbp() -> Self {
+        Currency {
+            symbol: "£".to_string(),
+            ..Currency::usd()
+        }
+    }
+
+    /// Yen, which has no minor unit: `¥1234`.
+    pub fn jpy() -> Self {
+        Currency {
+            symbol: "¥".to_string(),
+            decimals: 0,
+            ..Currency::usd()
+        }
+    }
+
+    /// Format an amount given in minor units (cents for dollars), so `999` is `$9.99`.
+    pub fn format(&self, minor_units: i64) -> String {
+        let number = self.number_format().format_minor(minor_units);
+        let (sign, number) = match number.strip_prefix('-') {
+            Some(number) => ("-", number),
+            None => ("", number.as_str()),
+        };
+        if self.symbol_after {
+            format!("{}{} {}", sign, number, self.symbol)
+        } else {
+            format!("{}{}{}", sign, self.symbol, number)
+        }
+    }
+
+    /// How the amount is written, without the symbol.
+    pub fn number_format(&self) -> NumberFormat {
+        NumberFormat {
+            decimals: self.decimals,
+            decimal_separator: self.decimal_separator,
+            grouping_separator: self.grouping_separator,
+        }
+    }
+}
@@ -0,0 +1,543 @@
+//! Shared-display daemon: a line protocol for driving one [`Vfd`] from many clients.
+//!
+//! Each request is a single line, answered with `OK` or `ERR <message>`:
+//!
+//! ```text
+//! write <text>                      write at the cursor
+//! line <row> [left|center|right] <text>
+//! clear
+//! cursor <x> <y>
+//! brightness <20|40|60|100>
+//! display <on|off>
+//! blink <milliseconds>              0 stops blinking
+//! annunciator <icon> <on|off>       light an icon such as total
+//! alert <milliseconds> <text>       show text, then restore the display
+//! init
+//! ping
+//! dump                              answered with OK <rows>, then the text of each row
+//! ```
+//!
+//! With the `json` feature, lines starting with `{` are read as JSON
+//! [requests](crate::protocol::Request) instead and answered with a JSON
+//! [reply](crate::protocol::Reply).
+//!
+//! On Unix the daemon can also read a named pipe with [`serve_fifo`], showing
+//! whatever is written to it. Text is drawn at a cursor that `\n` and `\r`
+//! move like a terminal would, and a few control sequences are understood:
+//!
+//! ```text
+//! \f            clear the display
+//! \e[<row>;<col>H  move to a 1-based row and column (\e[H for the top-left)
+//! \e[2J         clear the display
+//! \e[K          clear to the end of the line
+//! ```
+//!
+//! [`on_termination`] gives the daemon a chance to leave the display in a
+//! sensible state when it is stopped, and with the `systemd` feature it can
+//! run as a socket-activated service (see the `systemd` module).
+
+use crate::alert::Alert;
+use crate::annunciator::Annunciator;
+use crate::command_set::BrightnessLevel;
+use crate::error::VfdError;
+use crate::layout::Align;
+use crate::text;
+use crate::vfd::Vfd;
+use std::str::FromStr;
+use std::sync::{Mutex, PoisonError};
+use std::time::Duration;
+
+/// A request understood by the daemon.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DaemonCommand {
+    Write {
+        text: String,
+    },
+    Line {
+        row: u8,
+        align: Align,
+        text: String,
+    },
+    Clear,
+    Cursor {
+        x: u8,
+        y: u8,
+    },
+    Brightness(BrightnessLevel),
+    Display(bool),
+    Blink(Duration),
+    Annunciator {
+        icon: Annunciator,
+        on: bool,
+    },
+    Alert(Alert),
+    Init,
+    Ping,
+    /// Report the text on the display, see [`Vfd::dump`].
+    Dump,
+}
+
+impl FromStr for DaemonCommand {
+    type Err = String;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let line = line.trim_end_matches(['\r', '\n']);
+        let (verb, rest) = line.split_once(' ').unwrap_or((line, ""));
+        match verb.to_ascii_lowercase().as_str() {
+            "write" => Ok(DaemonCommand::Write {
+                text: rest.to_string(),
+            }),
+            "line" => {
+                let (row, rest) = rest.split_once(' ').unwrap_or((rest, ""));
+                let row = row.parse().map_err(|_| format!("Invalid row '{}'", row))?;
+                let (first, tail) = rest.split_once(' ').unwrap_or((rest, ""));
+                let (align, text) = match first.parse::<Align>() {
+                    Ok(align) => (align, tail),
+                    Err(_) => (Align::Left, rest),
+                };
+                Ok(DaemonCommand::Line {
+                    row,
+                    align,
+                    text: text.to_string(),
+                })
+            }
+            "clear" => Ok(DaemonCommand::Clear),
+            "cursor" => {
+                let mut args = rest.split_whitespace().map(str::parse::<u8>);
+                match (args.next(), args.next()) {
+                    (Some(Ok(x)), Some(Ok(y))) => Ok(DaemonCommand::Cursor { x, y }),
+                    _ => Err("Usage: cursor <x> <y>".to_string()),
+                }
+            }
+            "brightness" => Ok(DaemonCommand::Brightness(rest.parse()?)),
+            "display" => match rest.trim() {
+                "on" => Ok(DaemonCommand::Display(true)),
+                "off" => Ok(DaemonCommand::Display(false)),
+                _ => Err("Usage: display <on|off>".to_string()),
+            },
+            "blink" => {
+                let millis = rest
+                    .trim()
+                    .parse()
+                    .map_err(|_| "Usage: blink <milliseconds>".to_string())?;
+                Ok(DaemonCommand::Blink(Duration::from_millis(millis)))
+            }
+            "annunciator" => {
+                let usage = || "Usage: annunciator <icon> <on|off>".to_string();
+                let mut args = rest.split_whitespace();
+                let icon = args.next().ok_or_else(usage)?.parse()?;
+                let on = match args.next() {
+                    Some("on") => true,
+                    Some("off") => false,
+                    _ => return Err(usage()),
+                };
+                Ok(DaemonCommand::Annunciator { icon, on })
+            }
+            "alert" => {
+                let (millis, text) = rest.split_once(' ').unwrap_or((rest, ""));
+                let millis = millis
+                    .parse()
+                    .map_err(|_| "Usage: alert <milliseconds> <text>".to_string())?;
+                Ok(DaemonCommand::Alert(Alert::new(
+                    text,
+                    Duration::from_millis(millis),
+                )))
+            }
+            "init" => Ok(DaemonCommand::Init),
+            "ping" => Ok(DaemonCommand::Ping),
+            "dump" => Ok(DaemonCommand::Dump),
+            "" => Err("Empty command".to_string()),
+            other => Err(format!("Unknown command '{}'", other)),
+        }
+    }
+}
+
+impl DaemonCommand {
+    /// Apply the command to the display. Commands that only answer, such as
+    /// [`DaemonCommand::Dump`], change nothing.
+    pub fn execute(&self, vfd: &mut Vfd) -> Result<(), VfdError> {
+        match self {
+            DaemonCommand::Write { text } => vfd.write_text(text),
+            DaemonCommand::Line { row, align, text } => vfd.write_line(*row, text, *align),
+            DaemonCommand::Clear => vfd.clear(),
+            DaemonCommand::Cursor { x, y } => vfd.set_cursor(*x, *y),
+            DaemonCommand::Brightness(level) => vfd.set_brightness(*level),
+            DaemonCommand::Display(enabled) => vfd.set_display_enabled(*enabled),
+            DaemonCommand::Blink(interval) => vfd.blink(*interval),
+            DaemonCommand::Annunciator { icon, on } => vfd.set_annunciator(*icon, *on),
+            DaemonCommand::Alert(alert) => vfd.show_alert_with(alert),
+            DaemonCommand::Init => vfd.initialize(),
+            DaemonCommand::Ping | DaemonCommand::Dump => Ok(()),
+        }
+    }
+
+    /// Apply the command to a display shared with other threads, which keep
+    /// drawing while an alert shows, see [`Alert::show`].
+    pub fn execute_shared(&self, vfd: &Mutex<Vfd>) -> Result<(), VfdError> {
+        match self {
+            DaemonCommand::Alert(alert) => alert.show(vfd),
+            command => command.execute(&mut vfd.lock().unwrap_or_else(PoisonError::into_inner)),
+        }
+    }
+}
+
+/// Parse and run one protocol line, producing the reply to send back.
+///
+/// ```
+/// use vfd_dsp_v9fb_over_serial::daemon::handle_line;
+/// use vfd_dsp_v9fb_over_serial::{CommandSet, Simulator};
+///
+/// let mut vfd = Simulator::new(CommandSet::Birch, 10, 2).vfd();
+/// assert_eq!(handle_line(&mut vfd, "write Hello"), "OK");
+/// assert_eq!(handle_line(&mut vfd, "dump"), "OK 2\nHello     \n          ");
+/// ```
+pub fn handle_line(vfd: &mut Vfd, line: &str) -> String {
+    #[cfg(feature = "json")]
+    if line.trim_start().starts_with('{') {
+        return handle_json(line, |command| run(&command, vfd));
+    }
+    match line.parse::<DaemonCommand>() {
+        Ok(DaemonCommand::Dump) => {
+            let rows = vfd.dump();
+            format!("OK {}\n{}", rows.len(), rows.join("\n"))
+        }
+        Ok(command) => match command.execute(vfd) {
+            Ok(()) => "OK".to_string(),
+            Err(e) => format!("ERR {}", e),
+        },
+        Err(e) => format!("ERR {}", e),
+    }
+}
+
+/// [`handle_line`] for a display shared with other threads, holding its lock
+/// only while drawing an alert, see [`DaemonCommand::execute_shared`].
+pub fn handle_shared_line(vfd: &Mutex<Vfd>, line: &str) -> String {
+    #[cfg(feature = "json")]
+    if line.trim_start().starts_with('{') {
+        return handle_json(line, |command| match command {
+            DaemonCommand::Alert(_) => command.execute_shared(vfd).map(|()| None),
+            command => run(
+                &command,
+                &mut vfd.lock().unwrap_or_else(PoisonError::into_inner),
+            ),
+        });
+    }
+    match line.parse::<DaemonCommand>() {
+        Ok(command @ DaemonCommand::Alert(_)) => match command.execute_shared(vfd) {
+            Ok(()) => "OK".to_string(),
+            Err(e) => format!("ERR {}", e),
+        },
+        _ => handle_line(
+            &mut vfd.lock().unwrap_or_else(PoisonError::into_inner),
+            line,
+        ),
+    }
+}
+
+// Runs `command`, giving back the rows for a dump
+#[cfg(feature = "json")]
+fn run(command: &DaemonCommand, vfd: &mut Vfd) -> Result<Option<Vec<String>>, VfdError> {
+    match command {
+        DaemonCommand::Dump => Ok(Some(vfd.dump())),
+        command => command.execute(vfd).map(|()| None),
+    }
+}
+
+// Answers a JSON request, leaving it to `run` to apply the command
+#[cfg(feature = "json")]
+fn handle_json(
+    line: &str,
+    run: impl FnOnce(DaemonCommand) -> Result<Option<Vec<String>>, VfdError>,
+) -> String {
+    use crate::protocol::{Reply, Request};
+
+    let reply = match serde_json::from_str::<Request>(line)
+        .map_err(|e| e.to_string())
+        .and_then(DaemonCommand::try_from)
+    {
+        Ok(command) => match run(command) {
+            Ok(Some(rows)) => Reply::rows(rows),
+            Ok(None) => Reply::ok(),
+            Err(e) => Reply::error(e.to_string()),
+        },
+        Err(e) => Reply::error(e),
+    };
+    serde_json::to_string(&reply).expect("replies serialize")
+}
+
+// Longest escape sequence kept while waiting for its final byte
+const MAX_ESCAPE: usize = 16;
+
+/// Interprets text written to the daemon's named pipe, with its control sequences.
+///
+/// Escape sequences split across several [`TextStream::feed`] calls are kept
+/// until they are complete.
+#[derive(Debug, Clone, Default)]
+pub struct TextStream {
+    x: u8,
+    y: u8,
+    // Start of an escape sequence still waiting for its final byte
+    pending: String,
+}
+
+impl TextStream {
+    pub fn new() -> Self {
+        TextStream::default()
+    }
+
+    /// Draw `text`, acting on any control characters in it.
+    pub fn feed(&mut self, vfd: &mut Vfd, text: &str) -> Result<(), VfdError> {
+        let mut input = std::mem::take(&mut self.pending);
+        input.push_str(text);
+        let mut run = String::new();
+        let mut chars = input.char_indices().peekable();
+        while let Some((start, c)) = chars.next() {
+            match c {
+                '\x1b' => {
+                    self.flush_run(vfd, &mut run)?;
+                    let sequence = &input[start..];
+                    if !sequence[1..].starts_with('[') {
+                        if sequence.len() == 1 {
+                            // Incomplete; wait for the rest
+                            self.pending = sequence.to_string();
+                            return Ok(());
+                        }
+                        continue;
+                    }
+                    let end = sequence[2..]
+                        .find(|c: char| !c.is_ascii_digit() && c != ';')
+                        .map(|i| i + 2);
+                    match end {
+                        // Sequences end in an ASCII letter; anything else is not one
+                        Some(end) if sequence.as_bytes()[end].is_ascii() => {
+                            self.escape(vfd, &sequence[2..=end])?;
+                            while chars.next_if(|(i, _)| *i <= start + end).is_some() {}
+                        }
+                        None if sequence.len() < MAX_ESCAPE => {
+                            self.pending = sequence.to_string();
+                            return Ok(());
+                        }
+                        // Not a sequence we understand: drop the escape character
+                        _ => {}
+                    }
+                }
+                '\x0c' => {
+                    self.flush_run(vfd, &mut run)?;
+                    self.clear(vfd)?;
+                }
+                '\n' => {
+                    self.flush_run(vfd, &mut run)?;
+                    let (_, height) = vfd.size();
+                    self.x = 0;
+                    self.y = if self.y + 1 >= height { 0 } else { self.y + 1 };
+                }
+                '\r' => {
+                    self.flush_run(vfd, &mut run)?;
+                    self.x = 0;
+                }
+                c if c.is_control() => {}
+                c => run.push(c),
+            }
+        }
+        self.flush_run(vfd, &mut run)
+    }
+
+    // Write plain text gathered since the last control character, clipped at the line end
+    fn flush_run(&mut self, vfd: &mut Vfd, run: &mut String) -> Result<(), VfdError> {
+        let (width, _) = vfd.size();
+        if run.is_empty() || self.x >= width {
+            run.clear();
+            return Ok(());
+        }
+        vfd.set_cursor(self.x, self.y)?;
+        vfd.write_text_truncate(run)?;
+        // Measured as shown, since transliteration can make it longer
+        let shown = vfd.text_mapping().apply(run);
+        let written = text::display_width(&shown).min((width - self.x) as usize);
+        self.x += written as u8;
+        run.clear();
+        Ok(())
+    }
+
+    fn clear(&mut self, vfd: &mut Vfd) -> Result<(), VfdError> {
+        vfd.clear()?;
+        self.x = 0;
+        self.y = 0;
+        Ok(())
+    }
+
+    // `body` is everything after `ESC [`, including the final letter
+    fn escape(&mut self, vfd: &mut Vfd, body: &str) -> Result<(), VfdError> {
+        let Some(command) = body.chars().last() else {
+            return Ok(());
+        };
+        let params = &body[..body.len() - command.len_utf8()];
+        let (width, height) = vfd.size();
+        match command {
+            'H' | 'f' => {
+                let mut numbers = params.split(';').map(|n| n.parse::<u8>().unwrap_or(1));
+                let row = numbers.next().unwrap_or(1).max(1) - 1;
+                let col = numbers.next().unwrap_or(1).max(1) - 1;
+                self.y = row.min(height.saturating_sub(1));
+                self.x = col.min(width);
+            }
+            'J' if params == "2" => self.clear(vfd)?,
+            'K' if self.x < width => {
+                vfd.set_cursor(self.x, self.y)?;
+                vfd.write_text(&" ".repeat((width - self.x) as usize))?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+#[cfg(unix)]
+mod unix {
+    use super::{TextStream, handle_shared_line};
+    use crate::logging;
+    use crate::vfd::Vfd;
+    use std::ffi::CString;
+    use std::fs::File;
+    use std::io::{self, BufRead, BufReader, Read, Write};
+    use std::os::unix::ffi::OsStrExt;
+    use std::os::unix::fs::FileTypeExt;
+    use std::os::unix::net::{UnixListener, UnixStream};
+    use std::path::Path;
+    use std::sync::{Arc, Mutex, PoisonError};
+    use std::{mem, process, ptr, thread};
+
+    /// Accept clients on a Unix domain socket at `path`, serving each on its own thread.
+    ///
+    /// A stale socket file left behind by a previous run is removed first; any
+    /// other file at `path` is an error. This only returns if accepting
+    /// connections fails.
+    pub fn serve_unix(vfd: Arc<Mutex<Vfd>>, path: &Path) -> Result<(), io::Error> {
+        serve_listener(vfd, bind_unix(path)?)
+    }
+
+    /// Listen on a Unix domain socket at `path`, removing a stale socket file
+    /// left behind by a previous run first. Any other file at `path` is an error.
+    pub fn bind_unix(path: &Path) -> Result<UnixListener, io::Error> {
+        match std::fs::symlink_metadata(path) {
+            Ok(metadata) if metadata.file_type().is_socket() => std::fs::remove_file(path)?,
+            Ok(_) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::AlreadyExists,
+                    format!("{} exists and is not a socket", path.display()),
+                ));
+            }
+            Err(_) => {}
+        }
+        UnixListener::bind(path)
+    }
+
+    /// Like [`serve_unix`], on a socket that is already listening, such as one
+    /// passed in by systemd.
+    pub fn serve_listener(vfd: Arc<Mutex<Vfd>>, listener: UnixListener) -> Result<(), io::Error> {
+        for stream in listener.incoming() {
+            let stream = stream?;
+            let vfd = Arc::clone(&vfd);
+            thread::spawn(move || {
+                if let Err(e) = serve_client(&vfd, stream) {
+                    logging::warning!("Client connection failed: {}", e);
+                }
+            });
+        }
+        Ok(())
+    }
+
+    /// Create a named pipe at `path` and show everything written to it.
+    ///
+    /// An existing pipe is reused; any other file at `path` is an error. Writers may
+    /// come and go; this only returns if the pipe cannot be opened or read.
+    pub fn serve_fifo(vfd: Arc<Mutex<Vfd>>, path: &Path) -> Result<(), io::Error> {
+        match std::fs::metadata(path) {
+            Ok(metadata) if metadata.file_type().is_fifo() => {}
+            Ok(_) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::AlreadyExists,
+                    format!("{} exists and is not a FIFO", path.display()),
+                ));
+            }
+            Err(_) => {
+                let c_path = CString::new(path.as_os_str().as_bytes())?;
+                // SAFETY: `c_path` is a valid NUL-terminated string for the duration of the call
+                if unsafe { libc::mkfifo(c_path.as_ptr(), 0o622) } != 0 {
+                    return Err(io::Error::last_os_error());
+                }
+            }
+        }
+
+        let mut stream = TextStream::new();
+        loop {
+            // Blocks until a writer opens the pipe; reads end when the last one closes it
+            let mut fifo = File::open(path)?;
+            let mut buffer = [0u8; 512];
+            let mut partial = Vec::new();
+            loop {
+                let read = fifo.read(&mut buffer)?;
+                if read == 0 {
+                    break;
+                }
+                partial.extend_from_slice(&buffer[..read]);
+                // Keep a multi-byte character split across reads for the next one
+                let valid = match std::str::from_utf8(&partial) {
+                    Ok(text) => text.len(),
+                    Err(e) if e.error_len().is_none() => e.valid_up_to(),
+                    Err(_) => partial.len(),
+                };
+                let text = String::from_utf8_lossy(&partial[..valid]).into_owned();
+                partial.drain(..valid);
+                let mut vfd = vfd.lock().unwrap_or_else(PoisonError::into_inner);
+                if let Err(e) = stream.feed(&mut vfd, &text) {
+                    logging::warning!("FIFO write failed: {}", e);
+                }
+            }
+        }
+    }
+
+    /// Run `on_exit` and then exit once SIGTERM or SIGINT arrives.
+    ///
+    /// The signals are blocked in the calling thread, and so in every thread
+    /// it starts afterwards, and waited for on a thread of their own. Call
+    /// this before starting any other threads.
+    pub fn on_termination<F>(on_exit: F) -> Result<(), io::Error>
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        // SAFETY: sigemptyset initializes the set before it is read, and every
+        // pointer passed is valid for the duration of its call
+        let signals = unsafe {
+            let mut signals: libc::sigset_t = mem::zeroed();
+            libc::sigemptyset(&mut signals);
+            libc::sigaddset(&mut signals, libc::SIGTERM);
+            libc::sigaddset(&mut signals, libc::SIGINT);
+            let result = libc::pthread_sigmask(libc::SIG_BLOCK, &signals, ptr::null_mut());
+            if result != 0 {
+                return Err(io::Error::from_raw_os_error(result));
+            }
+            signals
+        };
+        thread::spawn(move || {
+            let mut signal = 0;
+            // SAFETY: both pointers are valid for the duration of the call
+            while unsafe { libc::sigwait(&signals, &mut signal) } != 0 {}
+            on_exit();
+            process::exit(0);
+        });
+        Ok(())
+    }
+
+    fn serve_client(vfd: &Mutex<Vfd>, stream: UnixStream) -> Result<(), io::Error> {
+        let mut writer = stream.try_clone()?;
+        for line in BufReader::new(stream).lines() {
+            let line = line?;
+            writeln!(writer, "{}", handle_shared_line(vfd, &line))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(unix)]
+pub use unix::{bind_unix, on_termination, serve_fifo, serve_listener, serve_unix};
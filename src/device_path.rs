@@ -0,0 +1,99 @@
+//! Serial device names across operating systems.
+//!
+//! Unix systems name serial ports by their device node (`/dev/ttyUSB0`),
+//! Windows by a `COM` name (`COM3`, `\\.\COM12`). The functions here take the
+//! [`Platform`] explicitly so the rules for either can be exercised anywhere.
+
+use std::cmp::Ordering;
+
+/// Operating system family, which decides what serial device names look like.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Platform {
+    Unix,
+    Windows,
+}
+
+impl Platform {
+    /// The platform this crate was built for.
+    pub const fn current() -> Self {
+        if cfg!(windows) {
+            Platform::Windows
+        } else {
+            Platform::Unix
+        }
+    }
+
+    /// Where a USB serial adapter usually shows up.
+    pub const fn default_device(self) -> &'static str {
+        match self {
+            Platform::Unix => "/dev/ttyUSB0",
+            Platform::Windows => "COM3",
+        }
+    }
+}
+
+/// Turn a device name as typed by a user into one the serial port can be opened with.
+///
+/// On Unix, bare names such as `ttyUSB0` get `/dev/` prepended. On Windows,
+/// `com3` and plain port numbers such as `3` become `COM3`, and the `\\.\`
+/// device namespace prefix is kept. Anything else is returned trimmed.
+///
+/// ```
+/// use vfd_dsp_v9fb_over_serial::device_path::{Platform, normalize};
+///
+/// assert_eq!(normalize("ttyACM0", Platform::Unix), "/dev/ttyACM0");
+/// assert_eq!(normalize("com12", Platform::Windows), "COM12");
+/// assert_eq!(normalize(r"\\.\COM12", Platform::Windows), r"\\.\COM12");
+/// ```
+pub fn normalize(path: &str, platform: Platform) -> String {
+    let path = path.trim();
+    match platform {
+        Platform::Unix if !path.is_empty() && !path.contains('/') => format!("/dev/{}", path),
+        Platform::Unix => path.to_string(),
+        Platform::Windows => {
+            let (prefix, name) = match path.strip_prefix(r"\\.\") {
+                Some(name) => (r"\\.\", name),
+                None => ("", path),
+            };
+            match com_number(name) {
+                Some(number) => format!("{}COM{}", prefix, number),
+                None => path.to_string(),
+            }
+        }
+    }
+}
+
+/// The name to show for `path`, without the Windows device namespace prefix.
+pub fn display_name(path: &str) -> &str {
+    path.strip_prefix(r"\\.\").unwrap_or(path)
+}
+
+/// Order device names the way people count them: `COM9` before `COM10`,
+/// `/dev/ttyUSB2` before `/dev/ttyUSB10`.
+pub fn compare(a: &str, b: &str) -> Ordering {
+    let (a_stem, a_number) = split_number(display_name(a));
+    let (b_stem, b_number) = split_number(display_name(b));
+    a_stem
+        .to_ascii_lowercase()
+        .cmp(&b_stem.to_ascii_lowercase())
+        .then(a_number.cmp(&b_number))
+        .then_with(|| a.cmp(b))
+}
+
+// The port number of `COMn` (any case) or a bare `n`
+fn com_number(name: &str) -> Option<u32> {
+    let digits = match name.get(..3) {
+        Some(prefix) if prefix.eq_ignore_ascii_case("com") => &name[3..],
+        _ => name,
+    };
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    digits.parse().ok().filter(|&number| number > 0)
+}
+
+// A name's leading part and the number it ends in, if any
+fn split_number(name: &str) -> (&str, Option<u64>) {
+    let stem = name.trim_end_matches(|c: char| c.is_ascii_digit());
+    (stem, name[stem.len()..].parse().ok())
+}
@@ -0,0 +1,115 @@
+use crate::command_set::CommandSet;
+use crate::device_path;
+use crate::error::VfdError;
+use crate::serial_profile::SerialProfile;
+use serialport::{SerialPortInfo, SerialPortType};
+use std::io::{self, Read, Write};
+use std::time::Duration;
+
+// How long to wait for a probed device to answer
+const PROBE_TIMEOUT: Duration = Duration::from_millis(300);
+
+/// A serial port that may have a display attached.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PortCandidate {
+    /// Path to pass to [`Vfd::new`](crate::Vfd::new), e.g. `/dev/ttyUSB0` or `COM3`.
+    pub path: String,
+    /// Human readable description of the adapter, when known.
+    pub description: String,
+    /// USB vendor and product IDs, for USB adapters.
+    pub usb_id: Option<(u16, u16)>,
+    /// Reply to the identify request, when the port was probed and answered.
+    pub probe_response: Option<Vec<u8>>,
+}
+
+impl PortCandidate {
+    fn from_info(info: SerialPortInfo) -> Self {
+        let (description, usb_id) = match info.port_type {
+            SerialPortType::UsbPort(usb) => {
+                let name = [usb.manufacturer, usb.product]
+                    .into_iter()
+                    .flatten()
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                let name = if name.is_empty() {
+                    "USB serial".to_string()
+                } else {
+                    name
+                };
+                (name, Some((usb.vid, usb.pid)))
+            }
+            SerialPortType::PciPort => ("PCI serial".to_string(), None),
+            SerialPortType::BluetoothPort => ("Bluetooth serial".to_string(), None),
+            SerialPortType::Unknown => ("Serial port".to_string(), None),
+        };
+        PortCandidate {
+            path: info.port_name,
+            description,
+            usb_id,
+            probe_response: None,
+        }
+    }
+}
+
+/// List serial ports that could host a display, USB adapters first, then by
+/// name in [`device_path::compare`] order.
+///
+/// With `probe`, each port is opened with that command set's factory
+/// [`SerialProfile`] and sent its identify request; ports that answer carry the reply in
+/// [`PortCandidate::probe_response`]. Ports that cannot be opened are still listed.
+pub fn discover(probe: Option<CommandSet>) -> Result<Vec<PortCandidate>, VfdError> {
+    let ports = serialport::available_ports()?;
+    let mut candidates: Vec<PortCandidate> =
+        ports.into_iter().map(PortCandidate::from_info).collect();
+    candidates.sort_by(|a, b| {
+        a.usb_id
+            .is_none()
+            .cmp(&b.usb_id.is_none())
+            .then_with(|| device_path::compare(&a.path, &b.path))
+    });
+
+    if let Some(command_set) = probe
+        && let Some(request) = command_set.identify_request()
+    {
+        let profile = SerialProfile::for_command_set(command_set);
+        for candidate in &mut candidates {
+            candidate.probe_response = probe_port(&candidate.path, profile, &request)
+                .ok()
+                .flatten();
+        }
+    }
+    Ok(candidates)
+}
+
+// Send the identify request and collect whatever arrives before the timeout
+fn probe_port(
+    path: &str,
+    profile: SerialProfile,
+    request: &[u8],
+) -> Result<Option<Vec<u8>>, io::Error> {
+    let mut port = serialport::new(path, profile.baud_rate)
+        .data_bits(profile.data_bits)
+        .parity(profile.parity)
+        .stop_bits(profile.stop_bits)
+        .flow_control(profile.flow_control)
+        .timeout(PROBE_TIMEOUT)
+        .open()?;
+    port.write_all(request)?;
+    port.flush()?;
+
+    let mut response = Vec::new();
+    let mut buf = [0u8; 64];
+    loop {
+        match port.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => response.extend_from_slice(&buf[..n]),
+            Err(e) if e.kind() == io::ErrorKind::TimedOut => break,
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(if response.is_empty() {
+        None
+    } else {
+        Some(response)
+    })
+}
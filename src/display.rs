@@ -0,0 +1,54 @@
+use crate::codepage::TextMapping;
+use crate::command_set::BrightnessLevel;
+use crate::error::VfdError;
+use crate::layout::{self, Align};
+use crate::text;
+
+/// Common operations shared by every display backend.
+pub trait VfdDisplay {
+    /// Display geometry as (columns, rows).
+    fn size(&self) -> (u8, u8);
+
+    /// Clear the screen and return the cursor to home.
+    fn clear(&mut self) -> Result<(), VfdError>;
+
+    /// Move the cursor to column `x`, row `y`.
+    fn set_cursor(&mut self, x: u8, y: u8) -> Result<(), VfdError>;
+
+    /// Current cursor position as (column, row).
+    fn get_cursor(&self) -> (u8, u8);
+
+    /// Write text at the cursor, wrapping onto following lines if needed.
+    fn write_text(&mut self, text: &str) -> Result<(), VfdError>;
+
+    /// How text is mapped onto the display's cells, see [`TextMapping`].
+    /// Layouts measure text after applying it. Text is left as it is by default.
+    fn text_mapping(&self) -> TextMapping {
+        TextMapping::identity()
+    }
+
+    /// Replace row `row` with `text`, aligned and padded to the full width.
+    fn write_line(&mut self, row: u8, text: &str, align: Align) -> Result<(), VfdError> {
+        let (width, _) = self.size();
+        let text = self.text_mapping().apply(text);
+        self.set_cursor(0, row)?;
+        self.write_text(&layout::align(&text, width as usize, align))
+    }
+
+    /// Show `text` from column `x` on row `y`, cut off at the end of the row.
+    ///
+    /// A [`Vfd`](crate::Vfd) sends only the cells that differ from what it
+    /// shows, see [`Vfd::update_span`](crate::Vfd::update_span).
+    fn update_span(&mut self, x: u8, y: u8, text: &str) -> Result<(), VfdError> {
+        let (width, _) = self.size();
+        let text = self.text_mapping().apply(text);
+        self.set_cursor(x, y)?;
+        self.write_text(text::truncate_to_width(
+            &text,
+            width.saturating_sub(x) as usize,
+        ))
+    }
+
+    /// Change the display brightness.
+    fn set_brightness(&mut self, level: BrightnessLevel) -> Result<(), VfdError>;
+}
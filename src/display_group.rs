@@ -0,0 +1,254 @@
+use crate::command_set::BrightnessLevel;
+use crate::display::VfdDisplay;
+use crate::error::VfdError;
+use crate::position::Position;
+use crate::text;
+use std::str::FromStr;
+
+/// How a [`DisplayGroup`] spreads what is drawn on it over its members.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GroupLayout {
+    /// Every member shows the same content.
+    #[default]
+    Mirror,
+    /// Members sit left to right and form one wider display.
+    SideBySide,
+    /// Members sit top to bottom and form one taller display.
+    Stacked,
+}
+
+impl FromStr for GroupLayout {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "mirror" => Ok(GroupLayout::Mirror),
+            "side-by-side" | "horizontal" => Ok(GroupLayout::SideBySide),
+            "stacked" | "vertical" => Ok(GroupLayout::Stacked),
+            other => Err(format!(
+                "Unknown group layout '{}'. Expected one of mirror, side-by-side, stacked.",
+                other
+            )),
+        }
+    }
+}
+
+/// Several displays driven together, each known by a name.
+///
+/// Members can be addressed one at a time with [`DisplayGroup::display`], all
+/// at once with [`DisplayGroup::for_each`], or through the group's own
+/// [`VfdDisplay`] implementation, which mirrors or splits the content
+/// according to its [`GroupLayout`].
+///
+/// ```
+/// use vfd_dsp_v9fb_over_serial::{Align, DisplayGroup, GroupLayout, MockVfd, VfdDisplay};
+///
+/// let mut group = DisplayGroup::new(GroupLayout::SideBySide);
+/// group.add("left", MockVfd::new(10, 2));
+/// group.add("right", MockVfd::new(10, 2));
+/// assert_eq!(group.size(), (20, 2));
+///
+/// group.write_line(0, "Welcome to the shop", Align::Left).unwrap();
+/// group.display("right").unwrap().write_line(1, "Till 2", Align::Right).unwrap();
+/// ```
+pub struct DisplayGroup {
+    layout: GroupLayout,
+    members: Vec<(String, Box<dyn VfdDisplay + Send>)>,
+    cursor_x: u8,
+    cursor_y: u8,
+}
+
+impl DisplayGroup {
+    pub fn new(layout: GroupLayout) -> Self {
+        DisplayGroup {
+            layout,
+            members: Vec::new(),
+            cursor_x: 0,
+            cursor_y: 0,
+        }
+    }
+
+    /// Add `display` under `name`, replacing any member already called that.
+    ///
+    /// Members of a split layout are placed in the order they were first added.
+    pub fn add(&mut self, name: &str, display: impl VfdDisplay + Send + 'static) -> &mut Self {
+        let display: Box<dyn VfdDisplay + Send> = Box::new(display);
+        match self.members.iter_mut().find(|(n, _)| n == name) {
+            Some((_, member)) => *member = display,
+            None => self.members.push((name.to_string(), display)),
+        }
+        self
+    }
+
+    /// Take the member called `name` out of the group.
+    pub fn remove(&mut self, name: &str) -> Option<Box<dyn VfdDisplay + Send>> {
+        let index = self.members.iter().position(|(n, _)| n == name)?;
+        Some(self.members.remove(index).1)
+    }
+
+    /// The member called `name`, to draw on it alone.
+    pub fn display(&mut self, name: &str) -> Option<&mut (dyn VfdDisplay + Send + 'static)> {
+        self.members
+            .iter_mut()
+            .find(|(n, _)| n == name)
+            .map(|(_, display)| display.as_mut())
+    }
+
+    /// Member names in layout order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.members.iter().map(|(name, _)| name.as_str())
+    }
+
+    pub fn len(&self) -> usize {
+        self.members.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+
+    pub fn layout(&self) -> GroupLayout {
+        self.layout
+    }
+
+    pub fn set_layout(&mut self, layout: GroupLayout) {
+        self.layout = layout;
+    }
+
+    /// Run `f` on every member.
+    ///
+    /// A failing member does not stop the others; the first error is returned
+    /// once all have been tried.
+    pub fn for_each<F>(&mut self, mut f: F) -> Result<(), VfdError>
+    where
+        F: FnMut(&str, &mut dyn VfdDisplay) -> Result<(), VfdError>,
+    {
+        let mut first_error = None;
+        for (name, display) in &mut self.members {
+            if let Err(e) = f(name, display.as_mut()) {
+                first_error.get_or_insert(e);
+            }
+        }
+        first_error.map_or(Ok(()), Err)
+    }
+
+    // Index of the member covering group cell (x, y) and the cell's position on it
+    fn locate(&self, x: u8, y: u8) -> Option<(usize, u8, u8)> {
+        let (mut x, mut y) = (x, y);
+        for (index, (_, display)) in self.members.iter().enumerate() {
+            let (width, height) = display.size();
+            match self.layout {
+                GroupLayout::SideBySide if x >= width => x -= width,
+                GroupLayout::Stacked if y >= height => y -= height,
+                _ => return Some((index, x, y)),
+            }
+        }
+        None
+    }
+
+    // Write text from the group cursor on, crossing from member to member and wrapping at the group's right edge
+    fn write_split(&mut self, text: &str) -> Result<(), VfdError> {
+        if self.members.is_empty() {
+            return Err(VfdError::NotSupported(
+                "Writing to an empty group".to_string(),
+            ));
+        }
+        let (width, height) = self.size();
+        let used = self.cursor_y as usize * width as usize + self.cursor_x as usize;
+        let available = (width as usize * height as usize).saturating_sub(used);
+
+        // Lay the text out first, measured the way each member maps it, so that
+        // nothing is sent for text that does not fit
+        let mut chunks = Vec::new();
+        let (mut x, mut y) = (self.cursor_x, self.cursor_y);
+        let mut remaining = text.to_string();
+        let mut placed = 0;
+        while !remaining.is_empty() {
+            if x >= width {
+                x = 0;
+                y += 1;
+            }
+            let Some((index, local_x, local_y)) = self.locate(x, y).filter(|_| y < height) else {
+                return Err(VfdError::TextTooLong {
+                    max: available,
+                    got: placed + text::display_width(&remaining),
+                });
+            };
+            let display = &self.members[index].1;
+            let mapped = display.text_mapping().apply(&remaining);
+            let (member_width, _) = display.size();
+            let (chunk, rest) = text::split_at_width(&mapped, (member_width - local_x) as usize);
+            let chunk_width = text::display_width(chunk);
+            chunks.push((index, local_x, local_y, chunk.to_string()));
+            placed += chunk_width;
+            x += chunk_width as u8;
+            remaining = rest.to_string();
+        }
+
+        for (index, local_x, local_y, chunk) in chunks {
+            let display = &mut self.members[index].1;
+            display.set_cursor(local_x, local_y)?;
+            display.write_text(&chunk)?;
+        }
+        (self.cursor_x, self.cursor_y) = (x, y);
+        Ok(())
+    }
+}
+
+impl VfdDisplay for DisplayGroup {
+    /// Mirrored groups are as large as their smallest member; split groups add
+    /// up their members along the split.
+    fn size(&self) -> (u8, u8) {
+        let sizes = self.members.iter().map(|(_, display)| display.size());
+        let min = |(w, h): (u8, u8), (width, height): (u8, u8)| (w.min(width), h.min(height));
+        match self.layout {
+            GroupLayout::Mirror => sizes.reduce(min).unwrap_or((0, 0)),
+            GroupLayout::SideBySide => sizes
+                .reduce(|(w, h), (width, height)| (w.saturating_add(width), h.min(height)))
+                .unwrap_or((0, 0)),
+            GroupLayout::Stacked => sizes
+                .reduce(|(w, h), (width, height)| (w.min(width), h.saturating_add(height)))
+                .unwrap_or((0, 0)),
+        }
+    }
+
+    fn clear(&mut self) -> Result<(), VfdError> {
+        (self.cursor_x, self.cursor_y) = (0, 0);
+        self.for_each(|_, display| display.clear())
+    }
+
+    fn set_cursor(&mut self, x: u8, y: u8) -> Result<(), VfdError> {
+        Position::new(x, y).check(self.size())?;
+        (self.cursor_x, self.cursor_y) = (x, y);
+        if self.layout == GroupLayout::Mirror {
+            return self.for_each(|_, display| display.set_cursor(x, y));
+        }
+        Ok(())
+    }
+
+    fn get_cursor(&self) -> (u8, u8) {
+        (self.cursor_x, self.cursor_y)
+    }
+
+    fn write_text(&mut self, text: &str) -> Result<(), VfdError> {
+        match self.layout {
+            GroupLayout::Mirror => {
+                // Members that map the text differently end up apart; the group
+                // cursor follows the one that got furthest
+                let length = self
+                    .members
+                    .iter()
+                    .map(|(_, display)| text::display_width(&display.text_mapping().apply(text)))
+                    .max()
+                    .unwrap_or(0);
+                self.cursor_x = self.cursor_x.saturating_add(length as u8);
+                self.for_each(|_, display| display.write_text(text))
+            }
+            GroupLayout::SideBySide | GroupLayout::Stacked => self.write_split(text),
+        }
+    }
+
+    fn set_brightness(&mut self, level: BrightnessLevel) -> Result<(), VfdError> {
+        self.for_each(|_, display| display.set_brightness(level))
+    }
+}
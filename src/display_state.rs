@@ -0,0 +1,25 @@
+use crate::codepage::CodePage;
+use crate::command_set::{BrightnessLevel, DisplayMode};
+use crate::frame_buffer::FrameBuffer;
+use crate::glyph::Glyph;
+use crate::position::Position;
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+/// Snapshot of what a display shows and how it is set up, see
+/// [`Vfd::save_state`](crate::Vfd::save_state).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DisplayState {
+    /// Text on every row.
+    pub frame: FrameBuffer,
+    pub cursor: Position,
+    pub brightness: BrightnessLevel,
+    pub display_mode: DisplayMode,
+    /// How often the whole display blinks, zero while it is steady. See
+    /// [`Vfd::blink`](crate::Vfd::blink).
+    pub blink: Duration,
+    /// Table the text was transcoded into.
+    pub code_page: CodePage,
+    /// Custom glyphs the text may use, by slot.
+    pub glyphs: BTreeMap<u8, Glyph>,
+}
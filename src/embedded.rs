@@ -0,0 +1,241 @@
+//! Displays on a microcontroller UART (`embedded-hal` feature).
+//!
+//! [`HalVfd`] encodes commands and text like [`Vfd`](crate::Vfd) does but
+//! writes them through an [`embedded_hal_nb::serial::Write`] implementation,
+//! as HALs for the ESP32, STM32 and RP2040 provide for their UARTs. It works
+//! without `std`, given an allocator; configure the UART's baud rate and
+//! frame format to match the display before handing it over.
+
+use crate::capabilities::Capabilities;
+use crate::codepage::{CodePage, encode_text, prepare_text};
+use crate::command_set::{BrightnessLevel, CommandSet};
+use crate::geometry::Geometry;
+use crate::glyph::Glyph;
+use crate::layout::{self, Align};
+use crate::symbols::SymbolMap;
+use core::fmt;
+use embedded_hal_nb::nb;
+use embedded_hal_nb::serial::Write;
+
+/// Errors from a [`HalVfd`], wrapping the UART's own error type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error<E> {
+    /// The UART failed to send.
+    Serial(E),
+    /// A position, row or slot lies outside what the display offers.
+    OutOfBounds {
+        what: &'static str,
+        value: usize,
+        max: usize,
+    },
+    /// The display's command set has no way to do what was asked.
+    NotSupported(&'static str),
+}
+
+impl<E: fmt::Debug> fmt::Display for Error<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Serial(e) => write!(f, "Serial port error: {:?}", e),
+            Error::OutOfBounds { what, value, max } => {
+                write!(f, "{} {} is out of range (maximum {})", what, value, max)
+            }
+            Error::NotSupported(what) => write!(f, "{} is not supported by this display", what),
+        }
+    }
+}
+
+impl<E: fmt::Debug> core::error::Error for Error<E> {}
+
+/// Driver for a VFD customer display on an `embedded-hal` serial port.
+///
+/// ```
+/// use embedded_hal_nb::nb;
+/// use embedded_hal_nb::serial::{ErrorKind, ErrorType, Write};
+/// use vfd_dsp_v9fb_over_serial::embedded::HalVfd;
+/// use vfd_dsp_v9fb_over_serial::{Align, CommandSet};
+///
+/// // Stands in for a HAL's UART transmitter
+/// struct Uart(Vec<u8>);
+///
+/// impl ErrorType for Uart {
+///     type Error = ErrorKind;
+/// }
+///
+/// impl Write for Uart {
+///     fn write(&mut self, word: u8) -> nb::Result<(), ErrorKind> {
+///         self.0.push(word);
+///         Ok(())
+///     }
+///
+///     fn flush(&mut self) -> nb::Result<(), ErrorKind> {
+///         Ok(())
+///     }
+/// }
+///
+/// let mut vfd = HalVfd::new(Uart(Vec::new()), CommandSet::Birch, 20, 2);
+/// vfd.initialize().unwrap();
+/// vfd.write_line(1, "Total 4.20", Align::Right).unwrap();
+/// assert!(vfd.release().0.ends_with(b"Total 4.20"));
+/// ```
+pub struct HalVfd<S> {
+    serial: S,
+    command_set: CommandSet,
+    capabilities: Capabilities,
+    code_page: CodePage,
+    transliterate: bool,
+    symbols: SymbolMap,
+    width: u8,
+    height: u8,
+}
+
+impl<S: Write<u8>> HalVfd<S> {
+    /// Drive a `width × height` display speaking `command_set` through `serial`.
+    ///
+    /// Nothing is sent until the first command; call [`HalVfd::initialize`] if needed.
+    pub fn new(serial: S, command_set: CommandSet, width: u8, height: u8) -> Self {
+        HalVfd {
+            serial,
+            command_set: command_set.clone(),
+            capabilities: Capabilities::new(command_set, Geometry::new(width, height)),
+            code_page: CodePage::default(),
+            transliterate: true,
+            symbols: SymbolMap::default(),
+            width,
+            height,
+        }
+    }
+
+    /// Give the serial port back.
+    pub fn release(self) -> S {
+        self.serial
+    }
+
+    /// Display geometry as (columns, rows)
+    pub fn size(&self) -> (u8, u8) {
+        (self.width, self.height)
+    }
+
+    /// What the display can do; by default everything its command set has commands for.
+    pub fn capabilities(&self) -> Capabilities {
+        self.capabilities
+    }
+
+    /// Narrow what the display is assumed to do, for models that ignore some
+    /// of their command set. The display takes the size in `capabilities`.
+    pub fn set_capabilities(&mut self, capabilities: Capabilities) {
+        (self.width, self.height) = capabilities.geometry.into();
+        self.capabilities = capabilities;
+    }
+
+    /// Reset the display to its power-on state, blanking it.
+    pub fn initialize(&mut self) -> Result<(), Error<S::Error>> {
+        let cmd = self.command_set.initialize();
+        self.send(&cmd)?;
+        self.code_page = CodePage::default();
+        Ok(())
+    }
+
+    pub fn clear(&mut self) -> Result<(), Error<S::Error>> {
+        let cmd = self.command_set.clear();
+        self.send(&cmd)
+    }
+
+    /// Move the cursor to column `x` and row `y`, counted from 0.
+    pub fn set_cursor(&mut self, x: u8, y: u8) -> Result<(), Error<S::Error>> {
+        self.check_row(y)?;
+        if x >= self.width {
+            return Err(Error::OutOfBounds {
+                what: "Column",
+                value: x as usize,
+                max: (self.width as usize).saturating_sub(1),
+            });
+        }
+        let cmd = self.command_set.set_cursor(x, y, self.width);
+        self.send(&cmd)
+    }
+
+    pub fn set_brightness(&mut self, level: BrightnessLevel) -> Result<(), Error<S::Error>> {
+        if !self.capabilities.supports_brightness {
+            return Err(Error::NotSupported("Changing the brightness"));
+        }
+        let cmd = self.command_set.set_brightness(level);
+        self.send(&cmd)
+    }
+
+    /// Select the character table text is encoded in.
+    pub fn set_code_page(&mut self, page: CodePage) -> Result<(), Error<S::Error>> {
+        let cmd = self
+            .command_set
+            .set_code_page(page)
+            .ok_or(Error::NotSupported("Switching code pages"))?;
+        self.send(&cmd)?;
+        self.code_page = page;
+        Ok(())
+    }
+
+    /// Replace characters the code page lacks with look-alikes (on by default).
+    pub fn set_transliterate(&mut self, enabled: bool) {
+        self.transliterate = enabled;
+    }
+
+    /// Replace symbols the code page lacks, such as box drawing, with the
+    /// nearest it has ([`SymbolMap::default`] unless set).
+    pub fn set_symbols(&mut self, symbols: SymbolMap) {
+        self.symbols = symbols;
+    }
+
+    /// Download `glyph` into custom character `slot`, shown wherever
+    /// [`custom_char`](crate::glyph::custom_char) of the slot appears in text.
+    pub fn define_custom_char(&mut self, slot: u8, glyph: &Glyph) -> Result<(), Error<S::Error>> {
+        if !self.capabilities.supports_cgram {
+            return Err(Error::NotSupported("Custom characters"));
+        }
+        let code = self
+            .command_set
+            .custom_char_code(slot)
+            .filter(|_| self.capabilities.has_custom_char(slot))
+            .ok_or(Error::OutOfBounds {
+                what: "Custom character slot",
+                value: slot as usize,
+                max: (self.capabilities.max_custom_chars as usize).saturating_sub(1),
+            })?;
+        let cmd = self.command_set.define_custom_char(code, glyph);
+        self.send(&cmd)
+    }
+
+    /// Write `text` at the cursor.
+    pub fn write_text(&mut self, text: &str) -> Result<(), Error<S::Error>> {
+        let text = prepare_text(text, self.code_page, self.transliterate, &self.symbols);
+        let bytes = encode_text(&text, self.command_set.clone(), self.code_page);
+        self.send(&bytes)
+    }
+
+    /// Replace `row` with `text`, padded or cut to the display width.
+    pub fn write_line(&mut self, row: u8, text: &str, align: Align) -> Result<(), Error<S::Error>> {
+        self.check_row(row)?;
+        let text = prepare_text(text, self.code_page, self.transliterate, &self.symbols);
+        let text = layout::align(&text, self.width as usize, align);
+        self.set_cursor(0, row)?;
+        let bytes = encode_text(&text, self.command_set.clone(), self.code_page);
+        self.send(&bytes)
+    }
+
+    /// Send raw bytes and wait until the UART has sent them.
+    pub fn send(&mut self, bytes: &[u8]) -> Result<(), Error<S::Error>> {
+        for &byte in bytes {
+            nb::block!(self.serial.write(byte)).map_err(Error::Serial)?;
+        }
+        nb::block!(self.serial.flush()).map_err(Error::Serial)
+    }
+
+    fn check_row(&self, row: u8) -> Result<(), Error<S::Error>> {
+        if row >= self.height {
+            return Err(Error::OutOfBounds {
+                what: "Row",
+                value: row as usize,
+                max: (self.height as usize).saturating_sub(1),
+            });
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,93 @@
+use std::io;
+use thiserror::Error;
+
+/// Errors returned by the display driver.
+#[derive(Debug, Error)]
+pub enum VfdError {
+    /// The serial port failed to open, read or write.
+    #[error("Serial port error: {0}")]
+    Io(io::Error),
+
+    /// The text does not fit in the space left from the cursor.
+    #[error(
+        "Text too long to fit on display. A maximum of {max} characters are available from the current cursor position. {got} characters were provided."
+    )]
+    TextTooLong { max: usize, got: usize },
+
+    /// A position, row or slot lies outside what the display offers.
+    #[error("{what} {value} is out of range (maximum {max})")]
+    OutOfBounds {
+        what: &'static str,
+        value: usize,
+        max: usize,
+    },
+
+    /// The display's command set has no way to do what was asked.
+    #[error("{0} is not supported by this display")]
+    NotSupported(String),
+
+    /// Text could not be represented in the display's character set.
+    #[error("Cannot encode {0:?} for the display")]
+    Encoding(String),
+
+    /// An argument such as a format string or script line could not be understood.
+    #[error("{0}")]
+    InvalidInput(String),
+
+    /// The display did not answer in time.
+    #[error("Timed out waiting for the display")]
+    Timeout,
+}
+
+impl VfdError {
+    /// Whether the error means the display is gone, e.g. a USB adapter was unplugged.
+    ///
+    /// Such errors will keep failing until the port is reopened with
+    /// [`Vfd::reconnect`](crate::Vfd::reconnect); other errors concern a single call.
+    pub fn is_disconnected(&self) -> bool {
+        match self {
+            VfdError::Io(e) => {
+                matches!(
+                    e.kind(),
+                    io::ErrorKind::BrokenPipe
+                        | io::ErrorKind::NotConnected
+                        | io::ErrorKind::NotFound
+                        | io::ErrorKind::UnexpectedEof
+                        | io::ErrorKind::ConnectionReset
+                        | io::ErrorKind::ConnectionAborted
+                ) || is_disconnect_os_error(e)
+            }
+            _ => false,
+        }
+    }
+}
+
+// ENXIO and EIO are what Linux reports when writing to an unplugged USB-serial adapter
+#[cfg(unix)]
+fn is_disconnect_os_error(e: &io::Error) -> bool {
+    matches!(e.raw_os_error(), Some(5) | Some(6))
+}
+
+#[cfg(not(unix))]
+fn is_disconnect_os_error(_e: &io::Error) -> bool {
+    false
+}
+
+impl From<io::Error> for VfdError {
+    fn from(e: io::Error) -> Self {
+        match e.kind() {
+            io::ErrorKind::TimedOut => VfdError::Timeout,
+            _ => VfdError::Io(e),
+        }
+    }
+}
+
+#[cfg(feature = "serial")]
+impl From<serialport::Error> for VfdError {
+    fn from(e: serialport::Error) -> Self {
+        VfdError::from(io::Error::from(e))
+    }
+}
+
+/// Result type used throughout the crate.
+pub type Result<T> = std::result::Result<T, VfdError>;
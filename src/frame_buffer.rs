@@ -0,0 +1,239 @@
+use crate::codepage::TextMapping;
+use crate::display::VfdDisplay;
+use crate::error::VfdError;
+use crate::text;
+use std::ops::Range;
+
+// Bytes needed to reposition the cursor (US $ x y). Unchanged runs shorter
+// than this are cheaper to rewrite than to skip over.
+const CURSOR_MOVE_COST: usize = 4;
+
+/// A run of cells on one row that needs to be sent to the display.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Span {
+    pub x: u8,
+    pub y: u8,
+    pub text: String,
+}
+
+/// In-memory `width × height` grid of characters mirroring the display.
+///
+/// Draw into the buffer, then call [`FrameBuffer::flush`] to send only the
+/// cells that differ from what was last flushed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FrameBuffer {
+    width: u8,
+    height: u8,
+    cells: Vec<char>,
+    // What the display is known to show; `None` when unknown
+    shown: Vec<Option<char>>,
+    mapping: TextMapping,
+}
+
+impl FrameBuffer {
+    /// A blank buffer. Nothing is assumed about the display, so the first flush redraws every cell.
+    pub fn new(width: u8, height: u8) -> Self {
+        let len = width as usize * height as usize;
+        FrameBuffer {
+            width,
+            height,
+            cells: vec![' '; len],
+            shown: vec![None; len],
+            mapping: TextMapping::identity(),
+        }
+    }
+
+    pub fn size(&self) -> (u8, u8) {
+        (self.width, self.height)
+    }
+
+    fn index(&self, x: u8, y: u8) -> Option<usize> {
+        if x < self.width && y < self.height {
+            Some(y as usize * self.width as usize + x as usize)
+        } else {
+            None
+        }
+    }
+
+    /// Character at column `x`, row `y`.
+    pub fn get(&self, x: u8, y: u8) -> Option<char> {
+        self.index(x, y).map(|i| self.cells[i])
+    }
+
+    /// Set a single cell. Cells outside the grid are ignored.
+    pub fn set(&mut self, x: u8, y: u8, c: char) {
+        if let Some(i) = self.index(x, y) {
+            self.cells[i] = c;
+        }
+    }
+
+    /// How text is mapped onto cells by [`FrameBuffer::put_str`], e.g. the
+    /// display's own so that each cell holds what it will show (none by default).
+    pub fn set_text_mapping(&mut self, mapping: TextMapping) {
+        self.mapping = mapping;
+    }
+
+    pub fn text_mapping(&self) -> &TextMapping {
+        &self.mapping
+    }
+
+    /// Write `text` starting at column `x`, row `y`, clipping at the end of the row.
+    pub fn put_str(&mut self, x: u8, y: u8, text: &str) {
+        let text = self.mapping.apply(text);
+        self.put_mapped(x, y, &text);
+    }
+
+    // Write text the mapping was already applied to
+    pub(crate) fn put_mapped(&mut self, x: u8, y: u8, text: &str) {
+        let chars = text.chars().filter(|c| text::char_width(*c) > 0);
+        for (col, c) in (x..self.width).zip(chars) {
+            self.set(col, y, c);
+        }
+    }
+
+    /// Move every row up one, blanking the bottom row.
+    pub fn scroll_up(&mut self) {
+        let width = self.width as usize;
+        self.cells.drain(..width.min(self.cells.len()));
+        self.cells.resize(width * self.height as usize, ' ');
+    }
+
+    /// Blank every cell.
+    pub fn clear(&mut self) {
+        self.cells.fill(' ');
+    }
+
+    /// Contents of row `y`.
+    pub fn row(&self, y: u8) -> String {
+        match self.index(0, y) {
+            Some(start) => self.cells[start..start + self.width as usize]
+                .iter()
+                .collect(),
+            None => String::new(),
+        }
+    }
+
+    /// The cells inside a `+`, `-` and `|` border, one line per row, for
+    /// showing the screen where there is no display.
+    ///
+    /// ```
+    /// use vfd_dsp_v9fb_over_serial::FrameBuffer;
+    ///
+    /// let mut frame = FrameBuffer::new(8, 2);
+    /// frame.put_str(0, 0, "Hi there");
+    /// assert_eq!(
+    ///     frame.ascii_art(),
+    ///     "+--------+\n|Hi there|\n|        |\n+--------+\n"
+    /// );
+    /// ```
+    pub fn ascii_art(&self) -> String {
+        let rule = format!("+{}+\n", "-".repeat(self.width as usize));
+        let mut out = rule.clone();
+        for y in 0..self.height {
+            out.push('|');
+            out.push_str(&self.row(y));
+            out.push_str("|\n");
+        }
+        out.push_str(&rule);
+        out
+    }
+
+    /// Forget what the display shows so the next flush redraws every cell.
+    pub fn invalidate(&mut self) {
+        self.shown.fill(None);
+    }
+
+    /// Whether any cell differs from what was last flushed.
+    pub fn is_dirty(&self) -> bool {
+        self.cells
+            .iter()
+            .zip(&self.shown)
+            .any(|(cell, shown)| Some(*cell) != *shown)
+    }
+
+    /// Runs of cells that differ from the display, merging runs separated by
+    /// gaps short enough that rewriting them beats moving the cursor.
+    pub fn diff(&self) -> Vec<Span> {
+        let mut spans = Vec::new();
+        let width = self.width as usize;
+        for y in 0..self.height {
+            let start = y as usize * width;
+            let changed: Vec<bool> = (start..start + width)
+                .map(|i| Some(self.cells[i]) != self.shown[i])
+                .collect();
+            for run in changed_runs(&changed) {
+                spans.push(Span {
+                    x: run.start as u8,
+                    y,
+                    text: self.cells[start + run.start..start + run.end]
+                        .iter()
+                        .collect(),
+                });
+            }
+        }
+        spans
+    }
+
+    // Runs of `text` from column `x` on row `y` that differ from the cells,
+    // clipped at the end of the row
+    pub(crate) fn changes(&self, x: u8, y: u8, text: &str) -> Vec<Span> {
+        let chars: Vec<char> = text
+            .chars()
+            .filter(|c| text::char_width(*c) > 0)
+            .take(self.width.saturating_sub(x) as usize)
+            .collect();
+        let changed: Vec<bool> = chars
+            .iter()
+            .zip(x..)
+            .map(|(c, col)| self.get(col, y) != Some(*c))
+            .collect();
+        changed_runs(&changed)
+            .into_iter()
+            .map(|run| Span {
+                x: x + run.start as u8,
+                y,
+                text: chars[run].iter().collect(),
+            })
+            .collect()
+    }
+
+    /// Send the changed cells to the display with the fewest cursor moves and writes.
+    pub fn flush<D: VfdDisplay + ?Sized>(&mut self, display: &mut D) -> Result<(), VfdError> {
+        for span in self.diff() {
+            display.set_cursor(span.x, span.y)?;
+            display.write_text(&span.text)?;
+            let start = span.y as usize * self.width as usize + span.x as usize;
+            for (i, c) in span.text.chars().enumerate() {
+                self.shown[start + i] = Some(c);
+            }
+        }
+        Ok(())
+    }
+}
+
+// Ranges covering the changed flags, merging runs separated by gaps short
+// enough that rewriting them beats moving the cursor
+pub(crate) fn changed_runs(changed: &[bool]) -> Vec<Range<usize>> {
+    let mut runs = Vec::new();
+    let mut x = 0;
+    while x < changed.len() {
+        if !changed[x] {
+            x += 1;
+            continue;
+        }
+        let start = x;
+        let mut end = x + 1;
+        let mut probe = end;
+        while probe < changed.len() {
+            if changed[probe] {
+                end = probe + 1;
+            } else if probe - end >= CURSOR_MOVE_COST {
+                break;
+            }
+            probe += 1;
+        }
+        runs.push(start..end);
+        x = end;
+    }
+    runs
+}
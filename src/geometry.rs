@@ -0,0 +1,83 @@
+use alloc::format;
+use alloc::string::String;
+use core::fmt;
+use core::str::FromStr;
+
+/// Size of a display in character cells.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Geometry {
+    pub width: u8,
+    pub height: u8,
+}
+
+impl Geometry {
+    /// 16 columns, 2 rows.
+    pub const SIZE_16X2: Geometry = Geometry::new(16, 2);
+    /// 20 columns, 2 rows: the Birch DSP-V9FB and most pole displays.
+    pub const SIZE_20X2: Geometry = Geometry::new(20, 2);
+    /// 20 columns, 4 rows.
+    pub const SIZE_20X4: Geometry = Geometry::new(20, 4);
+    /// 24 columns, 2 rows.
+    pub const SIZE_24X2: Geometry = Geometry::new(24, 2);
+
+    /// The common customer display sizes.
+    pub const PRESETS: [Geometry; 4] = [
+        Geometry::SIZE_16X2,
+        Geometry::SIZE_20X2,
+        Geometry::SIZE_20X4,
+        Geometry::SIZE_24X2,
+    ];
+
+    pub const fn new(width: u8, height: u8) -> Self {
+        Geometry { width, height }
+    }
+
+    /// Number of cells on the display.
+    pub fn cells(&self) -> usize {
+        self.width as usize * self.height as usize
+    }
+}
+
+impl Default for Geometry {
+    fn default() -> Self {
+        Geometry::SIZE_20X2
+    }
+}
+
+impl From<(u8, u8)> for Geometry {
+    fn from((width, height): (u8, u8)) -> Self {
+        Geometry { width, height }
+    }
+}
+
+impl From<Geometry> for (u8, u8) {
+    fn from(geometry: Geometry) -> Self {
+        (geometry.width, geometry.height)
+    }
+}
+
+impl FromStr for Geometry {
+    type Err = String;
+
+    /// Parse `COLUMNSxROWS`, e.g. `20x4`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parsed = s
+            .trim()
+            .to_ascii_lowercase()
+            .split_once('x')
+            .and_then(|(width, height)| Some((width.parse().ok()?, height.parse().ok()?)));
+        match parsed {
+            Some((width, height)) if width > 0 && height > 0 => Ok(Geometry { width, height }),
+            _ => Err(format!(
+                "Unknown geometry '{}'. Expected COLUMNSxROWS such as 16x2, 20x2, 20x4 or 24x2.",
+                s.trim()
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Geometry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}x{}", self.width, self.height)
+    }
+}
@@ -0,0 +1,77 @@
+/// A 5x7 dot-matrix bitmap, one byte per row from top to bottom.
+///
+/// The lower five bits of each row are used, bit 4 being the leftmost dot.
+pub type Glyph = [u8; 7];
+
+/// First codepoint of the Unicode private use area block mapped to custom character slots.
+pub(crate) const CUSTOM_CHAR_BASE: u32 = 0xE000;
+
+/// Character that refers to the custom glyph stored in `slot` when used in text.
+///
+/// ```
+/// use vfd_dsp_v9fb_over_serial::glyph::custom_char;
+/// let text = format!("21{}C", custom_char(0));
+/// ```
+pub fn custom_char(slot: u8) -> char {
+    char::from_u32(CUSTOM_CHAR_BASE + slot as u32).unwrap()
+}
+
+/// Custom slot referenced by `c`, if it is one of the [`custom_char`] codepoints.
+pub(crate) fn custom_slot(c: char) -> Option<u8> {
+    let code = c as u32;
+    if (CUSTOM_CHAR_BASE..CUSTOM_CHAR_BASE + 0x100).contains(&code) {
+        Some((code - CUSTOM_CHAR_BASE) as u8)
+    } else {
+        None
+    }
+}
+
+/// Degree sign.
+pub const DEGREE: Glyph = [
+    0b01100, 0b10010, 0b10010, 0b01100, 0b00000, 0b00000, 0b00000,
+];
+
+/// Battery outline, full.
+pub const BATTERY: Glyph = [
+    0b01110, 0b11111, 0b11111, 0b11111, 0b11111, 0b11111, 0b11111,
+];
+
+/// Sun, for clear weather.
+pub const SUN: Glyph = [
+    0b00100, 0b10101, 0b01110, 0b11111, 0b01110, 0b10101, 0b00100,
+];
+
+/// Sun behind a cloud.
+pub const PARTLY_CLOUDY: Glyph = [
+    0b00010, 0b00111, 0b00010, 0b01100, 0b11110, 0b11111, 0b00000,
+];
+
+/// Cloud.
+pub const CLOUD: Glyph = [
+    0b00000, 0b00110, 0b01111, 0b11111, 0b11111, 0b00000, 0b00000,
+];
+
+/// Bands of fog.
+pub const FOG: Glyph = [
+    0b00000, 0b11110, 0b00000, 0b01111, 0b00000, 0b11110, 0b00000,
+];
+
+/// Cloud with a few drops.
+pub const DRIZZLE: Glyph = [
+    0b00110, 0b01111, 0b11111, 0b00000, 0b01000, 0b00010, 0b00000,
+];
+
+/// Cloud with streaks of rain.
+pub const RAIN: Glyph = [
+    0b00110, 0b01111, 0b11111, 0b00000, 0b10101, 0b10101, 0b00000,
+];
+
+/// Cloud with snowflakes.
+pub const SNOW: Glyph = [
+    0b00110, 0b01111, 0b11111, 0b00000, 0b10001, 0b00100, 0b10001,
+];
+
+/// Cloud with a lightning bolt.
+pub const THUNDERSTORM: Glyph = [
+    0b00110, 0b01111, 0b11111, 0b00100, 0b01000, 0b11110, 0b00100,
+];
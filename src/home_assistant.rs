@@ -0,0 +1,406 @@
+//! Home Assistant dashboard (`home-assistant` feature).
+//!
+//! Connects to Home Assistant's websocket API, subscribes to the entities a
+//! [`Template`] names in its placeholders and draws the template again
+//! whenever one of their states changes. A placeholder is an entity id for
+//! its state, or an entity id followed by `.unit` or `.name` for its unit of
+//! measurement or friendly name:
+//!
+//! ```toml
+//! [templates.home-assistant]
+//! fields = [
+//!     { y = 0, text = "Out {sensor.outside_temperature}{sensor.outside_temperature.unit}" },
+//!     { y = 1, text = "Door {binary_sensor.front_door}", align = "right" },
+//! ]
+//! ```
+//!
+//! Only plain `ws://` connections are made, so Home Assistant has to be
+//! reachable without TLS, e.g. on the local network. The connection is made
+//! again after it drops or Home Assistant restarts.
+
+use crate::error::VfdError;
+use crate::logging;
+use crate::template::Template;
+use crate::vfd::Vfd;
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::TcpStream;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread;
+use std::time::Duration;
+
+// Quiet time after which Home Assistant is pinged, and then given up on
+const IDLE_TIMEOUT: Duration = Duration::from_secs(30);
+// Largest message accepted, far above what a subscription sends
+const MAX_MESSAGE: u64 = 16 * 1024 * 1024;
+
+const OP_CONTINUATION: u8 = 0x0;
+const OP_TEXT: u8 = 0x1;
+const OP_CLOSE: u8 = 0x8;
+const OP_PING: u8 = 0x9;
+const OP_PONG: u8 = 0xA;
+
+/// Server, access token and what to show.
+#[derive(Debug, Clone)]
+pub struct HomeAssistantConfig {
+    pub host: String,
+    pub port: u16,
+    /// A long-lived access token, created on the user's profile page.
+    pub token: String,
+    /// Drawn whenever a state it shows changes.
+    pub template: Template,
+    /// Pause before connecting again after the connection failed.
+    pub reconnect_delay: Duration,
+}
+
+impl Default for HomeAssistantConfig {
+    fn default() -> Self {
+        HomeAssistantConfig {
+            host: "localhost".to_string(),
+            port: 8123,
+            token: String::new(),
+            template: Template::default(),
+            reconnect_delay: Duration::from_secs(5),
+        }
+    }
+}
+
+/// Entity ids named by the placeholders of `template`.
+///
+/// ```
+/// use vfd_dsp_v9fb_over_serial::home_assistant::entities;
+/// use vfd_dsp_v9fb_over_serial::{Template, TemplateField};
+///
+/// let template = Template::new()
+///     .field(TemplateField::new(0, 0, "{sensor.power} {sensor.power.unit}"))
+///     .field(TemplateField::new(0, 1, "{light.porch.name}: {light.porch}"));
+/// assert_eq!(entities(&template), ["sensor.power", "light.porch"]);
+/// ```
+pub fn entities(template: &Template) -> Vec<String> {
+    let mut entities: Vec<String> = Vec::new();
+    for placeholder in template.placeholders() {
+        let entity = placeholder
+            .splitn(3, '.')
+            .take(2)
+            .collect::<Vec<_>>()
+            .join(".");
+        if !entities.contains(&entity) {
+            entities.push(entity);
+        }
+    }
+    entities
+}
+
+/// Show the template until the process exits.
+///
+/// Connection errors are logged and the connection is made again after
+/// [`HomeAssistantConfig::reconnect_delay`].
+pub fn serve_home_assistant(vfd: Arc<Mutex<Vfd>>, config: &HomeAssistantConfig) {
+    loop {
+        if let Err(e) = follow(&vfd, config) {
+            logging::warning!("Home Assistant connection error: {}", e);
+        }
+        thread::sleep(config.reconnect_delay);
+    }
+}
+
+/// Connect once and keep the template up to date until the connection drops.
+pub fn follow(vfd: &Arc<Mutex<Vfd>>, config: &HomeAssistantConfig) -> Result<(), VfdError> {
+    let mut socket = WebSocket::connect(&config.host, config.port, "/api/websocket")?;
+    authenticate(&mut socket, &config.token)?;
+    let entities = entities(&config.template);
+    let subscribe = json!({"id": 1, "type": "subscribe_entities", "entity_ids": entities});
+    socket.send_text(&subscribe.to_string())?;
+
+    let mut values = BTreeMap::new();
+    for entity in &entities {
+        values.insert(entity.clone(), "unknown".to_string());
+        values.insert(format!("{}.unit", entity), String::new());
+        values.insert(format!("{}.name", entity), entity.clone());
+    }
+    let mut shown = None;
+    let mut next_id = 2;
+    let mut pinged = false;
+    loop {
+        let Some(text) = socket.recv_text()? else {
+            if pinged {
+                return Err(io::Error::new(
+                    io::ErrorKind::TimedOut,
+                    "Home Assistant stopped answering",
+                )
+                .into());
+            }
+            socket.send_text(&json!({"id": next_id, "type": "ping"}).to_string())?;
+            next_id += 1;
+            pinged = true;
+            continue;
+        };
+        pinged = false;
+        let message = parse(&text)?;
+        match message["type"].as_str() {
+            Some("result") if message["success"] == false => {
+                return Err(VfdError::InvalidInput(format!(
+                    "Home Assistant refused the subscription: {}",
+                    message["error"]["message"]
+                        .as_str()
+                        .unwrap_or("no reason given")
+                )));
+            }
+            Some("event") if message["id"] == 1 => {
+                apply(&mut values, &message["event"]);
+                if shown.as_ref() != Some(&values) {
+                    let pairs: Vec<(&str, &str)> = values
+                        .iter()
+                        .map(|(name, value)| (name.as_str(), value.as_str()))
+                        .collect();
+                    let mut vfd = vfd.lock().unwrap_or_else(PoisonError::into_inner);
+                    config.template.render(&mut *vfd, &pairs)?;
+                    shown = Some(values.clone());
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn authenticate(socket: &mut WebSocket, token: &str) -> Result<(), VfdError> {
+    let greeting = next_message(socket)?;
+    if greeting["type"] != "auth_required" {
+        return Err(VfdError::InvalidInput(format!(
+            "Not a Home Assistant websocket: {}",
+            greeting
+        )));
+    }
+    socket.send_text(&json!({"type": "auth", "access_token": token}).to_string())?;
+    let answer = next_message(socket)?;
+    match answer["type"].as_str() {
+        Some("auth_ok") => Ok(()),
+        _ => Err(VfdError::InvalidInput(format!(
+            "Home Assistant did not accept the access token: {}",
+            answer["message"].as_str().unwrap_or("no reason given")
+        ))),
+    }
+}
+
+fn next_message(socket: &mut WebSocket) -> Result<Value, VfdError> {
+    let text = socket.recv_text()?.ok_or(VfdError::Timeout)?;
+    parse(&text)
+}
+
+fn parse(text: &str) -> Result<Value, VfdError> {
+    serde_json::from_str(text)
+        .map_err(|e| VfdError::InvalidInput(format!("Invalid Home Assistant message: {}", e)))
+}
+
+// Fold a `subscribe_entities` event into the placeholder values: `a` adds
+// entities, `c` changes them and `r` removes them
+fn apply(values: &mut BTreeMap<String, String>, event: &Value) {
+    for (entity, state) in event["a"].as_object().into_iter().flatten() {
+        update(values, entity, state);
+    }
+    for (entity, change) in event["c"].as_object().into_iter().flatten() {
+        update(values, entity, &change["+"]);
+        for key in change["-"]["a"].as_array().into_iter().flatten() {
+            if let Some(key) = key.as_str() {
+                set(values, entity, key, &Value::Null);
+            }
+        }
+    }
+    for entity in event["r"].as_array().into_iter().flatten() {
+        if let Some(entity) = entity.as_str() {
+            set(values, entity, "s", &Value::from("unavailable"));
+        }
+    }
+}
+
+// Take the state `s` and attributes `a` of an entity
+fn update(values: &mut BTreeMap<String, String>, entity: &str, state: &Value) {
+    if let Some(text) = state.get("s") {
+        set(values, entity, "s", text);
+    }
+    for (key, value) in state["a"].as_object().into_iter().flatten() {
+        set(values, entity, key, value);
+    }
+}
+
+// Store the state (`s`) or an attribute of an entity under its placeholder, if shown
+fn set(values: &mut BTreeMap<String, String>, entity: &str, key: &str, value: &Value) {
+    let name = match key {
+        "s" => entity.to_string(),
+        "unit_of_measurement" => format!("{}.unit", entity),
+        "friendly_name" => format!("{}.name", entity),
+        _ => return,
+    };
+    if let Some(slot) = values.get_mut(&name) {
+        *slot = match value {
+            Value::String(text) => text.clone(),
+            Value::Null if key == "friendly_name" => entity.to_string(),
+            Value::Null => String::new(),
+            other => other.to_string(),
+        };
+    }
+}
+
+// The client end of a websocket, as much of RFC 6455 as Home Assistant needs
+struct WebSocket {
+    stream: BufReader<TcpStream>,
+}
+
+impl WebSocket {
+    fn connect(host: &str, port: u16, path: &str) -> io::Result<WebSocket> {
+        let mut stream = TcpStream::connect((host, port))?;
+        stream.set_read_timeout(Some(IDLE_TIMEOUT))?;
+        write!(
+            stream,
+            "GET {} HTTP/1.1\r\nHost: {}:{}\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Key: {}\r\nSec-WebSocket-Version: 13\r\n\r\n",
+            path,
+            host,
+            port,
+            base64(&random_bytes::<16>()?)
+        )?;
+        let mut stream = BufReader::new(stream);
+        let mut status = String::new();
+        stream.read_line(&mut status)?;
+        if status.split_whitespace().nth(1) != Some("101") {
+            return Err(io::Error::other(format!(
+                "Websocket upgrade refused: {}",
+                status.trim()
+            )));
+        }
+        loop {
+            let mut header = String::new();
+            if stream.read_line(&mut header)? == 0 {
+                return Err(io::ErrorKind::UnexpectedEof.into());
+            }
+            if header.trim().is_empty() {
+                return Ok(WebSocket { stream });
+            }
+        }
+    }
+
+    fn send(&mut self, opcode: u8, payload: &[u8]) -> io::Result<()> {
+        let mut frame = vec![0x80 | opcode];
+        match payload.len() {
+            len if len < 126 => frame.push(0x80 | len as u8),
+            len if len <= u16::MAX as usize => {
+                frame.push(0x80 | 126);
+                frame.extend((len as u16).to_be_bytes());
+            }
+            len => {
+                frame.push(0x80 | 127);
+                frame.extend((len as u64).to_be_bytes());
+            }
+        }
+        // Frames from a client are always masked
+        let mask = random_bytes::<4>()?;
+        frame.extend(mask);
+        frame.extend(payload.iter().zip(mask.iter().cycle()).map(|(b, m)| b ^ m));
+        self.stream.get_mut().write_all(&frame)
+    }
+
+    fn send_text(&mut self, text: &str) -> io::Result<()> {
+        self.send(OP_TEXT, text.as_bytes())
+    }
+
+    // The next text message, answering pings on the way, or `None` when
+    // nothing arrived for the idle timeout
+    fn recv_text(&mut self) -> io::Result<Option<String>> {
+        let mut message = Vec::new();
+        loop {
+            let mut header = [0u8; 2];
+            match self.stream.read(&mut header[..1]) {
+                Ok(0) => return Err(io::ErrorKind::UnexpectedEof.into()),
+                Ok(_) => {}
+                Err(e)
+                    if message.is_empty()
+                        && matches!(
+                            e.kind(),
+                            io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+                        ) =>
+                {
+                    return Ok(None);
+                }
+                Err(e) => return Err(e),
+            }
+            self.stream.read_exact(&mut header[1..])?;
+            let len = match header[1] & 0x7F {
+                126 => {
+                    let mut len = [0u8; 2];
+                    self.stream.read_exact(&mut len)?;
+                    u16::from_be_bytes(len) as u64
+                }
+                127 => {
+                    let mut len = [0u8; 8];
+                    self.stream.read_exact(&mut len)?;
+                    u64::from_be_bytes(len)
+                }
+                len => len as u64,
+            };
+            // Counted over all of a message's frames, which can be any number
+            if message.len() as u64 + len > MAX_MESSAGE {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!(
+                        "Websocket message of more than {} bytes is too large",
+                        MAX_MESSAGE
+                    ),
+                ));
+            }
+            let mut mask = [0u8; 4];
+            if header[1] & 0x80 != 0 {
+                self.stream.read_exact(&mut mask)?;
+            }
+            let mut payload = vec![0u8; len as usize];
+            self.stream.read_exact(&mut payload)?;
+            for (b, m) in payload.iter_mut().zip(mask.iter().cycle()) {
+                *b ^= m;
+            }
+            match header[0] & 0x0F {
+                OP_PING => self.send(OP_PONG, &payload)?,
+                OP_CLOSE => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::ConnectionAborted,
+                        "Websocket closed by the server",
+                    ));
+                }
+                OP_TEXT | OP_CONTINUATION => {
+                    message.extend(payload);
+                    if header[0] & 0x80 != 0 {
+                        return String::from_utf8(message)
+                            .map(Some)
+                            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e));
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+// Bytes from the system's random number generator, for the handshake key
+// and frame masks, which the server must not be able to predict
+fn random_bytes<const N: usize>() -> io::Result<[u8; N]> {
+    let mut bytes = [0u8; N];
+    getrandom::fill(&mut bytes)?;
+    Ok(bytes)
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::new();
+    for chunk in bytes.chunks(3) {
+        let group = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |group, (i, &b)| group | (b as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(group >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
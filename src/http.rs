@@ -0,0 +1,151 @@
+//! Tiny HTTP front-end for pushing text to the display over the network.
+//!
+//! ```text
+//! POST /display
+//! {"line1": "ORDER 42", "line2": "READY", "align": "center"}
+//! ```
+//!
+//! Omitted lines are left untouched. Replies `204 No Content` on success and
+//! `400`/`500` with a plain-text message otherwise.
+//!
+//! ```text
+//! POST /command
+//! [{"cmd": "clear"}, {"cmd": "write", "row": 0, "text": "ORDER 42"}]
+//! ```
+//!
+//! Takes one [request](crate::protocol::Request) or an array of them, run in
+//! order until one fails. Replies with a [`Reply`] as JSON, with status `200`,
+//! `400` if the body is not a valid request or `500` if the display failed.
+//! A `dump` anywhere in the array answers with the rows once all of it has run.
+//!
+//! ```text
+//! GET /metrics
+//! ```
+//!
+//! Replies with the display's [`Metrics`](crate::Metrics) in the Prometheus
+//! text format, for scraping without waiting on the display.
+
+use crate::daemon::DaemonCommand;
+use crate::error::VfdError;
+use crate::layout::Align;
+use crate::logging;
+use crate::protocol::{Reply, Request};
+use crate::vfd::Vfd;
+use serde::Deserialize;
+use std::io;
+use std::sync::{Arc, Mutex, PoisonError};
+use tiny_http::{Method, Response, Server};
+
+/// Body of `POST /display`.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct DisplayRequest {
+    pub line1: Option<String>,
+    pub line2: Option<String>,
+    #[serde(default)]
+    pub align: Align,
+}
+
+impl DisplayRequest {
+    /// Write the requested lines to the display.
+    pub fn apply(&self, vfd: &mut Vfd) -> Result<(), VfdError> {
+        for (row, line) in [&self.line1, &self.line2].into_iter().enumerate() {
+            if let Some(text) = line {
+                vfd.write_line(row as u8, text, self.align)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Serve HTTP requests on `addr` (e.g. `0.0.0.0:8080`) until the listener fails.
+pub fn serve_http(vfd: Arc<Mutex<Vfd>>, addr: &str) -> Result<(), io::Error> {
+    let server = Server::http(addr).map_err(io::Error::other)?;
+    let metrics = vfd.lock().unwrap_or_else(PoisonError::into_inner).metrics();
+    for mut request in server.incoming_requests() {
+        let response = match (request.method(), request.url()) {
+            (Method::Post, "/display") => {
+                let mut body = String::new();
+                match request.as_reader().read_to_string(&mut body) {
+                    Ok(_) => handle_display(&vfd, &body),
+                    Err(e) => Response::from_string(e.to_string()).with_status_code(400),
+                }
+            }
+            (Method::Post, "/command") => {
+                let mut body = String::new();
+                match request.as_reader().read_to_string(&mut body) {
+                    Ok(_) => handle_command(&vfd, &body),
+                    Err(e) => Response::from_string(e.to_string()).with_status_code(400),
+                }
+            }
+            (Method::Get, "/metrics") => Response::from_string(metrics.prometheus()).with_header(
+                tiny_http::Header::from_bytes("Content-Type", "text/plain; version=0.0.4")
+                    .expect("header is valid"),
+            ),
+            (_, "/display" | "/command") => Response::from_string("Use POST").with_status_code(405),
+            (_, "/metrics") => Response::from_string("Use GET").with_status_code(405),
+            _ => Response::from_string("Not found").with_status_code(404),
+        };
+        if let Err(e) = request.respond(response) {
+            logging::warning!("Failed to send HTTP response: {}", e);
+        }
+    }
+    Ok(())
+}
+
+fn handle_display(vfd: &Mutex<Vfd>, body: &str) -> Response<io::Cursor<Vec<u8>>> {
+    let request: DisplayRequest = match serde_json::from_str(body) {
+        Ok(request) => request,
+        Err(e) => return Response::from_string(e.to_string()).with_status_code(400),
+    };
+    let mut vfd = vfd.lock().unwrap_or_else(PoisonError::into_inner);
+    match request.apply(&mut vfd) {
+        Ok(()) => Response::from_string("").with_status_code(204),
+        Err(e) => Response::from_string(e.to_string()).with_status_code(500),
+    }
+}
+
+// One request or several, as `POST /command` takes them
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Requests {
+    One(Request),
+    Many(Vec<Request>),
+}
+
+fn handle_command(vfd: &Mutex<Vfd>, body: &str) -> Response<io::Cursor<Vec<u8>>> {
+    let requests = match serde_json::from_str(body) {
+        Ok(Requests::One(request)) => vec![request],
+        Ok(Requests::Many(requests)) => requests,
+        Err(e) => return json_response(&Reply::error(e.to_string()), 400),
+    };
+    let commands = match requests
+        .into_iter()
+        .map(DaemonCommand::try_from)
+        .collect::<Result<Vec<_>, _>>()
+    {
+        Ok(commands) => commands,
+        Err(e) => return json_response(&Reply::error(e), 400),
+    };
+    let dump = commands
+        .iter()
+        .any(|command| matches!(command, DaemonCommand::Dump));
+    for command in commands {
+        if let Err(e) = command.execute_shared(vfd) {
+            return json_response(&Reply::error(e.to_string()), 500);
+        }
+    }
+    if dump {
+        let rows = vfd.lock().unwrap_or_else(PoisonError::into_inner).dump();
+        return json_response(&Reply::rows(rows), 200);
+    }
+    json_response(&Reply::ok(), 200)
+}
+
+fn json_response(reply: &Reply, status: u16) -> Response<io::Cursor<Vec<u8>>> {
+    let body = serde_json::to_string(reply).expect("replies serialize");
+    let content_type =
+        tiny_http::Header::from_bytes("Content-Type", "application/json").expect("header is valid");
+    Response::from_string(body)
+        .with_status_code(status)
+        .with_header(content_type)
+}
@@ -0,0 +1,66 @@
+use std::fmt;
+
+/// What a display reports about itself, see [`Vfd::identify`](crate::Vfd::identify).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DisplayInfo {
+    /// Model ID byte.
+    pub model_id: u8,
+    /// Device type ID byte, if the display answers the request.
+    pub type_id: Option<u8>,
+    /// ROM version ID byte, if the display answers the request.
+    pub rom_version: Option<u8>,
+}
+
+impl fmt::Display for DisplayInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "model 0x{:02x}", self.model_id)?;
+        if let Some(type_id) = self.type_id {
+            write!(f, ", type 0x{:02x}", type_id)?;
+        }
+        if let Some(version) = self.rom_version {
+            write!(f, ", ROM version 0x{:02x}", version)?;
+        }
+        Ok(())
+    }
+}
+
+/// Whether a display can take more data, see [`Vfd::read_status`](crate::Vfd::read_status).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Status {
+    /// Status byte, if the command set has a status request and the display answered it.
+    pub raw: Option<u8>,
+    /// CTS line, if the port reports it.
+    pub clear_to_send: Option<bool>,
+    /// DSR line, if the port reports it.
+    pub data_set_ready: Option<bool>,
+}
+
+impl Status {
+    // Bit 3 of the status byte: the display is offline
+    const OFFLINE: u8 = 0x08;
+
+    /// `true` if the status byte reports the display offline or the display holds CTS low.
+    pub fn busy(&self) -> bool {
+        self.raw.is_some_and(|raw| raw & Status::OFFLINE != 0) || self.clear_to_send == Some(false)
+    }
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(if self.busy() { "busy" } else { "ready" })?;
+        if let Some(raw) = self.raw {
+            write!(f, ", status 0x{:02x}", raw)?;
+        }
+        let line = |state: Option<bool>| match state {
+            Some(true) => "high",
+            Some(false) => "low",
+            None => "unknown",
+        };
+        write!(
+            f,
+            ", CTS {}, DSR {}",
+            line(self.clear_to_send),
+            line(self.data_set_ready)
+        )
+    }
+}
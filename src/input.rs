@@ -0,0 +1,300 @@
+//! Buttons next to the display (`input` feature).
+//!
+//! Kiosks often pair the display with a few buttons. On Linux they can be
+//! read as keys from an evdev device such as `/dev/input/event0`
+//! ([`EvdevButtons`]), or straight from GPIO pins through sysfs
+//! ([`GpioButtons`]). [`serve_buttons`] lets them step through a
+//! [`ScreenCycle`] and dismiss alerts.
+
+use crate::error::VfdError;
+use crate::logging;
+use crate::screen_cycle::ScreenCycle;
+use crate::vfd::Vfd;
+use crate::widget::Widget;
+use std::fmt;
+use std::io;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, PoisonError, TryLockError};
+use std::time::Duration;
+#[cfg(target_os = "linux")]
+use std::{
+    fs::{self, File},
+    io::Read,
+    os::fd::AsRawFd,
+    path::{Path, PathBuf},
+    thread,
+    time::Instant,
+};
+
+/// What a button does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ButtonAction {
+    /// Show the next screen.
+    Next,
+    /// Show the previous screen.
+    Previous,
+    /// Dismiss the alert on show.
+    Acknowledge,
+}
+
+impl FromStr for ButtonAction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "next" => Ok(ButtonAction::Next),
+            "previous" | "prev" => Ok(ButtonAction::Previous),
+            "acknowledge" | "ack" => Ok(ButtonAction::Acknowledge),
+            _ => Err(format!(
+                "Unknown button action '{}'. Expected one of next, previous, ack.",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for ButtonAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ButtonAction::Next => write!(f, "next"),
+            ButtonAction::Previous => write!(f, "previous"),
+            ButtonAction::Acknowledge => write!(f, "ack"),
+        }
+    }
+}
+
+/// Parse comma separated bindings of key codes or pin numbers to actions,
+/// such as `"106=next,105=previous,28=ack"`.
+pub fn parse_bindings(s: &str) -> Result<Vec<(u32, ButtonAction)>, String> {
+    s.split(',')
+        .filter(|binding| !binding.trim().is_empty())
+        .map(|binding| {
+            let (number, action) = binding.split_once('=').ok_or_else(|| {
+                format!(
+                    "Invalid button binding '{}'. Expected <number>=<action>",
+                    binding
+                )
+            })?;
+            let number = number
+                .trim()
+                .parse()
+                .map_err(|_| format!("Invalid key code or pin '{}'", number.trim()))?;
+            Ok((number, action.parse()?))
+        })
+        .collect()
+}
+
+/// Something that reports button presses.
+pub trait Buttons {
+    /// Wait up to `timeout` for a button bound to an action to be pressed.
+    fn wait(&mut self, timeout: Duration) -> io::Result<Option<ButtonAction>>;
+}
+
+// `EV_KEY` events carry keys, with a value of 1 when pressed
+#[cfg(target_os = "linux")]
+const EV_KEY: u16 = 0x01;
+
+/// Keys read from a Linux evdev device, e.g. a USB keypad or buttons wired up
+/// with the `gpio-keys` device tree overlay.
+#[cfg(target_os = "linux")]
+#[derive(Debug)]
+pub struct EvdevButtons {
+    device: File,
+    keys: Vec<(u32, ButtonAction)>,
+}
+
+#[cfg(target_os = "linux")]
+impl EvdevButtons {
+    /// Read `path`, mapping key codes such as 106 (`KEY_RIGHT`) to actions.
+    pub fn open(path: &Path, keys: &[(u32, ButtonAction)]) -> io::Result<Self> {
+        Ok(EvdevButtons::from_file(File::open(path)?, keys))
+    }
+
+    /// Read events from an already open device.
+    pub fn from_file(device: File, keys: &[(u32, ButtonAction)]) -> Self {
+        EvdevButtons {
+            device,
+            keys: keys.to_vec(),
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl Buttons for EvdevButtons {
+    fn wait(&mut self, timeout: Duration) -> io::Result<Option<ButtonAction>> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let left = deadline.saturating_duration_since(Instant::now());
+            let mut poll = libc::pollfd {
+                fd: self.device.as_raw_fd(),
+                events: libc::POLLIN,
+                revents: 0,
+            };
+            let millis = left.as_millis().min(i32::MAX as u128) as i32;
+            // SAFETY: `poll` is one valid pollfd for the duration of the call
+            match unsafe { libc::poll(&mut poll, 1, millis) } {
+                0 => return Ok(None),
+                ready if ready < 0 => {
+                    let error = io::Error::last_os_error();
+                    if error.kind() != io::ErrorKind::Interrupted {
+                        return Err(error);
+                    }
+                    continue;
+                }
+                _ => {}
+            }
+            // struct input_event: a timeval, then u16 type, u16 code and i32 value
+            let mut event = [0u8; size_of::<libc::input_event>()];
+            self.device.read_exact(&mut event)?;
+            let fields = &event[size_of::<libc::timeval>()..];
+            let kind = u16::from_ne_bytes([fields[0], fields[1]]);
+            let code = u16::from_ne_bytes([fields[2], fields[3]]) as u32;
+            let value = i32::from_ne_bytes([fields[4], fields[5], fields[6], fields[7]]);
+            if kind == EV_KEY && value == 1 {
+                let action = self.keys.iter().find(|(key, _)| *key == code);
+                if let Some((_, action)) = action {
+                    return Ok(Some(*action));
+                }
+            }
+        }
+    }
+}
+
+// How often the pins are read, and how long a pin has to settle after changing
+#[cfg(target_os = "linux")]
+const GPIO_POLL: Duration = Duration::from_millis(10);
+#[cfg(target_os = "linux")]
+const GPIO_DEBOUNCE: Duration = Duration::from_millis(50);
+
+/// Buttons on GPIO pins, read through the sysfs GPIO interface.
+///
+/// A pressed button reads low, as with a switch to ground and a pull-up,
+/// unless [`GpioButtons::active_high`] says otherwise.
+#[cfg(target_os = "linux")]
+#[derive(Debug)]
+pub struct GpioButtons {
+    pins: Vec<Pin>,
+    active_high: bool,
+}
+
+#[cfg(target_os = "linux")]
+#[derive(Debug)]
+struct Pin {
+    value: PathBuf,
+    action: ButtonAction,
+    // Unknown until first read, so that a button held down then counts only once pressed again
+    pressed: Option<bool>,
+    changed: Option<Instant>,
+}
+
+#[cfg(target_os = "linux")]
+impl GpioButtons {
+    /// Buttons on `pins` under `/sys/class/gpio`, exported and made inputs if needed.
+    pub fn open(pins: &[(u32, ButtonAction)]) -> io::Result<Self> {
+        GpioButtons::open_in(Path::new("/sys/class/gpio"), pins)
+    }
+
+    /// Buttons on `pins` under another sysfs GPIO directory.
+    pub fn open_in(root: &Path, pins: &[(u32, ButtonAction)]) -> io::Result<Self> {
+        let pins = pins
+            .iter()
+            .map(|&(number, action)| {
+                let dir = root.join(format!("gpio{}", number));
+                if !dir.exists() {
+                    fs::write(root.join("export"), number.to_string())?;
+                }
+                // Pins set up elsewhere may not let us change their direction
+                let _ = fs::write(dir.join("direction"), "in");
+                Ok(Pin {
+                    value: dir.join("value"),
+                    action,
+                    pressed: None,
+                    changed: None,
+                })
+            })
+            .collect::<io::Result<Vec<_>>>()?;
+        Ok(GpioButtons {
+            pins,
+            active_high: false,
+        })
+    }
+
+    /// Whether a pressed button reads high (default `false`).
+    pub fn active_high(mut self, active_high: bool) -> Self {
+        self.active_high = active_high;
+        self
+    }
+
+    fn is_pressed(&self, index: usize) -> io::Result<bool> {
+        let level = fs::read_to_string(&self.pins[index].value)?;
+        Ok((level.trim() == "1") == self.active_high)
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl Buttons for GpioButtons {
+    fn wait(&mut self, timeout: Duration) -> io::Result<Option<ButtonAction>> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            for index in 0..self.pins.len() {
+                let pressed = self.is_pressed(index)?;
+                let pin = &mut self.pins[index];
+                let settled = pin.changed.is_none_or(|at| at.elapsed() >= GPIO_DEBOUNCE);
+                if pin.pressed.is_none() {
+                    pin.pressed = Some(pressed);
+                } else if pin.pressed != Some(pressed) && settled {
+                    pin.pressed = Some(pressed);
+                    pin.changed = Some(Instant::now());
+                    if pressed {
+                        return Ok(Some(pin.action));
+                    }
+                }
+            }
+            let left = deadline.saturating_duration_since(Instant::now());
+            if left.is_zero() {
+                return Ok(None);
+            }
+            thread::sleep(left.min(GPIO_POLL));
+        }
+    }
+}
+
+/// Step through `screens` and dismiss alerts as buttons are pressed, ticking
+/// the screen on show in between, until reading the buttons fails.
+///
+/// A press to switch screens while something else holds the display, such as
+/// an alert, takes effect once it is free. Errors drawing a screen are logged
+/// and do not stop the loop.
+pub fn serve_buttons<B: Buttons>(
+    vfd: Arc<Mutex<Vfd>>,
+    mut buttons: B,
+    mut screens: ScreenCycle,
+) -> io::Result<()> {
+    // Taken up front, as the display stays locked while an alert is shown
+    let dismisser = vfd
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .alert_dismisser();
+    let mut pending = None;
+    loop {
+        match buttons.wait(screens.interval().min(Duration::from_millis(100)))? {
+            Some(ButtonAction::Acknowledge) => dismisser.dismiss(),
+            Some(action) => pending = Some(action),
+            None => {}
+        }
+        // Never wait for the lock, so that alerts can still be acknowledged
+        let mut vfd = match vfd.try_lock() {
+            Ok(vfd) => vfd,
+            Err(TryLockError::Poisoned(poisoned)) => poisoned.into_inner(),
+            Err(TryLockError::WouldBlock) => continue,
+        };
+        let result: Result<(), VfdError> = match pending.take() {
+            Some(ButtonAction::Next) => screens.next(&mut *vfd),
+            Some(ButtonAction::Previous) => screens.previous(&mut *vfd),
+            _ => screens.tick(&mut *vfd).map(|_| ()),
+        };
+        if let Err(e) = result {
+            logging::warning!("Could not draw screen: {}", e);
+        }
+    }
+}
@@ -0,0 +1,109 @@
+//! Helpers for fitting text into a fixed number of display cells.
+
+use crate::text;
+use alloc::format;
+use alloc::string::{String, ToString};
+use core::str::FromStr;
+
+/// Horizontal placement of text within a line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum Align {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+impl FromStr for Align {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "left" => Ok(Align::Left),
+            "center" | "centre" => Ok(Align::Center),
+            "right" => Ok(Align::Right),
+            other => Err(format!(
+                "Unknown alignment '{}'. Expected left, center or right.",
+                other
+            )),
+        }
+    }
+}
+
+/// Pad or truncate `text` to exactly `width` cells with the given alignment.
+pub fn align(text: &str, width: usize, align: Align) -> String {
+    let text = text::truncate_to_width(text, width);
+    let padding = width - text::display_width(text);
+    let (left, right) = match align {
+        Align::Left => (0, padding),
+        Align::Center => (padding / 2, padding - padding / 2),
+        Align::Right => (padding, 0),
+    };
+    format!("{}{}{}", " ".repeat(left), text, " ".repeat(right))
+}
+
+/// Cut `text` to `width` cells, ending it with `indicator` if anything was cut.
+pub fn ellipsize(text: &str, width: usize, indicator: &str) -> String {
+    if text::display_width(text) <= width {
+        return text.to_string();
+    }
+    let indicator = text::truncate_to_width(indicator, width);
+    let kept = text::truncate_to_width(text, width - text::display_width(indicator));
+    format!("{}{}", kept, indicator)
+}
+
+/// Center `text` in `width` cells, truncating it if it is longer.
+pub fn pad_center(text: &str, width: usize) -> String {
+    align(text, width, Align::Center)
+}
+
+/// Fill `text` with spaces on the right up to `width` cells, truncating it if it is longer.
+pub fn pad_right(text: &str, width: usize) -> String {
+    align(text, width, Align::Left)
+}
+
+/// Fill `text` with spaces on the left up to `width` cells, truncating it if it is longer.
+pub fn pad_left(text: &str, width: usize) -> String {
+    align(text, width, Align::Right)
+}
+
+/// Cut `text` to `width` cells, ending in `...` when anything was dropped.
+pub fn truncate_with_ellipsis(text: &str, width: usize) -> String {
+    const ELLIPSIS: &str = "...";
+    if text::display_width(text) <= width {
+        return text.to_string();
+    }
+    if width <= ELLIPSIS.len() {
+        return text::truncate_to_width(text, width).to_string();
+    }
+    let kept = text::truncate_to_width(text, width - ELLIPSIS.len());
+    format!("{}{}", kept.trim_end(), ELLIPSIS)
+}
+
+/// `left` flush left and `right` flush right on one `width` cell line, separated by spaces.
+///
+/// When both do not fit, `left` is shortened so `right` stays whole.
+pub fn justify(left: &str, right: &str, width: usize) -> String {
+    justify_with(left, right, width, ' ')
+}
+
+/// Like [`justify`], filling the gap with `fill`, e.g. `'.'` for `ITEM ...... $9.99` style leaders.
+pub fn justify_with(left: &str, right: &str, width: usize, fill: char) -> String {
+    let right = text::truncate_to_width(right, width);
+    let right_width = text::display_width(right);
+    // Keep at least one cell between the columns unless the right one fills the line
+    let room = width.saturating_sub(right_width + 1);
+    let left = text::truncate_to_width(left, room);
+    let gap = width - right_width - text::display_width(left);
+    let gap: String = if fill == ' ' || gap < 3 || left.is_empty() {
+        core::iter::repeat_n(fill, gap).collect()
+    } else {
+        format!(" {} ", fill.to_string().repeat(gap - 2))
+    };
+    format!("{}{}{}", left, gap, right)
+}
@@ -0,0 +1,272 @@
+//! Driver for VFD customer displays (Birch DSP-V9FB and compatibles) over a serial port.
+//!
+//! Opening serial ports needs the default `serial` feature. With only `std`
+//! the crate builds for targets such as WebAssembly, driving displays through
+//! any [`Transport`] with [`Vfd::from_transport`].
+//!
+//! Without the default `std` feature only the command encoding, code pages
+//! and text layout remain, which build for `no_std` targets with an
+//! allocator; the `embedded-hal` feature adds `embedded::HalVfd` to drive a
+//! display from a microcontroller UART.
+//!
+//! The `tracing` feature reports through the `tracing` crate: spans for
+//! opening, initializing and flushing a display, every byte sent at debug
+//! level, and the warnings otherwise printed to stderr.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+// Text encoding helpers are only used by the drivers
+#![cfg_attr(not(any(feature = "std", feature = "embedded-hal")), allow(dead_code))]
+
+extern crate alloc;
+
+#[cfg(feature = "std")]
+mod alert;
+#[cfg(feature = "std")]
+mod animation;
+mod annunciator;
+#[cfg(feature = "tokio")]
+mod async_vfd;
+#[cfg(feature = "std")]
+mod background;
+#[cfg(feature = "std")]
+mod big_number;
+#[cfg(feature = "serial")]
+mod builder;
+mod capabilities;
+#[cfg(feature = "capi")]
+pub mod capi;
+#[cfg(feature = "clock")]
+mod clock;
+mod codepage;
+#[cfg(feature = "std")]
+mod command;
+mod command_set;
+#[cfg(feature = "config")]
+pub mod config;
+#[cfg(feature = "std")]
+mod countdown;
+#[cfg(feature = "std")]
+mod currency;
+#[cfg(feature = "std")]
+pub mod daemon;
+#[cfg(feature = "std")]
+pub mod device_path;
+#[cfg(feature = "serial")]
+mod discovery;
+#[cfg(feature = "std")]
+mod display;
+#[cfg(feature = "std")]
+mod display_group;
+#[cfg(feature = "std")]
+mod display_state;
+#[cfg(feature = "embedded-hal")]
+pub mod embedded;
+#[cfg(feature = "std")]
+mod error;
+#[cfg(feature = "std")]
+mod frame_buffer;
+mod geometry;
+pub mod glyph;
+#[cfg(feature = "home-assistant")]
+pub mod home_assistant;
+#[cfg(feature = "http")]
+pub mod http;
+#[cfg(feature = "std")]
+mod info;
+#[cfg(feature = "input")]
+pub mod input;
+pub mod layout;
+#[cfg(feature = "std")]
+mod line;
+#[cfg(feature = "std")]
+mod log_view;
+#[cfg(feature = "std")]
+mod logging;
+#[cfg(feature = "std")]
+mod marquee;
+#[cfg(feature = "std")]
+mod message_queue;
+#[cfg(feature = "std")]
+mod metrics;
+#[cfg(feature = "std")]
+mod mock;
+#[cfg(feature = "mpd")]
+pub mod mpd;
+#[cfg(feature = "mqtt")]
+pub mod mqtt;
+#[cfg(feature = "std")]
+mod number_format;
+#[cfg(feature = "std")]
+mod observer;
+#[cfg(feature = "std")]
+mod on_drop;
+#[cfg(feature = "std")]
+mod paginator;
+mod position;
+#[cfg(feature = "std")]
+mod progress;
+#[cfg(feature = "serde")]
+pub mod protocol;
+mod protocol_definition;
+#[cfg(feature = "std")]
+mod recording;
+#[cfg(feature = "std")]
+mod region;
+#[cfg(feature = "std")]
+mod scheduler;
+#[cfg(feature = "std")]
+mod screen;
+#[cfg(feature = "std")]
+mod screen_cycle;
+#[cfg(feature = "std")]
+pub mod script;
+#[cfg(feature = "rhai")]
+pub mod scripting;
+#[cfg(feature = "serial")]
+mod serial_profile;
+#[cfg(feature = "std")]
+mod simulator;
+#[cfg(feature = "std")]
+mod spinner;
+#[cfg(feature = "std")]
+mod splash;
+#[cfg(feature = "std")]
+mod status_board;
+mod symbols;
+#[cfg(feature = "std")]
+pub mod sysmon;
+#[cfg(all(feature = "systemd", target_os = "linux"))]
+pub mod systemd;
+#[cfg(feature = "std")]
+mod tap;
+#[cfg(feature = "serial")]
+mod tcp_serial;
+#[cfg(feature = "std")]
+mod template;
+#[cfg(feature = "terminal")]
+pub mod terminal;
+pub mod text;
+#[cfg(feature = "std")]
+mod text_style;
+#[cfg(feature = "std")]
+mod throttle;
+#[cfg(feature = "std")]
+mod transport;
+#[cfg(feature = "std")]
+mod vfd;
+#[cfg(feature = "weather")]
+pub mod weather;
+#[cfg(feature = "std")]
+pub mod widget;
+#[cfg(feature = "std")]
+mod write_options;
+
+#[cfg(feature = "std")]
+pub use alert::{Alert, AlertDismisser};
+#[cfg(feature = "std")]
+pub use animation::{Animation, AnimationHandle, Frame};
+pub use annunciator::Annunciator;
+#[cfg(feature = "tokio")]
+pub use async_vfd::{AsyncBirchVfd, AsyncVfd};
+#[cfg(feature = "std")]
+pub use background::BackgroundVfd;
+#[cfg(feature = "std")]
+pub use big_number::BigFont;
+#[cfg(feature = "serial")]
+pub use builder::VfdBuilder;
+pub use capabilities::Capabilities;
+#[cfg(feature = "clock")]
+pub use clock::ClockWidget;
+pub use codepage::{CodePage, TextMapping};
+#[cfg(feature = "std")]
+pub use command::Command;
+pub use command_set::{
+    BrightnessLevel, CommandSet, CursorMove, CursorStyle, DisplayMode, Peripheral,
+};
+#[cfg(feature = "std")]
+pub use countdown::{Countdown, CountdownFormat};
+#[cfg(feature = "std")]
+pub use currency::Currency;
+#[cfg(feature = "serial")]
+pub use discovery::{PortCandidate, discover};
+#[cfg(feature = "std")]
+pub use display::VfdDisplay;
+#[cfg(feature = "std")]
+pub use display_group::{DisplayGroup, GroupLayout};
+#[cfg(feature = "std")]
+pub use display_state::DisplayState;
+#[cfg(feature = "std")]
+pub use error::{Result, VfdError};
+#[cfg(feature = "std")]
+pub use frame_buffer::{FrameBuffer, Span};
+pub use geometry::Geometry;
+pub use glyph::{Glyph, custom_char};
+#[cfg(feature = "std")]
+pub use info::{DisplayInfo, Status};
+pub use layout::Align;
+#[cfg(feature = "std")]
+pub use line::Line;
+#[cfg(feature = "std")]
+pub use log_view::LogView;
+#[cfg(feature = "std")]
+pub use marquee::{Marquee, MarqueeHandle, ScrollDirection};
+#[cfg(feature = "std")]
+pub use message_queue::{Message, MessageQueue};
+#[cfg(feature = "std")]
+pub use metrics::Metrics;
+#[cfg(feature = "std")]
+pub use mock::MockVfd;
+#[cfg(feature = "std")]
+pub use number_format::{Locale, NumberFormat};
+#[cfg(feature = "std")]
+pub use observer::VfdObserver;
+#[cfg(feature = "std")]
+pub use on_drop::OnDrop;
+#[cfg(feature = "std")]
+pub use paginator::Paginator;
+pub use position::Position;
+#[cfg(feature = "std")]
+pub use progress::{ProgressStyle, progress_bar};
+pub use protocol_definition::ProtocolDefinition;
+#[cfg(feature = "std")]
+pub use recording::Recording;
+#[cfg(feature = "std")]
+pub use region::{Region, RegionView};
+#[cfg(feature = "std")]
+pub use scheduler::{Scheduler, SchedulerHandle};
+#[cfg(feature = "std")]
+pub use screen::Screen;
+#[cfg(feature = "std")]
+pub use screen_cycle::ScreenCycle;
+#[cfg(feature = "serial")]
+pub use serial_profile::SerialProfile;
+#[cfg(feature = "serial")]
+pub use serialport::{DataBits, FlowControl, Parity, StopBits};
+#[cfg(feature = "std")]
+pub use simulator::Simulator;
+#[cfg(feature = "std")]
+pub use spinner::Spinner;
+#[cfg(feature = "std")]
+pub use splash::Splash;
+#[cfg(feature = "std")]
+pub use status_board::{StatusBoard, StatusField};
+pub use symbols::SymbolMap;
+#[cfg(feature = "std")]
+pub use tap::{Tap, TapCallback, TapSink, hex_dump};
+#[cfg(feature = "serial")]
+pub use tcp_serial::{BridgeMode, TcpSerial};
+#[cfg(feature = "std")]
+pub use template::{Template, TemplateField, Templates};
+pub use text::WrapPolicy;
+#[cfg(feature = "std")]
+pub use text_style::{BlinkHandle, TextStyle, spawn_blink};
+#[cfg(feature = "std")]
+pub use throttle::Throttle;
+#[cfg(feature = "std")]
+pub use transport::{Duplex, NullTransport, Transport};
+#[cfg(feature = "std")]
+pub use vfd::{BirchVfd, TextFit, Vfd};
+#[cfg(feature = "std")]
+pub use widget::{Widget, WidgetHandle};
+#[cfg(feature = "std")]
+pub use write_options::{FitPolicy, Overflow, WriteOptions};
@@ -0,0 +1,117 @@
+use crate::error::VfdError;
+use crate::layout::Align;
+use crate::vfd::Vfd;
+use std::fmt;
+
+/// One row of a [`Vfd`] to format text into with `write!` and `writeln!`,
+/// returned by [`Vfd::line`].
+///
+/// Text collects until a `\n`, which replaces the row with it and moves on to
+/// the next row, or until the handle is dropped, which writes whatever is left.
+///
+/// [`fmt::Write`] can only report that something failed, not what, so the
+/// first error is kept and can be read with [`Line::error`]. Errors from text
+/// written on drop are lost; call [`Line::finish`] to see them.
+///
+/// ```
+/// use std::fmt::Write;
+/// use vfd_dsp_v9fb_over_serial::{CommandSet, Simulator};
+///
+/// let simulator = Simulator::new(CommandSet::Birch, 20, 2);
+/// let mut vfd = simulator.vfd();
+/// let total = 12.5;
+/// writeln!(vfd.line(1), "Total: {:>8.2}", total).unwrap();
+/// assert_eq!(simulator.row(1), "Total:    12.50     ");
+/// ```
+pub struct Line<'a> {
+    vfd: &'a mut Vfd,
+    row: u8,
+    align: Align,
+    pending: String,
+    error: Option<VfdError>,
+}
+
+impl<'a> Line<'a> {
+    pub(crate) fn new(vfd: &'a mut Vfd, row: u8) -> Self {
+        Line {
+            vfd,
+            row,
+            align: Align::Left,
+            pending: String::new(),
+            error: None,
+        }
+    }
+
+    /// Align each row written through this handle (left by default).
+    pub fn align(mut self, align: Align) -> Self {
+        self.align = align;
+        self
+    }
+
+    /// The row the next line goes to.
+    pub fn row(&self) -> u8 {
+        self.row
+    }
+
+    /// The first error met while writing, if any.
+    pub fn error(&self) -> Option<&VfdError> {
+        self.error.as_ref()
+    }
+
+    /// Write any text not yet ended by `\n` and report the first error met.
+    pub fn finish(mut self) -> Result<(), VfdError> {
+        self.flush();
+        match self.error.take() {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
+    }
+
+    // Write the pending text to the current row and move to the next one
+    fn flush(&mut self) {
+        let text = std::mem::take(&mut self.pending);
+        if self.error.is_some() {
+            return;
+        }
+        match self.vfd.write_line(self.row, &text, self.align) {
+            Ok(()) => self.row = self.row.saturating_add(1),
+            Err(error) => self.error = Some(error),
+        }
+    }
+}
+
+impl fmt::Debug for Line<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Line")
+            .field("row", &self.row)
+            .field("align", &self.align)
+            .field("pending", &self.pending)
+            .field("error", &self.error)
+            .finish_non_exhaustive()
+    }
+}
+
+impl fmt::Write for Line<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let mut lines = s.split('\n');
+        if let Some(first) = lines.next() {
+            self.pending.push_str(first);
+        }
+        for line in lines {
+            self.flush();
+            self.pending.push_str(line);
+        }
+        match self.error {
+            Some(_) => Err(fmt::Error),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for Line<'_> {
+    fn drop(&mut self) {
+        if !self.pending.is_empty() {
+            self.flush();
+        }
+    }
+}
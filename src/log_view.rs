@@ -0,0 +1,68 @@
+use crate::display::VfdDisplay;
+use crate::error::VfdError;
+use crate::text;
+use std::collections::VecDeque;
+
+/// Scrolling log where each new line appears at the bottom and older lines move up.
+///
+/// The history is tracked in software, so it behaves the same whether or not the
+/// display is in its hardware vertical scroll mode.
+#[derive(Debug, Clone, Default)]
+pub struct LogView {
+    lines: VecDeque<String>,
+}
+
+impl LogView {
+    pub fn new() -> Self {
+        LogView::default()
+    }
+
+    /// Lines currently shown, oldest first.
+    pub fn lines(&self) -> impl Iterator<Item = &str> {
+        self.lines.iter().map(String::as_str)
+    }
+
+    /// Scroll existing lines up one row and write `line` on the bottom row.
+    ///
+    /// Lines longer than the display are truncated.
+    pub fn push_line<D: VfdDisplay + ?Sized>(
+        &mut self,
+        display: &mut D,
+        line: &str,
+    ) -> Result<(), VfdError> {
+        let (width, height) = display.size();
+        self.lines.push_back(line.to_string());
+        while self.lines.len() > height as usize {
+            self.lines.pop_front();
+        }
+        self.redraw(display, width, height)
+    }
+
+    /// Forget all lines and blank the display.
+    pub fn clear<D: VfdDisplay + ?Sized>(&mut self, display: &mut D) -> Result<(), VfdError> {
+        self.lines.clear();
+        display.clear()
+    }
+
+    fn redraw<D: VfdDisplay + ?Sized>(
+        &self,
+        display: &mut D,
+        width: u8,
+        height: u8,
+    ) -> Result<(), VfdError> {
+        // Bottom-align the history so the newest line is always on the last row
+        let first_row = height as usize - self.lines.len();
+        let mapping = display.text_mapping();
+        for row in 0..height as usize {
+            let line = match row.checked_sub(first_row) {
+                Some(index) => mapping.apply(&self.lines[index]),
+                None => String::new(),
+            };
+            let line = text::truncate_to_width(&line, width as usize);
+            let padding = width as usize - text::display_width(line);
+            display.set_cursor(0, row as u8)?;
+            display.write_text(&format!("{}{}", line, " ".repeat(padding)))?;
+        }
+        Ok(())
+    }
+}
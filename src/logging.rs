@@ -0,0 +1,42 @@
+// Diagnostics go through `tracing` with the `tracing` feature. Without it,
+// warnings are printed to stderr as before and debug output is dropped.
+
+#[cfg(feature = "tracing")]
+use std::fmt;
+
+// Something went wrong where no caller can be told, e.g. on a server thread
+macro_rules! warning {
+    ($($arg:tt)*) => {{
+        #[cfg(feature = "tracing")]
+        ::tracing::warn!($($arg)*);
+        #[cfg(not(feature = "tracing"))]
+        ::std::eprintln!("Warning: {}", ::std::format_args!($($arg)*));
+    }};
+}
+
+// Detail for tracking down problems; arguments are not evaluated without the feature
+macro_rules! debug {
+    ($($arg:tt)*) => {{
+        #[cfg(feature = "tracing")]
+        ::tracing::debug!($($arg)*);
+    }};
+}
+
+pub(crate) use {debug, warning};
+
+// Bytes as space-separated hex, for logging what is sent
+#[cfg(feature = "tracing")]
+pub(crate) struct Hex<'a>(pub(crate) &'a [u8]);
+
+#[cfg(feature = "tracing")]
+impl fmt::Display for Hex<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, byte) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str(" ")?;
+            }
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
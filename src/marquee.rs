@@ -0,0 +1,199 @@
+use crate::display::VfdDisplay;
+use crate::error::VfdError;
+use crate::widget::Widget;
+use std::io;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+// Blank cells between the end of the text and its next repetition
+const GAP: &str = "   ";
+
+/// Direction the text travels across the line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ScrollDirection {
+    #[default]
+    Left,
+    Right,
+}
+
+impl FromStr for ScrollDirection {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "left" => Ok(ScrollDirection::Left),
+            "right" => Ok(ScrollDirection::Right),
+            other => Err(format!(
+                "Unknown direction '{}'. Expected left or right.",
+                other
+            )),
+        }
+    }
+}
+
+/// Horizontally scrolling text on a single row.
+///
+/// Drive it by calling [`Marquee::tick`] from your own loop, hand it to
+/// [`Marquee::spawn`] to scroll on a background thread, or add it to a
+/// [`Scheduler`](crate::Scheduler) with other widgets.
+#[derive(Debug, Clone)]
+pub struct Marquee {
+    text: Vec<char>,
+    row: u8,
+    width: usize,
+    speed: Duration,
+    direction: ScrollDirection,
+    offset: usize,
+    running: bool,
+    last_step: Option<Instant>,
+}
+
+impl Marquee {
+    /// Scroll `text` on `row` of a display `width` cells wide, moving one cell every `speed`.
+    pub fn new(
+        text: &str,
+        row: u8,
+        width: u8,
+        speed: Duration,
+        direction: ScrollDirection,
+    ) -> Self {
+        let mut chars: Vec<char> = text.chars().collect();
+        if chars.len() > width as usize {
+            chars.extend(GAP.chars());
+        }
+        Marquee {
+            text: chars,
+            row,
+            width: width as usize,
+            speed,
+            direction,
+            offset: 0,
+            running: true,
+            last_step: None,
+        }
+    }
+
+    /// Resume scrolling.
+    pub fn start(&mut self) {
+        self.running = true;
+    }
+
+    /// Pause scrolling; the current frame stays on the display.
+    pub fn stop(&mut self) {
+        self.running = false;
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running
+    }
+
+    /// Text currently visible on the row.
+    pub fn frame(&self) -> String {
+        if self.text.len() <= self.width {
+            let text: String = self.text.iter().collect();
+            return format!("{:<width$}", text, width = self.width);
+        }
+        (0..self.width)
+            .map(|i| self.text[(self.offset + i) % self.text.len()])
+            .collect()
+    }
+
+    /// Advance one step if the marquee is running and its interval has elapsed,
+    /// redrawing the row. Returns whether the display was updated.
+    pub fn tick<D: VfdDisplay + ?Sized>(&mut self, display: &mut D) -> Result<bool, VfdError> {
+        if !self.running {
+            return Ok(false);
+        }
+        let now = Instant::now();
+        match self.last_step {
+            None => {}
+            Some(last) if now.duration_since(last) >= self.speed => self.step(),
+            Some(_) => return Ok(false),
+        }
+        self.last_step = Some(now);
+        Marquee::render(self, display)?;
+        Ok(true)
+    }
+
+    /// Draw the current frame without advancing.
+    pub fn render<D: VfdDisplay + ?Sized>(&self, display: &mut D) -> Result<(), VfdError> {
+        display.set_cursor(0, self.row)?;
+        display.write_text(&self.frame())
+    }
+
+    fn step(&mut self) {
+        let len = self.text.len();
+        if len <= self.width {
+            return;
+        }
+        self.offset = match self.direction {
+            ScrollDirection::Left => (self.offset + 1) % len,
+            ScrollDirection::Right => (self.offset + len - 1) % len,
+        };
+    }
+
+    /// Scroll on a background thread until the returned handle is stopped.
+    pub fn spawn<D>(mut self, display: Arc<Mutex<D>>) -> MarqueeHandle
+    where
+        D: VfdDisplay + Send + 'static,
+    {
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread_stop = Arc::clone(&stop);
+        let thread = thread::spawn(move || {
+            while !thread_stop.load(Ordering::Relaxed) {
+                {
+                    let mut display = display.lock().unwrap_or_else(PoisonError::into_inner);
+                    self.tick(&mut *display)?;
+                }
+                thread::sleep(self.speed.min(Duration::from_millis(50)));
+            }
+            Ok(())
+        });
+        MarqueeHandle {
+            stop,
+            thread: Some(thread),
+        }
+    }
+}
+
+impl Widget for Marquee {
+    fn tick(&mut self, display: &mut dyn VfdDisplay) -> Result<bool, VfdError> {
+        Marquee::tick(self, display)
+    }
+
+    fn render(&mut self, display: &mut dyn VfdDisplay) -> Result<(), VfdError> {
+        Marquee::render(self, display)
+    }
+
+    fn interval(&self) -> Duration {
+        self.speed
+    }
+}
+
+/// Control handle for a marquee running on a background thread.
+pub struct MarqueeHandle {
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<Result<(), VfdError>>>,
+}
+
+impl MarqueeHandle {
+    /// Stop scrolling and wait for the thread to finish, returning any write error it hit.
+    pub fn stop(mut self) -> Result<(), VfdError> {
+        self.stop.store(true, Ordering::Relaxed);
+        match self.thread.take() {
+            Some(thread) => thread
+                .join()
+                .unwrap_or_else(|_| Err(io::Error::other("Marquee thread panicked").into())),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for MarqueeHandle {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
@@ -0,0 +1,243 @@
+use crate::display::VfdDisplay;
+use crate::error::VfdError;
+use crate::layout::Align;
+use crate::widget::Widget;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
+
+/// Text shown by a [`MessageQueue`], with how long and how urgently it is shown.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Message {
+    pub rows: Vec<String>,
+    pub align: Align,
+    /// Higher priorities interrupt and suppress lower ones while queued.
+    pub priority: u8,
+    pub duration: Duration,
+    /// Drop the message after it has been shown once instead of rotating it.
+    pub once: bool,
+}
+
+impl Message {
+    /// A message showing `rows`, top to bottom, centered for 5 seconds at priority 0.
+    pub fn new(rows: &[&str]) -> Self {
+        Message {
+            rows: rows.iter().map(|row| row.to_string()).collect(),
+            align: Align::Center,
+            priority: 0,
+            duration: Duration::from_secs(5),
+            once: false,
+        }
+    }
+
+    pub fn align(mut self, align: Align) -> Self {
+        self.align = align;
+        self
+    }
+
+    pub fn priority(mut self, priority: u8) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    pub fn duration(mut self, duration: Duration) -> Self {
+        self.duration = duration;
+        self
+    }
+
+    /// Show the message a single time, then remove it from the queue.
+    pub fn once(mut self) -> Self {
+        self.once = true;
+        self
+    }
+}
+
+#[derive(Debug, Default)]
+struct State {
+    entries: Vec<(u64, Message)>,
+    next_id: u64,
+    // Id of the message on the display and when it went up
+    current: Option<(u64, Instant)>,
+    // Set when the current message must be redrawn even though it did not change
+    dirty: bool,
+}
+
+impl State {
+    fn top_priority(&self) -> Option<u8> {
+        self.entries
+            .iter()
+            .map(|(_, message)| message.priority)
+            .max()
+    }
+
+    fn position(&self, id: u64) -> Option<usize> {
+        self.entries.iter().position(|(entry, _)| *entry == id)
+    }
+
+    // The message to show after the one at `after`, among the most urgent ones
+    fn next_after(&self, after: Option<usize>) -> Option<u64> {
+        let priority = self.top_priority()?;
+        let start = after.map_or(0, |index| index + 1);
+        let count = self.entries.len();
+        (0..count)
+            .map(|offset| &self.entries[(start + offset) % count])
+            .find(|(_, message)| message.priority == priority)
+            .map(|(id, _)| *id)
+    }
+}
+
+/// Messages rotated on the display automatically, e.g. promotions cycling every
+/// few seconds until a higher-priority message such as a sale total interrupts them.
+///
+/// Only messages of the highest queued priority are rotated. Clones share the
+/// same queue, so messages can be pushed from anywhere while a clone runs
+/// through [`widget::spawn`](crate::widget::spawn).
+#[derive(Debug, Clone, Default)]
+pub struct MessageQueue {
+    state: Arc<Mutex<State>>,
+}
+
+impl MessageQueue {
+    pub fn new() -> Self {
+        MessageQueue::default()
+    }
+
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Add `message` to the rotation, returning an id for [`MessageQueue::remove`].
+    ///
+    /// A message more urgent than the one on the display replaces it on the next tick.
+    pub fn push(&self, message: Message) -> u64 {
+        let mut state = self.state();
+        let id = state.next_id;
+        state.next_id += 1;
+        state.entries.push((id, message));
+        id
+    }
+
+    /// Replace the text of a queued message, redrawing it if it is on the display.
+    pub fn update(&self, id: u64, rows: &[&str]) -> bool {
+        let mut state = self.state();
+        let Some(index) = state.position(id) else {
+            return false;
+        };
+        state.entries[index].1.rows = rows.iter().map(|row| row.to_string()).collect();
+        if state.current.is_some_and(|(current, _)| current == id) {
+            state.dirty = true;
+        }
+        true
+    }
+
+    /// Take a message out of the rotation. Returns whether it was queued.
+    pub fn remove(&self, id: u64) -> bool {
+        let mut state = self.state();
+        match state.position(id) {
+            Some(index) => {
+                state.entries.remove(index);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Remove every message.
+    pub fn clear(&self) {
+        self.state().entries.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.state().entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.state().entries.is_empty()
+    }
+
+    /// The message on the display, if any.
+    pub fn current(&self) -> Option<Message> {
+        let state = self.state();
+        let (id, _) = state.current?;
+        let index = state.position(id)?;
+        Some(state.entries[index].1.clone())
+    }
+
+    fn draw(display: &mut dyn VfdDisplay, message: &Message) -> Result<(), VfdError> {
+        let (_, height) = display.size();
+        for y in 0..height {
+            let row = message.rows.get(y as usize).map_or("", String::as_str);
+            display.write_line(y, row, message.align)?;
+        }
+        Ok(())
+    }
+}
+
+impl Widget for MessageQueue {
+    fn tick(&mut self, display: &mut dyn VfdDisplay) -> Result<bool, VfdError> {
+        let mut state = self.state();
+        let now = Instant::now();
+        let current = state
+            .current
+            .and_then(|(id, since)| Some((state.position(id)?, since)));
+
+        let next = match current {
+            None => state.next_after(None),
+            Some((index, since)) => {
+                let message = &state.entries[index].1;
+                let outranked = state.top_priority() > Some(message.priority);
+                let expired = now.duration_since(since) >= message.duration;
+                if !outranked && !expired {
+                    if !state.dirty {
+                        return Ok(false);
+                    }
+                    // Redraw updated text without restarting its time on the display
+                    let message = message.clone();
+                    state.dirty = false;
+                    drop(state);
+                    MessageQueue::draw(display, &message)?;
+                    return Ok(true);
+                } else {
+                    let once = message.once && expired;
+                    let next = state.next_after(Some(index));
+                    if once {
+                        state.entries.remove(index);
+                    }
+                    // A one-off message that was alone at its priority has just gone
+                    next.filter(|id| state.position(*id).is_some())
+                        .or_else(|| state.next_after(None))
+                }
+            }
+        };
+
+        let Some(id) = next else {
+            let was_showing = state.current.take().is_some();
+            if was_showing {
+                display.clear()?;
+            }
+            return Ok(was_showing);
+        };
+        let unchanged = !state.dirty && state.current.is_some_and(|(current, _)| current == id);
+        state.current = Some((id, now));
+        state.dirty = false;
+        if unchanged {
+            // Sole message at its priority: keep it up without redrawing
+            return Ok(false);
+        }
+        let index = state.position(id).expect("next message is queued");
+        let message = state.entries[index].1.clone();
+        drop(state);
+        MessageQueue::draw(display, &message)?;
+        Ok(true)
+    }
+
+    fn render(&mut self, display: &mut dyn VfdDisplay) -> Result<(), VfdError> {
+        match self.current() {
+            Some(message) => MessageQueue::draw(display, &message),
+            None => Ok(()),
+        }
+    }
+
+    fn interval(&self) -> Duration {
+        Duration::from_millis(50)
+    }
+}
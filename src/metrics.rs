@@ -0,0 +1,142 @@
+use std::fmt::Write;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+/// Counters kept by a [`Vfd`](crate::Vfd) about what it sent, for
+/// monitoring displays across many machines.
+///
+/// Get them with [`Vfd::metrics`](crate::Vfd::metrics); they can be read from
+/// any thread without locking the display. The counts start at zero when the
+/// display is opened and are kept across reconnects.
+///
+/// ```
+/// use vfd_dsp_v9fb_over_serial::{CommandSet, Simulator};
+///
+/// let mut vfd = Simulator::new(CommandSet::Birch, 20, 2).vfd();
+/// let metrics = vfd.metrics();
+/// vfd.write_text("Hello").unwrap();
+/// assert_eq!(metrics.bytes_written(), 5);
+/// assert!(metrics.prometheus().contains("vfd_writes_total 1\n"));
+/// ```
+#[derive(Debug)]
+pub struct Metrics {
+    bytes_written: AtomicU64,
+    writes: AtomicU64,
+    write_errors: AtomicU64,
+    disconnects: AtomicU64,
+    reconnects: AtomicU64,
+    connected: AtomicBool,
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Metrics {
+            bytes_written: AtomicU64::new(0),
+            writes: AtomicU64::new(0),
+            write_errors: AtomicU64::new(0),
+            disconnects: AtomicU64::new(0),
+            reconnects: AtomicU64::new(0),
+            connected: AtomicBool::new(true),
+        }
+    }
+}
+
+impl Metrics {
+    /// Bytes sent to the display.
+    pub fn bytes_written(&self) -> u64 {
+        self.bytes_written.load(Ordering::Relaxed)
+    }
+
+    /// Writes to the port that succeeded; a batch goes out as one.
+    pub fn writes(&self) -> u64 {
+        self.writes.load(Ordering::Relaxed)
+    }
+
+    /// Writes to the port that failed, for whatever reason.
+    pub fn write_errors(&self) -> u64 {
+        self.write_errors.load(Ordering::Relaxed)
+    }
+
+    /// Times the display went away.
+    pub fn disconnects(&self) -> u64 {
+        self.disconnects.load(Ordering::Relaxed)
+    }
+
+    /// Times the port was reopened.
+    pub fn reconnects(&self) -> u64 {
+        self.reconnects.load(Ordering::Relaxed)
+    }
+
+    /// Whether the display is there, as far as the last write could tell.
+    pub fn is_connected(&self) -> bool {
+        self.connected.load(Ordering::Relaxed)
+    }
+
+    /// The metrics in the Prometheus text exposition format.
+    pub fn prometheus(&self) -> String {
+        let mut out = String::new();
+        let metrics = [
+            (
+                "vfd_bytes_written_total",
+                "counter",
+                "Bytes sent to the display.",
+                self.bytes_written(),
+            ),
+            (
+                "vfd_writes_total",
+                "counter",
+                "Writes to the display's port.",
+                self.writes(),
+            ),
+            (
+                "vfd_write_errors_total",
+                "counter",
+                "Writes to the display's port that failed.",
+                self.write_errors(),
+            ),
+            (
+                "vfd_disconnects_total",
+                "counter",
+                "Times the display went away.",
+                self.disconnects(),
+            ),
+            (
+                "vfd_reconnects_total",
+                "counter",
+                "Times the display's port was reopened.",
+                self.reconnects(),
+            ),
+            (
+                "vfd_connected",
+                "gauge",
+                "Whether the display is connected.",
+                self.is_connected() as u64,
+            ),
+        ];
+        for (name, kind, help, value) in metrics {
+            let _ = writeln!(out, "# HELP {} {}", name, help);
+            let _ = writeln!(out, "# TYPE {} {}", name, kind);
+            let _ = writeln!(out, "{} {}", name, value);
+        }
+        out
+    }
+
+    pub(crate) fn record_write(&self, len: usize) {
+        self.writes.fetch_add(1, Ordering::Relaxed);
+        self.bytes_written.fetch_add(len as u64, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_write_error(&self) {
+        self.write_errors.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_disconnect(&self) {
+        self.disconnects.fetch_add(1, Ordering::Relaxed);
+        self.connected.store(false, Ordering::Relaxed);
+    }
+
+    #[cfg(feature = "serial")]
+    pub(crate) fn record_reconnect(&self) {
+        self.reconnects.fetch_add(1, Ordering::Relaxed);
+        self.connected.store(true, Ordering::Relaxed);
+    }
+}
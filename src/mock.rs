@@ -0,0 +1,140 @@
+use crate::codepage::TextMapping;
+use crate::command_set::{BrightnessLevel, CommandSet};
+use crate::display::VfdDisplay;
+use crate::error::VfdError;
+use crate::frame_buffer::FrameBuffer;
+use crate::layout::Align;
+use crate::vfd::Vfd;
+use std::io::{self, Write};
+use std::ops::{Deref, DerefMut};
+use std::sync::{Arc, Mutex, PoisonError};
+
+// In-memory transport whose contents stay readable after it is boxed into a Vfd
+#[derive(Clone, Default)]
+struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+impl SharedBuffer {
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<u8>> {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.lock().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// A [`Vfd`] without hardware, for testing code that drives a display.
+///
+/// Every byte the driver sends is recorded, and the text written is kept in
+/// a cell grid so tests can assert on what the screen would show. All `Vfd`
+/// methods are available through `Deref`.
+///
+/// ```
+/// use vfd_dsp_v9fb_over_serial::MockVfd;
+///
+/// let mut vfd = MockVfd::new(20, 2);
+/// vfd.clear().unwrap();
+/// vfd.write_text("Hello").unwrap();
+/// assert_eq!(vfd.row(0), "Hello               ");
+/// assert!(vfd.bytes().ends_with(b"Hello"));
+/// ```
+pub struct MockVfd {
+    vfd: Vfd,
+    bytes: SharedBuffer,
+}
+
+impl MockVfd {
+    /// A `width × height` display speaking the default command set.
+    pub fn new(width: u8, height: u8) -> Self {
+        MockVfd::with_command_set(width, height, CommandSet::default())
+    }
+
+    /// A `width × height` display speaking `command_set`.
+    pub fn with_command_set(width: u8, height: u8, command_set: CommandSet) -> Self {
+        let bytes = SharedBuffer::default();
+        MockVfd {
+            vfd: Vfd::from_transport(bytes.clone(), command_set, width, height),
+            bytes,
+        }
+    }
+
+    /// Every byte sent so far.
+    pub fn bytes(&self) -> Vec<u8> {
+        self.bytes.lock().clone()
+    }
+
+    /// Return the bytes sent so far and start recording afresh.
+    pub fn take_bytes(&mut self) -> Vec<u8> {
+        std::mem::take(&mut *self.bytes.lock())
+    }
+
+    /// Contents of row `y`, padded with spaces to the display width.
+    pub fn row(&self, y: u8) -> String {
+        self.vfd.shown().row(y)
+    }
+
+    /// Contents of every row, top to bottom.
+    pub fn rows(&self) -> Vec<String> {
+        self.vfd.dump()
+    }
+
+    /// The simulated cell grid.
+    pub fn frame(&self) -> &FrameBuffer {
+        self.vfd.shown()
+    }
+}
+
+impl Deref for MockVfd {
+    type Target = Vfd;
+
+    fn deref(&self) -> &Vfd {
+        &self.vfd
+    }
+}
+
+impl DerefMut for MockVfd {
+    fn deref_mut(&mut self) -> &mut Vfd {
+        &mut self.vfd
+    }
+}
+
+impl VfdDisplay for MockVfd {
+    fn size(&self) -> (u8, u8) {
+        self.vfd.size()
+    }
+
+    fn clear(&mut self) -> Result<(), VfdError> {
+        self.vfd.clear()
+    }
+
+    fn set_cursor(&mut self, x: u8, y: u8) -> Result<(), VfdError> {
+        self.vfd.set_cursor(x, y)
+    }
+
+    fn get_cursor(&self) -> (u8, u8) {
+        self.vfd.get_cursor()
+    }
+
+    fn text_mapping(&self) -> TextMapping {
+        self.vfd.text_mapping()
+    }
+
+    fn write_text(&mut self, text: &str) -> Result<(), VfdError> {
+        self.vfd.write_text(text)
+    }
+
+    fn write_line(&mut self, row: u8, text: &str, align: Align) -> Result<(), VfdError> {
+        self.vfd.write_line(row, text, align)
+    }
+
+    fn set_brightness(&mut self, level: BrightnessLevel) -> Result<(), VfdError> {
+        self.vfd.set_brightness(level)
+    }
+}
@@ -0,0 +1,192 @@
+//! Now-playing front-end for Music Player Daemon (`mpd` feature).
+//!
+//! Polls MPD over its text protocol and shows the current artist on the top
+//! row and the track title on the second, scrolling either when it does not
+//! fit. The display is redrawn whenever the track or play state changes.
+
+use crate::error::VfdError;
+use crate::layout::Align;
+use crate::logging;
+use crate::marquee::{Marquee, ScrollDirection};
+use crate::vfd::Vfd;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::TcpStream;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Server address and how the track is shown.
+#[derive(Debug, Clone)]
+pub struct MpdConfig {
+    pub host: String,
+    pub port: u16,
+    pub password: Option<String>,
+    /// How often MPD is asked for the current track.
+    pub poll_interval: Duration,
+    /// Time per marquee step for text wider than the display.
+    pub scroll_speed: Duration,
+}
+
+impl Default for MpdConfig {
+    fn default() -> Self {
+        MpdConfig {
+            host: "localhost".to_string(),
+            port: 6600,
+            password: None,
+            poll_interval: Duration::from_secs(1),
+            scroll_speed: Duration::from_millis(300),
+        }
+    }
+}
+
+/// What MPD is playing.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Track {
+    pub artist: String,
+    /// The `Title` tag, or the file name for untagged files.
+    pub title: String,
+    /// `false` while paused or stopped.
+    pub playing: bool,
+}
+
+/// A connection speaking the MPD protocol.
+pub struct MpdClient {
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+}
+
+impl MpdClient {
+    /// Connect and authenticate if the config has a password.
+    pub fn connect(config: &MpdConfig) -> io::Result<MpdClient> {
+        let stream = TcpStream::connect((config.host.as_str(), config.port))?;
+        stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+        let mut client = MpdClient {
+            reader: BufReader::new(stream.try_clone()?),
+            writer: stream,
+        };
+        let mut greeting = String::new();
+        client.reader.read_line(&mut greeting)?;
+        if !greeting.starts_with("OK MPD") {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Not an MPD server: {}", greeting.trim()),
+            ));
+        }
+        if let Some(password) = &config.password {
+            client.command(&format!("password {}", quote(password)))?;
+        }
+        Ok(client)
+    }
+
+    /// Send `command` and return the `key: value` pairs of its response.
+    pub fn command(&mut self, command: &str) -> io::Result<Vec<(String, String)>> {
+        writeln!(self.writer, "{}", command)?;
+        let mut pairs = Vec::new();
+        loop {
+            let mut line = String::new();
+            if self.reader.read_line(&mut line)? == 0 {
+                return Err(io::ErrorKind::UnexpectedEof.into());
+            }
+            let line = line.trim_end_matches('\n');
+            if line == "OK" {
+                return Ok(pairs);
+            }
+            if let Some(error) = line.strip_prefix("ACK ") {
+                return Err(io::Error::other(format!("MPD error: {}", error)));
+            }
+            if let Some((key, value)) = line.split_once(": ") {
+                pairs.push((key.to_string(), value.to_string()));
+            }
+        }
+    }
+
+    /// The current track and play state.
+    pub fn current_track(&mut self) -> io::Result<Track> {
+        let status = self.command("status")?;
+        let song = self.command("currentsong")?;
+        let field = |pairs: &[(String, String)], key: &str| {
+            pairs
+                .iter()
+                .find(|(k, _)| k == key)
+                .map(|(_, value)| value.clone())
+        };
+        let title = field(&song, "Title").or_else(|| {
+            field(&song, "file").map(|file| file.rsplit('/').next().unwrap_or("").to_string())
+        });
+        Ok(Track {
+            artist: field(&song, "Artist").unwrap_or_default(),
+            title: title.unwrap_or_default(),
+            playing: field(&status, "state").as_deref() == Some("play"),
+        })
+    }
+}
+
+// Quote an argument for the MPD protocol
+fn quote(arg: &str) -> String {
+    format!("\"{}\"", arg.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Show the current track until the process exits.
+///
+/// Connection errors are logged and retried after a short pause.
+pub fn serve_mpd(vfd: Arc<Mutex<Vfd>>, config: &MpdConfig) {
+    loop {
+        if let Err(e) = follow(&vfd, config) {
+            logging::warning!("MPD connection error: {}", e);
+        }
+        thread::sleep(Duration::from_secs(5));
+    }
+}
+
+// Poll one connection, redrawing on changes and stepping the marquees in between
+fn follow(vfd: &Arc<Mutex<Vfd>>, config: &MpdConfig) -> Result<(), VfdError> {
+    let mut client = MpdClient::connect(config)?;
+    let mut shown: Option<Track> = None;
+    let mut marquees: Vec<Marquee> = Vec::new();
+    let mut polled: Option<Instant> = None;
+    loop {
+        if polled.is_none_or(|at| at.elapsed() >= config.poll_interval) {
+            polled = Some(Instant::now());
+            let track = client.current_track()?;
+            if shown.as_ref() != Some(&track) {
+                let mut vfd = vfd.lock().unwrap_or_else(PoisonError::into_inner);
+                marquees = show(&mut vfd, &track, config.scroll_speed)?;
+                shown = Some(track);
+            }
+        }
+        {
+            let mut vfd = vfd.lock().unwrap_or_else(PoisonError::into_inner);
+            for marquee in &mut marquees {
+                marquee.tick(&mut *vfd)?;
+            }
+        }
+        thread::sleep(Duration::from_millis(50));
+    }
+}
+
+fn show(vfd: &mut Vfd, track: &Track, speed: Duration) -> Result<Vec<Marquee>, VfdError> {
+    let (_, height) = vfd.size();
+    if !track.playing || track.title.is_empty() {
+        let state = if track.title.is_empty() {
+            "Stopped"
+        } else {
+            "Paused"
+        };
+        vfd.clear()?;
+        vfd.write_line(0, state, Align::Center)?;
+        return Ok(Vec::new());
+    }
+    let rows = if height >= 2 {
+        vec![track.artist.clone(), track.title.clone()]
+    } else if track.artist.is_empty() {
+        vec![track.title.clone()]
+    } else {
+        vec![format!("{} - {}", track.artist, track.title)]
+    };
+    vfd.clear()?;
+    Ok(rows
+        .iter()
+        .enumerate()
+        .map(|(y, text)| vfd.marquee(text, y as u8, speed, ScrollDirection::Left))
+        .collect())
+}
@@ -0,0 +1,113 @@
+//! MQTT front-end, the usual way to integrate the display with Home Assistant.
+//!
+//! Subscribes to `<prefix>/line1` … `<prefix>/lineN`, whose payloads replace the
+//! corresponding row, and `<prefix>/command`, whose payloads are lines of the
+//! [daemon protocol](crate::daemon).
+
+use crate::daemon::DaemonCommand;
+use crate::layout::Align;
+use crate::logging;
+use crate::vfd::Vfd;
+use rumqttc::{Client, Event, MqttOptions, Packet, QoS};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread;
+use std::time::Duration;
+
+/// Broker connection and topic settings.
+#[derive(Debug, Clone)]
+pub struct MqttConfig {
+    pub host: String,
+    pub port: u16,
+    pub client_id: String,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub topic_prefix: String,
+}
+
+impl Default for MqttConfig {
+    fn default() -> Self {
+        MqttConfig {
+            host: "localhost".to_string(),
+            port: 1883,
+            client_id: "vfdd".to_string(),
+            username: None,
+            password: None,
+            topic_prefix: "vfd".to_string(),
+        }
+    }
+}
+
+/// Render messages from the broker until the process exits.
+///
+/// Connection errors are logged and retried after a short pause.
+pub fn serve_mqtt(vfd: Arc<Mutex<Vfd>>, config: &MqttConfig) {
+    let mut options = MqttOptions::new(&config.client_id, &config.host, config.port);
+    options.set_keep_alive(Duration::from_secs(30));
+    if let (Some(username), Some(password)) = (&config.username, &config.password) {
+        options.set_credentials(username, password);
+    }
+
+    let (client, mut connection) = Client::new(options, 10);
+    let prefix = config.topic_prefix.trim_end_matches('/');
+
+    for notification in connection.iter() {
+        match notification {
+            // Subscriptions do not survive a reconnect with a clean session
+            Ok(Event::Incoming(Packet::ConnAck(_))) => {
+                let topic = format!("{}/#", prefix);
+                if let Err(e) = client.subscribe(topic, QoS::AtLeastOnce) {
+                    logging::warning!("MQTT subscribe failed: {}", e);
+                }
+            }
+            Ok(Event::Incoming(Packet::Publish(publish))) => {
+                let payload = String::from_utf8_lossy(&publish.payload);
+                if let Err(e) = handle_message(&vfd, prefix, &publish.topic, &payload) {
+                    logging::warning!("MQTT message on {} failed: {}", publish.topic, e);
+                }
+            }
+            Ok(_) => {}
+            Err(e) => {
+                logging::warning!("MQTT connection error: {}", e);
+                thread::sleep(Duration::from_secs(1));
+            }
+        }
+    }
+}
+
+fn handle_message(
+    vfd: &Arc<Mutex<Vfd>>,
+    prefix: &str,
+    topic: &str,
+    payload: &str,
+) -> Result<(), String> {
+    let Some(suffix) = topic.strip_prefix(prefix).and_then(|t| t.strip_prefix('/')) else {
+        return Ok(());
+    };
+    if suffix == "command" {
+        let command: DaemonCommand = payload.parse()?;
+        if let DaemonCommand::Alert(alert) = command {
+            // Shown on its own thread, so that the connection keeps being served
+            let vfd = Arc::clone(vfd);
+            thread::spawn(move || {
+                if let Err(e) = alert.show(&vfd) {
+                    logging::warning!("MQTT alert failed: {}", e);
+                }
+            });
+            return Ok(());
+        }
+        return command.execute_shared(vfd).map_err(|e| e.to_string());
+    }
+    if let Some(row) = suffix
+        .strip_prefix("line")
+        .and_then(|n| n.parse::<u8>().ok())
+    {
+        let mut vfd = vfd.lock().unwrap_or_else(PoisonError::into_inner);
+        let (_, height) = vfd.size();
+        if row == 0 || row > height {
+            return Err(format!("No line {} on a {} line display", row, height));
+        }
+        vfd.write_line(row - 1, payload, Align::Left)
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
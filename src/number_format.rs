@@ -0,0 +1,180 @@
+use crate::currency::Currency;
+use std::fmt;
+use std::str::FromStr;
+
+/// How numbers are written on the display: `1,234.56`, `1.234,56` and so on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NumberFormat {
+    /// Number of fractional digits, always shown.
+    pub decimals: u8,
+    /// Character between the whole and fractional parts.
+    pub decimal_separator: char,
+    /// Character between each group of three whole digits, if any.
+    pub grouping_separator: Option<char>,
+}
+
+impl Default for NumberFormat {
+    /// Two decimals after a `.`, no grouping: `1234.56`.
+    fn default() -> Self {
+        NumberFormat {
+            decimals: 2,
+            decimal_separator: '.',
+            grouping_separator: None,
+        }
+    }
+}
+
+impl NumberFormat {
+    pub fn decimals(mut self, decimals: u8) -> Self {
+        self.decimals = decimals;
+        self
+    }
+
+    pub fn decimal_separator(mut self, separator: char) -> Self {
+        self.decimal_separator = separator;
+        self
+    }
+
+    pub fn grouping_separator(mut self, separator: Option<char>) -> Self {
+        self.grouping_separator = separator;
+        self
+    }
+
+    /// `value` rounded to the number of decimals.
+    ///
+    /// ```
+    /// use vfd_dsp_v9fb_over_serial::{Locale, NumberFormat};
+    ///
+    /// assert_eq!(NumberFormat::default().format(-3.14159), "-3.14");
+    /// assert_eq!(Locale::DeDe.number_format().format(1234.5), "1.234,50");
+    /// ```
+    pub fn format(&self, value: f64) -> String {
+        let digits = format!("{:.*}", self.decimals as usize, value.abs());
+        let (whole, fraction) = digits.split_once('.').unwrap_or((&digits, ""));
+        // Rounding to zero drops the sign rather than showing "-0.00"
+        let negative = value < 0.0 && digits.bytes().any(|b| matches!(b, b'1'..=b'9'));
+        self.join(negative, whole, fraction)
+    }
+
+    /// An amount given in minor units, such as cents, with as many of them in
+    /// a whole as the number of decimals says: `123456` is `1234.56`.
+    pub fn format_minor(&self, minor_units: i64) -> String {
+        let amount = minor_units.unsigned_abs();
+        let scale = 10u64.pow(self.decimals as u32);
+        let fraction = format!("{:0width$}", amount % scale, width = self.decimals as usize);
+        let fraction = if self.decimals == 0 { "" } else { &fraction };
+        self.join(minor_units < 0, &(amount / scale).to_string(), fraction)
+    }
+
+    fn join(&self, negative: bool, whole: &str, fraction: &str) -> String {
+        let mut text = String::new();
+        if negative {
+            text.push('-');
+        }
+        for (i, digit) in whole.chars().enumerate() {
+            if i > 0 && (whole.len() - i).is_multiple_of(3) {
+                text.extend(self.grouping_separator);
+            }
+            text.push(digit);
+        }
+        if !fraction.is_empty() {
+            text.push(self.decimal_separator);
+            text.push_str(fraction);
+        }
+        text
+    }
+}
+
+/// Presets for how a country writes numbers and its currency.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Locale {
+    /// `1,234.56`, `$1,234.56`
+    #[default]
+    EnUs,
+    /// `1,234.56`, `£1,234.56`
+    EnGb,
+    /// `1.234,56`, `1.234,56 €`
+    DeDe,
+    /// `1 234,56`, `1 234,56 €`
+    FrFr,
+    /// `1.234,56`, `1.234,56 €`
+    EsEs,
+    /// `1.234,56`, `1.234,56 €`
+    ItIt,
+    /// `1,234.56`, `¥1,235`
+    JaJp,
+}
+
+impl Locale {
+    /// How numbers are written, with two decimals.
+    pub fn number_format(&self) -> NumberFormat {
+        let (decimal_separator, grouping_separator) = match self {
+            Locale::EnUs | Locale::EnGb | Locale::JaJp => ('.', ','),
+            Locale::DeDe | Locale::EsEs | Locale::ItIt => (',', '.'),
+            Locale::FrFr => (',', ' '),
+        };
+        NumberFormat {
+            decimals: 2,
+            decimal_separator,
+            grouping_separator: Some(grouping_separator),
+        }
+    }
+
+    /// The local currency, written the local way.
+    ///
+    /// ```
+    /// use vfd_dsp_v9fb_over_serial::Locale;
+    ///
+    /// assert_eq!(Locale::DeDe.currency().format(123456), "1.234,56 €");
+    /// assert_eq!(Locale::EnUs.currency().format(-999), "-$9.99");
+    /// ```
+    pub fn currency(&self) -> Currency {
+        let currency = match self {
+            Locale::EnUs => Currency::usd(),
+            Locale::EnGb => Currency::gbp(),
+            Locale::JaJp => Currency::jpy(),
+            Locale::DeDe | Locale::FrFr | Locale::EsEs | Locale::ItIt => Currency::eur(),
+        };
+        let format = self.number_format();
+        Currency {
+            decimal_separator: format.decimal_separator,
+            grouping_separator: format.grouping_separator,
+            ..currency
+        }
+    }
+}
+
+impl FromStr for Locale {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().replace('_', "-").as_str() {
+            "en-us" => Ok(Locale::EnUs),
+            "en-gb" => Ok(Locale::EnGb),
+            "de-de" => Ok(Locale::DeDe),
+            "fr-fr" => Ok(Locale::FrFr),
+            "es-es" => Ok(Locale::EsEs),
+            "it-it" => Ok(Locale::ItIt),
+            "ja-jp" => Ok(Locale::JaJp),
+            _ => Err(format!(
+                "Unknown locale '{}'. Expected one of en-US, en-GB, de-DE, fr-FR, es-ES, it-IT, ja-JP.",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Locale::EnUs => "en-US",
+            Locale::EnGb => "en-GB",
+            Locale::DeDe => "de-DE",
+            Locale::FrFr => "fr-FR",
+            Locale::EsEs => "es-ES",
+            Locale::ItIt => "it-IT",
+            Locale::JaJp => "ja-JP",
+        };
+        write!(f, "{}", name)
+    }
+}
@@ -0,0 +1,77 @@
+use crate::error::VfdError;
+#[cfg(feature = "serial")]
+use std::fmt;
+use std::sync::Arc;
+
+/// Receives notice of what happens to the connection to a display, e.g. to
+/// log or raise an alert when it drops off the bus.
+///
+/// Every method does nothing unless implemented. They are called on the
+/// thread using the display while it waits, so they should return quickly.
+///
+/// ```
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+/// use vfd_dsp_v9fb_over_serial::{VfdError, VfdObserver};
+///
+/// #[derive(Default)]
+/// struct Alarm {
+///     failures: AtomicUsize,
+/// }
+///
+/// impl VfdObserver for Alarm {
+///     fn on_disconnected(&self, error: &VfdError) {
+///         eprintln!("Customer display went away: {}", error);
+///         self.failures.fetch_add(1, Ordering::Relaxed);
+///     }
+///
+///     fn on_reconnected(&self) {
+///         eprintln!("Customer display is back");
+///     }
+/// }
+/// ```
+pub trait VfdObserver: Send + Sync {
+    /// The port was opened and the display set up, see [`VfdBuilder::observer`](crate::VfdBuilder::observer).
+    fn on_connected(&self) {}
+
+    /// A write failed because the display went away, see [`VfdError::is_disconnected`].
+    ///
+    /// Reported once, not again for the writes that fail until it is reconnected.
+    fn on_disconnected(&self, _error: &VfdError) {}
+
+    /// A write to the display failed, for whatever reason.
+    fn on_write_error(&self, _error: &VfdError) {}
+
+    /// The port was reopened and the display restored, automatically or by
+    /// [`Vfd::reconnect`](crate::Vfd::reconnect).
+    fn on_reconnected(&self) {}
+}
+
+impl<O: VfdObserver + ?Sized> VfdObserver for Arc<O> {
+    fn on_connected(&self) {
+        (**self).on_connected()
+    }
+
+    fn on_disconnected(&self, error: &VfdError) {
+        (**self).on_disconnected(error)
+    }
+
+    fn on_write_error(&self, error: &VfdError) {
+        (**self).on_write_error(error)
+    }
+
+    fn on_reconnected(&self) {
+        (**self).on_reconnected()
+    }
+}
+
+// An observer kept in settings, which need to be Clone and Debug
+#[cfg(feature = "serial")]
+#[derive(Clone)]
+pub(crate) struct SharedObserver(pub(crate) Arc<dyn VfdObserver>);
+
+#[cfg(feature = "serial")]
+impl fmt::Debug for SharedObserver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("VfdObserver")
+    }
+}
@@ -0,0 +1,63 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// What a [`Vfd`](crate::Vfd) leaves on the display when it is dropped.
+///
+/// Drop runs when the program returns from `main` and while a panic unwinds,
+/// but not on `std::process::exit` or with `panic = "abort"`. Failures to
+/// reach the display at that point are ignored.
+///
+/// ```
+/// use vfd_dsp_v9fb_over_serial::OnDrop;
+///
+/// assert_eq!("clear".parse(), Ok(OnDrop::Clear));
+/// assert_eq!(
+///     "message:CLOSED".parse(),
+///     Ok(OnDrop::Message("CLOSED".to_string()))
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum OnDrop {
+    /// Keep showing whatever is on the display.
+    #[default]
+    Leave,
+    /// Blank the display.
+    Clear,
+    /// Blank the display and show this text, wrapped like
+    /// [`Vfd::write_text`](crate::Vfd::write_text).
+    Message(String),
+    /// Turn the display off, keeping its contents for the next time it is turned on.
+    DisplayOff,
+}
+
+impl FromStr for OnDrop {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some((kind, text)) = s.split_once(':')
+            && kind.trim().eq_ignore_ascii_case("message")
+        {
+            return Ok(OnDrop::Message(text.to_string()));
+        }
+        match s.trim().to_ascii_lowercase().as_str() {
+            "leave" => Ok(OnDrop::Leave),
+            "clear" => Ok(OnDrop::Clear),
+            "display_off" | "off" => Ok(OnDrop::DisplayOff),
+            other => Err(format!(
+                "Unknown drop behavior '{}'. Expected one of leave, clear, message:<text>, display_off.",
+                other
+            )),
+        }
+    }
+}
+
+impl fmt::Display for OnDrop {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OnDrop::Leave => write!(f, "leave"),
+            OnDrop::Clear => write!(f, "clear"),
+            OnDrop::Message(text) => write!(f, "message:{}", text),
+            OnDrop::DisplayOff => write!(f, "display_off"),
+        }
+    }
+}
@@ -0,0 +1,201 @@
+use crate::display::VfdDisplay;
+use crate::error::VfdError;
+use crate::layout::{self, Align};
+use crate::text::{self, WrapPolicy};
+use crate::widget::Widget;
+use std::time::{Duration, Instant};
+
+/// Text longer than the display, shown one screenful at a time.
+///
+/// Lines are wrapped to the display width and split into pages of as many
+/// rows as the display has. With more than one page a `1/3` style indicator
+/// takes the bottom-right corner, and the text is wrapped to the columns
+/// left of it so that it never runs under it.
+///
+/// Turn pages with [`Paginator::next_page`] and [`Paginator::prev_page`], or
+/// set [`Paginator::auto_advance`] and drive it as a [`Widget`].
+///
+/// ```
+/// use vfd_dsp_v9fb_over_serial::{CommandSet, Paginator, Simulator};
+///
+/// let simulator = Simulator::new(CommandSet::Birch, 20, 2);
+/// let mut vfd = simulator.vfd();
+/// let mut pages = Paginator::new(
+///     "Store closes at 9pm today. Pickup orders can be collected at the side entrance.",
+/// );
+/// pages.show(&mut vfd).unwrap();
+/// assert_eq!(simulator.rows(), ["Store closes at     ", "9pm today.       1/3"]);
+/// pages.next_page(&mut vfd).unwrap();
+/// assert_eq!(simulator.rows(), ["Pickup orders       ", "can be collected 2/3"]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Paginator {
+    lines: Vec<String>,
+    page: usize,
+    indicator: bool,
+    wrap: WrapPolicy,
+    auto_advance: Option<Duration>,
+    // When the current page was drawn, if it was
+    shown_at: Option<Instant>,
+}
+
+impl Paginator {
+    /// Pages of `text`, with `\n` starting a new line.
+    pub fn new(text: &str) -> Self {
+        Paginator::from_lines(text.lines())
+    }
+
+    /// Pages of `lines`, each starting on a new row.
+    pub fn from_lines<I, S>(lines: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        Paginator {
+            lines: lines
+                .into_iter()
+                .map(|line| line.as_ref().to_string())
+                .collect(),
+            page: 0,
+            indicator: true,
+            wrap: WrapPolicy::WORD,
+            auto_advance: None,
+            shown_at: None,
+        }
+    }
+
+    /// Whether to show the page number in the bottom-right corner when there
+    /// is more than one page (default `true`).
+    pub fn indicator(mut self, indicator: bool) -> Self {
+        self.indicator = indicator;
+        self
+    }
+
+    /// How lines longer than the display are broken (by word by default).
+    pub fn wrap(mut self, policy: WrapPolicy) -> Self {
+        self.wrap = policy;
+        self
+    }
+
+    /// Turn to the next page every `period` when ticked, going back to the
+    /// first after the last.
+    pub fn auto_advance(mut self, period: Duration) -> Self {
+        self.auto_advance = Some(period);
+        self
+    }
+
+    /// Index of the current page, starting at 0.
+    pub fn page(&self) -> usize {
+        self.page
+    }
+
+    /// Number of pages on a `(columns, rows)` display.
+    pub fn page_count(&self, size: (u8, u8)) -> usize {
+        self.layout(size).0.len()
+    }
+
+    // Rows of each page on a `(columns, rows)` display, and the columns kept
+    // for the indicator
+    fn layout(&self, (width, height): (u8, u8)) -> (Vec<Vec<String>>, usize) {
+        let (width, height) = (width as usize, (height as usize).max(1));
+        let mut reserved = 0;
+        loop {
+            let columns = width.saturating_sub(reserved).max(1);
+            let rows: Vec<String> = self
+                .lines
+                .iter()
+                .flat_map(|line| {
+                    if line.is_empty() {
+                        vec![String::new()]
+                    } else {
+                        text::wrap(line, columns, columns, self.wrap)
+                    }
+                })
+                .collect();
+            let pages: Vec<Vec<String>> = rows.chunks(height).map(<[String]>::to_vec).collect();
+            let pages = if pages.is_empty() {
+                vec![Vec::new()]
+            } else {
+                pages
+            };
+            // A space and "n/n", wide enough for the number of pages
+            let needed = if self.indicator && pages.len() > 1 {
+                2 * pages.len().to_string().len() + 2
+            } else {
+                0
+            };
+            if needed <= reserved || needed >= width {
+                return (pages, reserved);
+            }
+            reserved = needed;
+        }
+    }
+
+    /// Draw the current page.
+    pub fn show<D: VfdDisplay + ?Sized>(&mut self, display: &mut D) -> Result<(), VfdError> {
+        let size = display.size();
+        let (pages, reserved) = self.layout(size);
+        self.page = self.page.min(pages.len() - 1);
+        let (width, height) = (size.0 as usize, size.1);
+        let columns = width - reserved;
+        let mapping = display.text_mapping();
+        for y in 0..height {
+            let row = pages[self.page].get(y as usize).map_or("", String::as_str);
+            let mut line = layout::align(&mapping.apply(row), columns, Align::Left);
+            if y + 1 == height && reserved > 0 {
+                let indicator = format!("{}/{}", self.page + 1, pages.len());
+                line.push_str(&layout::align(&indicator, reserved, Align::Right));
+            } else {
+                line.push_str(&" ".repeat(reserved));
+            }
+            display.update_span(0, y, &line)?;
+        }
+        self.shown_at = Some(Instant::now());
+        Ok(())
+    }
+
+    /// Show page `page`, the last one if there are fewer.
+    pub fn go_to<D: VfdDisplay + ?Sized>(
+        &mut self,
+        display: &mut D,
+        page: usize,
+    ) -> Result<(), VfdError> {
+        self.page = page;
+        self.show(display)
+    }
+
+    /// Show the next page, or the first after the last.
+    pub fn next_page<D: VfdDisplay + ?Sized>(&mut self, display: &mut D) -> Result<(), VfdError> {
+        let pages = self.page_count(display.size());
+        self.page = (self.page + 1) % pages;
+        self.show(display)
+    }
+
+    /// Show the previous page, or the last before the first.
+    pub fn prev_page<D: VfdDisplay + ?Sized>(&mut self, display: &mut D) -> Result<(), VfdError> {
+        let pages = self.page_count(display.size());
+        self.page = (self.page + pages - 1) % pages;
+        self.show(display)
+    }
+}
+
+impl Widget for Paginator {
+    fn tick(&mut self, display: &mut dyn VfdDisplay) -> Result<bool, VfdError> {
+        match (self.shown_at, self.auto_advance) {
+            (None, _) => self.show(display)?,
+            (Some(shown_at), Some(period)) if shown_at.elapsed() >= period => {
+                self.next_page(display)?
+            }
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    fn render(&mut self, display: &mut dyn VfdDisplay) -> Result<(), VfdError> {
+        self.show(display)
+    }
+
+    fn interval(&self) -> Duration {
+        self.auto_advance.unwrap_or(Duration::from_secs(1))
+    }
+}
@@ -0,0 +1,95 @@
+#[cfg(feature = "std")]
+use crate::error::VfdError;
+use core::fmt;
+
+/// A cell on the display: column `x` and row `y`, both counted from 0 at the top-left.
+///
+/// This is the only convention the crate's API uses. Protocols that count
+/// from 1 (the ESC/POS `US $` command, ANSI cursor escapes) or address cells
+/// linearly convert at the edge with the helpers below.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Position {
+    pub x: u8,
+    pub y: u8,
+}
+
+impl Position {
+    /// The top-left cell.
+    pub const ORIGIN: Position = Position { x: 0, y: 0 };
+
+    pub const fn new(x: u8, y: u8) -> Self {
+        Position { x, y }
+    }
+
+    /// Convert a 1-based column and row, or `None` if either is 0.
+    pub fn from_one_based(x: u8, y: u8) -> Option<Position> {
+        Some(Position {
+            x: x.checked_sub(1)?,
+            y: y.checked_sub(1)?,
+        })
+    }
+
+    /// Column and row counted from 1.
+    ///
+    /// Saturates at 255, which no position on a real display reaches.
+    pub fn to_one_based(self) -> (u8, u8) {
+        (self.x.saturating_add(1), self.y.saturating_add(1))
+    }
+
+    /// Cell number counting row by row on a display `width` cells wide.
+    pub fn to_index(self, width: u8) -> usize {
+        self.y as usize * width as usize + self.x as usize
+    }
+
+    /// The cell at `index` counting row by row on a display `width` cells wide, or `None`
+    /// if the row does not fit in a `u8` or the width is 0.
+    pub fn from_index(index: usize, width: u8) -> Option<Position> {
+        let width = width as usize;
+        if width == 0 {
+            return None;
+        }
+        Some(Position {
+            x: (index % width) as u8,
+            y: u8::try_from(index / width).ok()?,
+        })
+    }
+
+    /// Return the position if it lies on a `width × height` display, or which coordinate is off it.
+    #[cfg(feature = "std")]
+    pub fn check(self, (width, height): (u8, u8)) -> Result<Position, VfdError> {
+        if self.x >= width {
+            return Err(VfdError::OutOfBounds {
+                what: "Column",
+                value: self.x as usize,
+                max: (width as usize).saturating_sub(1),
+            });
+        }
+        if self.y >= height {
+            return Err(VfdError::OutOfBounds {
+                what: "Row",
+                value: self.y as usize,
+                max: (height as usize).saturating_sub(1),
+            });
+        }
+        Ok(self)
+    }
+}
+
+impl From<(u8, u8)> for Position {
+    fn from((x, y): (u8, u8)) -> Self {
+        Position { x, y }
+    }
+}
+
+impl From<Position> for (u8, u8) {
+    fn from(position: Position) -> Self {
+        (position.x, position.y)
+    }
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "column {}, row {}", self.x, self.y)
+    }
+}
@@ -0,0 +1,85 @@
+use crate::glyph::{Glyph, custom_char};
+use std::str::FromStr;
+
+/// How [`progress_bar`] draws the bar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ProgressStyle {
+    /// `#` for filled cells and `-` for empty ones; works on every display.
+    #[default]
+    Ascii,
+    /// Solid `█` and shaded `░` cells from the PC code pages.
+    Shade,
+    /// Custom glyphs giving five steps per cell, stored in the five slots starting at `first_slot`.
+    Blocks { first_slot: u8 },
+}
+
+impl FromStr for ProgressStyle {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "ascii" => Ok(ProgressStyle::Ascii),
+            "shade" => Ok(ProgressStyle::Shade),
+            "blocks" => Ok(ProgressStyle::Blocks { first_slot: 0 }),
+            other => Err(format!(
+                "Unknown progress style '{}'. Expected one of ascii, shade, blocks.",
+                other
+            )),
+        }
+    }
+}
+
+impl ProgressStyle {
+    /// Custom glyphs the style needs on the display, as (slot, glyph) pairs.
+    pub fn glyphs(&self) -> Vec<(u8, Glyph)> {
+        match self {
+            ProgressStyle::Blocks { first_slot } => (1..=5)
+                .map(|columns| {
+                    (
+                        first_slot.saturating_add(columns - 1),
+                        partial_block(columns),
+                    )
+                })
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+// Full-height block with the `columns` leftmost dot columns lit
+fn partial_block(columns: u8) -> Glyph {
+    [(0b11111 << (5 - columns)) & 0b11111; 7]
+}
+
+/// A `width` cell bar filled to `fraction` (clamped to 0.0..=1.0).
+///
+/// With [`ProgressStyle::Blocks`] the text refers to custom characters, which
+/// must be loaded first (see [`ProgressStyle::glyphs`]).
+pub fn progress_bar(width: usize, fraction: f32, style: ProgressStyle) -> String {
+    let fraction = if fraction.is_nan() {
+        0.0
+    } else {
+        fraction.clamp(0.0, 1.0)
+    };
+    match style {
+        ProgressStyle::Ascii | ProgressStyle::Shade => {
+            let (full, empty) = match style {
+                ProgressStyle::Ascii => ('#', '-'),
+                _ => ('█', '░'),
+            };
+            let filled = (fraction * width as f32).round() as usize;
+            (0..width)
+                .map(|i| if i < filled { full } else { empty })
+                .collect()
+        }
+        ProgressStyle::Blocks { first_slot } => {
+            let steps = (fraction * width as f32 * 5.0).round() as usize;
+            (0..width)
+                .map(|i| match steps.saturating_sub(i * 5).min(5) {
+                    0 => ' ',
+                    columns => custom_char(first_slot.saturating_add(columns as u8 - 1)),
+                })
+                .collect()
+        }
+    }
+}
@@ -0,0 +1,182 @@
+//! JSON messages for driving the display over the daemon socket and HTTP (`serde` feature).
+//!
+//! A request is an object whose `cmd` field names the command; the other
+//! fields are its arguments. Optional fields may be left out.
+//!
+//! ```text
+//! {"cmd": "write", "text": "Hello"}                             write at the cursor
+//! {"cmd": "write", "row": 1, "text": "2.50", "align": "right"}  replace a row
+//! {"cmd": "clear"}
+//! {"cmd": "cursor", "x": 0, "y": 1}
+//! {"cmd": "brightness", "level": 60}                            20, 40, 60 or 100
+//! {"cmd": "display", "on": false}
+//! {"cmd": "blink", "interval_ms": 500}                          0 stops blinking
+//! {"cmd": "annunciator", "icon": "total", "on": true}           sum, total, change, currency or a number
+//! {"cmd": "alert", "text": "Back soon", "duration_ms": 3000, "blink_ms": 500, "align": "center"}
+//! {"cmd": "init"}
+//! {"cmd": "ping"}
+//! {"cmd": "dump"}                                               answered with "rows"
+//! ```
+//!
+//! `align` is `left`, `center` or `right`; rows default to left and alerts
+//! to center. Every request is answered with a [`Reply`]: `{"ok": true}` or
+//! `{"ok": false, "error": "..."}`, and `dump` with
+//! `{"ok": true, "rows": ["...", "..."]}`.
+//!
+//! With the `json` feature, [`crate::daemon::handle_line`] answers lines
+//! starting with `{` in this format, and the `http` feature adds
+//! `POST /command` taking one request or an array of them.
+
+use crate::alert::Alert;
+use crate::daemon::DaemonCommand;
+use crate::layout::Align;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// A JSON request, tagged by its `cmd` field.
+///
+/// ```
+/// use vfd_dsp_v9fb_over_serial::Align;
+/// use vfd_dsp_v9fb_over_serial::daemon::DaemonCommand;
+/// use vfd_dsp_v9fb_over_serial::protocol::Request;
+///
+/// let request = Request::Write {
+///     text: "Ready".to_string(),
+///     row: Some(1),
+///     align: Some(Align::Center),
+/// };
+/// assert_eq!(
+///     DaemonCommand::try_from(request),
+///     Ok(DaemonCommand::Line { row: 1, align: Align::Center, text: "Ready".to_string() })
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "cmd", rename_all = "snake_case", deny_unknown_fields)]
+pub enum Request {
+    /// Write at the cursor, or replace `row` when given.
+    Write {
+        text: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        row: Option<u8>,
+        /// Only used together with `row`.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        align: Option<Align>,
+    },
+    Clear,
+    Cursor {
+        x: u8,
+        y: u8,
+    },
+    Brightness {
+        /// Percentage: 20, 40, 60 or 100.
+        level: u8,
+    },
+    Display {
+        on: bool,
+    },
+    Blink {
+        interval_ms: u64,
+    },
+    Annunciator {
+        icon: String,
+        on: bool,
+    },
+    Alert {
+        text: String,
+        duration_ms: u64,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        blink_ms: Option<u64>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        align: Option<Align>,
+    },
+    Init,
+    Ping,
+    Dump,
+}
+
+/// The answer to a [`Request`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Reply {
+    pub ok: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// The text on the display, answering `dump`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rows: Option<Vec<String>>,
+}
+
+impl Reply {
+    pub fn ok() -> Self {
+        Reply {
+            ok: true,
+            error: None,
+            rows: None,
+        }
+    }
+
+    pub fn error(message: impl Into<String>) -> Self {
+        Reply {
+            ok: false,
+            error: Some(message.into()),
+            rows: None,
+        }
+    }
+
+    /// A successful reply carrying the text on the display.
+    pub fn rows(rows: Vec<String>) -> Self {
+        Reply {
+            rows: Some(rows),
+            ..Reply::ok()
+        }
+    }
+}
+
+impl TryFrom<Request> for DaemonCommand {
+    type Error = String;
+
+    fn try_from(request: Request) -> Result<Self, Self::Error> {
+        Ok(match request {
+            Request::Write {
+                text,
+                row: Some(row),
+                align,
+            } => DaemonCommand::Line {
+                row,
+                align: align.unwrap_or_default(),
+                text,
+            },
+            Request::Write {
+                text, row: None, ..
+            } => DaemonCommand::Write { text },
+            Request::Clear => DaemonCommand::Clear,
+            Request::Cursor { x, y } => DaemonCommand::Cursor { x, y },
+            Request::Brightness { level } => DaemonCommand::Brightness(level.to_string().parse()?),
+            Request::Display { on } => DaemonCommand::Display(on),
+            Request::Blink { interval_ms } => {
+                DaemonCommand::Blink(Duration::from_millis(interval_ms))
+            }
+            Request::Annunciator { icon, on } => DaemonCommand::Annunciator {
+                icon: icon.parse()?,
+                on,
+            },
+            Request::Alert {
+                text,
+                duration_ms,
+                blink_ms,
+                align,
+            } => {
+                let mut alert = Alert::new(&text, Duration::from_millis(duration_ms));
+                if let Some(blink_ms) = blink_ms {
+                    alert = alert.blink(Duration::from_millis(blink_ms));
+                }
+                if let Some(align) = align {
+                    alert = alert.align(align);
+                }
+                DaemonCommand::Alert(alert)
+            }
+            Request::Init => DaemonCommand::Init,
+            Request::Ping => DaemonCommand::Ping,
+            Request::Dump => DaemonCommand::Dump,
+        })
+    }
+}
@@ -0,0 +1,275 @@
+// Definitions are only read from TOML, with the `config` feature
+#![cfg_attr(not(feature = "config"), allow(dead_code))]
+
+use crate::codepage::CodePage;
+use crate::command_set::BrightnessLevel;
+use crate::position::Position;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+/// The commands of a display the crate has no built-in command set for,
+/// loaded at runtime and used as [`CommandSet::Custom`](crate::CommandSet::Custom).
+///
+/// A definition is a TOML file whose commands are byte sequences, written as
+/// hex bytes (`1B` or `0x1B`) or quoted characters (`'@'`) separated by spaces:
+///
+/// ```toml
+/// name = "Acme PD-2000"
+/// init = "1B 40"                # optional, sends `clear` when left out
+/// clear = "0C"
+/// # x and y are the 0-based column and row, cell the 0-based index
+/// # counting across rows; +N adds to them
+/// cursor = "1F 24 x+1 y+1"
+/// clear_line = "18"             # optional
+/// cursor_on = "1F 43 01"        # optional
+/// cursor_off = "1F 43 00"       # optional
+///
+/// [brightness]                  # optional; missing levels use the nearest one
+/// 20 = "1F 58 01"
+/// 100 = "1F 58 04"
+///
+/// [code_pages]                  # optional
+/// pc437 = "1B 74 00"
+/// pc858 = "1B 74 13"
+/// ```
+///
+/// Anything else, such as custom characters or scrolling modes, is reported
+/// as not supported.
+#[derive(Clone, PartialEq, Eq)]
+pub struct ProtocolDefinition {
+    name: String,
+    initialize: Vec<u8>,
+    clear: Vec<u8>,
+    cursor: Vec<Part>,
+    clear_line: Option<Vec<u8>>,
+    cursor_on: Option<Vec<u8>>,
+    cursor_off: Option<Vec<u8>>,
+    brightness: Vec<(BrightnessLevel, Vec<u8>)>,
+    code_pages: Vec<(CodePage, Vec<u8>)>,
+}
+
+// One element of the cursor command: a fixed byte, or the column, row or
+// cell index plus an offset
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Part {
+    Byte(u8),
+    Column(u8),
+    Row(u8),
+    Cell(u8),
+}
+
+impl ProtocolDefinition {
+    /// Name of the display, as given in the file.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub(crate) fn initialize(&self) -> &[u8] {
+        &self.initialize
+    }
+
+    pub(crate) fn clear(&self) -> &[u8] {
+        &self.clear
+    }
+
+    pub(crate) fn clear_line(&self) -> Option<&[u8]> {
+        self.clear_line.as_deref()
+    }
+
+    pub(crate) fn cursor_template(&self) -> &[Part] {
+        &self.cursor
+    }
+
+    pub(crate) fn set_cursor(&self, x: u8, y: u8, width: u8) -> Vec<u8> {
+        let cell = u8::try_from(Position::new(x, y).to_index(width)).unwrap_or(u8::MAX);
+        self.cursor
+            .iter()
+            .map(|part| match *part {
+                Part::Byte(byte) => byte,
+                Part::Column(offset) => x.saturating_add(offset),
+                Part::Row(offset) => y.saturating_add(offset),
+                Part::Cell(offset) => cell.saturating_add(offset),
+            })
+            .collect()
+    }
+
+    pub(crate) fn set_cursor_visible(&self, visible: bool) -> Option<&[u8]> {
+        if visible {
+            self.cursor_on.as_deref()
+        } else {
+            self.cursor_off.as_deref()
+        }
+    }
+
+    pub(crate) fn has_brightness(&self) -> bool {
+        !self.brightness.is_empty()
+    }
+
+    // The sequence for `level`, or for the defined level closest to it
+    pub(crate) fn set_brightness(&self, level: BrightnessLevel) -> Option<&[u8]> {
+        self.brightness
+            .iter()
+            .min_by_key(|(defined, _)| defined.percent().abs_diff(level.percent()))
+            .map(|(_, bytes)| bytes.as_slice())
+    }
+
+    pub(crate) fn brightness_levels(&self) -> &[(BrightnessLevel, Vec<u8>)] {
+        &self.brightness
+    }
+
+    pub(crate) fn code_pages(&self) -> &[(CodePage, Vec<u8>)] {
+        &self.code_pages
+    }
+
+    pub(crate) fn set_code_page(&self, page: CodePage) -> Option<&[u8]> {
+        self.code_pages
+            .iter()
+            .find(|(defined, _)| *defined == page)
+            .map(|(_, bytes)| bytes.as_slice())
+    }
+}
+
+impl fmt::Debug for ProtocolDefinition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProtocolDefinition")
+            .field("name", &self.name)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "config")]
+mod file {
+    use super::{Part, ProtocolDefinition};
+    use crate::error::VfdError;
+    use serde::Deserialize;
+    use std::collections::BTreeMap;
+    use std::fs;
+    use std::path::Path;
+
+    // The file as written, before its sequences are parsed
+    #[derive(Deserialize)]
+    #[serde(deny_unknown_fields)]
+    struct RawDefinition {
+        name: String,
+        init: Option<String>,
+        clear: String,
+        cursor: String,
+        clear_line: Option<String>,
+        cursor_on: Option<String>,
+        cursor_off: Option<String>,
+        #[serde(default)]
+        brightness: BTreeMap<String, String>,
+        #[serde(default)]
+        code_pages: BTreeMap<String, String>,
+    }
+
+    impl ProtocolDefinition {
+        /// Read a definition from a TOML file.
+        pub fn load(path: &Path) -> Result<Self, VfdError> {
+            let text = fs::read_to_string(path)?;
+            Self::from_toml(&text).map_err(|e| match e {
+                VfdError::InvalidInput(message) => {
+                    VfdError::InvalidInput(format!("{}: {}", path.display(), message))
+                }
+                other => other,
+            })
+        }
+
+        /// Parse a definition from TOML text.
+        pub fn from_toml(text: &str) -> Result<Self, VfdError> {
+            let raw: RawDefinition =
+                toml::from_str(text).map_err(|e| VfdError::InvalidInput(e.to_string()))?;
+            let invalid = |key: &str, e: String| VfdError::InvalidInput(format!("{}: {}", key, e));
+            let optional = |key: &str, value: &Option<String>| {
+                value
+                    .as_deref()
+                    .map(parse_bytes)
+                    .transpose()
+                    .map_err(|e| invalid(key, e))
+            };
+
+            let clear = parse_bytes(&raw.clear).map_err(|e| invalid("clear", e))?;
+            let initialize = optional("init", &raw.init)?.unwrap_or_else(|| clear.clone());
+            let cursor = parse_template(&raw.cursor).map_err(|e| invalid("cursor", e))?;
+            let brightness = raw
+                .brightness
+                .iter()
+                .map(|(level, bytes)| {
+                    let key = format!("brightness.{}", level);
+                    Ok((
+                        level.parse().map_err(|e| invalid(&key, e))?,
+                        parse_bytes(bytes).map_err(|e| invalid(&key, e))?,
+                    ))
+                })
+                .collect::<Result<_, VfdError>>()?;
+            let code_pages = raw
+                .code_pages
+                .iter()
+                .map(|(page, bytes)| {
+                    let key = format!("code_pages.{}", page);
+                    Ok((
+                        page.parse().map_err(|e| invalid(&key, e))?,
+                        parse_bytes(bytes).map_err(|e| invalid(&key, e))?,
+                    ))
+                })
+                .collect::<Result<_, VfdError>>()?;
+
+            Ok(ProtocolDefinition {
+                clear_line: optional("clear_line", &raw.clear_line)?,
+                cursor_on: optional("cursor_on", &raw.cursor_on)?,
+                cursor_off: optional("cursor_off", &raw.cursor_off)?,
+                name: raw.name,
+                initialize,
+                clear,
+                cursor,
+                brightness,
+                code_pages,
+            })
+        }
+    }
+
+    // "1B 40" or "0x1B '@'"
+    fn parse_bytes(text: &str) -> Result<Vec<u8>, String> {
+        text.split_whitespace().map(parse_byte).collect()
+    }
+
+    fn parse_byte(token: &str) -> Result<u8, String> {
+        if let Some(c) = token.strip_prefix('\'').and_then(|t| t.strip_suffix('\''))
+            && let Ok(c) = c.parse::<char>()
+            && c.is_ascii()
+        {
+            return Ok(c as u8);
+        }
+        let hex = token.strip_prefix("0x").unwrap_or(token);
+        u8::from_str_radix(hex, 16).map_err(|_| {
+            format!(
+                "Invalid byte '{}'. Expected hex such as 1B or a character such as '@'.",
+                token
+            )
+        })
+    }
+
+    // A byte sequence that may also contain x, y and cell, each optionally +N
+    fn parse_template(text: &str) -> Result<Vec<Part>, String> {
+        text.split_whitespace()
+            .map(|token| {
+                let (name, offset) = match token.split_once('+') {
+                    Some((name, offset)) => (
+                        name,
+                        offset
+                            .parse()
+                            .map_err(|_| format!("Invalid offset in '{}'", token))?,
+                    ),
+                    None => (token, 0),
+                };
+                match name {
+                    "x" => Ok(Part::Column(offset)),
+                    "y" => Ok(Part::Row(offset)),
+                    "cell" => Ok(Part::Cell(offset)),
+                    _ => parse_byte(token).map(Part::Byte),
+                }
+            })
+            .collect()
+    }
+}
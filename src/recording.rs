@@ -0,0 +1,115 @@
+use crate::error::VfdError;
+use crate::tap::HEX_WIDTH;
+use crate::vfd::Vfd;
+use std::path::Path;
+use std::thread;
+use std::time::Duration;
+
+/// A captured byte stream with the time each chunk was sent, for playing back to a display.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Recording {
+    // Offset from the first chunk, and the bytes sent then
+    chunks: Vec<(Duration, Vec<u8>)>,
+}
+
+impl Recording {
+    /// Read a recording from `path`.
+    ///
+    /// Hex dumps written by a [`Tap`](crate::Tap) keep their timing; any other
+    /// file is taken as raw bytes to send in one go.
+    pub fn load(path: &Path) -> Result<Recording, VfdError> {
+        let contents = std::fs::read(path)?;
+        Ok(std::str::from_utf8(&contents)
+            .ok()
+            .and_then(Recording::parse_hex_dump)
+            .unwrap_or_else(|| Recording::raw(contents)))
+    }
+
+    /// A recording of `bytes` sent all at once.
+    pub fn raw(bytes: Vec<u8>) -> Recording {
+        Recording {
+            chunks: vec![(Duration::ZERO, bytes)],
+        }
+    }
+
+    /// Parse the output of [`hex_dump`](crate::hex_dump), or `None` if `text` is not a hex dump.
+    pub fn parse_hex_dump(text: &str) -> Option<Recording> {
+        let mut chunks: Vec<(Duration, Vec<u8>)> = Vec::new();
+        let mut start = None;
+        for line in text.lines().filter(|line| !line.trim().is_empty()) {
+            let (timestamp, rest) = line.split_once("  ")?;
+            // The hex column is padded to a fixed width; the ASCII column may contain anything
+            let hex = rest.get(..HEX_WIDTH)?;
+            if !rest[HEX_WIDTH..].starts_with("  |") {
+                return None;
+            }
+            let at = parse_timestamp(timestamp)?;
+            let bytes = hex
+                .split_whitespace()
+                .map(|byte| u8::from_str_radix(byte, 16).ok())
+                .collect::<Option<Vec<u8>>>()?;
+
+            let offset = at.saturating_sub(*start.get_or_insert(at));
+            match chunks.last_mut() {
+                // Lines of one write share a timestamp
+                Some((last, chunk)) if *last == offset => chunk.extend(bytes),
+                _ => chunks.push((offset, bytes)),
+            }
+        }
+        (!chunks.is_empty()).then_some(Recording { chunks })
+    }
+
+    /// Chunks in the order they were sent, with their offset from the start.
+    pub fn chunks(&self) -> &[(Duration, Vec<u8>)] {
+        &self.chunks
+    }
+
+    /// Time between the first and last chunk.
+    pub fn duration(&self) -> Duration {
+        self.chunks.last().map_or(Duration::ZERO, |(at, _)| *at)
+    }
+
+    /// How long replaying at `speed` takes, see [`Recording::replay`].
+    pub fn replay_duration(&self, speed: f64) -> Duration {
+        let mut previous = Duration::ZERO;
+        let mut total = Duration::ZERO;
+        for (at, _) in &self.chunks {
+            total += pause(at.saturating_sub(previous), speed);
+            previous = *at;
+        }
+        total
+    }
+
+    /// Send the recording to `vfd`, `speed` times faster than it was captured.
+    ///
+    /// A speed of zero or less, or one that is not a number, sends everything
+    /// without pausing. No pause lasts longer than a minute, however slow the
+    /// speed.
+    pub fn replay(&self, vfd: &mut Vfd, speed: f64) -> Result<(), VfdError> {
+        let mut previous = Duration::ZERO;
+        for (at, bytes) in &self.chunks {
+            thread::sleep(pause(at.saturating_sub(previous), speed));
+            previous = *at;
+            vfd.send_raw(bytes)?;
+        }
+        vfd.flush()
+    }
+}
+
+// Longest pause between chunks, so that a tiny speed cannot stall a replay
+const MAX_PAUSE: Duration = Duration::from_secs(60);
+
+// `gap` played back `speed` times faster
+fn pause(gap: Duration, speed: f64) -> Duration {
+    if speed.is_nan() || speed <= 0.0 {
+        return Duration::ZERO;
+    }
+    Duration::try_from_secs_f64(gap.as_secs_f64() / speed)
+        .map_or(MAX_PAUSE, |pause| pause.min(MAX_PAUSE))
+}
+
+// `1760486400.125` as a duration since the epoch
+fn parse_timestamp(timestamp: &str) -> Option<Duration> {
+    let (secs, millis) = timestamp.split_once('.')?;
+    Some(Duration::from_secs(secs.parse().ok()?) + Duration::from_millis(millis.parse().ok()?))
+}
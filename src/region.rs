@@ -0,0 +1,174 @@
+use crate::codepage::TextMapping;
+use crate::command_set::BrightnessLevel;
+use crate::display::VfdDisplay;
+use crate::error::VfdError;
+use crate::layout::{self, Align};
+use crate::position::Position;
+use crate::text;
+
+/// A rectangular part of the display with its own cursor.
+///
+/// Writes wrap at the region's right edge and never touch cells outside it,
+/// so several regions can share one display. Use [`Region::view`] to hand the
+/// region to anything that draws on a [`VfdDisplay`], such as a
+/// [`LogView`](crate::LogView) or a [`Marquee`](crate::Marquee).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Region {
+    x: u8,
+    y: u8,
+    width: u8,
+    height: u8,
+    cursor_x: u8,
+    cursor_y: u8,
+}
+
+impl Region {
+    /// The `width × height` area whose top-left cell is column `x`, row `y`.
+    pub fn new(x: u8, y: u8, width: u8, height: u8) -> Self {
+        Region {
+            x,
+            y,
+            width,
+            height,
+            cursor_x: 0,
+            cursor_y: 0,
+        }
+    }
+
+    /// Position of the top-left cell on the display.
+    pub fn origin(&self) -> (u8, u8) {
+        (self.x, self.y)
+    }
+
+    pub fn size(&self) -> (u8, u8) {
+        (self.width, self.height)
+    }
+
+    /// Cursor position relative to the region.
+    pub fn get_cursor(&self) -> (u8, u8) {
+        (self.cursor_x, self.cursor_y)
+    }
+
+    /// Move the region's cursor; nothing is sent until the next write.
+    pub fn set_cursor(&mut self, x: u8, y: u8) {
+        self.cursor_x = x.min(self.width);
+        self.cursor_y = y.min(self.height.saturating_sub(1));
+    }
+
+    /// Borrow the region as a display of its own.
+    pub fn view<'a, D: VfdDisplay + ?Sized>(&'a mut self, display: &'a mut D) -> RegionView<'a, D> {
+        RegionView {
+            region: self,
+            display,
+        }
+    }
+
+    /// Blank the region and move its cursor to the top-left.
+    pub fn clear<D: VfdDisplay + ?Sized>(&mut self, display: &mut D) -> Result<(), VfdError> {
+        self.view(display).clear()
+    }
+
+    /// Write text at the region's cursor, wrapping at its right edge.
+    pub fn write_text<D: VfdDisplay + ?Sized>(
+        &mut self,
+        display: &mut D,
+        text: &str,
+    ) -> Result<(), VfdError> {
+        self.view(display).write_text(text)
+    }
+
+    /// Replace row `row` of the region with `text`, aligned and padded to the region width.
+    pub fn write_line<D: VfdDisplay + ?Sized>(
+        &mut self,
+        display: &mut D,
+        row: u8,
+        text: &str,
+        align: Align,
+    ) -> Result<(), VfdError> {
+        self.view(display).write_line(row, text, align)
+    }
+}
+
+/// A [`Region`] bound to the display it lives on, usable wherever a [`VfdDisplay`] is.
+pub struct RegionView<'a, D: VfdDisplay + ?Sized> {
+    region: &'a mut Region,
+    display: &'a mut D,
+}
+
+impl<D: VfdDisplay + ?Sized> VfdDisplay for RegionView<'_, D> {
+    fn size(&self) -> (u8, u8) {
+        self.region.size()
+    }
+
+    fn clear(&mut self) -> Result<(), VfdError> {
+        let blank = " ".repeat(self.region.width as usize);
+        for row in 0..self.region.height {
+            self.display
+                .set_cursor(self.region.x, self.region.y + row)?;
+            self.display.write_text(&blank)?;
+        }
+        self.set_cursor(0, 0)
+    }
+
+    fn set_cursor(&mut self, x: u8, y: u8) -> Result<(), VfdError> {
+        Position::new(x, y).check(self.region.size())?;
+        self.region.set_cursor(x, y);
+        Ok(())
+    }
+
+    fn get_cursor(&self) -> (u8, u8) {
+        self.region.get_cursor()
+    }
+
+    fn text_mapping(&self) -> TextMapping {
+        self.display.text_mapping()
+    }
+
+    fn write_text(&mut self, text: &str) -> Result<(), VfdError> {
+        let mapped = self.display.text_mapping().apply(text);
+        let text = mapped.as_str();
+        let region = &mut *self.region;
+        let width = region.width as usize;
+        let used = region.cursor_y as usize * width + region.cursor_x as usize;
+        let available = (width * region.height as usize).saturating_sub(used);
+        let length = text::display_width(text);
+        if length > available {
+            return Err(VfdError::TextTooLong {
+                max: available,
+                got: length,
+            });
+        }
+
+        let mut remaining = text;
+        while !remaining.is_empty() {
+            if region.cursor_x as usize >= width {
+                region.cursor_x = 0;
+                region.cursor_y += 1;
+            }
+            let (chunk, rest) = text::split_at_width(remaining, width - region.cursor_x as usize);
+            self.display
+                .set_cursor(region.x + region.cursor_x, region.y + region.cursor_y)?;
+            self.display.write_text(chunk)?;
+            region.cursor_x += text::display_width(chunk) as u8;
+            remaining = rest;
+        }
+        Ok(())
+    }
+
+    fn write_line(&mut self, row: u8, text: &str, align: Align) -> Result<(), VfdError> {
+        if row >= self.region.height {
+            return Err(VfdError::OutOfBounds {
+                what: "Row",
+                value: row as usize,
+                max: self.region.height as usize - 1,
+            });
+        }
+        let text = self.display.text_mapping().apply(text);
+        self.set_cursor(0, row)?;
+        self.write_text(&layout::align(&text, self.region.width as usize, align))
+    }
+
+    fn set_brightness(&mut self, level: BrightnessLevel) -> Result<(), VfdError> {
+        self.display.set_brightness(level)
+    }
+}
@@ -0,0 +1,305 @@
+use crate::codepage::TextMapping;
+use crate::command_set::BrightnessLevel;
+use crate::display::VfdDisplay;
+use crate::error::VfdError;
+use crate::frame_buffer;
+use crate::position::Position;
+use crate::text;
+use crate::widget::Widget;
+use std::io;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+// Frames per second unless changed, about 96 bytes each at 9600 baud
+const DEFAULT_FRAME_RATE: u32 = 10;
+
+/// Drives several widgets, each at its own [`Widget::interval`], from one loop.
+///
+/// The widgets draw into an off-screen canvas and whatever they changed is
+/// sent in one pass per frame, with only the cells that differ from what was
+/// sent before. Frames come no faster than [`Scheduler::max_frame_rate`], so a
+/// fast spinner cannot flood a slow serial line; widgets that come due between
+/// frames are drawn together in the next one.
+///
+/// Cells no widget has drawn are left alone, so other text may share the display.
+///
+/// ```
+/// use vfd_dsp_v9fb_over_serial::{CommandSet, Scheduler, Simulator, Spinner};
+///
+/// let simulator = Simulator::new(CommandSet::Birch, 20, 2);
+/// let mut vfd = simulator.vfd();
+/// let mut scheduler = Scheduler::new(20, 2)
+///     .widget(Spinner::new(0, 0))
+///     .widget(Spinner::with_frames(19, 1, &['.', 'o', 'O']))
+///     .max_frame_rate(25);
+/// assert!(scheduler.tick(&mut vfd).unwrap());
+/// assert_eq!(simulator.row(0).trim(), "|");
+/// assert_eq!(simulator.row(1).trim(), ".");
+/// ```
+pub struct Scheduler {
+    entries: Vec<Entry>,
+    canvas: Canvas,
+    frame_period: Duration,
+    last_frame: Option<Instant>,
+}
+
+// A widget and when it next needs ticking
+struct Entry {
+    widget: Box<dyn Widget + Send>,
+    due: Instant,
+}
+
+impl Scheduler {
+    /// A scheduler for a `width × height` display, with no widgets yet.
+    pub fn new(width: u8, height: u8) -> Self {
+        Scheduler {
+            entries: Vec::new(),
+            canvas: Canvas::new(width, height),
+            frame_period: Duration::from_secs(1) / DEFAULT_FRAME_RATE,
+            last_frame: None,
+        }
+    }
+
+    /// A scheduler matching the geometry of `display`.
+    pub fn for_display<D: VfdDisplay + ?Sized>(display: &D) -> Self {
+        let (width, height) = display.size();
+        Scheduler::new(width, height)
+    }
+
+    /// Add a widget, first drawn on the next frame.
+    pub fn widget<W: Widget + Send + 'static>(mut self, widget: W) -> Self {
+        self.entries.push(Entry {
+            widget: Box::new(widget),
+            due: Instant::now(),
+        });
+        self
+    }
+
+    /// Send at most `fps` frames a second (10 by default); 0 removes the limit.
+    pub fn max_frame_rate(mut self, fps: u32) -> Self {
+        self.frame_period = match fps {
+            0 => Duration::ZERO,
+            fps => Duration::from_secs(1) / fps,
+        };
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Resend everything the widgets show on the next frame, e.g. after the display was cleared.
+    pub fn invalidate(&mut self) {
+        self.canvas.sent.fill(None);
+        for entry in &mut self.entries {
+            entry.due = Instant::now();
+        }
+    }
+
+    /// When the next frame is due: the earliest widget, but no sooner than the frame rate allows.
+    pub fn next_frame(&self) -> Option<Instant> {
+        let due = self.entries.iter().map(|entry| entry.due).min()?;
+        Some(match self.last_frame {
+            Some(last) => due.max(last + self.frame_period),
+            None => due,
+        })
+    }
+
+    /// Tick the widgets that are due and send what they changed.
+    ///
+    /// Returns whether a frame was drawn; nothing happens before the next
+    /// frame is due.
+    pub fn tick<D: VfdDisplay + ?Sized>(&mut self, display: &mut D) -> Result<bool, VfdError> {
+        self.tick_at(display, Instant::now())
+    }
+
+    /// Like [`Scheduler::tick`], taking `now` as the current time, e.g. to
+    /// follow a simulated clock.
+    pub fn tick_at<D: VfdDisplay + ?Sized>(
+        &mut self,
+        display: &mut D,
+        now: Instant,
+    ) -> Result<bool, VfdError> {
+        if self.next_frame().is_none_or(|next| next > now) {
+            return Ok(false);
+        }
+        self.canvas.mapping = display.text_mapping();
+        for entry in self.entries.iter_mut().filter(|entry| entry.due <= now) {
+            entry.widget.tick(&mut self.canvas)?;
+            entry.due = now + entry.widget.interval();
+        }
+        self.last_frame = Some(now);
+        self.canvas.flush(display)?;
+        Ok(true)
+    }
+
+    /// Run the widgets on a background thread until the returned handle is stopped.
+    ///
+    /// The display is only locked while a frame is sent.
+    pub fn spawn<D>(mut self, display: Arc<Mutex<D>>) -> SchedulerHandle
+    where
+        D: VfdDisplay + Send + 'static,
+    {
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread_stop = Arc::clone(&stop);
+        let thread = thread::spawn(move || {
+            while !thread_stop.load(Ordering::Relaxed) {
+                {
+                    let mut display = display.lock().unwrap_or_else(PoisonError::into_inner);
+                    self.tick(&mut *display)?;
+                }
+                let wait = self.next_frame().map_or(Duration::MAX, |next| {
+                    next.saturating_duration_since(Instant::now())
+                });
+                thread::sleep(wait.min(Duration::from_millis(50)));
+            }
+            Ok(())
+        });
+        SchedulerHandle {
+            stop,
+            thread: Some(thread),
+        }
+    }
+}
+
+/// Control handle for a scheduler running on a background thread.
+pub struct SchedulerHandle {
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<Result<(), VfdError>>>,
+}
+
+impl SchedulerHandle {
+    /// Stop the widgets and wait for the thread to finish, returning any write error it hit.
+    pub fn stop(mut self) -> Result<(), VfdError> {
+        self.stop.store(true, Ordering::Relaxed);
+        match self.thread.take() {
+            Some(thread) => thread
+                .join()
+                .unwrap_or_else(|_| Err(io::Error::other("Scheduler thread panicked").into())),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for SchedulerHandle {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+// What the widgets draw between frames. Cells are `None` until a widget
+// draws them, and `sent` is what the display was last sent for each cell.
+struct Canvas {
+    width: u8,
+    height: u8,
+    cells: Vec<Option<char>>,
+    sent: Vec<Option<char>>,
+    cursor: (u8, u8),
+    brightness: Option<BrightnessLevel>,
+    // The display's, taken each frame, so widgets measure what it will show
+    mapping: TextMapping,
+}
+
+impl Canvas {
+    fn new(width: u8, height: u8) -> Self {
+        let len = width as usize * height as usize;
+        Canvas {
+            width,
+            height,
+            cells: vec![None; len],
+            sent: vec![None; len],
+            cursor: (0, 0),
+            brightness: None,
+            mapping: TextMapping::identity(),
+        }
+    }
+
+    // Send the drawn cells that changed. Runs are only merged across cells a
+    // widget drew, so text drawn by others between them survives.
+    fn flush<D: VfdDisplay + ?Sized>(&mut self, display: &mut D) -> Result<(), VfdError> {
+        if let Some(level) = self.brightness.take() {
+            display.set_brightness(level)?;
+        }
+        let width = self.width as usize;
+        for y in 0..self.height {
+            let start = y as usize * width;
+            let changed: Vec<bool> = (start..start + width)
+                .map(|i| self.cells[i].is_some() && self.cells[i] != self.sent[i])
+                .collect();
+            for run in frame_buffer::changed_runs(&changed) {
+                let drawn = &self.cells[start + run.start..start + run.end];
+                let mut x = run.start;
+                for piece in drawn.split(Option::is_none) {
+                    if !piece.is_empty() {
+                        let text: String = piece.iter().flatten().collect();
+                        display.set_cursor(x as u8, y)?;
+                        display.write_text(&text)?;
+                        self.sent[start + x..start + x + piece.len()].copy_from_slice(piece);
+                    }
+                    x += piece.len() + 1;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl VfdDisplay for Canvas {
+    fn size(&self) -> (u8, u8) {
+        (self.width, self.height)
+    }
+
+    fn clear(&mut self) -> Result<(), VfdError> {
+        self.cells.fill(Some(' '));
+        self.cursor = (0, 0);
+        Ok(())
+    }
+
+    fn set_cursor(&mut self, x: u8, y: u8) -> Result<(), VfdError> {
+        Position::new(x, y).check(self.size())?;
+        self.cursor = (x, y);
+        Ok(())
+    }
+
+    fn get_cursor(&self) -> (u8, u8) {
+        self.cursor
+    }
+
+    fn text_mapping(&self) -> TextMapping {
+        self.mapping.clone()
+    }
+
+    fn write_text(&mut self, text: &str) -> Result<(), VfdError> {
+        let text = &self.mapping.apply(text);
+        let (x, y) = self.cursor;
+        let used = y as usize * self.width as usize + x as usize;
+        let available = self.cells.len().saturating_sub(used);
+        let chars: Vec<char> = text.chars().filter(|c| text::char_width(*c) > 0).collect();
+        if chars.len() > available {
+            return Err(VfdError::TextTooLong {
+                max: available,
+                got: chars.len(),
+            });
+        }
+        let end = used + chars.len();
+        for (cell, c) in self.cells[used..end].iter_mut().zip(chars) {
+            *cell = Some(c);
+        }
+        // Past the last cell the cursor wraps home, as on the display
+        let end = end % self.cells.len().max(1);
+        let width = self.width as usize;
+        self.cursor = ((end % width) as u8, (end / width) as u8);
+        Ok(())
+    }
+
+    fn set_brightness(&mut self, level: BrightnessLevel) -> Result<(), VfdError> {
+        self.brightness = Some(level);
+        Ok(())
+    }
+}
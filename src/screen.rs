@@ -0,0 +1,71 @@
+use crate::display::VfdDisplay;
+use crate::error::VfdError;
+use crate::frame_buffer::FrameBuffer;
+use crate::layout::{self, Align};
+
+/// Off-device composition surface for a whole screen.
+///
+/// Draw with [`Screen::text`] and [`Screen::line`], then [`Screen::present`]
+/// sends the result in one pass, touching only the cells that changed since
+/// the previous present. Nothing reaches the display in between, so partially
+/// drawn states never flicker on screen.
+#[derive(Debug, Clone)]
+pub struct Screen {
+    frame: FrameBuffer,
+}
+
+impl Screen {
+    pub fn new(width: u8, height: u8) -> Self {
+        Screen {
+            frame: FrameBuffer::new(width, height),
+        }
+    }
+
+    /// A screen matching the geometry and text mapping of `display`.
+    pub fn for_display<D: VfdDisplay + ?Sized>(display: &D) -> Self {
+        let (width, height) = display.size();
+        let mut screen = Screen::new(width, height);
+        screen.frame.set_text_mapping(display.text_mapping());
+        screen
+    }
+
+    pub fn size(&self) -> (u8, u8) {
+        self.frame.size()
+    }
+
+    /// Blank the whole screen.
+    pub fn clear(&mut self) -> &mut Self {
+        self.frame.clear();
+        self
+    }
+
+    /// Place `text` at column `x`, row `y`, clipped at the end of the row.
+    pub fn text(&mut self, x: u8, y: u8, text: &str) -> &mut Self {
+        self.frame.put_str(x, y, text);
+        self
+    }
+
+    /// Replace row `y` with `text`, padded or truncated to the full width.
+    pub fn line(&mut self, y: u8, text: &str, align: Align) -> &mut Self {
+        let (width, _) = self.frame.size();
+        let text = self.frame.text_mapping().apply(text);
+        self.frame
+            .put_mapped(0, y, &layout::align(&text, width as usize, align));
+        self
+    }
+
+    /// The composed cells.
+    pub fn frame(&self) -> &FrameBuffer {
+        &self.frame
+    }
+
+    /// Force the next present to redraw every cell, e.g. after something else wrote to the display.
+    pub fn invalidate(&mut self) {
+        self.frame.invalidate();
+    }
+
+    /// Send the composed screen to the display.
+    pub fn present<D: VfdDisplay + ?Sized>(&mut self, display: &mut D) -> Result<(), VfdError> {
+        self.frame.flush(display)
+    }
+}
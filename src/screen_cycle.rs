@@ -0,0 +1,126 @@
+use crate::display::VfdDisplay;
+use crate::error::VfdError;
+use crate::widget::Widget;
+use std::fmt;
+use std::time::Duration;
+
+/// Full-screen widgets shown one at a time, such as opening hours and a
+/// clock, stepped through with [`ScreenCycle::next`] and
+/// [`ScreenCycle::previous`], e.g. from buttons.
+///
+/// Nothing is drawn until the first switch, so whatever else is on the
+/// display stays until then. Switching clears the display and draws the new
+/// screen; ticking ticks the screen on show.
+///
+/// ```
+/// use vfd_dsp_v9fb_over_serial::{CommandSet, Paginator, ScreenCycle, Simulator};
+///
+/// let simulator = Simulator::new(CommandSet::Birch, 20, 2);
+/// let mut vfd = simulator.vfd();
+/// let mut screens = ScreenCycle::new()
+///     .screen("hours", Paginator::new("Mon-Fri 9-18\nSat 10-14"))
+///     .screen("wifi", Paginator::new("WiFi: Guest\nPass: coffee"));
+/// screens.next(&mut vfd).unwrap();
+/// assert_eq!(screens.current(), Some("hours"));
+/// screens.previous(&mut vfd).unwrap();
+/// assert_eq!(screens.current(), Some("wifi"));
+/// assert_eq!(simulator.row(0).trim_end(), "WiFi: Guest");
+/// ```
+#[derive(Default)]
+pub struct ScreenCycle {
+    screens: Vec<(String, Box<dyn Widget + Send>)>,
+    // Index of the screen on show, once one was switched to
+    current: Option<usize>,
+}
+
+impl ScreenCycle {
+    pub fn new() -> Self {
+        ScreenCycle::default()
+    }
+
+    /// Add `widget` as the screen called `name`, after the others.
+    pub fn screen<W: Widget + Send + 'static>(mut self, name: &str, widget: W) -> Self {
+        self.screens.push((name.to_string(), Box::new(widget)));
+        self
+    }
+
+    /// Names of the screens, in the order they are cycled through.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.screens.iter().map(|(name, _)| name.as_str())
+    }
+
+    pub fn len(&self) -> usize {
+        self.screens.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.screens.is_empty()
+    }
+
+    /// Name of the screen on show, if one was switched to.
+    pub fn current(&self) -> Option<&str> {
+        self.current.map(|index| self.screens[index].0.as_str())
+    }
+
+    /// Show the screen after the current one, or the first after the last.
+    pub fn next(&mut self, display: &mut dyn VfdDisplay) -> Result<(), VfdError> {
+        let index = self.current.map_or(0, |index| index + 1);
+        self.switch(display, index)
+    }
+
+    /// Show the screen before the current one, or the last before the first.
+    pub fn previous(&mut self, display: &mut dyn VfdDisplay) -> Result<(), VfdError> {
+        let count = self.screens.len();
+        let index = self.current.map_or(count, |index| index + count) - 1;
+        self.switch(display, index)
+    }
+
+    /// Show the screen called `name`.
+    pub fn show(&mut self, display: &mut dyn VfdDisplay, name: &str) -> Result<(), VfdError> {
+        let index = self
+            .names()
+            .position(|screen| screen == name)
+            .ok_or_else(|| VfdError::InvalidInput(format!("No screen named '{}'", name)))?;
+        self.switch(display, index)
+    }
+
+    fn switch(&mut self, display: &mut dyn VfdDisplay, index: usize) -> Result<(), VfdError> {
+        if self.screens.is_empty() {
+            return Ok(());
+        }
+        self.current = Some(index % self.screens.len());
+        self.render(display)
+    }
+}
+
+impl fmt::Debug for ScreenCycle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ScreenCycle")
+            .field("screens", &self.names().collect::<Vec<_>>())
+            .field("current", &self.current())
+            .finish()
+    }
+}
+
+impl Widget for ScreenCycle {
+    fn tick(&mut self, display: &mut dyn VfdDisplay) -> Result<bool, VfdError> {
+        match self.current {
+            Some(index) => self.screens[index].1.tick(display),
+            None => Ok(false),
+        }
+    }
+
+    fn render(&mut self, display: &mut dyn VfdDisplay) -> Result<(), VfdError> {
+        if let Some(index) = self.current {
+            display.clear()?;
+            self.screens[index].1.render(display)?;
+        }
+        Ok(())
+    }
+
+    fn interval(&self) -> Duration {
+        self.current.map_or(Duration::from_secs(1), |index| {
+            self.screens[index].1.interval()
+        })
+    }
+}
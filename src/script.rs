@@ -0,0 +1,319 @@
+//! Display scripts: sequences of display commands in a small text format.
+//!
+//! One command per line; `#` starts a comment. Text goes in double quotes,
+//! where `\"`, `\\` and `\n` can be used. Columns and rows count from 0 and
+//! durations are written like `500ms`, `2s` or `1.5s`.
+//!
+//! ```text
+//! # Welcome loop for the front till
+//! clear
+//! brightness 60
+//! line 0 center "Welcome!"
+//! goto 0 1
+//! write "Open 9-5"
+//! sleep 3s
+//! marquee "Today: fresh bread and coffee" row 1 for 8s speed 250ms
+//! alert "Back in 5 minutes" for 2s blink 500ms
+//! ```
+//!
+//! | Command | Effect |
+//! |---|---|
+//! | `clear` | blank the display |
+//! | `goto <x> <y>` | move the cursor |
+//! | `write "<text>"` | write at the cursor |
+//! | `line <row> [left\|center\|right] "<text>"` | replace a row |
+//! | `sleep <duration>` | wait |
+//! | `brightness <20\|40\|60\|100>` | change the brightness |
+//! | `marquee "<text>" [row <y>] [for <duration>] [speed <duration>] [left\|right]` | scroll text across a row (row 0, 5s, 300ms a step, leftwards by default) |
+//! | `alert "<text>" [for <duration>] [blink <duration>]` | show text over everything, then restore it (3s by default) |
+
+use crate::alert::Alert;
+use crate::command_set::BrightnessLevel;
+use crate::display::VfdDisplay;
+use crate::error::VfdError;
+use crate::layout::Align;
+use crate::marquee::{Marquee, ScrollDirection};
+use crate::vfd::Vfd;
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// One command of a [`Script`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Step {
+    Clear,
+    Goto {
+        x: u8,
+        y: u8,
+    },
+    Write(String),
+    Line {
+        row: u8,
+        align: Align,
+        text: String,
+    },
+    Sleep(Duration),
+    Brightness(BrightnessLevel),
+    Marquee {
+        text: String,
+        row: u8,
+        duration: Duration,
+        speed: Duration,
+        direction: ScrollDirection,
+    },
+    Alert(Alert),
+}
+
+/// A parsed display script, see the [module documentation](self) for the format.
+///
+/// ```
+/// use vfd_dsp_v9fb_over_serial::MockVfd;
+/// use vfd_dsp_v9fb_over_serial::script::Script;
+///
+/// let script: Script = "clear\nline 1 right \"2.50\"".parse().unwrap();
+/// let mut vfd = MockVfd::new(20, 2);
+/// script.run(&mut vfd).unwrap();
+/// assert_eq!(vfd.row(1).trim(), "2.50");
+/// ```
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Script {
+    pub steps: Vec<Step>,
+}
+
+impl Script {
+    /// Read and parse a script file.
+    pub fn load(path: &Path) -> Result<Script, io::Error> {
+        let contents = std::fs::read_to_string(path)?;
+        contents.parse().map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Invalid script {}: {}", path.display(), e),
+            )
+        })
+    }
+
+    /// Run every step in order on `vfd`, stopping at the first error.
+    pub fn run(&self, vfd: &mut Vfd) -> Result<(), VfdError> {
+        for step in &self.steps {
+            step.run(vfd)?;
+        }
+        Ok(())
+    }
+}
+
+impl Step {
+    /// Apply the step to the display, waiting out sleeps, marquees and alerts.
+    pub fn run(&self, vfd: &mut Vfd) -> Result<(), VfdError> {
+        match self {
+            Step::Clear => vfd.clear(),
+            Step::Goto { x, y } => vfd.set_cursor(*x, *y),
+            Step::Write(text) => vfd.write_text(text),
+            Step::Line { row, align, text } => vfd.write_line(*row, text, *align),
+            Step::Sleep(duration) => {
+                vfd.flush()?;
+                thread::sleep(*duration);
+                Ok(())
+            }
+            Step::Brightness(level) => vfd.set_brightness(*level),
+            Step::Marquee {
+                text,
+                row,
+                duration,
+                speed,
+                direction,
+            } => {
+                let (width, _) = VfdDisplay::size(vfd);
+                let mut marquee = Marquee::new(text, *row, width, *speed, *direction);
+                let started = Instant::now();
+                while started.elapsed() < *duration {
+                    marquee.tick(vfd)?;
+                    thread::sleep(Duration::from_millis(20));
+                }
+                Ok(())
+            }
+            Step::Alert(alert) => vfd.show_alert_with(alert),
+        }
+    }
+}
+
+impl FromStr for Script {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut steps = Vec::new();
+        for (number, line) in s.lines().enumerate() {
+            let words = tokenize(line).map_err(|e| format!("Line {}: {}", number + 1, e))?;
+            if words.is_empty() {
+                continue;
+            }
+            let step = parse_step(&words).map_err(|e| format!("Line {}: {}", number + 1, e))?;
+            steps.push(step);
+        }
+        Ok(Script { steps })
+    }
+}
+
+// A command and its arguments, quotes removed and escapes resolved
+fn tokenize(line: &str) -> Result<Vec<String>, String> {
+    let mut words = Vec::new();
+    let mut chars = line.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c == '#' {
+            break;
+        } else if c == '"' {
+            chars.next();
+            let mut word = String::new();
+            loop {
+                match chars.next() {
+                    Some('"') => break,
+                    Some('\\') => match chars.next() {
+                        Some('n') => word.push('\n'),
+                        Some(escaped @ ('"' | '\\')) => word.push(escaped),
+                        Some(other) => return Err(format!("Unknown escape '\\{}'", other)),
+                        None => return Err("Unterminated string".to_string()),
+                    },
+                    Some(c) => word.push(c),
+                    None => return Err("Unterminated string".to_string()),
+                }
+            }
+            words.push(word);
+        } else {
+            let mut word = String::new();
+            while let Some(&c) = chars.peek() {
+                if c.is_whitespace() || c == '"' {
+                    break;
+                }
+                word.push(c);
+                chars.next();
+            }
+            words.push(word);
+        }
+    }
+    Ok(words)
+}
+
+fn parse_step(words: &[String]) -> Result<Step, String> {
+    let (command, args) = words.split_first().ok_or("Empty command")?;
+    let arity = |count: usize, usage: &str| {
+        if args.len() == count {
+            Ok(())
+        } else {
+            Err(format!("Usage: {}", usage))
+        }
+    };
+    match command.to_ascii_lowercase().as_str() {
+        "clear" => arity(0, "clear").map(|_| Step::Clear),
+        "goto" => {
+            arity(2, "goto <x> <y>")?;
+            Ok(Step::Goto {
+                x: parse_number(&args[0], "column")?,
+                y: parse_number(&args[1], "row")?,
+            })
+        }
+        "write" => {
+            arity(1, "write \"<text>\"")?;
+            Ok(Step::Write(args[0].clone()))
+        }
+        "line" => {
+            let usage = "line <row> [left|center|right] \"<text>\"";
+            let (row, align, text) = match args {
+                [row, text] => (row, Align::Left, text),
+                [row, align, text] => (row, align.parse()?, text),
+                _ => return Err(format!("Usage: {}", usage)),
+            };
+            Ok(Step::Line {
+                row: parse_number(row, "row")?,
+                align,
+                text: text.clone(),
+            })
+        }
+        "sleep" => {
+            arity(1, "sleep <duration>")?;
+            Ok(Step::Sleep(parse_duration(&args[0])?))
+        }
+        "brightness" => {
+            arity(1, "brightness <20|40|60|100>")?;
+            Ok(Step::Brightness(args[0].parse()?))
+        }
+        "marquee" => {
+            let (text, options) = args
+                .split_first()
+                .ok_or("Usage: marquee \"<text>\" [row <y>] [for <duration>] [speed <duration>] [left|right]")?;
+            let mut row = 0;
+            let mut duration = Duration::from_secs(5);
+            let mut speed = Duration::from_millis(300);
+            let mut direction = ScrollDirection::Left;
+            let mut options = options.iter();
+            while let Some(option) = options.next() {
+                let mut value = || {
+                    options
+                        .next()
+                        .ok_or(format!("Missing value for '{}'", option))
+                };
+                match option.as_str() {
+                    "row" => row = parse_number(value()?, "row")?,
+                    "for" => duration = parse_duration(value()?)?,
+                    "speed" => speed = parse_duration(value()?)?,
+                    other => direction = other.parse()?,
+                }
+            }
+            Ok(Step::Marquee {
+                text: text.clone(),
+                row,
+                duration,
+                speed,
+                direction,
+            })
+        }
+        "alert" => {
+            let (text, options) = args
+                .split_first()
+                .ok_or("Usage: alert \"<text>\" [for <duration>] [blink <duration>]")?;
+            let mut alert = Alert::new(text, Duration::from_secs(3));
+            let mut options = options.iter();
+            while let Some(option) = options.next() {
+                let value = options
+                    .next()
+                    .ok_or(format!("Missing value for '{}'", option))?;
+                match option.as_str() {
+                    "for" => alert.duration = parse_duration(value)?,
+                    "blink" => alert.blink = Some(parse_duration(value)?),
+                    other => return Err(format!("Unknown alert option '{}'", other)),
+                }
+            }
+            Ok(Step::Alert(alert))
+        }
+        other => Err(format!("Unknown command '{}'", other)),
+    }
+}
+
+fn parse_number(word: &str, what: &str) -> Result<u8, String> {
+    word.parse()
+        .map_err(|_| format!("Invalid {} '{}'", what, word))
+}
+
+/// Parse a duration such as `500ms`, `2s` or `1.5s`.
+pub fn parse_duration(word: &str) -> Result<Duration, String> {
+    let error = || {
+        format!(
+            "Invalid duration '{}'. Expected a number followed by ms or s.",
+            word
+        )
+    };
+    if let Some(millis) = word.strip_suffix("ms") {
+        return millis
+            .parse()
+            .map(Duration::from_millis)
+            .map_err(|_| error());
+    }
+    let secs: f64 = word
+        .strip_suffix('s')
+        .ok_or_else(error)?
+        .parse()
+        .map_err(|_| error())?;
+    Duration::try_from_secs_f64(secs).map_err(|_| error())
+}
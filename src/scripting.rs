@@ -0,0 +1,177 @@
+//! Rhai scripts driving the display (`rhai` feature).
+//!
+//! Kiosks can drop a `screen.rhai` file next to the daemon and change what it
+//! shows without recompiling. Scripts see these functions:
+//!
+//! ```text
+//! write(text)                    write at the cursor
+//! write_line(row, text)          replace a row, left aligned
+//! write_line(row, text, align)   ... with align "left", "center" or "right"
+//! clear()
+//! cursor(x, y)                   move the cursor, counted from 0
+//! brightness(level)              20, 40, 60 or 100
+//! sleep(milliseconds)
+//! width(), height()              display size
+//! http_get(url)                  body of a GET request, as a string
+//! ```
+//!
+//! ```rhai
+//! let price = http_get("http://localhost:8000/price");
+//! clear();
+//! write_line(0, "Bitcoin", "center");
+//! write_line(1, price, "right");
+//! ```
+
+use crate::command_set::BrightnessLevel;
+use crate::display::VfdDisplay;
+use crate::error::VfdError;
+use crate::layout::Align;
+use crate::vfd::Vfd;
+use rhai::{Dynamic, Engine, EvalAltResult, Position};
+use std::path::Path;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread;
+use std::time::Duration;
+
+// How long http_get waits for a server
+const HTTP_TIMEOUT: Duration = Duration::from_secs(10);
+
+type ScriptResult<T> = Result<T, Box<EvalAltResult>>;
+
+/// A Rhai engine with the display functions registered.
+///
+/// ```
+/// use std::sync::{Arc, Mutex};
+/// use vfd_dsp_v9fb_over_serial::scripting::ScriptEngine;
+/// use vfd_dsp_v9fb_over_serial::{CommandSet, Simulator};
+///
+/// let simulator = Simulator::new(CommandSet::Birch, 20, 2);
+/// let engine = ScriptEngine::new(Arc::new(Mutex::new(simulator.vfd())));
+/// engine.run(r#"write_line(1, "Total " + (2 * 3), "right");"#).unwrap();
+/// assert_eq!(simulator.row(1).trim(), "Total 6");
+/// ```
+pub struct ScriptEngine {
+    engine: Engine,
+}
+
+impl ScriptEngine {
+    /// An engine whose scripts draw on `display`, which stays usable by other threads between calls.
+    pub fn new(display: Arc<Mutex<Vfd>>) -> Self {
+        let mut engine = Engine::new();
+
+        let d = Arc::clone(&display);
+        engine.register_fn("write", move |text: &str| {
+            with_display(&d, |vfd| vfd.write_text(text))
+        });
+        let d = Arc::clone(&display);
+        engine.register_fn("write_line", move |row: i64, text: &str| {
+            let row = to_u8(row, "row")?;
+            with_display(&d, |vfd| vfd.write_line(row, text, Align::Left))
+        });
+        let d = Arc::clone(&display);
+        engine.register_fn("write_line", move |row: i64, text: &str, align: &str| {
+            let row = to_u8(row, "row")?;
+            let align: Align = align.parse()?;
+            with_display(&d, |vfd| vfd.write_line(row, text, align))
+        });
+        let d = Arc::clone(&display);
+        engine.register_fn("clear", move || with_display(&d, |vfd| vfd.clear()));
+        let d = Arc::clone(&display);
+        engine.register_fn("cursor", move |x: i64, y: i64| {
+            let (x, y) = (to_u8(x, "column")?, to_u8(y, "row")?);
+            with_display(&d, |vfd| vfd.set_cursor(x, y))
+        });
+        let d = Arc::clone(&display);
+        engine.register_fn("brightness", move |level: i64| {
+            let level: BrightnessLevel = level.to_string().parse()?;
+            with_display(&d, |vfd| vfd.set_brightness(level))
+        });
+        let d = Arc::clone(&display);
+        engine.register_fn("width", move || {
+            with_display(&d, |vfd| Ok(VfdDisplay::size(vfd).0 as i64))
+        });
+        let d = Arc::clone(&display);
+        engine.register_fn("height", move || {
+            with_display(&d, |vfd| Ok(VfdDisplay::size(vfd).1 as i64))
+        });
+        let d = Arc::clone(&display);
+        engine.register_fn("sleep", move |millis: i64| -> ScriptResult<()> {
+            // Let queued output reach the display before waiting
+            with_display(&d, |vfd| vfd.flush())?;
+            thread::sleep(Duration::from_millis(millis.max(0) as u64));
+            Ok(())
+        });
+        engine.register_fn("http_get", http_get);
+        // Display errors caught with try/catch print as their message
+        engine.register_fn("to_string", |error: &mut Arc<VfdError>| error.to_string());
+
+        ScriptEngine { engine }
+    }
+
+    /// Run a script given as source text.
+    ///
+    /// A script that fails to parse or run fails with
+    /// [`VfdError::InvalidInput`]; when the display failed, its error is
+    /// returned as it is.
+    pub fn run(&self, source: &str) -> Result<(), VfdError> {
+        self.engine
+            .run(source)
+            .map_err(|e| script_error(e, |e| format!("Script error: {}", e)))
+    }
+
+    /// Run the script in `path`, failing like [`ScriptEngine::run`] or with
+    /// [`VfdError::Io`] when it cannot be read.
+    pub fn run_file(&self, path: &Path) -> Result<(), VfdError> {
+        let source = std::fs::read_to_string(path)?;
+        self.engine
+            .run(&source)
+            .map_err(|e| script_error(e, |e| format!("Script error in {}: {}", path.display(), e)))
+    }
+
+    /// The underlying engine, to register more functions.
+    pub fn engine_mut(&mut self) -> &mut Engine {
+        &mut self.engine
+    }
+}
+
+// Run `f` with the display locked, carrying its error through the script
+// for script_error to take out again
+fn with_display<T>(
+    display: &Mutex<Vfd>,
+    f: impl FnOnce(&mut Vfd) -> Result<T, VfdError>,
+) -> ScriptResult<T> {
+    let mut vfd = display.lock().unwrap_or_else(PoisonError::into_inner);
+    f(&mut vfd)
+        .map_err(|e| EvalAltResult::ErrorRuntime(Dynamic::from(Arc::new(e)), Position::NONE).into())
+}
+
+// The display error that ended a script, or the script's own failure as
+// described by `describe`
+fn script_error(
+    error: Box<EvalAltResult>,
+    describe: impl FnOnce(&EvalAltResult) -> String,
+) -> VfdError {
+    let carried = match error.unwrap_inner() {
+        EvalAltResult::ErrorRuntime(value, _) => value.clone().try_cast::<Arc<VfdError>>(),
+        _ => None,
+    };
+    let Some(carried) = carried else {
+        return VfdError::InvalidInput(describe(&error));
+    };
+    // The script is done with the error once it ended the run
+    drop(error);
+    Arc::try_unwrap(carried).unwrap_or_else(|shared| VfdError::InvalidInput(shared.to_string()))
+}
+
+fn to_u8(value: i64, what: &str) -> ScriptResult<u8> {
+    u8::try_from(value).map_err(|_| format!("Invalid {} {}", what, value).into())
+}
+
+fn http_get(url: &str) -> ScriptResult<String> {
+    ureq::get(url)
+        .timeout(HTTP_TIMEOUT)
+        .call()
+        .map_err(|e| format!("GET {} failed: {}", url, e))?
+        .into_string()
+        .map_err(|e| format!("GET {} failed: {}", url, e).into())
+}
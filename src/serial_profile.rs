@@ -0,0 +1,196 @@
+use crate::command_set::CommandSet;
+use serialport::{DataBits, FlowControl, Parity, StopBits};
+use std::fmt;
+use std::str::FromStr;
+
+// Baud rates customer displays can be DIP-switched to, most common first
+const COMMON_BAUD_RATES: [u32; 4] = [9600, 19200, 4800, 38400];
+
+/// Serial line settings a display expects.
+///
+/// Displays of the same model can be switched to other settings, usually with
+/// DIP switches under the cover; [`SerialProfile::for_command_set`] gives the
+/// factory ones and [`SerialProfile::probe_order`] the ones worth trying when
+/// the switches are unknown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SerialProfile {
+    pub baud_rate: u32,
+    pub data_bits: DataBits,
+    pub parity: Parity,
+    pub stop_bits: StopBits,
+    /// [`FlowControl::Hardware`] holds writes back while the display drops CTS.
+    pub flow_control: FlowControl,
+    /// Level to drive DTR to once the port is open; `None` leaves it as the
+    /// operating system set it. Many RS-232 displays take power or their
+    /// "host ready" signal from DTR.
+    pub dtr: Option<bool>,
+    /// Level to drive RTS to once the port is open, ignored with hardware flow
+    /// control, which drives RTS itself.
+    pub rts: Option<bool>,
+}
+
+impl SerialProfile {
+    /// Eight data bits, one stop bit, no flow control and DTR and RTS
+    /// asserted, at `baud_rate` with `parity`.
+    pub const fn new(baud_rate: u32, parity: Parity) -> Self {
+        SerialProfile {
+            baud_rate,
+            data_bits: DataBits::Eight,
+            parity,
+            stop_bits: StopBits::One,
+            flow_control: FlowControl::None,
+            dtr: Some(true),
+            rts: Some(true),
+        }
+    }
+
+    pub const fn flow_control(mut self, flow_control: FlowControl) -> Self {
+        self.flow_control = flow_control;
+        self
+    }
+
+    /// Factory settings of displays speaking `command_set`.
+    ///
+    /// Noritake CU modules report a full input buffer on their BUSY line,
+    /// which is wired to CTS, so they get hardware flow control.
+    pub fn for_command_set(command_set: CommandSet) -> Self {
+        match command_set {
+            CommandSet::Birch
+            | CommandSet::EpsonDmD
+            | CommandSet::Futaba
+            | CommandSet::Custom(_) => SerialProfile::new(9600, Parity::None),
+            CommandSet::NoritakeCu => {
+                SerialProfile::new(19200, Parity::None).flow_control(FlowControl::Hardware)
+            }
+        }
+    }
+
+    /// Settings to try, in order, when probing a display speaking `command_set`:
+    /// its factory settings, then the common baud rates without parity, with
+    /// even parity and with odd parity, keeping the factory flow control.
+    pub fn probe_order(command_set: CommandSet) -> Vec<SerialProfile> {
+        let factory = SerialProfile::for_command_set(command_set);
+        let mut profiles = vec![factory];
+        for parity in [Parity::None, Parity::Even, Parity::Odd] {
+            for baud_rate in COMMON_BAUD_RATES {
+                let profile =
+                    SerialProfile::new(baud_rate, parity).flow_control(factory.flow_control);
+                if !profiles.contains(&profile) {
+                    profiles.push(profile);
+                }
+            }
+        }
+        profiles
+    }
+
+    /// Parse a parity name: `none`, `even` or `odd`, or their initials.
+    pub fn parse_parity(s: &str) -> Result<Parity, String> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "none" | "n" => Ok(Parity::None),
+            "even" | "e" => Ok(Parity::Even),
+            "odd" | "o" => Ok(Parity::Odd),
+            other => Err(format!(
+                "Unknown parity '{}'. Expected one of none, even, odd.",
+                other
+            )),
+        }
+    }
+
+    /// Parse a flow control name: `none`, `software` (XON/XOFF) or `hardware` (RTS/CTS).
+    pub fn parse_flow_control(s: &str) -> Result<FlowControl, String> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "none" => Ok(FlowControl::None),
+            "software" | "xon/xoff" | "xonxoff" => Ok(FlowControl::Software),
+            "hardware" | "rts/cts" | "rtscts" => Ok(FlowControl::Hardware),
+            other => Err(format!(
+                "Unknown flow control '{}'. Expected one of none, software, hardware.",
+                other
+            )),
+        }
+    }
+}
+
+impl Default for SerialProfile {
+    fn default() -> Self {
+        SerialProfile::for_command_set(CommandSet::default())
+    }
+}
+
+impl FromStr for SerialProfile {
+    type Err = String;
+
+    /// Parse a baud rate optionally followed by the frame format and flow
+    /// control, e.g. `9600`, `19200 8E1`, `9600-7O2` or `9600 8N1 RTS/CTS`, as
+    /// [`Display`](fmt::Display) writes them. DTR and RTS are asserted.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || {
+            format!(
+                "Unknown serial profile '{}'. Expected BAUD, BAUD-FORMAT or BAUD-FORMAT-FLOW such as 9600, 19200-8E1 or 9600-8N1-RTS/CTS.",
+                s.trim()
+            )
+        };
+        let mut parts = s
+            .trim()
+            .split(['-', ' ', ',', ':'])
+            .filter(|p| !p.is_empty());
+        let baud_rate = parts
+            .next()
+            .and_then(|baud| baud.parse().ok())
+            .filter(|&baud| baud > 0)
+            .ok_or_else(error)?;
+        let mut profile = SerialProfile::new(baud_rate, Parity::None);
+        if let Some(format) = parts.next() {
+            let mut chars = format.chars();
+            let (Some(data_bits), Some(parity), Some(stop_bits), None) =
+                (chars.next(), chars.next(), chars.next(), chars.next())
+            else {
+                return Err(error());
+            };
+            profile.data_bits = match data_bits {
+                '5' => DataBits::Five,
+                '6' => DataBits::Six,
+                '7' => DataBits::Seven,
+                '8' => DataBits::Eight,
+                _ => return Err(error()),
+            };
+            profile.parity =
+                SerialProfile::parse_parity(&parity.to_string()).map_err(|_| error())?;
+            profile.stop_bits = match stop_bits {
+                '1' => StopBits::One,
+                '2' => StopBits::Two,
+                _ => return Err(error()),
+            };
+        }
+        if let Some(flow_control) = parts.next() {
+            profile.flow_control =
+                SerialProfile::parse_flow_control(flow_control).map_err(|_| error())?;
+        }
+        match parts.next() {
+            Some(_) => Err(error()),
+            None => Ok(profile),
+        }
+    }
+}
+
+impl fmt::Display for SerialProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let parity = match self.parity {
+            Parity::None => 'N',
+            Parity::Even => 'E',
+            Parity::Odd => 'O',
+        };
+        write!(
+            f,
+            "{} {}{}{}",
+            self.baud_rate,
+            u8::from(self.data_bits),
+            parity,
+            u8::from(self.stop_bits)
+        )?;
+        match self.flow_control {
+            FlowControl::None => Ok(()),
+            FlowControl::Software => write!(f, " XON/XOFF"),
+            FlowControl::Hardware => write!(f, " RTS/CTS"),
+        }
+    }
+}
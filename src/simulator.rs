@@ -0,0 +1,571 @@
+use crate::annunciator::Annunciator;
+use crate::codepage::CodePage;
+use crate::command_set::{BrightnessLevel, CommandSet, DisplayMode, Peripheral};
+use crate::frame_buffer::FrameBuffer;
+use crate::info::DisplayInfo;
+use crate::protocol_definition::{Part, ProtocolDefinition};
+use crate::transport::Duplex;
+use crate::vfd::Vfd;
+use std::collections::{BTreeSet, VecDeque};
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+
+const ESC: u8 = 0x1B;
+const US: u8 = 0x1F;
+// DLE EOT 1 answer with bits 1 and 4 set, as they always are, and the offline bit clear
+const STATUS_ONLINE: u8 = 0x12;
+
+/// A model of the display that interprets the bytes sent to it into a grid of cells.
+///
+/// Where [`MockVfd`](crate::MockVfd) records what the driver *believes* it
+/// drew, a simulator shows what the byte stream actually draws, so the two can
+/// be compared. It understands the commands of all four command sets; bytes
+/// it cannot make sense of are counted rather than rejected.
+///
+/// Clones share their state, so one clone can be handed to a [`Vfd`] as its
+/// transport while another is inspected. Identify and status requests are
+/// answered as set with [`Simulator::set_identity`] and [`Simulator::set_status`].
+///
+/// ```
+/// use vfd_dsp_v9fb_over_serial::{Align, CommandSet, Simulator};
+///
+/// let simulator = Simulator::new(CommandSet::Birch, 20, 2);
+/// let mut vfd = simulator.vfd();
+/// vfd.write_line(1, "Total: 4.20", Align::Right).unwrap();
+/// assert_eq!(simulator.row(1), "         Total: 4.20");
+/// ```
+#[derive(Clone)]
+pub struct Simulator {
+    state: Arc<Mutex<State>>,
+}
+
+struct State {
+    command_set: CommandSet,
+    frame: FrameBuffer,
+    width: u8,
+    height: u8,
+    cursor_x: u8,
+    cursor_y: u8,
+    mode: DisplayMode,
+    code_page: CodePage,
+    brightness: BrightnessLevel,
+    // Blink interval in 50 ms steps, 0 when steady
+    blink: u8,
+    peripheral: Peripheral,
+    // Positions of the lit icons
+    annunciators: BTreeSet<u8>,
+    identity: DisplayInfo,
+    status: u8,
+    // Answers to requests, not read back yet
+    answers: VecDeque<u8>,
+    // Start of a command still waiting for its remaining bytes
+    pending: Vec<u8>,
+    unknown: usize,
+}
+
+// What a complete command does to the model
+enum Op {
+    Print(u8),
+    Clear,
+    ClearLine,
+    Left,
+    Right,
+    Up,
+    Down,
+    Home,
+    CarriageReturn,
+    Rightmost,
+    Bottom,
+    MoveTo(u8, u8),
+    MoveToCell(u8),
+    Mode(DisplayMode),
+    CodeTable(u8),
+    Brightness(BrightnessLevel),
+    Blink(u8),
+    Initialize,
+    Select(u8),
+    Annunciator(u8, bool),
+    Identify(u8),
+    Status,
+    // Understood but without a visible effect on the cells
+    Ignore,
+    Unknown,
+}
+
+impl Simulator {
+    /// A blank `width × height` display speaking `command_set`, in its power-on state.
+    pub fn new(command_set: CommandSet, width: u8, height: u8) -> Self {
+        Simulator {
+            state: Arc::new(Mutex::new(State {
+                command_set,
+                frame: FrameBuffer::new(width, height),
+                width,
+                height,
+                cursor_x: 0,
+                cursor_y: 0,
+                mode: DisplayMode::default(),
+                code_page: CodePage::default(),
+                brightness: BrightnessLevel::Percent100,
+                blink: 0,
+                peripheral: Peripheral::Display,
+                annunciators: BTreeSet::new(),
+                identity: DisplayInfo {
+                    model_id: 0,
+                    type_id: Some(0),
+                    rom_version: Some(0),
+                },
+                status: STATUS_ONLINE,
+                answers: VecDeque::new(),
+                pending: Vec::new(),
+                unknown: 0,
+            })),
+        }
+    }
+
+    /// A driver whose bytes go to this simulator. Nothing is sent on creation.
+    pub fn vfd(&self) -> Vfd {
+        let state = self.lock();
+        Vfd::from_duplex(
+            self.clone(),
+            state.command_set.clone(),
+            state.width,
+            state.height,
+        )
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Interpret `bytes`. A command split across calls is completed by the next one.
+    pub fn feed(&self, bytes: &[u8]) {
+        let mut state = self.lock();
+        state.pending.extend_from_slice(bytes);
+        let pending = std::mem::take(&mut state.pending);
+        let mut rest = pending.as_slice();
+        while !rest.is_empty() {
+            let Some((len, op)) = parse(&state.command_set, rest) else {
+                break;
+            };
+            state.apply(op);
+            rest = &rest[len..];
+        }
+        state.pending = rest.to_vec();
+    }
+
+    /// Contents of row `y`, padded with spaces to the display width.
+    pub fn row(&self, y: u8) -> String {
+        self.lock().frame.row(y)
+    }
+
+    /// Contents of every row, top to bottom.
+    pub fn rows(&self) -> Vec<String> {
+        let state = self.lock();
+        (0..state.height).map(|y| state.frame.row(y)).collect()
+    }
+
+    /// The simulated cells.
+    pub fn frame(&self) -> FrameBuffer {
+        self.lock().frame.clone()
+    }
+
+    /// Cursor position as (column, row). The column equals the width after
+    /// the last cell of a row was written, until the next character wraps.
+    pub fn cursor(&self) -> (u8, u8) {
+        let state = self.lock();
+        (state.cursor_x, state.cursor_y)
+    }
+
+    pub fn brightness(&self) -> BrightnessLevel {
+        self.lock().brightness
+    }
+
+    pub fn display_mode(&self) -> DisplayMode {
+        self.lock().mode
+    }
+
+    pub fn code_page(&self) -> CodePage {
+        self.lock().code_page
+    }
+
+    /// How often the whole display blinks, zero while it is steady.
+    pub fn blink(&self) -> Duration {
+        Duration::from_millis(50 * self.lock().blink as u64)
+    }
+
+    /// Whether `icon` is lit.
+    pub fn annunciator(&self, icon: Annunciator) -> bool {
+        let state = self.lock();
+        state
+            .command_set
+            .annunciator_position(icon)
+            .is_some_and(|position| state.annunciators.contains(&position))
+    }
+
+    /// IDs to answer identify requests with (all zero by default), for
+    /// command sets that have them.
+    pub fn set_identity(&self, identity: DisplayInfo) {
+        self.lock().identity = identity;
+    }
+
+    /// Byte to answer status requests with (online by default), for command
+    /// sets that have them.
+    pub fn set_status(&self, status: u8) {
+        self.lock().status = status;
+    }
+
+    /// Number of commands and control bytes the simulator did not recognise.
+    pub fn unknown_bytes(&self) -> usize {
+        self.lock().unknown
+    }
+}
+
+impl Write for Simulator {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.feed(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+// Answers are queued as requests are written, so nothing more arrives by waiting
+impl Duplex for Simulator {
+    fn read(&mut self, buf: &mut [u8], _timeout: Duration) -> io::Result<usize> {
+        let mut state = self.lock();
+        let len = buf.len().min(state.answers.len());
+        for (slot, byte) in buf.iter_mut().zip(state.answers.drain(..len)) {
+            *slot = byte;
+        }
+        Ok(len)
+    }
+
+    fn discard_input(&mut self) -> io::Result<()> {
+        self.lock().answers.clear();
+        Ok(())
+    }
+}
+
+impl State {
+    fn apply(&mut self, op: Op) {
+        let (last_x, last_y) = (self.width.saturating_sub(1), self.height.saturating_sub(1));
+        // With only the printer selected the display ignores everything but reselection
+        if self.peripheral == Peripheral::Printer && !matches!(op, Op::Select(_)) {
+            return;
+        }
+        match op {
+            Op::Print(byte) => self.print(byte),
+            Op::Clear => {
+                self.frame.clear();
+                (self.cursor_x, self.cursor_y) = (0, 0);
+            }
+            Op::ClearLine => {
+                self.frame
+                    .put_str(0, self.cursor_y, &" ".repeat(self.width as usize));
+                self.cursor_x = 0;
+            }
+            Op::Left => self.cursor_x = self.cursor_x.saturating_sub(1).min(last_x),
+            Op::Right => self.cursor_x = (self.cursor_x + 1).min(last_x),
+            Op::Up => self.cursor_y = self.cursor_y.saturating_sub(1),
+            Op::Down if self.cursor_y < last_y => self.cursor_y += 1,
+            Op::Down if self.mode == DisplayMode::VerticalScroll => self.frame.scroll_up(),
+            Op::Down => {}
+            Op::Home => (self.cursor_x, self.cursor_y) = (0, 0),
+            Op::CarriageReturn => self.cursor_x = 0,
+            Op::Rightmost => self.cursor_x = last_x,
+            Op::Bottom => (self.cursor_x, self.cursor_y) = (0, last_y),
+            Op::MoveTo(x, y) => (self.cursor_x, self.cursor_y) = (x.min(last_x), y.min(last_y)),
+            Op::MoveToCell(cell) => {
+                let width = self.width.max(1);
+                (self.cursor_x, self.cursor_y) = (cell % width, (cell / width).min(last_y));
+            }
+            Op::Mode(mode) => self.mode = mode,
+            Op::CodeTable(n) => {
+                if let Some(page) = CodePage::from_table_number(n) {
+                    self.code_page = page;
+                }
+            }
+            Op::Brightness(level) => self.brightness = level,
+            Op::Blink(steps) => self.blink = steps,
+            Op::Initialize => {
+                self.frame.clear();
+                (self.cursor_x, self.cursor_y) = (0, 0);
+                self.mode = DisplayMode::default();
+                self.code_page = CodePage::default();
+                self.brightness = BrightnessLevel::Percent100;
+                self.blink = 0;
+                self.annunciators.clear();
+            }
+            Op::Select(n) => {
+                self.peripheral = match n {
+                    1 => Peripheral::Printer,
+                    3 => Peripheral::Both,
+                    _ => Peripheral::Display,
+                }
+            }
+            Op::Annunciator(position, true) => {
+                self.annunciators.insert(position);
+            }
+            Op::Annunciator(position, false) => {
+                self.annunciators.remove(&position);
+            }
+            Op::Identify(n) => {
+                let answer = match n {
+                    1 => Some(self.identity.model_id),
+                    2 => self.identity.type_id,
+                    3 => self.identity.rom_version,
+                    _ => None,
+                };
+                self.answers.extend(answer);
+            }
+            Op::Status => self.answers.push_back(self.status),
+            Op::Ignore => {}
+            Op::Unknown => self.unknown += 1,
+        }
+    }
+
+    // Show a character at the cursor, first wrapping or scrolling if the row is full
+    fn print(&mut self, byte: u8) {
+        if self.width == 0 || self.height == 0 {
+            return;
+        }
+        if self.cursor_x >= self.width {
+            match self.mode {
+                DisplayMode::Overwrite => {
+                    self.cursor_x = 0;
+                    self.cursor_y = (self.cursor_y + 1) % self.height;
+                }
+                DisplayMode::VerticalScroll if self.cursor_y + 1 >= self.height => {
+                    self.frame.scroll_up();
+                    self.cursor_x = 0;
+                }
+                DisplayMode::VerticalScroll => {
+                    self.cursor_x = 0;
+                    self.cursor_y += 1;
+                }
+                DisplayMode::HorizontalScroll => {
+                    let shifted: String = self.frame.row(self.cursor_y).chars().skip(1).collect();
+                    self.frame.put_str(0, self.cursor_y, &shifted);
+                    self.cursor_x = self.width - 1;
+                }
+            }
+        }
+        let c = self.code_page.decode(byte).unwrap_or('?');
+        self.frame.set(self.cursor_x, self.cursor_y, c);
+        self.cursor_x += 1;
+    }
+}
+
+// The first command in `bytes` and its length, or `None` if it is incomplete
+fn parse(command_set: &CommandSet, bytes: &[u8]) -> Option<(usize, Op)> {
+    match command_set {
+        CommandSet::Birch | CommandSet::EpsonDmD => parse_epson(bytes),
+        CommandSet::NoritakeCu => parse_noritake(bytes),
+        CommandSet::Futaba => parse_futaba(bytes),
+        CommandSet::Custom(protocol) => parse_custom(protocol, bytes),
+    }
+}
+
+// The sequences a definition names, and printable text
+fn parse_custom(protocol: &ProtocolDefinition, bytes: &[u8]) -> Option<(usize, Op)> {
+    let mut incomplete = false;
+    let mut fixed: Vec<(&[u8], Op)> = vec![
+        (protocol.initialize(), Op::Initialize),
+        (protocol.clear(), Op::Clear),
+    ];
+    if let Some(clear_line) = protocol.clear_line() {
+        fixed.push((clear_line, Op::ClearLine));
+    }
+    for visible in [true, false] {
+        if let Some(sequence) = protocol.set_cursor_visible(visible) {
+            fixed.push((sequence, Op::Ignore));
+        }
+    }
+    for (level, sequence) in protocol.brightness_levels() {
+        fixed.push((sequence, Op::Brightness(*level)));
+    }
+    for (page, sequence) in protocol.code_pages() {
+        fixed.push((sequence, Op::CodeTable(page.table_number())));
+    }
+    for (sequence, op) in fixed {
+        if sequence.is_empty() {
+            continue;
+        }
+        if bytes.starts_with(sequence) {
+            return Some((sequence.len(), op));
+        }
+        incomplete |= sequence.starts_with(bytes);
+    }
+
+    let template = protocol.cursor_template();
+    let (mut x, mut y, mut cell) = (None, None, None);
+    let mut matched = true;
+    for (i, part) in template.iter().enumerate() {
+        let Some(&byte) = bytes.get(i) else {
+            incomplete = true;
+            matched = false;
+            break;
+        };
+        let value = match *part {
+            Part::Byte(expected) => (byte == expected).then_some(byte),
+            Part::Column(offset) => byte.checked_sub(offset).inspect(|v| x = Some(*v)),
+            Part::Row(offset) => byte.checked_sub(offset).inspect(|v| y = Some(*v)),
+            Part::Cell(offset) => byte.checked_sub(offset).inspect(|v| cell = Some(*v)),
+        };
+        if value.is_none() {
+            matched = false;
+            break;
+        }
+    }
+    if matched && !template.is_empty() {
+        let op = match cell {
+            Some(cell) => Op::MoveToCell(cell),
+            None => Op::MoveTo(x.unwrap_or(0), y.unwrap_or(0)),
+        };
+        return Some((template.len(), op));
+    }
+
+    match bytes[0] {
+        _ if incomplete => None,
+        0x20.. => Some((1, Op::Print(bytes[0]))),
+        _ => Some((1, Op::Unknown)),
+    }
+}
+
+// `len` bytes once they have all arrived
+fn need(bytes: &[u8], len: usize, op: impl FnOnce(&[u8]) -> Op) -> Option<(usize, Op)> {
+    (bytes.len() >= len).then(|| (len, op(bytes)))
+}
+
+fn parse_epson(bytes: &[u8]) -> Option<(usize, Op)> {
+    let first = bytes[0];
+    match first {
+        0x20.. => Some((1, Op::Print(first))),
+        0x08 => Some((1, Op::Left)),
+        0x09 => Some((1, Op::Right)),
+        0x0A => Some((1, Op::Down)),
+        0x0B => Some((1, Op::Home)),
+        0x0C => Some((1, Op::Clear)),
+        0x0D => Some((1, Op::CarriageReturn)),
+        0x18 => Some((1, Op::ClearLine)),
+        US => match *bytes.get(1)? {
+            0x01 => Some((2, Op::Mode(DisplayMode::Overwrite))),
+            0x02 => Some((2, Op::Mode(DisplayMode::VerticalScroll))),
+            0x03 => Some((2, Op::Mode(DisplayMode::HorizontalScroll))),
+            0x0A => Some((2, Op::Up)),
+            0x0D => Some((2, Op::Rightmost)),
+            b'B' => Some((2, Op::Bottom)),
+            b'$' => need(bytes, 4, |b| {
+                Op::MoveTo(b[2].saturating_sub(1), b[3].saturating_sub(1))
+            }),
+            b'X' => need(bytes, 3, |b| match b[2] {
+                1 => Op::Brightness(BrightnessLevel::Percent20),
+                2 => Op::Brightness(BrightnessLevel::Percent40),
+                3 => Op::Brightness(BrightnessLevel::Percent60),
+                4 => Op::Brightness(BrightnessLevel::Percent100),
+                _ => Op::Unknown,
+            }),
+            b'E' => need(bytes, 3, |b| Op::Blink(b[2])),
+            b'C' | b'r' => need(bytes, 3, |_| Op::Ignore),
+            b'#' => need(bytes, 4, |b| Op::Annunciator(b[3], b[2] != 0)),
+            b'T' | b'^' => need(bytes, 4, |_| Op::Ignore),
+            b'U' | b'@' | b':' => Some((2, Op::Ignore)),
+            _ => Some((2, Op::Unknown)),
+        },
+        ESC => match *bytes.get(1)? {
+            b'@' => Some((2, Op::Initialize)),
+            b't' => need(bytes, 3, |b| Op::CodeTable(b[2])),
+            b'=' => need(bytes, 3, |b| Op::Select(b[2])),
+            b'R' | b'%' | b'?' => need(bytes, 3, |_| Op::Ignore),
+            b'&' => define_length(bytes).map(|len| (len, Op::Ignore)),
+            _ => Some((2, Op::Unknown)),
+        },
+        // GS I n, DLE EOT n
+        0x1D => need(bytes, 3, |b| match b[1] {
+            b'I' => Op::Identify(b[2]),
+            _ => Op::Ignore,
+        }),
+        0x10 => need(bytes, 3, |b| match b[1] {
+            0x04 => Op::Status,
+            _ => Op::Ignore,
+        }),
+        _ => Some((1, Op::Unknown)),
+    }
+}
+
+// Length of ESC & y c1 c2 followed by a width byte and `y × width` bytes per character
+fn define_length(bytes: &[u8]) -> Option<usize> {
+    let (rows, first, last) = (*bytes.get(2)?, *bytes.get(3)?, *bytes.get(4)?);
+    let mut len = 5;
+    for _ in first..=last {
+        let columns = *bytes.get(len)? as usize;
+        len += 1 + rows as usize * columns;
+    }
+    (bytes.len() >= len).then_some(len)
+}
+
+fn parse_noritake(bytes: &[u8]) -> Option<(usize, Op)> {
+    let first = bytes[0];
+    match first {
+        ESC => match *bytes.get(1)? {
+            b'I' => Some((2, Op::Initialize)),
+            b'H' => need(bytes, 3, |b| Op::MoveToCell(b[2])),
+            b't' => need(bytes, 3, |b| Op::CodeTable(b[2])),
+            b'L' => need(bytes, 3, |b| match b[2] {
+                0x00..=0x3F => Op::Brightness(BrightnessLevel::Percent20),
+                0x40..=0x7F => Op::Brightness(BrightnessLevel::Percent40),
+                0x80..=0xBF => Op::Brightness(BrightnessLevel::Percent60),
+                _ => Op::Brightness(BrightnessLevel::Percent100),
+            }),
+            b'C' => need(bytes, 8, |_| Op::Ignore),
+            _ => Some((2, Op::Unknown)),
+        },
+        // US r n, reverse video
+        US => match *bytes.get(1)? {
+            b'r' => need(bytes, 3, |_| Op::Ignore),
+            _ => Some((2, Op::Unknown)),
+        },
+        _ => parse_shared(bytes),
+    }
+}
+
+fn parse_futaba(bytes: &[u8]) -> Option<(usize, Op)> {
+    let first = bytes[0];
+    match first {
+        0x1F => Some((1, Op::Initialize)),
+        0x10 => need(bytes, 2, |b| Op::MoveToCell(b[1])),
+        0x04 => need(bytes, 2, |b| match b[1] {
+            0x00 => Op::Ignore,
+            0x01..=0x20 => Op::Brightness(BrightnessLevel::Percent20),
+            0x21..=0x40 => Op::Brightness(BrightnessLevel::Percent40),
+            0x41..=0x60 => Op::Brightness(BrightnessLevel::Percent60),
+            _ => Op::Brightness(BrightnessLevel::Percent100),
+        }),
+        ESC => match *bytes.get(1)? {
+            b'C' => need(bytes, 8, |_| Op::Ignore),
+            _ => Some((2, Op::Unknown)),
+        },
+        _ => parse_shared(bytes),
+    }
+}
+
+// Single-byte commands common to the Noritake and Futaba sets
+fn parse_shared(bytes: &[u8]) -> Option<(usize, Op)> {
+    let op = match bytes[0] {
+        byte @ 0x20.. => Op::Print(byte),
+        0x08 => Op::Left,
+        0x09 => Op::Right,
+        0x0A => Op::Down,
+        0x0B => Op::Home,
+        0x0D => Op::CarriageReturn,
+        0x0E => Op::Clear,
+        0x11 => Op::Mode(DisplayMode::Overwrite),
+        0x12 => Op::Mode(DisplayMode::VerticalScroll),
+        0x13..=0x15 => Op::Ignore,
+        _ => Op::Unknown,
+    };
+    Some((1, op))
+}
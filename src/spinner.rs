@@ -0,0 +1,102 @@
+use crate::display::VfdDisplay;
+use crate::error::VfdError;
+use crate::widget::Widget;
+use std::time::{Duration, Instant};
+
+// Classic rotation used by `Spinner::new`
+const LINE_FRAMES: &[char] = &['|', '/', '-', '\\'];
+
+/// A single cell that cycles through characters to show that work is in progress.
+///
+/// Only its own cell is rewritten on each step. Drive it with [`Widget::tick`]
+/// or hand it to [`widget::spawn`](crate::widget::spawn).
+#[derive(Debug, Clone)]
+pub struct Spinner {
+    frames: Vec<char>,
+    x: u8,
+    y: u8,
+    speed: Duration,
+    index: usize,
+    running: bool,
+    last_step: Option<Instant>,
+}
+
+impl Spinner {
+    /// A `|/-\` spinner at column `x`, row `y`, stepping every 150 ms.
+    pub fn new(x: u8, y: u8) -> Self {
+        Spinner::with_frames(x, y, LINE_FRAMES)
+    }
+
+    /// A spinner cycling through `frames`, e.g. [`custom_char`](crate::custom_char) glyphs.
+    pub fn with_frames(x: u8, y: u8, frames: &[char]) -> Self {
+        Spinner {
+            frames: frames.to_vec(),
+            x,
+            y,
+            speed: Duration::from_millis(150),
+            index: 0,
+            running: true,
+            last_step: None,
+        }
+    }
+
+    /// Time between steps.
+    pub fn speed(mut self, speed: Duration) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    /// Resume spinning.
+    pub fn start(&mut self) {
+        self.running = true;
+    }
+
+    /// Pause on the current character.
+    pub fn stop(&mut self) {
+        self.running = false;
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running
+    }
+
+    /// Character currently shown.
+    pub fn frame(&self) -> char {
+        self.frames.get(self.index).copied().unwrap_or(' ')
+    }
+
+    /// Blank the spinner's cell, e.g. once the work is done.
+    pub fn erase(&mut self, display: &mut dyn VfdDisplay) -> Result<(), VfdError> {
+        self.stop();
+        display.set_cursor(self.x, self.y)?;
+        display.write_text(" ")
+    }
+}
+
+impl Widget for Spinner {
+    fn tick(&mut self, display: &mut dyn VfdDisplay) -> Result<bool, VfdError> {
+        if !self.running || self.frames.is_empty() {
+            return Ok(false);
+        }
+        let now = Instant::now();
+        match self.last_step {
+            None => {}
+            Some(last) if now.duration_since(last) >= self.speed => {
+                self.index = (self.index + 1) % self.frames.len();
+            }
+            Some(_) => return Ok(false),
+        }
+        self.last_step = Some(now);
+        self.render(display)?;
+        Ok(true)
+    }
+
+    fn render(&mut self, display: &mut dyn VfdDisplay) -> Result<(), VfdError> {
+        display.set_cursor(self.x, self.y)?;
+        display.write_text(&self.frame().to_string())
+    }
+
+    fn interval(&self) -> Duration {
+        self.speed
+    }
+}
@@ -0,0 +1,58 @@
+use crate::animation::Animation;
+use crate::display::VfdDisplay;
+use crate::error::VfdError;
+use crate::layout::Align;
+use std::thread;
+use std::time::Duration;
+
+/// A screen shown for a moment when the display is opened, see
+/// [`VfdBuilder::splash`](crate::VfdBuilder::splash).
+///
+/// ```
+/// use std::time::Duration;
+/// use vfd_dsp_v9fb_over_serial::{CommandSet, Simulator, Splash};
+///
+/// let simulator = Simulator::new(CommandSet::Birch, 20, 2);
+/// let mut vfd = simulator.vfd();
+/// Splash::Text {
+///     text: "ACME\nGroceries".to_string(),
+///     duration: Duration::ZERO,
+/// }
+/// .show(&mut vfd)
+/// .unwrap();
+/// // Cleared again once the splash is over
+/// assert_eq!(simulator.row(0).trim(), "");
+/// ```
+#[derive(Debug, Clone)]
+pub enum Splash {
+    /// Text centered on the display, with `\n` starting a new row, shown for `duration`.
+    Text { text: String, duration: Duration },
+    /// An animation played through, cut short once `duration` has passed.
+    Animation {
+        animation: Animation,
+        duration: Duration,
+    },
+}
+
+impl Splash {
+    /// Show the splash, wait for it to finish and clear the display again.
+    pub fn show<D: VfdDisplay + ?Sized>(&self, display: &mut D) -> Result<(), VfdError> {
+        match self {
+            Splash::Text { text, duration } => {
+                let (_, height) = display.size();
+                let rows: Vec<&str> = text.lines().take(height as usize).collect();
+                let top = (height as usize - rows.len()) / 2;
+                display.clear()?;
+                for (y, row) in rows.iter().enumerate() {
+                    display.write_line((top + y) as u8, row, Align::Center)?;
+                }
+                thread::sleep(*duration);
+            }
+            Splash::Animation {
+                animation,
+                duration,
+            } => animation.play_for(display, *duration)?,
+        }
+        display.clear()
+    }
+}
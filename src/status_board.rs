@@ -0,0 +1,177 @@
+use crate::display::VfdDisplay;
+use crate::error::VfdError;
+use crate::layout::{self, Align};
+use crate::widget::Widget;
+use std::time::Duration;
+
+/// Where a [`StatusBoard`] shows one of its values.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatusField {
+    pub x: u8,
+    pub y: u8,
+    /// Columns the field occupies, label included; defaults to the rest of the row.
+    pub width: Option<u8>,
+    pub align: Align,
+    /// Text shown in front of the value, such as `"T:"`.
+    pub label: String,
+}
+
+impl StatusField {
+    /// A left-aligned field at column `x`, row `y` that runs to the end of the row.
+    pub fn new(x: u8, y: u8) -> Self {
+        StatusField {
+            x,
+            y,
+            width: None,
+            align: Align::Left,
+            label: String::new(),
+        }
+    }
+
+    pub fn width(mut self, width: u8) -> Self {
+        self.width = Some(width);
+        self
+    }
+
+    pub fn align(mut self, align: Align) -> Self {
+        self.align = align;
+        self
+    }
+
+    pub fn label(mut self, label: &str) -> Self {
+        self.label = label.to_string();
+        self
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Entry {
+    name: String,
+    field: StatusField,
+    value: String,
+    // Changed since it was last drawn
+    dirty: bool,
+}
+
+/// Named values at fixed places on the display, such as readings from a
+/// few sensors, each redrawn on its own when it changes.
+///
+/// [`StatusBoard::set_value`] only stores the value; the next
+/// [`Widget::tick`] draws the fields that changed and nothing else. On a
+/// [`Vfd`](crate::Vfd) only the cells that differ are sent, so a reading going
+/// from `23.4C` to `23.5C` costs one character.
+///
+/// ```
+/// use vfd_dsp_v9fb_over_serial::widget::Widget;
+/// use vfd_dsp_v9fb_over_serial::{Align, CommandSet, Simulator, StatusBoard, StatusField};
+///
+/// let simulator = Simulator::new(CommandSet::Birch, 20, 2);
+/// let mut vfd = simulator.vfd();
+/// let mut board = StatusBoard::new()
+///     .field("TEMP", StatusField::new(0, 1).width(10).label("T:"))
+///     .field("HUM", StatusField::new(10, 1).width(10).align(Align::Right));
+/// board.set_value("TEMP", "23.4C").unwrap();
+/// board.set_value("HUM", "41%").unwrap();
+/// board.tick(&mut vfd).unwrap();
+/// assert_eq!(simulator.row(1), "T:23.4C          41%");
+///
+/// board.set_value("TEMP", "23.5C").unwrap();
+/// assert!(board.tick(&mut vfd).unwrap());
+/// assert_eq!(simulator.row(1), "T:23.5C          41%");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct StatusBoard {
+    fields: Vec<Entry>,
+}
+
+impl StatusBoard {
+    pub fn new() -> Self {
+        StatusBoard::default()
+    }
+
+    /// Show the value called `name` in `field`, replacing any field of that name.
+    pub fn field(mut self, name: &str, field: StatusField) -> Self {
+        let entry = Entry {
+            name: name.to_string(),
+            field,
+            value: String::new(),
+            dirty: true,
+        };
+        match self.fields.iter_mut().find(|entry| entry.name == name) {
+            Some(existing) => *existing = entry,
+            None => self.fields.push(entry),
+        }
+        self
+    }
+
+    /// Change the value called `name`, to be drawn on the next tick if it differs.
+    pub fn set_value(&mut self, name: &str, value: &str) -> Result<(), VfdError> {
+        let entry = self
+            .fields
+            .iter_mut()
+            .find(|entry| entry.name == name)
+            .ok_or_else(|| VfdError::InvalidInput(format!("No status field named '{}'", name)))?;
+        if entry.value != value {
+            entry.value = value.to_string();
+            entry.dirty = true;
+        }
+        Ok(())
+    }
+
+    pub fn value(&self, name: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|entry| entry.name == name)
+            .map(|entry| entry.value.as_str())
+    }
+
+    /// Names of the fields, in the order they were added.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.fields.iter().map(|entry| entry.name.as_str())
+    }
+
+    /// Draw every field again on the next tick, e.g. after the display was cleared.
+    pub fn invalidate(&mut self) {
+        for entry in &mut self.fields {
+            entry.dirty = true;
+        }
+    }
+
+    fn draw(entry: &mut Entry, display: &mut dyn VfdDisplay) -> Result<(), VfdError> {
+        let field = &entry.field;
+        let (width, _) = display.size();
+        let room = field.width.unwrap_or_else(|| width.saturating_sub(field.x));
+        let text = display
+            .text_mapping()
+            .apply(&format!("{}{}", field.label, entry.value));
+        display.update_span(
+            field.x,
+            field.y,
+            &layout::align(&text, room as usize, field.align),
+        )?;
+        entry.dirty = false;
+        Ok(())
+    }
+}
+
+impl Widget for StatusBoard {
+    fn tick(&mut self, display: &mut dyn VfdDisplay) -> Result<bool, VfdError> {
+        let mut drawn = false;
+        for entry in self.fields.iter_mut().filter(|entry| entry.dirty) {
+            StatusBoard::draw(entry, display)?;
+            drawn = true;
+        }
+        Ok(drawn)
+    }
+
+    fn render(&mut self, display: &mut dyn VfdDisplay) -> Result<(), VfdError> {
+        for entry in &mut self.fields {
+            StatusBoard::draw(entry, display)?;
+        }
+        Ok(())
+    }
+
+    fn interval(&self) -> Duration {
+        Duration::from_millis(100)
+    }
+}
@@ -0,0 +1,186 @@
+use crate::codepage::CodePage;
+use crate::glyph::custom_slot;
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+// Symbols and what to show instead, nearest first. Box drawing falls back to
+// the light lines most code pages have, then to ASCII.
+const BUILTIN: &[(char, &[&str])] = &[
+    ('─', &["-"]),
+    ('│', &["|"]),
+    ('┌', &["+"]),
+    ('┐', &["+"]),
+    ('└', &["+"]),
+    ('┘', &["+"]),
+    ('├', &["+"]),
+    ('┤', &["+"]),
+    ('┬', &["+"]),
+    ('┴', &["+"]),
+    ('┼', &["+"]),
+    ('━', &["─", "-"]),
+    ('┃', &["│", "|"]),
+    ('┏', &["┌", "+"]),
+    ('┓', &["┐", "+"]),
+    ('┗', &["└", "+"]),
+    ('┛', &["┘", "+"]),
+    ('┣', &["├", "+"]),
+    ('┫', &["┤", "+"]),
+    ('┳', &["┬", "+"]),
+    ('┻', &["┴", "+"]),
+    ('╋', &["┼", "+"]),
+    ('╭', &["┌", "+"]),
+    ('╮', &["┐", "+"]),
+    ('╰', &["└", "+"]),
+    ('╯', &["┘", "+"]),
+    ('┄', &["─", "-"]),
+    ('┈', &["─", "-"]),
+    ('╌', &["─", "-"]),
+    ('┆', &["│", "|"]),
+    ('┊', &["│", "|"]),
+    ('╎', &["│", "|"]),
+    ('═', &["─", "="]),
+    ('║', &["│", "|"]),
+    ('╔', &["┌", "+"]),
+    ('╗', &["┐", "+"]),
+    ('╚', &["└", "+"]),
+    ('╝', &["┘", "+"]),
+    ('╠', &["├", "+"]),
+    ('╣', &["┤", "+"]),
+    ('╦', &["┬", "+"]),
+    ('╩', &["┴", "+"]),
+    ('╬', &["┼", "+"]),
+    ('█', &["#"]),
+    ('▓', &["█", "#"]),
+    ('▒', &["▓", "#"]),
+    ('░', &["▒", ":"]),
+    ('▀', &["█", "#"]),
+    ('▄', &["█", "#"]),
+    ('▌', &["█", "#"]),
+    ('▐', &["█", "#"]),
+    ('■', &["█", "#"]),
+    ('↑', &["^"]),
+    ('↓', &["v"]),
+    ('⇑', &["^"]),
+    ('⇓', &["v"]),
+    ('▲', &["^"]),
+    ('▼', &["v"]),
+    ('▶', &[">"]),
+    ('►', &[">"]),
+    ('◀', &["<"]),
+    ('◄', &["<"]),
+    ('μ', &["µ", "u"]),
+    ('µ', &["u"]),
+    ('˚', &["°", "º", "o"]),
+    ('°', &["º", "o"]),
+    ('℃', &["°C", "ºC", "C"]),
+    ('℉', &["°F", "ºF", "F"]),
+    ('Ω', &["Ohm"]),
+    ('±', &["+-"]),
+    ('≈', &["~"]),
+    ('²', &["2"]),
+    ('³', &["3"]),
+    ('½', &["1/2"]),
+    ('¼', &["1/4"]),
+    ('¾', &["3/4"]),
+    ('¢', &["c"]),
+    ('₩', &["W"]),
+    ('₹', &["Rs"]),
+    ('₽', &["RUB"]),
+    ('₺', &["TL"]),
+    ('₴', &["UAH"]),
+    ('₫', &["d"]),
+    ('₿', &["BTC"]),
+];
+
+/// Replacements for symbols the code page lacks, tried before the ASCII
+/// [`transliterate`](crate::text::transliterate) fallback.
+///
+/// Each symbol has a list of candidates, nearest first; the first one the
+/// code page can show in full is used. The default map covers arrows, box
+/// drawing, `±`, `°`, `µ` and currency signs, e.g. a rounded corner `╭`
+/// becomes `┌` on code pages with box drawing and `+` on the rest.
+///
+/// Candidates may use [`custom_char`](crate::glyph::custom_char) to point at
+/// a glyph downloaded to the display, for symbols no code page has.
+///
+/// ```
+/// use vfd_dsp_v9fb_over_serial::{CodePage, SymbolMap, custom_char};
+///
+/// let mut symbols = SymbolMap::default();
+/// assert_eq!(symbols.resolve('╭', CodePage::Pc437), Some("┌"));
+/// assert_eq!(symbols.resolve('╭', CodePage::Wpc1252), Some("+"));
+///
+/// let arrow = custom_char(0).to_string();
+/// symbols.insert('↑', [arrow.as_str(), "^"]);
+/// assert_eq!(symbols.resolve('↑', CodePage::Pc437), Some(arrow.as_str()));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SymbolMap {
+    entries: BTreeMap<char, Vec<String>>,
+}
+
+impl Default for SymbolMap {
+    fn default() -> Self {
+        let mut symbols = SymbolMap::empty();
+        for (symbol, candidates) in BUILTIN {
+            symbols.insert(*symbol, candidates.iter().copied());
+        }
+        symbols
+    }
+}
+
+impl SymbolMap {
+    /// A map without any symbols, leaving everything to transliteration.
+    pub fn empty() -> Self {
+        SymbolMap {
+            entries: BTreeMap::new(),
+        }
+    }
+
+    /// Show `symbol` as the first of `candidates` the code page has,
+    /// replacing what it was mapped to before.
+    pub fn insert<I, S>(&mut self, symbol: char, candidates: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.entries
+            .insert(symbol, candidates.into_iter().map(Into::into).collect());
+    }
+
+    /// Stop mapping `symbol`.
+    pub fn remove(&mut self, symbol: char) {
+        self.entries.remove(&symbol);
+    }
+
+    /// Add the symbols of `other`, which take precedence over the ones in `self`.
+    pub fn extend(&mut self, other: SymbolMap) {
+        self.entries.extend(other.entries);
+    }
+
+    /// What `symbol` may be shown as, nearest first.
+    pub fn candidates(&self, symbol: char) -> &[String] {
+        self.entries.get(&symbol).map_or(&[], Vec::as_slice)
+    }
+
+    /// The first candidate for `symbol` that `code_page` can show in full.
+    pub fn resolve(&self, symbol: char, code_page: CodePage) -> Option<&str> {
+        self.candidates(symbol)
+            .iter()
+            .find(|candidate| {
+                candidate
+                    .chars()
+                    .all(|c| custom_slot(c).is_some() || code_page.encode_char(c).is_some())
+            })
+            .map(String::as_str)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
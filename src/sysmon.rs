@@ -0,0 +1,267 @@
+//! Host statistics dashboard, read from Linux `/proc`.
+
+use crate::display::VfdDisplay;
+use crate::error::VfdError;
+use crate::layout::{self, Align};
+use crate::widget::Widget;
+use std::fs;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+/// A statistic the [`SystemMonitor`] can show.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Metric {
+    /// CPU busy percentage since the previous sample.
+    Cpu,
+    /// Share of memory in use, excluding reclaimable caches.
+    Memory,
+    /// One-minute load average.
+    Load,
+    /// Received and sent bytes per second, summed over every interface except loopback.
+    Network,
+}
+
+impl FromStr for Metric {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "cpu" => Ok(Metric::Cpu),
+            "mem" | "memory" => Ok(Metric::Memory),
+            "load" => Ok(Metric::Load),
+            "net" | "network" => Ok(Metric::Network),
+            other => Err(format!(
+                "Unknown metric '{}'. Expected one of: cpu, mem, load, net",
+                other
+            )),
+        }
+    }
+}
+
+/// Parse a comma separated row layout such as `"cpu,mem"`.
+pub fn parse_row(s: &str) -> Result<Vec<Metric>, String> {
+    s.split(',')
+        .filter(|metric| !metric.trim().is_empty())
+        .map(str::parse)
+        .collect()
+}
+
+// Counters from the previous sample, for the metrics measured as rates
+#[derive(Debug, Clone, Copy)]
+struct Counters {
+    at: Instant,
+    cpu_idle: u64,
+    cpu_total: u64,
+    received: u64,
+    sent: u64,
+}
+
+/// Widget redrawing host CPU, memory, load and network figures at a fixed interval.
+///
+/// Each display row shows the metrics listed for it; two metrics on a row are
+/// pushed to opposite ends.
+#[derive(Debug, Clone)]
+pub struct SystemMonitor {
+    rows: Vec<Vec<Metric>>,
+    refresh: Duration,
+    previous: Option<Counters>,
+}
+
+impl Default for SystemMonitor {
+    fn default() -> Self {
+        SystemMonitor::new(vec![
+            vec![Metric::Cpu, Metric::Memory],
+            vec![Metric::Load, Metric::Network],
+        ])
+    }
+}
+
+impl SystemMonitor {
+    /// A monitor showing `rows[n]` on display row `n`, refreshing every second.
+    pub fn new(rows: Vec<Vec<Metric>>) -> Self {
+        SystemMonitor {
+            rows,
+            refresh: Duration::from_secs(1),
+            previous: None,
+        }
+    }
+
+    /// Time between samples.
+    pub fn refresh(mut self, refresh: Duration) -> Self {
+        self.refresh = refresh;
+        self
+    }
+
+    /// Take a sample and return the figures on every row, one string per metric.
+    pub fn sample(&mut self) -> Result<Vec<Vec<String>>, VfdError> {
+        let (cpu_idle, cpu_total) = read_cpu()?;
+        let (received, sent) = read_network()?;
+        let now = Counters {
+            at: Instant::now(),
+            cpu_idle,
+            cpu_total,
+            received,
+            sent,
+        };
+        let previous = self.previous.replace(now);
+
+        let mut rows = Vec::with_capacity(self.rows.len());
+        for metrics in &self.rows {
+            let mut parts = Vec::with_capacity(metrics.len());
+            for metric in metrics {
+                parts.push(match metric {
+                    Metric::Cpu => format_cpu(previous, now),
+                    Metric::Memory => format!("MEM {}%", read_memory()?),
+                    Metric::Load => format!("LOAD {}", read_load()?),
+                    Metric::Network => format_network(previous, now),
+                });
+            }
+            rows.push(parts);
+        }
+        Ok(rows)
+    }
+}
+
+impl Widget for SystemMonitor {
+    fn tick(&mut self, display: &mut dyn VfdDisplay) -> Result<bool, VfdError> {
+        if self
+            .previous
+            .is_some_and(|previous| previous.at.elapsed() < self.refresh)
+        {
+            return Ok(false);
+        }
+        self.render(display)?;
+        Ok(true)
+    }
+
+    fn render(&mut self, display: &mut dyn VfdDisplay) -> Result<(), VfdError> {
+        let (width, height) = display.size();
+        let rows = self.sample()?;
+        for (y, parts) in rows.iter().enumerate().take(height as usize) {
+            let line = match parts.as_slice() {
+                [left, right] => layout::justify(left, right, width as usize),
+                parts => parts.join(" "),
+            };
+            display.write_line(y as u8, &line, Align::Left)?;
+        }
+        Ok(())
+    }
+
+    fn interval(&self) -> Duration {
+        self.refresh
+    }
+}
+
+fn format_cpu(previous: Option<Counters>, now: Counters) -> String {
+    let (idle, total) = match previous {
+        Some(previous) => (
+            now.cpu_idle.saturating_sub(previous.cpu_idle),
+            now.cpu_total.saturating_sub(previous.cpu_total),
+        ),
+        None => (now.cpu_idle, now.cpu_total),
+    };
+    let busy = (total - idle.min(total)) * 100 / total.max(1);
+    format!("CPU {}%", busy)
+}
+
+fn format_network(previous: Option<Counters>, now: Counters) -> String {
+    let Some(previous) = previous else {
+        return "NET --".to_string();
+    };
+    let seconds = now.at.duration_since(previous.at).as_secs_f64().max(0.001);
+    let rate = |now: u64, before: u64| (now.saturating_sub(before) as f64 / seconds) as u64;
+    format!(
+        "NET {}/{}",
+        format_bytes(rate(now.received, previous.received)),
+        format_bytes(rate(now.sent, previous.sent))
+    )
+}
+
+// Three significant characters at most, e.g. `512`, `12K`, `1.2M`
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["K", "M", "G", "T"];
+    if bytes < 1000 {
+        return bytes.to_string();
+    }
+    let mut value = bytes as f64;
+    for unit in UNITS {
+        value /= 1000.0;
+        if value < 10.0 {
+            return format!("{:.1}{}", value, unit);
+        }
+        if value < 1000.0 {
+            return format!("{:.0}{}", value, unit);
+        }
+    }
+    format!("{:.0}P", value / 1000.0)
+}
+
+fn invalid(file: &str) -> VfdError {
+    VfdError::InvalidInput(format!("Unexpected contents in {}", file))
+}
+
+// Idle and total jiffies over all CPUs
+fn read_cpu() -> Result<(u64, u64), VfdError> {
+    let stat = fs::read_to_string("/proc/stat")?;
+    let fields: Vec<u64> = stat
+        .lines()
+        .next()
+        .and_then(|line| line.strip_prefix("cpu "))
+        .ok_or_else(|| invalid("/proc/stat"))?
+        .split_whitespace()
+        .take(8)
+        .filter_map(|field| field.parse().ok())
+        .collect();
+    if fields.len() < 5 {
+        return Err(invalid("/proc/stat"));
+    }
+    // idle + iowait
+    Ok((fields[3] + fields[4], fields.iter().sum()))
+}
+
+fn read_memory() -> Result<u64, VfdError> {
+    let meminfo = fs::read_to_string("/proc/meminfo")?;
+    let field = |name: &str| {
+        meminfo
+            .lines()
+            .find_map(|line| line.strip_prefix(name))
+            .and_then(|rest| rest.split_whitespace().next())
+            .and_then(|kb| kb.parse::<u64>().ok())
+    };
+    let total = field("MemTotal:").ok_or_else(|| invalid("/proc/meminfo"))?;
+    let available = field("MemAvailable:").ok_or_else(|| invalid("/proc/meminfo"))?;
+    Ok((total - available.min(total)) * 100 / total.max(1))
+}
+
+fn read_load() -> Result<String, VfdError> {
+    let loadavg = fs::read_to_string("/proc/loadavg")?;
+    loadavg
+        .split_whitespace()
+        .next()
+        .map(str::to_string)
+        .ok_or_else(|| invalid("/proc/loadavg"))
+}
+
+// Received and sent bytes over every interface except loopback
+fn read_network() -> Result<(u64, u64), VfdError> {
+    let dev = fs::read_to_string("/proc/net/dev")?;
+    let mut totals = (0, 0);
+    // Two header lines, then `iface: rx_bytes ... (8 rx fields) tx_bytes ...`
+    for line in dev.lines().skip(2) {
+        let Some((name, counters)) = line.split_once(':') else {
+            continue;
+        };
+        if name.trim() == "lo" {
+            continue;
+        }
+        let counters: Vec<u64> = counters
+            .split_whitespace()
+            .filter_map(|field| field.parse().ok())
+            .collect();
+        if let (Some(received), Some(sent)) = (counters.first(), counters.get(8)) {
+            totals.0 += received;
+            totals.1 += sent;
+        }
+    }
+    Ok(totals)
+}
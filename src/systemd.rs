@@ -0,0 +1,70 @@
+//! Running the daemon as a systemd service (`systemd` feature, Linux only).
+//!
+//! With socket activation systemd creates the daemon's Unix socket and hands
+//! it over ([`listener`]), so clients can connect while the display is still
+//! being opened. The daemon tells systemd when it is ready ([`notify`]) for
+//! `Type=notify` units. Outside systemd both do nothing.
+//!
+//! `systemd/vfdd.socket` and `systemd/vfdd.service` in the repository are
+//! units to start from; the daemon needs to be built with this feature.
+
+use std::env;
+use std::io;
+use std::os::fd::{FromRawFd, RawFd};
+use std::os::linux::net::SocketAddrExt;
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::net::{SocketAddr, UnixDatagram, UnixListener};
+use std::process;
+
+// First descriptor systemd passes sockets in, after stdin, stdout and stderr
+const LISTEN_FDS_START: RawFd = 3;
+
+/// The Unix socket systemd passed in, or `None` when the daemon was not
+/// socket-activated.
+///
+/// Fails when systemd passed more than one socket or something other than a
+/// Unix socket.
+pub fn listener() -> Result<Option<UnixListener>, io::Error> {
+    // Inherited variables meant for another process are ignored
+    let for_us = env::var("LISTEN_PID").is_ok_and(|pid| pid == process::id().to_string());
+    if !for_us {
+        return Ok(None);
+    }
+    match env::var("LISTEN_FDS").as_deref() {
+        Ok("1") => {}
+        Ok("0") | Err(_) => return Ok(None),
+        Ok(count) => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Expected one socket from systemd, got {}", count),
+            ));
+        }
+    }
+
+    // SAFETY: systemd leaves descriptor 3 open for us, and nothing else claims it
+    let listener = unsafe { UnixListener::from_raw_fd(LISTEN_FDS_START) };
+    // Keep it from leaking into processes started later
+    // SAFETY: fcntl on a descriptor we own
+    if unsafe { libc::fcntl(LISTEN_FDS_START, libc::F_SETFD, libc::FD_CLOEXEC) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    // Fails for sockets of any other family
+    listener.local_addr()?;
+    Ok(Some(listener))
+}
+
+/// Send `state` to systemd, e.g. `"READY=1"` once the daemon takes commands
+/// or `"STOPPING=1"` when it begins shutting down.
+///
+/// Returns whether systemd is listening for notifications.
+pub fn notify(state: &str) -> Result<bool, io::Error> {
+    let Some(path) = env::var_os("NOTIFY_SOCKET") else {
+        return Ok(false);
+    };
+    let address = match path.as_bytes().strip_prefix(b"@") {
+        Some(name) => SocketAddr::from_abstract_name(name)?,
+        None => SocketAddr::from_pathname(&path)?,
+    };
+    UnixDatagram::unbound()?.send_to_addr(state.as_bytes(), &address)?;
+    Ok(true)
+}
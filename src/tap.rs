@@ -0,0 +1,130 @@
+use crate::transport::Duplex;
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+// Bytes shown on each line of a hex dump
+const BYTES_PER_LINE: usize = 16;
+// Characters in the hex column of a full line
+pub(crate) const HEX_WIDTH: usize = BYTES_PER_LINE * 3 - 1;
+
+/// Callback receiving every chunk of bytes written to the display and when it was sent.
+pub type TapCallback = Arc<dyn Fn(SystemTime, &[u8]) + Send + Sync>;
+
+/// Where a [`Tap`] mirrors the bytes it sees.
+#[derive(Clone)]
+pub enum TapSink {
+    /// Append a timestamped hex dump to a file, see [`hex_dump`].
+    File(PathBuf),
+    Callback(TapCallback),
+}
+
+impl fmt::Debug for TapSink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TapSink::File(path) => f.debug_tuple("File").field(path).finish(),
+            TapSink::Callback(_) => f.write_str("Callback"),
+        }
+    }
+}
+
+enum Output {
+    File(File),
+    Callback(TapCallback),
+}
+
+/// A transport that mirrors every byte written through it, for comparing
+/// what the driver sends against a protocol reference.
+pub struct Tap<T> {
+    inner: T,
+    output: Output,
+}
+
+impl<T: Write> Tap<T> {
+    /// Wrap `inner`, opening the sink's file for appending if it has one.
+    pub fn new(inner: T, sink: &TapSink) -> io::Result<Self> {
+        let output = match sink {
+            TapSink::File(path) => Output::File(open_log(path)?),
+            TapSink::Callback(callback) => Output::Callback(Arc::clone(callback)),
+        };
+        Ok(Tap { inner, output })
+    }
+
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+fn open_log(path: &Path) -> io::Result<File> {
+    OpenOptions::new().create(true).append(true).open(path)
+}
+
+impl<T: Write> Write for Tap<T> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        let now = SystemTime::now();
+        match &mut self.output {
+            Output::File(file) => file.write_all(hex_dump(now, &buf[..written]).as_bytes())?,
+            Output::Callback(callback) => callback(now, &buf[..written]),
+        }
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if let Output::File(file) = &mut self.output {
+            file.flush()?;
+        }
+        self.inner.flush()
+    }
+}
+
+// Only what is written is mirrored; answers from the display pass straight through
+impl<T: Duplex> Duplex for Tap<T> {
+    fn read(&mut self, buf: &mut [u8], timeout: Duration) -> io::Result<usize> {
+        self.inner.read(buf, timeout)
+    }
+
+    fn discard_input(&mut self) -> io::Result<()> {
+        self.inner.discard_input()
+    }
+}
+
+/// Format `bytes` sent at `at` like `hexdump -C`, one line per 16 bytes,
+/// each prefixed with seconds since the Unix epoch:
+///
+/// ```text
+/// 1760486400.125  1b 40 0c 48 65 6c 6c 6f                          |.@.Hello|
+/// ```
+pub fn hex_dump(at: SystemTime, bytes: &[u8]) -> String {
+    let since_epoch = at.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let timestamp = format!(
+        "{}.{:03}",
+        since_epoch.as_secs(),
+        since_epoch.subsec_millis()
+    );
+    let mut out = String::new();
+    for chunk in bytes.chunks(BYTES_PER_LINE) {
+        let hex: Vec<String> = chunk.iter().map(|byte| format!("{:02x}", byte)).collect();
+        let ascii: String = chunk
+            .iter()
+            .map(|&byte| {
+                if byte.is_ascii_graphic() || byte == b' ' {
+                    byte as char
+                } else {
+                    '.'
+                }
+            })
+            .collect();
+        out.push_str(&format!(
+            "{}  {:<width$}  |{}|\n",
+            timestamp,
+            hex.join(" "),
+            ascii,
+            width = HEX_WIDTH
+        ));
+    }
+    out
+}
@@ -0,0 +1,351 @@
+use crate::serial_profile::SerialProfile;
+use crate::transport::Duplex;
+use serialport::{FlowControl, Parity, StopBits};
+use std::fmt;
+use std::io::{self, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+// Telnet commands (RFC 854)
+const IAC: u8 = 255;
+const DONT: u8 = 254;
+const DO: u8 = 253;
+const WONT: u8 = 252;
+const WILL: u8 = 251;
+const SB: u8 = 250;
+const SE: u8 = 240;
+
+// Telnet options we agree to
+const BINARY: u8 = 0;
+const SUPPRESS_GO_AHEAD: u8 = 3;
+const COM_PORT_OPTION: u8 = 44;
+
+// COM-PORT-OPTION client commands (RFC 2217)
+const SET_BAUDRATE: u8 = 1;
+const SET_DATASIZE: u8 = 2;
+const SET_PARITY: u8 = 3;
+const SET_STOPSIZE: u8 = 4;
+const SET_CONTROL: u8 = 5;
+
+/// How a serial device server exposes its port.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BridgeMode {
+    /// Bytes pass through unchanged; the server decides the line settings.
+    #[default]
+    Raw,
+    /// Telnet with RFC 2217 COM port control.
+    Rfc2217,
+}
+
+impl BridgeMode {
+    /// The mode and `host:port` address of a `tcp://` or `rfc2217://` device
+    /// path, or `None` for a local serial device.
+    ///
+    /// ```
+    /// use vfd_dsp_v9fb_over_serial::BridgeMode;
+    ///
+    /// assert_eq!(
+    ///     BridgeMode::from_device("rfc2217://till-3.local:4001"),
+    ///     Some((BridgeMode::Rfc2217, "till-3.local:4001"))
+    /// );
+    /// assert_eq!(BridgeMode::from_device("/dev/ttyUSB0"), None);
+    /// ```
+    pub fn from_device(device: &str) -> Option<(BridgeMode, &str)> {
+        let (scheme, address) = device.trim().split_once("://")?;
+        Some((scheme.parse().ok()?, address))
+    }
+}
+
+impl FromStr for BridgeMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "tcp" | "raw" => Ok(BridgeMode::Raw),
+            "rfc2217" | "telnet" => Ok(BridgeMode::Rfc2217),
+            other => Err(format!(
+                "Unknown bridge mode '{}'. Expected tcp or rfc2217.",
+                other
+            )),
+        }
+    }
+}
+
+impl fmt::Display for BridgeMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BridgeMode::Raw => write!(f, "tcp"),
+            BridgeMode::Rfc2217 => write!(f, "rfc2217"),
+        }
+    }
+}
+
+// Where the Telnet decoder is between reads
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Telnet {
+    Data,
+    Command,
+    Negotiation(u8),
+    Subnegotiation,
+    SubnegotiationCommand,
+}
+
+/// A [`Duplex`] link to a serial port shared over the network by a serial
+/// device server such as ser2net.
+///
+/// Device servers either pass bytes through unchanged on a TCP port
+/// ([`BridgeMode::Raw`], ser2net's `raw` mode, with the line settings fixed
+/// on the server) or speak Telnet with the COM port control extension of
+/// RFC 2217 ([`BridgeMode::Rfc2217`], ser2net's `telnet` mode with
+/// `remctl`), which lets the client choose the baud rate, frame format, flow
+/// control and modem lines. [`VfdBuilder`](crate::VfdBuilder) opens a bridge
+/// when the device is given as `tcp://host:port` or `rfc2217://host:port`.
+///
+/// ```no_run
+/// use std::time::Duration;
+/// use vfd_dsp_v9fb_over_serial::{BridgeMode, CommandSet, SerialProfile, TcpSerial, Vfd};
+///
+/// let profile = SerialProfile::for_command_set(CommandSet::Birch);
+/// let bridge = TcpSerial::connect("10.0.0.7:4001", BridgeMode::Rfc2217, &profile, Duration::from_secs(2))?;
+/// let mut vfd = Vfd::from_duplex(bridge, CommandSet::Birch, 20, 2);
+/// vfd.initialize()?;
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug)]
+pub struct TcpSerial {
+    stream: TcpStream,
+    mode: BridgeMode,
+    telnet: Telnet,
+}
+
+impl TcpSerial {
+    /// Connect to `address` (`host:port`), waiting at most `timeout`.
+    ///
+    /// In [`BridgeMode::Rfc2217`] the server is asked to set its port to
+    /// `profile`; in raw mode `profile` is not used.
+    pub fn connect(
+        address: &str,
+        mode: BridgeMode,
+        profile: &SerialProfile,
+        timeout: Duration,
+    ) -> io::Result<TcpSerial> {
+        let mut last_error = None;
+        for addr in address.to_socket_addrs()? {
+            match TcpStream::connect_timeout(&addr, timeout) {
+                Ok(stream) => {
+                    stream.set_nodelay(true)?;
+                    let mut bridge = TcpSerial {
+                        stream,
+                        mode,
+                        telnet: Telnet::Data,
+                    };
+                    if mode == BridgeMode::Rfc2217 {
+                        bridge.negotiate(profile)?;
+                    }
+                    return Ok(bridge);
+                }
+                Err(e) => last_error = Some(e),
+            }
+        }
+        Err(last_error.unwrap_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("No address found for {}", address),
+            )
+        }))
+    }
+
+    pub fn mode(&self) -> BridgeMode {
+        self.mode
+    }
+
+    // Offer the options RFC 2217 needs and send the line settings
+    fn negotiate(&mut self, profile: &SerialProfile) -> io::Result<()> {
+        let mut out = vec![
+            IAC,
+            WILL,
+            BINARY,
+            IAC,
+            DO,
+            BINARY,
+            IAC,
+            WILL,
+            SUPPRESS_GO_AHEAD,
+            IAC,
+            DO,
+            SUPPRESS_GO_AHEAD,
+            IAC,
+            WILL,
+            COM_PORT_OPTION,
+        ];
+        let data_size = u8::from(profile.data_bits);
+        let parity = match profile.parity {
+            Parity::None => 1,
+            Parity::Odd => 2,
+            Parity::Even => 3,
+        };
+        let stop_size = match profile.stop_bits {
+            StopBits::One => 1,
+            StopBits::Two => 2,
+        };
+        let flow = match profile.flow_control {
+            FlowControl::None => 1,
+            FlowControl::Software => 2,
+            FlowControl::Hardware => 3,
+        };
+        subnegotiation(&mut out, SET_BAUDRATE, &profile.baud_rate.to_be_bytes());
+        subnegotiation(&mut out, SET_DATASIZE, &[data_size]);
+        subnegotiation(&mut out, SET_PARITY, &[parity]);
+        subnegotiation(&mut out, SET_STOPSIZE, &[stop_size]);
+        subnegotiation(&mut out, SET_CONTROL, &[flow]);
+        if let Some(level) = profile.dtr {
+            subnegotiation(&mut out, SET_CONTROL, &[if level { 8 } else { 9 }]);
+        }
+        if let Some(level) = profile.rts
+            && profile.flow_control != FlowControl::Hardware
+        {
+            subnegotiation(&mut out, SET_CONTROL, &[if level { 11 } else { 12 }]);
+        }
+        self.stream.write_all(&out)
+    }
+
+    // Strip Telnet commands out of `raw` in place, returning how many data
+    // bytes are left at its start and collecting refusals to send back
+    fn decode(&mut self, raw: &mut [u8], replies: &mut Vec<u8>) -> usize {
+        let mut len = 0;
+        for i in 0..raw.len() {
+            let byte = raw[i];
+            self.telnet = match (self.telnet, byte) {
+                (Telnet::Data, IAC) => Telnet::Command,
+                (Telnet::Data, _) => {
+                    raw[len] = byte;
+                    len += 1;
+                    Telnet::Data
+                }
+                // An escaped 0xFF data byte
+                (Telnet::Command, IAC) => {
+                    raw[len] = IAC;
+                    len += 1;
+                    Telnet::Data
+                }
+                (Telnet::Command, WILL | WONT | DO | DONT) => Telnet::Negotiation(byte),
+                (Telnet::Command, SB) => Telnet::Subnegotiation,
+                (Telnet::Command, _) => Telnet::Data,
+                (Telnet::Negotiation(verb), option) => {
+                    let agreed = matches!(option, BINARY | SUPPRESS_GO_AHEAD | COM_PORT_OPTION);
+                    match verb {
+                        DO if !agreed => replies.extend_from_slice(&[IAC, WONT, option]),
+                        WILL if !agreed => replies.extend_from_slice(&[IAC, DONT, option]),
+                        _ => {}
+                    }
+                    Telnet::Data
+                }
+                // Notifications such as modem line changes are not used
+                (Telnet::Subnegotiation, IAC) => Telnet::SubnegotiationCommand,
+                (Telnet::Subnegotiation, _) => Telnet::Subnegotiation,
+                (Telnet::SubnegotiationCommand, SE) => Telnet::Data,
+                (Telnet::SubnegotiationCommand, _) => Telnet::Subnegotiation,
+            };
+        }
+        len
+    }
+
+    // Read and decode whatever is waiting, returning the number of data bytes
+    fn read_decoded(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = match Read::read(&mut self.stream, buf)? {
+            0 => {
+                return Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "Device server closed the connection",
+                ));
+            }
+            read => read,
+        };
+        if self.mode == BridgeMode::Raw {
+            return Ok(read);
+        }
+        let mut replies = Vec::new();
+        let len = self.decode(&mut buf[..read], &mut replies);
+        if !replies.is_empty() {
+            self.stream.write_all(&replies)?;
+        }
+        Ok(len)
+    }
+}
+
+// Append a COM-PORT-OPTION command, doubling any 0xFF in its value
+fn subnegotiation(out: &mut Vec<u8>, command: u8, value: &[u8]) {
+    out.extend_from_slice(&[IAC, SB, COM_PORT_OPTION, command]);
+    for &byte in value {
+        out.push(byte);
+        if byte == IAC {
+            out.push(IAC);
+        }
+    }
+    out.extend_from_slice(&[IAC, SE]);
+}
+
+impl Write for TcpSerial {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self.mode {
+            BridgeMode::Raw => self.stream.write(buf),
+            BridgeMode::Rfc2217 => {
+                let mut escaped = Vec::with_capacity(buf.len());
+                for &byte in buf {
+                    escaped.push(byte);
+                    if byte == IAC {
+                        escaped.push(IAC);
+                    }
+                }
+                self.stream.write_all(&escaped)?;
+                Ok(buf.len())
+            }
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.stream.flush()
+    }
+}
+
+impl Duplex for TcpSerial {
+    fn read(&mut self, buf: &mut [u8], timeout: Duration) -> io::Result<usize> {
+        let deadline = Instant::now() + timeout;
+        // Telnet commands can take up a whole read, so keep going until data or the deadline
+        loop {
+            let Some(remaining) = deadline.checked_duration_since(Instant::now()) else {
+                return Ok(0);
+            };
+            self.stream
+                .set_read_timeout(Some(remaining.max(Duration::from_millis(1))))?;
+            match self.read_decoded(buf) {
+                Ok(0) => continue,
+                // Unix reports an expired read timeout as WouldBlock, Windows as TimedOut
+                Err(e)
+                    if matches!(
+                        e.kind(),
+                        io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+                    ) =>
+                {
+                    return Ok(0);
+                }
+                result => return result,
+            }
+        }
+    }
+
+    fn discard_input(&mut self) -> io::Result<()> {
+        self.stream.set_nonblocking(true)?;
+        let mut buf = [0u8; 64];
+        let result = loop {
+            match self.read_decoded(&mut buf) {
+                Ok(_) => continue,
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => break Ok(()),
+                Err(e) => break Err(e),
+            }
+        };
+        self.stream.set_nonblocking(false)?;
+        result
+    }
+}
@@ -0,0 +1,231 @@
+use crate::display::VfdDisplay;
+use crate::error::VfdError;
+use crate::layout::{self, Align};
+use std::collections::BTreeMap;
+
+/// One piece of text in a [`Template`], with `{name}` placeholders filled in at render time.
+///
+/// Write `{{` and `}}` for literal braces.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(deny_unknown_fields)
+)]
+pub struct TemplateField {
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub x: u8,
+    pub y: u8,
+    /// Columns the field occupies; defaults to the rest of the row.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub width: Option<u8>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub align: Align,
+    pub text: String,
+}
+
+impl TemplateField {
+    /// A left-aligned field at column `x`, row `y` that runs to the end of the row.
+    pub fn new(x: u8, y: u8, text: &str) -> Self {
+        TemplateField {
+            x,
+            y,
+            width: None,
+            align: Align::Left,
+            text: text.to_string(),
+        }
+    }
+
+    pub fn width(mut self, width: u8) -> Self {
+        self.width = Some(width);
+        self
+    }
+
+    pub fn align(mut self, align: Align) -> Self {
+        self.align = align;
+        self
+    }
+}
+
+/// A screen layout made of positioned fields, e.g. an item name on the left
+/// of the top row and its price right-aligned below it.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default, deny_unknown_fields)
+)]
+pub struct Template {
+    /// Blank the display before drawing the fields.
+    pub clear: bool,
+    pub fields: Vec<TemplateField>,
+}
+
+impl Template {
+    pub fn new() -> Self {
+        Template::default()
+    }
+
+    pub fn field(mut self, field: TemplateField) -> Self {
+        self.fields.push(field);
+        self
+    }
+
+    pub fn clear(mut self, clear: bool) -> Self {
+        self.clear = clear;
+        self
+    }
+
+    /// Names of the placeholders in the fields, each once, in the order they first appear.
+    pub fn placeholders(&self) -> Vec<String> {
+        let mut names: Vec<String> = Vec::new();
+        for name in self
+            .fields
+            .iter()
+            .flat_map(|field| placeholders(&field.text))
+        {
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+        names
+    }
+
+    /// Draw every field, looking placeholders up in `values`.
+    ///
+    /// Fails without writing anything if a placeholder has no value.
+    pub fn render<D: VfdDisplay + ?Sized>(
+        &self,
+        display: &mut D,
+        values: &[(&str, &str)],
+    ) -> Result<(), VfdError> {
+        let (width, _) = display.size();
+        let mapping = display.text_mapping();
+        let texts = self
+            .fields
+            .iter()
+            .map(|field| {
+                let space = width.saturating_sub(field.x);
+                let width = field.width.map_or(space, |width| width.min(space));
+                let text = mapping.apply(&fill(&field.text, values)?);
+                Ok(layout::align(&text, width as usize, field.align))
+            })
+            .collect::<Result<Vec<_>, VfdError>>()?;
+
+        if self.clear {
+            display.clear()?;
+        }
+        for (field, text) in self.fields.iter().zip(texts) {
+            display.set_cursor(field.x, field.y)?;
+            display.write_text(&text)?;
+        }
+        Ok(())
+    }
+}
+
+/// Named templates, usually loaded from the `[templates]` table of a config file.
+///
+/// Templates are replaced as a whole: giving a name again, through
+/// [`Templates::insert`], [`Templates::extend`] or an overriding config,
+/// drops every field of the earlier template rather than merging them.
+///
+/// ```toml
+/// [templates.sale]
+/// clear = true
+/// fields = [
+///     { y = 0, text = "{item}" },
+///     { y = 1, text = "{price}", align = "right" },
+/// ]
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
+pub struct Templates {
+    templates: BTreeMap<String, Template>,
+}
+
+impl Templates {
+    pub fn new() -> Self {
+        Templates::default()
+    }
+
+    /// Add or replace the template called `name`.
+    pub fn insert(&mut self, name: &str, template: Template) {
+        self.templates.insert(name.to_string(), template);
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Template> {
+        self.templates.get(name)
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.templates.keys().map(String::as_str)
+    }
+
+    /// Draw the template called `name` with `values` filled in.
+    pub fn render<D: VfdDisplay + ?Sized>(
+        &self,
+        display: &mut D,
+        name: &str,
+        values: &[(&str, &str)],
+    ) -> Result<(), VfdError> {
+        self.get(name)
+            .ok_or_else(|| VfdError::InvalidInput(format!("Unknown template '{}'", name)))?
+            .render(display, values)
+    }
+
+    /// Merge templates, replacing existing ones with the same name.
+    pub fn extend(&mut self, other: Templates) {
+        self.templates.extend(other.templates);
+    }
+}
+
+// Names between single braces in `pattern`
+fn placeholders(pattern: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut chars = pattern.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' | '}' if chars.peek() == Some(&c) => {
+                chars.next();
+            }
+            '{' => names.push(chars.by_ref().take_while(|&c| c != '}').collect()),
+            _ => {}
+        }
+    }
+    names
+}
+
+// Substitute `{name}` placeholders with their values
+fn fill(pattern: &str, values: &[(&str, &str)]) -> Result<String, VfdError> {
+    let mut out = String::with_capacity(pattern.len());
+    let mut chars = pattern.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                out.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                out.push('}');
+            }
+            '{' => {
+                let name: String = chars.by_ref().take_while(|&c| c != '}').collect();
+                let value = values
+                    .iter()
+                    .find(|(key, _)| *key == name)
+                    .map(|(_, value)| *value)
+                    .ok_or_else(|| {
+                        VfdError::InvalidInput(format!("No value for placeholder '{{{}}}'", name))
+                    })?;
+                out.push_str(value);
+            }
+            c => out.push(c),
+        }
+    }
+    Ok(out)
+}
@@ -0,0 +1,159 @@
+//! Display backend that draws into the local terminal (`terminal` feature).
+
+use crate::command_set::BrightnessLevel;
+use crate::display::VfdDisplay;
+use crate::error::VfdError;
+use crate::frame_buffer::FrameBuffer;
+use crate::text;
+use crossterm::style::{Color, Print, ResetColor, SetForegroundColor};
+use crossterm::{cursor, queue};
+use std::io::{self, Stdout, Write};
+
+/// A virtual display drawn as a boxed region of the terminal.
+///
+/// Lay out screens on a development machine, then swap in a [`Vfd`](crate::Vfd)
+/// on the real hardware; both implement [`VfdDisplay`].
+pub struct TerminalVfd {
+    out: Stdout,
+    frame: FrameBuffer,
+    origin: (u16, u16),
+    brightness: BrightnessLevel,
+    cursor_x: u8,
+    cursor_y: u8,
+}
+
+impl TerminalVfd {
+    /// A `width × height` display whose frame has its top-left corner at the top-left of the terminal.
+    pub fn new(width: u8, height: u8) -> Result<Self, VfdError> {
+        TerminalVfd::at(width, height, 0, 0)
+    }
+
+    /// A `width × height` display whose frame has its top-left corner at terminal `column`, `row`.
+    pub fn at(width: u8, height: u8, column: u16, row: u16) -> Result<Self, VfdError> {
+        let mut vfd = TerminalVfd {
+            out: io::stdout(),
+            frame: FrameBuffer::new(width, height),
+            origin: (column, row),
+            brightness: BrightnessLevel::Percent100,
+            cursor_x: 0,
+            cursor_y: 0,
+        };
+        vfd.draw_border()?;
+        vfd.render()?;
+        Ok(vfd)
+    }
+
+    /// The cells currently shown.
+    pub fn frame(&self) -> &FrameBuffer {
+        &self.frame
+    }
+
+    fn draw_border(&mut self) -> Result<(), VfdError> {
+        let (width, height) = self.frame.size();
+        let (column, row) = self.origin;
+        let rule = "─".repeat(width as usize);
+        queue!(
+            self.out,
+            ResetColor,
+            cursor::MoveTo(column, row),
+            Print(format!("┌{}┐", rule))
+        )?;
+        for y in 0..height as u16 {
+            queue!(
+                self.out,
+                cursor::MoveTo(column, row + 1 + y),
+                Print("│"),
+                cursor::MoveTo(column + 1 + width as u16, row + 1 + y),
+                Print("│")
+            )?;
+        }
+        queue!(
+            self.out,
+            cursor::MoveTo(column, row + 1 + height as u16),
+            Print(format!("└{}┘", rule))
+        )?;
+        Ok(())
+    }
+
+    // Redraw every cell in the colour matching the current brightness
+    fn render(&mut self) -> Result<(), VfdError> {
+        let (_, height) = self.frame.size();
+        let (column, row) = self.origin;
+        queue!(self.out, SetForegroundColor(glow(self.brightness)))?;
+        for y in 0..height {
+            queue!(
+                self.out,
+                cursor::MoveTo(column + 1, row + 1 + y as u16),
+                Print(self.frame.row(y))
+            )?;
+        }
+        queue!(
+            self.out,
+            ResetColor,
+            cursor::MoveTo(column, row + 2 + height as u16)
+        )?;
+        self.out.flush()?;
+        Ok(())
+    }
+}
+
+// Blue-green phosphor, dimmed like the real tube
+fn glow(level: BrightnessLevel) -> Color {
+    let scale = |channel: u16| (channel * level.percent() as u16 / 100) as u8;
+    Color::Rgb {
+        r: scale(64),
+        g: scale(255),
+        b: scale(220),
+    }
+}
+
+impl VfdDisplay for TerminalVfd {
+    fn size(&self) -> (u8, u8) {
+        self.frame.size()
+    }
+
+    fn clear(&mut self) -> Result<(), VfdError> {
+        self.frame.clear();
+        self.cursor_x = 0;
+        self.cursor_y = 0;
+        self.render()
+    }
+
+    fn set_cursor(&mut self, x: u8, y: u8) -> Result<(), VfdError> {
+        let (width, height) = self.frame.size();
+        self.cursor_x = x.min(width);
+        self.cursor_y = y.min(height);
+        Ok(())
+    }
+
+    fn get_cursor(&self) -> (u8, u8) {
+        (self.cursor_x, self.cursor_y)
+    }
+
+    fn write_text(&mut self, text: &str) -> Result<(), VfdError> {
+        let (width, height) = self.frame.size();
+        let cells_left = (width as usize * height as usize)
+            .saturating_sub(self.cursor_y as usize * width as usize + self.cursor_x as usize);
+        let length = text::display_width(text);
+        if length > cells_left {
+            return Err(VfdError::TextTooLong {
+                max: cells_left,
+                got: length,
+            });
+        }
+        for c in text.chars().filter(|c| text::char_width(*c) > 0) {
+            if self.cursor_x >= width {
+                self.cursor_x = 0;
+                self.cursor_y += 1;
+            }
+            self.frame.set(self.cursor_x, self.cursor_y, c);
+            self.cursor_x += 1;
+        }
+        self.render()
+    }
+
+    fn set_brightness(&mut self, level: BrightnessLevel) -> Result<(), VfdError> {
+        self.brightness = level;
+        self.render()
+    }
+}
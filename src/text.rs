@@ -0,0 +1,551 @@
+//! Display-cell aware helpers for measuring, splitting, wrapping and transliterating text.
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::str::FromStr;
+
+/// Whether `c` combines with the previous character instead of taking a cell of its own.
+fn is_combining(c: char) -> bool {
+    matches!(
+        c as u32,
+        0x0300..=0x036F
+            | 0x0591..=0x05BD
+            | 0x05BF
+            | 0x05C1..=0x05C2
+            | 0x05C4..=0x05C5
+            | 0x05C7
+            | 0x064B..=0x065F
+            | 0x0670
+            | 0x1AB0..=0x1AFF
+            | 0x20D0..=0x20FF
+            | 0xFE20..=0xFE2F
+    )
+}
+
+/// Number of display cells `c` occupies (0 or 1).
+pub fn char_width(c: char) -> usize {
+    if is_combining(c) { 0 } else { 1 }
+}
+
+/// Number of display cells `text` occupies.
+pub fn display_width(text: &str) -> usize {
+    text.chars().map(char_width).sum()
+}
+
+/// Split `text` after at most `cells` display cells, never inside a codepoint.
+///
+/// Combining marks stay attached to the character before them.
+pub fn split_at_width(text: &str, cells: usize) -> (&str, &str) {
+    let mut used = 0;
+    for (index, c) in text.char_indices() {
+        let width = char_width(c);
+        if used + width > cells {
+            return text.split_at(index);
+        }
+        used += width;
+    }
+    (text, "")
+}
+
+/// Take at most `cells` display cells from the start of `text`.
+pub fn truncate_to_width(text: &str, cells: usize) -> &str {
+    split_at_width(text, cells).0
+}
+
+/// How text that does not fit on the rest of a row continues on the next.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WrapPolicy {
+    /// Break at the last cell of the row, even inside a word.
+    #[default]
+    Character,
+    /// Break at whitespace where possible.
+    Word {
+        /// Break words longer than a row with a `-` in their last cell instead of cutting them.
+        hyphenate: bool,
+        /// Squeeze runs of whitespace into a single space before wrapping.
+        collapse_spaces: bool,
+    },
+}
+
+impl WrapPolicy {
+    /// Word wrapping without hyphenation that keeps spacing as written.
+    pub const WORD: WrapPolicy = WrapPolicy::Word {
+        hyphenate: false,
+        collapse_spaces: false,
+    };
+}
+
+impl FromStr for WrapPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let policy = |hyphenate, collapse_spaces| WrapPolicy::Word {
+            hyphenate,
+            collapse_spaces,
+        };
+        match s.trim().to_ascii_lowercase().as_str() {
+            "char" | "character" => Ok(WrapPolicy::Character),
+            "word" => Ok(policy(false, false)),
+            "hyphenate" => Ok(policy(true, false)),
+            "collapse" => Ok(policy(false, true)),
+            other => Err(format!(
+                "Unknown wrap policy '{}'. Expected one of char, word, hyphenate, collapse.",
+                other
+            )),
+        }
+    }
+}
+
+/// `text` with every line trimmed, runs of spaces within a line squeezed into
+/// one and blank lines dropped. The remaining line breaks are kept.
+///
+/// ```
+/// use vfd_dsp_v9fb_over_serial::text::compact;
+///
+/// assert_eq!(compact("  Total:    4.20\n\n  Thank you  "), "Total: 4.20\nThank you");
+/// ```
+pub fn compact(text: &str) -> String {
+    text.lines()
+        .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Break `text` into rows: the first `first` cells wide, the rest `width` cells wide.
+///
+/// Spaces where a row was broken are dropped. An empty first row means the
+/// text starts on the next one.
+pub fn wrap(text: &str, first: usize, width: usize, policy: WrapPolicy) -> Vec<String> {
+    if width == 0 {
+        return vec![text.to_string()];
+    }
+    let (hyphenate, collapse_spaces) = match policy {
+        WrapPolicy::Character => return wrap_characters(text, first, width),
+        WrapPolicy::Word {
+            hyphenate,
+            collapse_spaces,
+        } => (hyphenate, collapse_spaces),
+    };
+    let collapsed;
+    let text = if collapse_spaces {
+        collapsed = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        collapsed.as_str()
+    } else {
+        text
+    };
+
+    let mut rows = Vec::new();
+    let mut row = String::new();
+    let mut room = first;
+    let mut rest = text;
+    while !rest.is_empty() {
+        // The whitespace before the next word, and the word itself
+        let gap_end = rest
+            .find(|c: char| !c.is_whitespace())
+            .unwrap_or(rest.len());
+        let (gap, after) = rest.split_at(gap_end);
+        let word_end = after.find(char::is_whitespace).unwrap_or(after.len());
+        let (word, after) = after.split_at(word_end);
+        rest = after;
+        if word.is_empty() {
+            break;
+        }
+
+        // Leading spaces are kept on the first row only
+        let gap = if row.is_empty() && !rows.is_empty() {
+            ""
+        } else {
+            gap
+        };
+        let needed = display_width(gap) + display_width(word);
+        if display_width(&row) + needed <= room {
+            row.push_str(gap);
+            row.push_str(word);
+            continue;
+        }
+        // Start long words on the current row, shorter ones on the next
+        let mut word = word;
+        if display_width(word) <= width || display_width(&row) + display_width(gap) >= room {
+            rows.push(row.trim_end().to_string());
+            row = String::new();
+            room = width;
+        } else {
+            row.push_str(gap);
+        }
+        while display_width(&row) + display_width(word) > room {
+            let space = room - display_width(&row);
+            if hyphenate && space >= 2 {
+                let (head, tail) = split_at_width(word, space - 1);
+                row.push_str(head);
+                row.push('-');
+                word = tail;
+            } else {
+                let (head, tail) = split_at_width(word, space);
+                row.push_str(head);
+                word = tail;
+            }
+            rows.push(core::mem::take(&mut row));
+            room = width;
+        }
+        row.push_str(word);
+    }
+    rows.push(row.trim_end().to_string());
+    rows
+}
+
+// Rows cut at exactly the available width
+fn wrap_characters(text: &str, first: usize, width: usize) -> Vec<String> {
+    let (head, mut rest) = split_at_width(text, first);
+    let mut rows = vec![head.to_string()];
+    while !rest.is_empty() {
+        let (head, tail) = split_at_width(rest, width);
+        rows.push(head.to_string());
+        rest = tail;
+    }
+    rows
+}
+
+/// ASCII stand-in for characters commonly missing from display code pages.
+pub fn transliterate(c: char) -> Option<&'static str> {
+    let replacement = match c {
+        'À' | 'Á' | 'Â' | 'Ã' | 'Ä' | 'Å' | 'Ā' | 'Ă' | 'Ą' => "A",
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' | 'ă' | 'ą' => "a",
+        'Ç' | 'Ć' | 'Č' => "C",
+        'ç' | 'ć' | 'č' => "c",
+        'Ď' | 'Đ' | 'Ð' => "D",
+        'ď' | 'đ' | 'ð' => "d",
+        'È' | 'É' | 'Ê' | 'Ë' | 'Ē' | 'Ė' | 'Ę' | 'Ě' => "E",
+        'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ė' | 'ę' | 'ě' => "e",
+        'Ğ' => "G",
+        'ğ' => "g",
+        'Ì' | 'Í' | 'Î' | 'Ï' | 'Ī' | 'İ' => "I",
+        'ì' | 'í' | 'î' | 'ï' | 'ī' | 'ı' => "i",
+        'Ł' => "L",
+        'ł' => "l",
+        'Ñ' | 'Ń' | 'Ň' => "N",
+        'ñ' | 'ń' | 'ň' => "n",
+        'Ò' | 'Ó' | 'Ô' | 'Õ' | 'Ö' | 'Ø' | 'Ō' | 'Ő' => "O",
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'ō' | 'ő' => "o",
+        'Ř' => "R",
+        'ř' => "r",
+        'Ś' | 'Š' | 'Ş' => "S",
+        'ś' | 'š' | 'ş' => "s",
+        'Ť' | 'Ţ' => "T",
+        'ť' | 'ţ' => "t",
+        'Ù' | 'Ú' | 'Û' | 'Ü' | 'Ū' | 'Ů' | 'Ű' => "U",
+        'ù' | 'ú' | 'û' | 'ü' | 'ū' | 'ů' | 'ű' => "u",
+        'Ý' | 'Ÿ' => "Y",
+        'ý' | 'ÿ' => "y",
+        'Ź' | 'Ż' | 'Ž' => "Z",
+        'ź' | 'ż' | 'ž' => "z",
+        'Æ' => "AE",
+        'æ' => "ae",
+        'Œ' => "OE",
+        'œ' => "oe",
+        'ß' => "ss",
+        'Þ' => "Th",
+        'þ' => "th",
+        '‘' | '’' | '‚' | '′' => "'",
+        '“' | '”' | '„' | '″' | '«' | '»' => "\"",
+        '‐' | '‑' | '‒' | '–' | '—' | '−' => "-",
+        '…' => "...",
+        '•' | '·' => "*",
+        '→' => "->",
+        '←' => "<-",
+        '↔' => "<->",
+        '⇒' => "=>",
+        '≤' => "<=",
+        '≥' => ">=",
+        '≠' => "!=",
+        '×' => "x",
+        '÷' => "/",
+        '±' => "+-",
+        '€' => "EUR",
+        '£' => "GBP",
+        '¥' => "JPY",
+        '©' => "(C)",
+        '®' => "(R)",
+        '™' => "TM",
+        '°' => "o",
+        '\u{A0}' | '\u{2007}' | '\u{202F}' => " ",
+        _ => return None,
+    };
+    Some(replacement)
+}
+
+// Half-width forms of the full-width Katakana ァ (U+30A1) to ヶ (U+30F6)
+const HALF_WIDTH_KATAKANA: [&str; 86] = [
+    "ｧ", "ｱ", "ｨ", "ｲ", "ｩ", "ｳ", "ｪ", "ｴ", "ｫ", "ｵ", "ｶ", "ｶﾞ", "ｷ", "ｷﾞ", "ｸ", "ｸﾞ", "ｹ", "ｹﾞ", "ｺ",
+    "ｺﾞ", "ｻ", "ｻﾞ", "ｼ", "ｼﾞ", "ｽ", "ｽﾞ", "ｾ", "ｾﾞ", "ｿ", "ｿﾞ", "ﾀ", "ﾀﾞ", "ﾁ", "ﾁﾞ", "ｯ", "ﾂ", "ﾂﾞ", "ﾃ",
+    "ﾃﾞ", "ﾄ", "ﾄﾞ", "ﾅ", "ﾆ", "ﾇ", "ﾈ", "ﾉ", "ﾊ", "ﾊﾞ", "ﾊﾟ", "ﾋ", "ﾋﾞ", "ﾋﾟ", "ﾌ", "ﾌﾞ", "ﾌﾟ", "ﾍ", "ﾍﾞ",
+    "ﾍﾟ", "ﾎ", "ﾎﾞ", "ﾎﾟ", "ﾏ", "ﾐ", "ﾑ", "ﾒ", "ﾓ", "ｬ", "ﾔ", "ｭ", "ﾕ", "ｮ", "ﾖ", "ﾗ", "ﾘ", "ﾙ", "ﾚ",
+    "ﾛ", "ﾜ", "ﾜ", "ｲ", "ｴ", "ｦ", "ﾝ", "ｳﾞ", "ｶ", "ｹ",
+];
+
+// Printable ASCII, which full-width Ｆｏｒｍｓ (U+FF01 to U+FF5E) stand for
+const ASCII_PRINTABLE: &str = "!\"#$%&'()*+,-./0123456789:;<=>?@ABCDEFGHIJKLMNOPQRSTUVWXYZ[\\]^_`abcdefghijklmnopqrstuvwxyz{|}~";
+
+/// Half-width form of a full-width character, as typed by Japanese input methods.
+///
+/// Hiragana and Katakana become half-width Katakana, with voiced kana such as
+/// ガ followed by a separate ﾞ, and full-width Latin letters, digits and
+/// punctuation become ASCII. The display shows the kana with
+/// [`CodePage::Katakana`](crate::CodePage::Katakana) selected.
+pub fn half_width(c: char) -> Option<&'static str> {
+    let code = c as u32;
+    let half = match code {
+        // Hiragana sit 0x60 below the Katakana they sound like
+        0x3041..=0x3096 => HALF_WIDTH_KATAKANA[(code - 0x3041) as usize],
+        0x30A1..=0x30F6 => HALF_WIDTH_KATAKANA[(code - 0x30A1) as usize],
+        0xFF01..=0xFF5E => {
+            let i = (code - 0xFF01) as usize;
+            &ASCII_PRINTABLE[i..i + 1]
+        }
+        _ => match c {
+            '\u{3000}' => " ",
+            '。' => "｡",
+            '「' => "｢",
+            '」' => "｣",
+            '、' => "､",
+            '・' => "･",
+            'ー' => "ｰ",
+            '゛' | '\u{3099}' => "ﾞ",
+            '゜' | '\u{309A}' => "ﾟ",
+            _ => return None,
+        },
+    };
+    Some(half)
+}
+
+/// `text` with every full-width character replaced by its [`half_width`] form.
+///
+/// ```
+/// use vfd_dsp_v9fb_over_serial::text::to_half_width;
+///
+/// assert_eq!(to_half_width("ありがとう　ＰＯＳ"), "ｱﾘｶﾞﾄｳ POS");
+/// ```
+pub fn to_half_width(text: &str) -> String {
+    let mut half = String::with_capacity(text.len());
+    for c in text.chars() {
+        match half_width(c) {
+            Some(replacement) => half.push_str(replacement),
+            None => half.push(c),
+        }
+    }
+    half
+}
+
+/// Whether `c` is written right to left, as Hebrew and Arabic letters are.
+pub fn is_rtl(c: char) -> bool {
+    matches!(
+        c as u32,
+        0x05D0..=0x05FF | 0x0600..=0x07BF | 0x08A0..=0x08FF | 0xFB1D..=0xFDFF | 0xFE70..=0xFEFF
+    ) && !is_combining(c)
+        && !matches!(c as u32, 0x0660..=0x0669 | 0x06F0..=0x06F9)
+}
+
+/// Whether the first letter of `text` is written right to left, making it a
+/// right-to-left line.
+pub fn is_rtl_line(text: &str) -> bool {
+    text.chars()
+        .find(|c| is_rtl(*c) || c.is_alphabetic())
+        .is_some_and(is_rtl)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BidiClass {
+    Left,
+    Right,
+    Number,
+    Neutral,
+}
+
+// Shown as part of a number next to it, like a currency sign or percent
+fn is_number_affix(c: char) -> bool {
+    matches!(
+        c,
+        '$' | '%' | '#' | '+' | '-' | '°' | '¢' | '£' | '¥' | '\u{20A0}'..='\u{20CF}'
+    )
+}
+
+// Keeps numbers such as 4.20 or 12:30 together
+fn is_number_separator(c: char) -> bool {
+    matches!(c, '.' | ',' | ':' | '/')
+}
+
+fn mirrored(c: char) -> char {
+    match c {
+        '(' => ')',
+        ')' => '(',
+        '[' => ']',
+        ']' => '[',
+        '{' => '}',
+        '}' => '{',
+        '<' => '>',
+        '>' => '<',
+        '«' => '»',
+        '»' => '«',
+        other => other,
+    }
+}
+
+/// Reorder one line from the order it is written in to the order it is shown
+/// in from left to right.
+///
+/// A basic form of the Unicode bidirectional algorithm: the first letter sets
+/// the direction of the line, right-to-left runs are reversed with their
+/// brackets mirrored, and numbers and left-to-right words inside them keep
+/// their order. Text without right-to-left letters is returned unchanged.
+///
+/// ```
+/// use vfd_dsp_v9fb_over_serial::text::visual_order;
+///
+/// assert_eq!(visual_order("שלום"), "םולש");
+/// assert_eq!(visual_order("סה\"כ 4.20"), "4.20 כ\"הס");
+/// assert_eq!(visual_order("Total: 4.20"), "Total: 4.20");
+/// ```
+pub fn visual_order(line: &str) -> String {
+    if !line.chars().any(is_rtl) {
+        return line.to_string();
+    }
+    // Base characters with the combining marks that follow them
+    let mut clusters: Vec<&str> = Vec::new();
+    let mut start = 0;
+    for (i, c) in line.char_indices().skip(1) {
+        if char_width(c) > 0 {
+            clusters.push(&line[start..i]);
+            start = i;
+        }
+    }
+    clusters.push(&line[start..]);
+
+    let rtl = is_rtl_line(line);
+    let mut classes: Vec<BidiClass> = clusters
+        .iter()
+        .map(|cluster| {
+            let c = cluster.chars().next().unwrap_or(' ');
+            if is_rtl(c) {
+                BidiClass::Right
+            } else if c.is_numeric() {
+                BidiClass::Number
+            } else if c.is_alphabetic() {
+                BidiClass::Left
+            } else {
+                BidiClass::Neutral
+            }
+        })
+        .collect();
+    let first = |i: usize| clusters[i].chars().next().unwrap_or(' ');
+
+    // A separator between digits and signs next to digits belong to the number
+    for i in 1..classes.len().saturating_sub(1) {
+        if is_number_separator(first(i))
+            && classes[i - 1] == BidiClass::Number
+            && classes[i + 1] == BidiClass::Number
+        {
+            classes[i] = BidiClass::Number;
+        }
+    }
+    for i in 1..classes.len() {
+        if classes[i - 1] == BidiClass::Number && is_number_affix(first(i)) {
+            classes[i] = BidiClass::Number;
+        }
+    }
+    for i in (0..classes.len().saturating_sub(1)).rev() {
+        if classes[i + 1] == BidiClass::Number && is_number_affix(first(i)) {
+            classes[i] = BidiClass::Number;
+        }
+    }
+
+    // Numbers after left-to-right text are part of it
+    let base = if rtl {
+        BidiClass::Right
+    } else {
+        BidiClass::Left
+    };
+    let mut last_strong = base;
+    for class in &mut classes {
+        match *class {
+            BidiClass::Left | BidiClass::Right => last_strong = *class,
+            BidiClass::Number if last_strong == BidiClass::Left => *class = BidiClass::Left,
+            _ => {}
+        }
+    }
+
+    // Neutrals take the direction around them, or the line's when it differs
+    let strong = |class: BidiClass| match class {
+        BidiClass::Number => Some(BidiClass::Right),
+        BidiClass::Neutral => None,
+        other => Some(other),
+    };
+    let mut i = 0;
+    while i < classes.len() {
+        if classes[i] != BidiClass::Neutral {
+            i += 1;
+            continue;
+        }
+        let end = (i..classes.len())
+            .find(|&j| classes[j] != BidiClass::Neutral)
+            .unwrap_or(classes.len());
+        let before = if i == 0 {
+            base
+        } else {
+            strong(classes[i - 1]).unwrap_or(base)
+        };
+        let after = classes
+            .get(end)
+            .and_then(|class| strong(*class))
+            .unwrap_or(base);
+        // Spaces at the end of the line follow its direction
+        let resolved = if before == after && end < classes.len() {
+            before
+        } else {
+            base
+        };
+        classes[i..end].fill(resolved);
+        i = end;
+    }
+
+    let levels: Vec<u8> = classes
+        .iter()
+        .map(|class| match (rtl, class) {
+            (false, BidiClass::Right) => 1,
+            (false, BidiClass::Number) => 2,
+            (false, _) => 0,
+            (true, BidiClass::Right) => 1,
+            (true, _) => 2,
+        })
+        .collect();
+
+    let mut order: Vec<usize> = (0..clusters.len()).collect();
+    let highest = levels.iter().copied().max().unwrap_or(0);
+    for level in (1..=highest).rev() {
+        let mut i = 0;
+        while i < order.len() {
+            if levels[order[i]] < level {
+                i += 1;
+                continue;
+            }
+            let end = (i..order.len())
+                .find(|&j| levels[order[j]] < level)
+                .unwrap_or(order.len());
+            order[i..end].reverse();
+            i = end;
+        }
+    }
+
+    let mut visual = String::with_capacity(line.len());
+    for i in order {
+        let mut chars = clusters[i].chars();
+        match chars.next() {
+            Some(c) if levels[i] % 2 == 1 => visual.push(mirrored(c)),
+            Some(c) => visual.push(c),
+            None => {}
+        }
+        visual.push_str(chars.as_str());
+    }
+    visual
+}
@@ -0,0 +1,186 @@
+use crate::error::VfdError;
+use crate::frame_buffer::FrameBuffer;
+use crate::vfd::Vfd;
+use std::fmt;
+use std::io;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// Character attributes for text written with [`Vfd::write_text_with`].
+///
+/// Reverse video and underline need a command set that has them, see
+/// [`CommandSet::set_reverse`](crate::CommandSet::set_reverse). Blinking text
+/// is drawn in software instead, alternately shown and blanked by
+/// [`Vfd::tick_blink`] or [`spawn_blink`].
+///
+/// ```
+/// use vfd_dsp_v9fb_over_serial::TextStyle;
+///
+/// let style = TextStyle::default().reverse().blink();
+/// assert_eq!("reverse+blink".parse(), Ok(style));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TextStyle {
+    pub reverse: bool,
+    pub underline: bool,
+    pub blink: bool,
+}
+
+impl TextStyle {
+    pub fn reverse(mut self) -> Self {
+        self.reverse = true;
+        self
+    }
+
+    pub fn underline(mut self) -> Self {
+        self.underline = true;
+        self
+    }
+
+    pub fn blink(mut self) -> Self {
+        self.blink = true;
+        self
+    }
+
+    /// Whether no attribute is set.
+    pub fn is_plain(&self) -> bool {
+        *self == TextStyle::default()
+    }
+}
+
+impl FromStr for TextStyle {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut style = TextStyle::default();
+        for name in s.split(['+', ',']) {
+            style = match name.trim().to_ascii_lowercase().as_str() {
+                "plain" | "" => style,
+                "reverse" => style.reverse(),
+                "underline" => style.underline(),
+                "blink" => style.blink(),
+                other => {
+                    return Err(format!(
+                        "Unknown text style '{}'. Expected plain or a combination of reverse, underline, blink.",
+                        other
+                    ));
+                }
+            };
+        }
+        Ok(style)
+    }
+}
+
+impl fmt::Display for TextStyle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names: Vec<&str> = [
+            (self.reverse, "reverse"),
+            (self.underline, "underline"),
+            (self.blink, "blink"),
+        ]
+        .into_iter()
+        .filter_map(|(set, name)| set.then_some(name))
+        .collect();
+        if names.is_empty() {
+            write!(f, "plain")
+        } else {
+            write!(f, "{}", names.join("+"))
+        }
+    }
+}
+
+// Text written with the blink attribute, redrawn by the driver
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct BlinkSpan {
+    pub(crate) x: u8,
+    pub(crate) y: u8,
+    pub(crate) text: String,
+    // Attributes to draw it with when shown, without blink
+    pub(crate) style: TextStyle,
+    // Whether it is shown rather than blanked at the moment
+    pub(crate) visible: bool,
+}
+
+impl BlinkSpan {
+    // Whether the span is still there rather than other text written over it
+    // since. Its cells hold the text through the blank phase too.
+    pub(crate) fn is_intact(&self, shown: &FrameBuffer) -> bool {
+        self.text
+            .chars()
+            .zip(self.x..)
+            .all(|(c, x)| shown.get(x, self.y) == Some(c))
+    }
+}
+
+// State of the software blink of every span written with the blink attribute
+#[derive(Debug, Clone)]
+pub(crate) struct SoftBlink {
+    pub(crate) spans: Vec<BlinkSpan>,
+    pub(crate) interval: Duration,
+    // Phase the spans were last switched to
+    pub(crate) visible: bool,
+    pub(crate) last_toggle: Instant,
+}
+
+impl Default for SoftBlink {
+    fn default() -> Self {
+        SoftBlink {
+            spans: Vec::new(),
+            interval: Duration::from_millis(500),
+            visible: true,
+            last_toggle: Instant::now(),
+        }
+    }
+}
+
+/// Blink text on a background thread until the returned handle is stopped,
+/// calling [`Vfd::tick_blink`] as often as needed.
+pub fn spawn_blink(vfd: Arc<Mutex<Vfd>>) -> BlinkHandle {
+    let stop = Arc::new(AtomicBool::new(false));
+    let thread_stop = Arc::clone(&stop);
+    let thread = thread::spawn(move || {
+        while !thread_stop.load(Ordering::Relaxed) {
+            let interval = {
+                let mut vfd = vfd.lock().unwrap_or_else(PoisonError::into_inner);
+                vfd.tick_blink()?;
+                vfd.text_blink_interval()
+            };
+            thread::sleep(interval.min(Duration::from_millis(50)));
+        }
+        let mut vfd = vfd.lock().unwrap_or_else(PoisonError::into_inner);
+        vfd.stop_text_blink()
+    });
+    BlinkHandle {
+        stop,
+        thread: Some(thread),
+    }
+}
+
+/// Control handle for text blinking on a background thread.
+pub struct BlinkHandle {
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<Result<(), VfdError>>>,
+}
+
+impl BlinkHandle {
+    /// Stop blinking, leaving the text shown, and wait for the thread to
+    /// finish, returning any write error it hit.
+    pub fn stop(mut self) -> Result<(), VfdError> {
+        self.stop.store(true, Ordering::Relaxed);
+        match self.thread.take() {
+            Some(thread) => thread
+                .join()
+                .unwrap_or_else(|_| Err(io::Error::other("Blink thread panicked").into())),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for BlinkHandle {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
@@ -0,0 +1,100 @@
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+// Bytes sent between pauses when pacing to a byte rate, aiming for ~10ms of line time
+const RATE_SLICES_PER_SECOND: u32 = 100;
+
+/// Pacing of writes, for displays that drop bytes at full line speed.
+///
+/// Paced writes are sent in chunks; after each one the driver waits until the
+/// port has drained it and then pauses, so the display's input buffer gets
+/// time to empty. Unpaced writes go out in one piece.
+///
+/// ```
+/// use std::time::Duration;
+/// use vfd_dsp_v9fb_over_serial::Throttle;
+///
+/// let chunked: Throttle = "16/5ms".parse().unwrap();
+/// assert_eq!(chunked, Throttle::Chunked { bytes: 16, delay: Duration::from_millis(5) });
+/// assert_eq!("480/s".parse(), Ok(Throttle::BytesPerSecond(480)));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Throttle {
+    #[default]
+    Off,
+    /// Pause `delay` after every `bytes` bytes.
+    Chunked { bytes: usize, delay: Duration },
+    /// Send no more than this many bytes per second.
+    BytesPerSecond(u32),
+}
+
+impl Throttle {
+    // Largest piece of a write sent before pausing, or None when unpaced
+    pub(crate) fn chunk_size(&self) -> Option<usize> {
+        match *self {
+            Throttle::Off | Throttle::Chunked { bytes: 0, .. } | Throttle::BytesPerSecond(0) => {
+                None
+            }
+            Throttle::Chunked { bytes, .. } => Some(bytes),
+            Throttle::BytesPerSecond(rate) => Some(rate.div_ceil(RATE_SLICES_PER_SECOND) as usize),
+        }
+    }
+
+    // How long to wait after sending a chunk of `len` bytes
+    pub(crate) fn pause_after(&self, len: usize) -> Duration {
+        match *self {
+            Throttle::Off => Duration::ZERO,
+            Throttle::Chunked { delay, .. } => delay,
+            Throttle::BytesPerSecond(0) => Duration::ZERO,
+            Throttle::BytesPerSecond(rate) => Duration::from_secs_f64(len as f64 / rate as f64),
+        }
+    }
+}
+
+impl FromStr for Throttle {
+    type Err = String;
+
+    /// Parse `off`, `BYTES/s` for a byte rate, or `BYTES/DELAY` such as
+    /// `16/5ms` for chunks with a pause after each.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim().to_ascii_lowercase();
+        let error = || {
+            format!(
+                "Unknown throttle '{}'. Expected off, BYTES/s such as 480/s, or BYTES/DELAY such as 16/5ms.",
+                s
+            )
+        };
+        if s == "off" || s == "none" {
+            return Ok(Throttle::Off);
+        }
+        let (bytes, period) = s.split_once('/').ok_or_else(error)?;
+        let bytes: u32 = bytes.trim().parse().map_err(|_| error())?;
+        match period.trim() {
+            "s" | "sec" => Ok(Throttle::BytesPerSecond(bytes)),
+            period => {
+                let delay = match period.strip_suffix("ms") {
+                    Some(ms) => Duration::from_millis(ms.trim().parse().map_err(|_| error())?),
+                    None => {
+                        let secs = period.strip_suffix('s').ok_or_else(error)?;
+                        Duration::from_secs(secs.trim().parse().map_err(|_| error())?)
+                    }
+                };
+                Ok(Throttle::Chunked {
+                    bytes: bytes as usize,
+                    delay,
+                })
+            }
+        }
+    }
+}
+
+impl fmt::Display for Throttle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Throttle::Off => write!(f, "off"),
+            Throttle::Chunked { bytes, delay } => write!(f, "{}/{}ms", bytes, delay.as_millis()),
+            Throttle::BytesPerSecond(rate) => write!(f, "{}/s", rate),
+        }
+    }
+}
@@ -0,0 +1,167 @@
+use std::io::{self, Read, Write};
+use std::net::TcpStream;
+use std::time::Duration;
+
+/// Byte link a [`Vfd`](crate::Vfd) drives a display through.
+///
+/// Anything that can write is a transport, such as a buffer handed to Web
+/// Serial from WebAssembly:
+///
+/// ```
+/// use std::io::{self, Write};
+/// use vfd_dsp_v9fb_over_serial::{Align, CommandSet, Vfd};
+///
+/// struct Outbox(Vec<u8>);
+///
+/// impl Write for Outbox {
+///     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+///         self.0.extend_from_slice(buf);
+///         Ok(buf.len())
+///     }
+///
+///     fn flush(&mut self) -> io::Result<()> {
+///         Ok(())
+///     }
+/// }
+///
+/// let mut vfd = Vfd::from_transport(Outbox(Vec::new()), CommandSet::Birch, 20, 2);
+/// vfd.write_line(0, "Hello", Align::Left).unwrap();
+/// ```
+///
+/// Displays that answer requests (see [`Vfd::identify`](crate::Vfd::identify))
+/// also need their answers read back, which takes a [`Duplex`] link.
+pub trait Transport: Write + Send {}
+
+impl<T: Write + Send + ?Sized> Transport for T {}
+
+/// A [`Transport`] the display's answers can be read back from, opened with
+/// [`Vfd::from_duplex`](crate::Vfd::from_duplex).
+///
+/// Serial ports (`serial` feature), TCP streams and
+/// [`TcpSerial`](crate::TcpSerial) bridges are duplex links already. Both
+/// methods fail with [`io::ErrorKind::Unsupported`] unless overridden.
+pub trait Duplex: Transport {
+    /// Read what the display sent into `buf`, waiting up to `timeout` for it.
+    ///
+    /// Returns `Ok(0)` when nothing arrived in time.
+    fn read(&mut self, buf: &mut [u8], timeout: Duration) -> io::Result<usize> {
+        let _ = (buf, timeout);
+        Err(cannot_read())
+    }
+
+    /// Drop bytes received but not read yet, so the answer to the next
+    /// request is not mixed up with leftovers.
+    fn discard_input(&mut self) -> io::Result<()> {
+        Err(cannot_read())
+    }
+}
+
+fn cannot_read() -> io::Error {
+    io::Error::new(io::ErrorKind::Unsupported, "Transport cannot be read from")
+}
+
+impl<T: Duplex + ?Sized> Duplex for Box<T> {
+    fn read(&mut self, buf: &mut [u8], timeout: Duration) -> io::Result<usize> {
+        (**self).read(buf, timeout)
+    }
+
+    fn discard_input(&mut self) -> io::Result<()> {
+        (**self).discard_input()
+    }
+}
+
+// A link only written to, read through the failing defaults
+pub(crate) struct WriteOnly<T>(pub(crate) T);
+
+impl<T: Write> Write for WriteOnly<T> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.flush()
+    }
+}
+
+impl<T: Transport> Duplex for WriteOnly<T> {}
+
+/// A transport that throws away everything sent, for running code that
+/// drives a display on a machine without one.
+///
+/// Reads fail as on any write-only link. What the driver believes the
+/// display shows is still tracked, e.g. for
+/// [`Vfd::save_state`](crate::Vfd::save_state).
+///
+/// ```
+/// use vfd_dsp_v9fb_over_serial::{Align, CommandSet, NullTransport, Vfd};
+///
+/// let mut vfd = Vfd::from_transport(NullTransport, CommandSet::Birch, 20, 2);
+/// vfd.write_line(0, "Nothing sent", Align::Center).unwrap();
+/// assert_eq!(vfd.save_state().frame.row(0), "    Nothing sent    ");
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NullTransport;
+
+impl Write for NullTransport {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Duplex for TcpStream {
+    fn read(&mut self, buf: &mut [u8], timeout: Duration) -> io::Result<usize> {
+        // A zero timeout would mean waiting forever
+        self.set_read_timeout(Some(timeout.max(Duration::from_millis(1))))?;
+        match Read::read(self, buf) {
+            // Unix reports an expired read timeout as WouldBlock, Windows as TimedOut
+            Err(e)
+                if matches!(
+                    e.kind(),
+                    io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+                ) =>
+            {
+                Ok(0)
+            }
+            result => result,
+        }
+    }
+
+    fn discard_input(&mut self) -> io::Result<()> {
+        self.set_nonblocking(true)?;
+        let mut buf = [0u8; 64];
+        let result = loop {
+            match Read::read(self, &mut buf) {
+                Ok(0) => break Ok(()),
+                Ok(_) => continue,
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => break Ok(()),
+                Err(e) => break Err(e),
+            }
+        };
+        self.set_nonblocking(false)?;
+        result
+    }
+}
+
+#[cfg(feature = "serial")]
+impl Duplex for dyn serialport::SerialPort {
+    fn read(&mut self, buf: &mut [u8], timeout: Duration) -> io::Result<usize> {
+        // The timeout also bounds writes, so put the configured one back afterwards
+        let configured = self.timeout();
+        self.set_timeout(timeout)?;
+        let result = match Read::read(self, buf) {
+            Err(e) if e.kind() == io::ErrorKind::TimedOut => Ok(0),
+            result => result,
+        };
+        self.set_timeout(configured)?;
+        result
+    }
+
+    fn discard_input(&mut self) -> io::Result<()> {
+        self.clear(serialport::ClearBuffer::Input)?;
+        Ok(())
+    }
+}
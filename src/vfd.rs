@@ -0,0 +1,2163 @@
+use crate::alert::{Alert, AlertDismisser, AlertOverlay};
+use crate::annunciator::Annunciator;
+use crate::background::BackgroundVfd;
+use crate::big_number::BigFont;
+#[cfg(feature = "serial")]
+use crate::builder::VfdBuilder;
+use crate::capabilities::Capabilities;
+use crate::codepage::{CodePage, TextMapping, encode_text, prepare_text, unencodable};
+use crate::command::Command;
+use crate::command_set::{
+    BrightnessLevel, CommandSet, CursorMove, CursorStyle, DisplayMode, Peripheral,
+};
+use crate::currency::Currency;
+use crate::display::VfdDisplay;
+use crate::display_state::DisplayState;
+use crate::error::VfdError;
+use crate::frame_buffer::FrameBuffer;
+use crate::geometry::Geometry;
+use crate::glyph::Glyph;
+use crate::info::{DisplayInfo, Status};
+use crate::layout::{self, Align};
+use crate::line::Line;
+use crate::logging;
+use crate::marquee::{Marquee, ScrollDirection};
+use crate::metrics::Metrics;
+use crate::number_format::NumberFormat;
+use crate::observer::VfdObserver;
+use crate::on_drop::OnDrop;
+use crate::position::Position;
+use crate::progress::{self, ProgressStyle};
+use crate::region::Region;
+use crate::symbols::SymbolMap;
+use crate::template::Templates;
+use crate::text::{self, WrapPolicy};
+use crate::text_style::{BlinkSpan, SoftBlink, TextStyle};
+use crate::throttle::Throttle;
+use crate::transport::{Duplex, Transport, WriteOnly};
+use crate::write_options::{FitPolicy, Overflow, WriteOptions};
+#[cfg(feature = "serial")]
+use serialport::SerialPort;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::{self, Write};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How a piece of text fits on the display from the current cursor position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextFit {
+    OneLine,
+    NeedsWrap,
+    TooLong,
+    OneLineTruncated,
+}
+
+/// Driver for a VFD customer display over serial.
+pub struct Vfd {
+    port: Box<dyn Duplex>,
+    // The serial port behind `port`, for its modem control lines
+    #[cfg(feature = "serial")]
+    control: Option<Box<dyn SerialPort>>,
+    // How long to wait for an answer to a request
+    response_timeout: Duration,
+    command_set: CommandSet,
+    capabilities: Capabilities,
+    brightness: BrightnessLevel,
+    code_page: CodePage,
+    display_mode: DisplayMode,
+    // Device selected on a pass-through chain, if one was ever selected
+    peripheral: Option<Peripheral>,
+    transliterate: bool,
+    symbols: SymbolMap,
+    // Put right-to-left text in display order
+    bidi: bool,
+    wrap_policy: WrapPolicy,
+    throttle: Throttle,
+    // Marks text cut by Overflow::Ellipsis; chosen from the code page when unset
+    ellipsis: Option<char>,
+    currency: Currency,
+    number_format: NumberFormat,
+    templates: Templates,
+    width: u8,
+    height: u8,
+    cursor_x: u8,
+    cursor_y: u8,
+    // Custom glyphs downloaded since the last initialize, by slot
+    glyphs: BTreeMap<u8, Glyph>,
+    // How the port was opened, kept so it can be reopened after a disconnect
+    #[cfg(feature = "serial")]
+    settings: Option<VfdBuilder>,
+    // Text written since the last clear, redrawn after reconnecting
+    shown: FrameBuffer,
+    #[cfg(feature = "serial")]
+    reconnecting: bool,
+    // Commands held back until the next flush, while batching
+    batch: Option<Vec<u8>>,
+    // Where the display's own cursor is, when nothing sent since could have moved it elsewhere
+    known_cursor: Option<Position>,
+    on_drop: OnDrop,
+    // Text written with the blink attribute, blinked in software
+    soft_blink: SoftBlink,
+    alert_dismisser: AlertDismisser,
+    // Output held back while an alert shows over the display
+    alert: Option<AlertOverlay>,
+    // How often the whole display blinks, zero while it is steady
+    blink_interval: Duration,
+    observer: Option<Arc<dyn VfdObserver>>,
+    metrics: Arc<Metrics>,
+    // Set once a write failed for the display having gone away, until it is reconnected
+    disconnected: bool,
+}
+
+/// Driver for a Birch DSP-V9FB display, the default command set.
+pub type BirchVfd = Vfd;
+
+impl Vfd {
+    /// Open the serial device at 9600 8N1 and initialize the display.
+    #[cfg(feature = "serial")]
+    pub fn new(device_path: &str, width: u8, height: u8) -> Result<Self, VfdError> {
+        Self::builder(device_path, width, height).open()
+    }
+
+    /// Like [`Vfd::new`], for a display speaking the given command set.
+    #[cfg(feature = "serial")]
+    pub fn with_command_set(
+        device_path: &str,
+        width: u8,
+        height: u8,
+        command_set: CommandSet,
+    ) -> Result<Self, VfdError> {
+        Self::builder(device_path, width, height)
+            .command_set(command_set)
+            .open()
+    }
+
+    /// Start configuring a display connection with the default 9600 8N1 settings.
+    #[cfg(feature = "serial")]
+    pub fn builder(device_path: &str, width: u8, height: u8) -> VfdBuilder {
+        VfdBuilder::new(device_path, width, height)
+    }
+
+    /// Drive a display through any byte sink, e.g. a socket or an in-memory buffer.
+    ///
+    /// Nothing is sent until the first command; call [`Vfd::initialize`] if needed.
+    /// Requests answered by the display fail with [`VfdError::NotSupported`];
+    /// use [`Vfd::from_duplex`] for links that can be read from.
+    pub fn from_transport<T: Transport + 'static>(
+        transport: T,
+        command_set: CommandSet,
+        width: u8,
+        height: u8,
+    ) -> Self {
+        Self::from_port(Box::new(WriteOnly(transport)), command_set, width, height)
+    }
+
+    /// Drive a display through a link its answers are read back from, such as a
+    /// [`TcpSerial`](crate::TcpSerial) bridge, so [`Vfd::identify`] works.
+    ///
+    /// Nothing is sent until the first command; call [`Vfd::initialize`] if needed.
+    pub fn from_duplex<T: Duplex + 'static>(
+        link: T,
+        command_set: CommandSet,
+        width: u8,
+        height: u8,
+    ) -> Self {
+        Self::from_port(Box::new(link), command_set, width, height)
+    }
+
+    pub(crate) fn from_port(
+        port: Box<dyn Duplex>,
+        command_set: CommandSet,
+        width: u8,
+        height: u8,
+    ) -> Self {
+        Vfd {
+            port,
+            #[cfg(feature = "serial")]
+            control: None,
+            response_timeout: Duration::from_secs(1),
+            command_set: command_set.clone(),
+            capabilities: Capabilities::new(command_set, Geometry::new(width, height)),
+            brightness: BrightnessLevel::Percent100,
+            code_page: CodePage::default(),
+            display_mode: DisplayMode::default(),
+            peripheral: None,
+            transliterate: true,
+            symbols: SymbolMap::default(),
+            bidi: false,
+            wrap_policy: WrapPolicy::default(),
+            throttle: Throttle::default(),
+            ellipsis: None,
+            currency: Currency::default(),
+            number_format: NumberFormat::default(),
+            templates: Templates::default(),
+            width,
+            height,
+            cursor_x: 0,
+            cursor_y: 0,
+            glyphs: BTreeMap::new(),
+            #[cfg(feature = "serial")]
+            settings: None,
+            shown: FrameBuffer::new(width, height),
+            #[cfg(feature = "serial")]
+            reconnecting: false,
+            batch: None,
+            known_cursor: None,
+            on_drop: OnDrop::default(),
+            soft_blink: SoftBlink::default(),
+            alert_dismisser: AlertDismisser::default(),
+            alert: None,
+            blink_interval: Duration::ZERO,
+            observer: None,
+            metrics: Arc::default(),
+            disconnected: false,
+        }
+    }
+
+    #[cfg(feature = "serial")]
+    pub(crate) fn set_control_port(&mut self, port: Option<Box<dyn SerialPort>>) {
+        self.control = port;
+    }
+
+    /// How long [`Vfd::identify`] and [`Vfd::read_status`] wait for the display to answer (1 s by default)
+    pub fn set_response_timeout(&mut self, timeout: Duration) {
+        self.response_timeout = timeout;
+    }
+
+    #[cfg(feature = "serial")]
+    pub(crate) fn set_settings(&mut self, settings: VfdBuilder) {
+        self.settings = Some(settings);
+    }
+
+    /// Reopen the serial port after an error for which [`VfdError::is_disconnected`] holds.
+    ///
+    /// The display is re-initialized if it was on open, then the brightness,
+    /// code page, text written since the last clear and cursor position in
+    /// effect before the disconnect are restored.
+    #[cfg(feature = "serial")]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+    pub fn reconnect(&mut self) -> Result<(), VfdError> {
+        let settings = self.settings.clone().ok_or_else(|| {
+            VfdError::NotSupported("Reconnecting a display not opened by path".to_string())
+        })?;
+        let (port, control) = settings.open_port()?;
+        self.port = port;
+        self.control = control;
+        self.known_cursor = None;
+
+        let state = self.save_state();
+        if settings.passes_through() {
+            self.select_peripheral(Peripheral::Display)?;
+        }
+        if settings.auto_initializes() {
+            self.initialize()?;
+        }
+        // The display may have lost power, so everything is sent again
+        self.shown.clear();
+        self.glyphs.clear();
+        self.restore_state(&state)?;
+        self.disconnected = false;
+        self.metrics.record_reconnect();
+        if let Some(observer) = &self.observer {
+            observer.on_reconnected();
+        }
+        Ok(())
+    }
+
+    /// Report connection events for this display to `observer`, replacing any set before.
+    pub fn set_observer<O: VfdObserver + 'static>(&mut self, observer: O) {
+        self.observer = Some(Arc::new(observer));
+    }
+
+    // Tell the observer the display is set up, once opened
+    #[cfg(feature = "serial")]
+    pub(crate) fn notify_connected(&self) {
+        if let Some(observer) = &self.observer {
+            observer.on_connected();
+        }
+    }
+
+    // Count a failed write and tell the observer, and that the display is
+    // gone the first time a write fails for that
+    fn notify_write_error(&mut self, error: &VfdError) {
+        let newly_disconnected = error.is_disconnected() && !self.disconnected;
+        self.metrics.record_write_error();
+        if newly_disconnected {
+            self.disconnected = true;
+            self.metrics.record_disconnect();
+        }
+        if let Some(observer) = &self.observer {
+            observer.on_write_error(error);
+            if newly_disconnected {
+                observer.on_disconnected(error);
+            }
+        }
+    }
+
+    /// Counts of what has been sent, failed and reconnected, shared with the returned handle.
+    pub fn metrics(&self) -> Arc<Metrics> {
+        Arc::clone(&self.metrics)
+    }
+
+    /// Snapshot the text on the display, the cursor, brightness, display mode,
+    /// blinking, code page and custom glyphs, for [`Vfd::restore_state`].
+    pub fn save_state(&self) -> DisplayState {
+        DisplayState {
+            frame: self.shown.clone(),
+            cursor: self.position(),
+            brightness: self.brightness,
+            display_mode: self.display_mode,
+            blink: self.blink_interval,
+            code_page: self.code_page,
+            glyphs: self.glyphs.clone(),
+        }
+    }
+
+    /// Put the display back the way `state` was saved, e.g. after showing an alert.
+    ///
+    /// Only what differs is sent: settings that changed, glyphs that were
+    /// replaced and rows whose text is not the same.
+    ///
+    /// ```
+    /// use vfd_dsp_v9fb_over_serial::{Align, MockVfd};
+    ///
+    /// let mut vfd = MockVfd::new(20, 2);
+    /// vfd.write_line(0, "Total", Align::Left).unwrap();
+    /// let state = vfd.save_state();
+    /// vfd.write_line(0, "Card declined", Align::Center).unwrap();
+    /// vfd.restore_state(&state).unwrap();
+    /// assert_eq!(vfd.row(0).trim_end(), "Total");
+    /// ```
+    pub fn restore_state(&mut self, state: &DisplayState) -> Result<(), VfdError> {
+        self.batch(|vfd| {
+            for (slot, glyph) in &state.glyphs {
+                vfd.ensure_custom_char(*slot, glyph)?;
+            }
+            if state.brightness != vfd.brightness {
+                vfd.set_brightness(state.brightness)?;
+            }
+            if state.code_page != vfd.code_page {
+                vfd.set_code_page(state.code_page)?;
+            }
+            if state.display_mode != vfd.display_mode {
+                vfd.set_display_mode(state.display_mode)?;
+            }
+            if state.blink != vfd.blink_interval {
+                vfd.blink(state.blink)?;
+            }
+            for y in 0..vfd.height.min(state.frame.size().1) {
+                let row = state.frame.row(y);
+                if row != vfd.shown.row(y) {
+                    vfd.set_cursor(0, y)?;
+                    vfd.write(&row)?;
+                }
+            }
+            vfd.restore_cursor(state.cursor.x, state.cursor.y)
+        })
+    }
+
+    // All output goes through here: collected while batching, sent otherwise
+    fn send(&mut self, bytes: &[u8]) -> Result<(), VfdError> {
+        // Any command may move the cursor; callers that know where it ends up say so afterwards
+        self.known_cursor = None;
+        match &mut self.batch {
+            Some(batch) => {
+                batch.extend_from_slice(bytes);
+                Ok(())
+            }
+            None => self.transmit(bytes),
+        }
+    }
+
+    // Write to the port, retrying after a reconnect if the connection dropped
+    fn transmit(&mut self, bytes: &[u8]) -> Result<(), VfdError> {
+        if let Some(overlay) = &mut self.alert
+            && !overlay.passing
+        {
+            overlay.held.extend_from_slice(bytes);
+            return Ok(());
+        }
+        let error = match self.write_paced(bytes) {
+            Ok(()) => return Ok(()),
+            Err(e) => VfdError::from(e),
+        };
+        self.notify_write_error(&error);
+        #[cfg(feature = "serial")]
+        return self.retry_after_reconnect(bytes, error);
+        #[cfg(not(feature = "serial"))]
+        Err(error)
+    }
+
+    // Reopen the port as the settings allow after `error`, then send `bytes` again
+    #[cfg(feature = "serial")]
+    fn retry_after_reconnect(&mut self, bytes: &[u8], error: VfdError) -> Result<(), VfdError> {
+        let (attempts, delay) = match &self.settings {
+            Some(settings) if !self.reconnecting && error.is_disconnected() => {
+                settings.reconnect_policy()
+            }
+            _ => return Err(error),
+        };
+        if attempts == 0 {
+            return Err(error);
+        }
+
+        self.reconnecting = true;
+        let mut result = Err(error);
+        for _ in 0..attempts {
+            thread::sleep(delay);
+            result = self.reconnect();
+            if result.is_ok() {
+                break;
+            }
+        }
+        self.reconnecting = false;
+        result?;
+        self.write_paced(bytes).map_err(|e| {
+            let error = VfdError::from(e);
+            self.notify_write_error(&error);
+            error
+        })
+    }
+
+    // Write `bytes` in the pieces the throttle allows, letting each drain before pausing
+    fn write_paced(&mut self, bytes: &[u8]) -> io::Result<()> {
+        logging::debug!(bytes = %logging::Hex(bytes), "Sending {} bytes", bytes.len());
+        match self.throttle.chunk_size() {
+            None => self.port.write_all(bytes)?,
+            Some(size) => {
+                for chunk in bytes.chunks(size) {
+                    self.port.write_all(chunk)?;
+                    self.port.flush()?;
+                    thread::sleep(self.throttle.pause_after(chunk.len()));
+                }
+            }
+        }
+        self.metrics.record_write(bytes.len());
+        Ok(())
+    }
+
+    /// Send the standard initialization command (ESC @ on Birch)
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+    pub fn initialize(&mut self) -> Result<(), VfdError> {
+        let cmd = self.command_set.initialize();
+        self.send(&cmd)?;
+        self.reset_to_power_on();
+        Ok(())
+    }
+
+    // Initialization restores the power-on settings and blanks the screen
+    fn reset_to_power_on(&mut self) {
+        self.brightness = BrightnessLevel::Percent100;
+        self.code_page = CodePage::default();
+        self.display_mode = DisplayMode::default();
+        self.blink_interval = Duration::ZERO;
+        self.glyphs.clear();
+        self.shown.clear();
+        self.soft_blink.spans.clear();
+        (self.cursor_x, self.cursor_y) = (0, 0);
+    }
+
+    /// Choose which device on a pass-through chain receives what is sent next.
+    ///
+    /// While the printer alone is selected everything the driver sends,
+    /// including text meant for the display, goes to the printer.
+    pub fn select_peripheral(&mut self, peripheral: Peripheral) -> Result<(), VfdError> {
+        let cmd = self
+            .command_set
+            .select_peripheral(peripheral)
+            .ok_or_else(|| {
+                VfdError::NotSupported(format!("Pass-through on {:?} displays", self.command_set))
+            })?;
+        self.send(&cmd)?;
+        self.peripheral = Some(peripheral);
+        Ok(())
+    }
+
+    /// Device last selected with [`Vfd::select_peripheral`], `None` if none was
+    pub fn peripheral(&self) -> Option<Peripheral> {
+        self.peripheral
+    }
+
+    /// Send `bytes` to the printer behind the display, then hand the line back to the display
+    pub fn print(&mut self, bytes: &[u8]) -> Result<(), VfdError> {
+        self.select_peripheral(Peripheral::Printer)?;
+        self.send(bytes)?;
+        self.select_peripheral(Peripheral::Display)?;
+        self.flush()
+    }
+
+    /// Command set this display was opened with
+    pub fn command_set(&self) -> &CommandSet {
+        &self.command_set
+    }
+
+    /// Clear screen and return cursor to home (top-left)
+    pub fn clear(&mut self) -> Result<(), VfdError> {
+        let cmd = self.command_set.clear();
+        self.send(&cmd)?;
+        self.shown.clear();
+        self.soft_blink.spans.clear();
+        // VFDs are slow; a tiny flush ensures the command hits the hardware
+        match self.port.flush() {
+            Ok(_) => (),
+            Err(e) => logging::warning!("Failed to flush Serial port after clear command: {}", e),
+        }
+        self.set_cursor(0, 0)?;
+        Ok(())
+    }
+
+    /// Blank row `row` and leave the cursor at its start
+    pub fn clear_line(&mut self, row: u8) -> Result<(), VfdError> {
+        let Some(cmd) = self.command_set.clear_line() else {
+            self.write_line(row, "", Align::Left)?;
+            return self.set_cursor(0, row);
+        };
+        if row >= self.height {
+            return Err(VfdError::OutOfBounds {
+                what: "Row",
+                value: row as usize,
+                max: self.height as usize - 1,
+            });
+        }
+        self.set_cursor(0, row)?;
+        self.send(&cmd)?;
+        self.shown.put_str(0, row, &" ".repeat(self.width as usize));
+        Ok(())
+    }
+
+    /// Blank from the cursor to the end of its row, leaving the cursor where it is
+    pub fn clear_to_eol(&mut self) -> Result<(), VfdError> {
+        let (cursor_x, cursor_y) = self.get_cursor();
+        let blank = " ".repeat(self.width.saturating_sub(cursor_x) as usize);
+        if !blank.is_empty() {
+            self.write(&blank)?;
+        }
+        self.restore_cursor(cursor_x, cursor_y)
+    }
+
+    /// Blank the `width × height` area starting at column `x`, row `y`
+    pub fn clear_region(&mut self, x: u8, y: u8, width: u8, height: u8) -> Result<(), VfdError> {
+        let (cursor_x, cursor_y) = self.get_cursor();
+        self.region(x, y, width, height)?.clear(self)?;
+        self.restore_cursor(cursor_x, cursor_y)
+    }
+
+    /// Move cursor to column `x`, row `y`, counted from 0 at the top-left.
+    ///
+    /// Fails with [`VfdError::OutOfBounds`] for a cell off the display.
+    pub fn set_cursor(&mut self, x: u8, y: u8) -> Result<(), VfdError> {
+        self.set_position(Position::new(x, y))
+    }
+
+    /// Move cursor to `position`, see [`Vfd::set_cursor`]
+    pub fn set_position(&mut self, position: Position) -> Result<(), VfdError> {
+        let Position { x, y } = position.check(self.size())?;
+        if self.batch.is_some() && self.known_cursor == Some(position) {
+            (self.cursor_x, self.cursor_y) = (x, y);
+            return Ok(());
+        }
+        let cmd = self.command_set.set_cursor(x, y, self.width);
+        self.send(&cmd)?;
+        (self.cursor_x, self.cursor_y) = (x, y);
+        self.known_cursor = Some(position);
+        Ok(())
+    }
+
+    /// Current cursor position
+    pub fn position(&self) -> Position {
+        Position::new(self.cursor_x, self.cursor_y)
+    }
+
+    // Put the cursor back where it was saved. A cursor just past the end of a
+    // row is only tracked, since no command can address that cell.
+    fn restore_cursor(&mut self, x: u8, y: u8) -> Result<(), VfdError> {
+        if x >= self.width && y < self.height {
+            (self.cursor_x, self.cursor_y) = (x, y);
+            return Ok(());
+        }
+        self.set_cursor(x, y)
+    }
+
+    /// Move the cursor to the top-left cell
+    pub fn cursor_home(&mut self) -> Result<(), VfdError> {
+        self.move_cursor(CursorMove::Home)
+    }
+
+    /// Move the cursor one column left
+    pub fn cursor_left(&mut self) -> Result<(), VfdError> {
+        self.move_cursor(CursorMove::Left)
+    }
+
+    /// Move the cursor one column right
+    pub fn cursor_right(&mut self) -> Result<(), VfdError> {
+        self.move_cursor(CursorMove::Right)
+    }
+
+    /// Move the cursor one row up
+    pub fn cursor_up(&mut self) -> Result<(), VfdError> {
+        self.move_cursor(CursorMove::Up)
+    }
+
+    /// Move the cursor one row down
+    pub fn cursor_down(&mut self) -> Result<(), VfdError> {
+        self.move_cursor(CursorMove::Down)
+    }
+
+    /// Move the cursor to the start of its row
+    pub fn carriage_return(&mut self) -> Result<(), VfdError> {
+        self.move_cursor(CursorMove::CarriageReturn)
+    }
+
+    /// Move the cursor one row down, scrolling the display up from the bottom row in vertical scroll mode
+    pub fn line_feed(&mut self) -> Result<(), VfdError> {
+        self.move_cursor(CursorMove::LineFeed)
+    }
+
+    /// Apply a single cursor movement.
+    ///
+    /// Uses the display's one-byte command where the command set has one and
+    /// an absolute move otherwise. Movements that would leave the display do
+    /// nothing, apart from a line feed on the bottom row in vertical scroll mode.
+    pub fn move_cursor(&mut self, movement: CursorMove) -> Result<(), VfdError> {
+        let (x, y) = self.get_cursor();
+        let target = match movement {
+            CursorMove::Home => Some((0, 0)),
+            CursorMove::Left => x.checked_sub(1).map(|x| (x, y)),
+            CursorMove::Right => (x + 1 < self.width).then_some((x + 1, y)),
+            CursorMove::Up => y.checked_sub(1).map(|y| (x, y)),
+            CursorMove::Down | CursorMove::LineFeed => (y + 1 < self.height).then_some((x, y + 1)),
+            CursorMove::CarriageReturn => Some((0, y)),
+        };
+        let cmd = self.command_set.move_cursor(movement);
+        match (target, cmd) {
+            (Some((x, y)), Some(cmd)) => {
+                self.send(&cmd)?;
+                (self.cursor_x, self.cursor_y) = (x, y);
+            }
+            (Some((x, y)), None) => self.set_cursor(x, y)?,
+            (None, Some(cmd))
+                if movement == CursorMove::LineFeed
+                    && self.display_mode == DisplayMode::VerticalScroll =>
+            {
+                self.send(&cmd)?;
+                self.shown.scroll_up();
+            }
+            (None, _) => {}
+        }
+        Ok(())
+    }
+
+    /// Send bytes to the display as they are.
+    ///
+    /// The driver cannot tell what they do, so its cursor position, settings and
+    /// screen contents may no longer match the display afterwards. Prefer
+    /// [`Vfd::send_command`] for documented sequences.
+    pub fn send_raw(&mut self, bytes: &[u8]) -> Result<(), VfdError> {
+        self.send(bytes)
+    }
+
+    /// Send an ESC/POS customer display command, tracking its effect where it is known.
+    ///
+    /// Only available with the Birch and Epson command sets.
+    pub fn send_command(&mut self, command: &Command) -> Result<(), VfdError> {
+        if !matches!(self.command_set, CommandSet::Birch | CommandSet::EpsonDmD) {
+            return Err(VfdError::NotSupported(format!(
+                "ESC/POS commands on the {:?} command set",
+                self.command_set
+            )));
+        }
+        self.send(&command.to_bytes()?)?;
+
+        let (x, y) = (self.cursor_x, self.cursor_y);
+        let (last_x, last_y) = (self.width.saturating_sub(1), self.height.saturating_sub(1));
+        match command {
+            Command::Initialize => self.reset_to_power_on(),
+            Command::Clear => {
+                self.shown.clear();
+                (self.cursor_x, self.cursor_y) = (0, 0);
+            }
+            Command::ClearLine => {
+                self.shown.put_str(0, y, &" ".repeat(self.width as usize));
+                self.cursor_x = 0;
+            }
+            Command::CursorLeft => self.cursor_x = x.saturating_sub(1),
+            Command::CursorRight => self.cursor_x = (x + 1).min(last_x),
+            Command::CursorUp => self.cursor_y = y.saturating_sub(1),
+            Command::CursorDown => self.cursor_y = (y + 1).min(last_y),
+            Command::CursorHome => (self.cursor_x, self.cursor_y) = (0, 0),
+            Command::CursorLeftmost => self.cursor_x = 0,
+            Command::CursorRightmost => self.cursor_x = last_x,
+            Command::CursorBottom => (self.cursor_x, self.cursor_y) = (0, last_y),
+            Command::MoveCursor { x, y } => {
+                (self.cursor_x, self.cursor_y) = ((*x).min(last_x), (*y).min(last_y));
+            }
+            Command::Brightness(level) => self.brightness = *level,
+            Command::DisplayMode(mode) => self.display_mode = *mode,
+            Command::CodeTable(n) => {
+                if let Some(page) = CodePage::from_table_number(*n) {
+                    self.code_page = page;
+                }
+            }
+            Command::DefineChar { code, glyph } => {
+                if let Some(slot) = (0..self.command_set.max_custom_chars())
+                    .find(|slot| self.command_set.custom_char_code(*slot) == Some(*code))
+                {
+                    self.glyphs.insert(slot, *glyph);
+                }
+            }
+            Command::CancelUserChar(code) => {
+                self.glyphs
+                    .retain(|slot, _| self.command_set.custom_char_code(*slot) != Some(*code));
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Ask the display for its model, type and ROM version IDs.
+    ///
+    /// Needs a [`Duplex`](crate::Duplex) link, such as a serial port.
+    pub fn identify(&mut self) -> Result<DisplayInfo, VfdError> {
+        let request = self.command_set.identify_request().ok_or_else(|| {
+            VfdError::NotSupported(format!("Identifying a {:?} display", self.command_set))
+        })?;
+        let model_id = self.query(&request)?;
+        let type_id = self.query_optional(self.command_set.type_request())?;
+        let rom_version = self.query_optional(self.command_set.rom_version_request())?;
+        Ok(DisplayInfo {
+            model_id,
+            type_id,
+            rom_version,
+        })
+    }
+
+    /// Collect whatever the display sends within `timeout`, up to `max` bytes.
+    ///
+    /// Returns as soon as `max` bytes have arrived; an empty result means the
+    /// display stayed silent. Needs a [`Duplex`](crate::Duplex) link.
+    pub fn read_response(&mut self, max: usize, timeout: Duration) -> Result<Vec<u8>, VfdError> {
+        let deadline = Instant::now() + timeout;
+        let mut response = Vec::new();
+        let mut buf = [0u8; 64];
+        while response.len() < max {
+            let Some(remaining) = deadline.checked_duration_since(Instant::now()) else {
+                break;
+            };
+            let want = (max - response.len()).min(buf.len());
+            match self.port.read(&mut buf[..want], remaining) {
+                Ok(0) => break,
+                Ok(n) => response.extend_from_slice(&buf[..n]),
+                Err(e) => return Err(read_error(e)),
+            }
+        }
+        Ok(response)
+    }
+
+    // Send a request answered with a single byte and wait for the answer
+    fn query(&mut self, request: &[u8]) -> Result<u8, VfdError> {
+        // Drop anything left over from an earlier request
+        self.port.discard_input().map_err(read_error)?;
+        // An alert holds back only what changes the display
+        self.unheld(|vfd| {
+            vfd.send(request)?;
+            vfd.flush()
+        })?;
+        match self.read_response(1, self.response_timeout)?.first() {
+            Some(answer) => Ok(*answer),
+            None => Err(VfdError::Timeout),
+        }
+    }
+
+    /// Ask the display whether it is ready, combining its status byte with the handshake lines.
+    pub fn read_status(&mut self) -> Result<Status, VfdError> {
+        let raw = self.query_optional(self.command_set.status_request())?;
+        #[cfg(feature = "serial")]
+        let (clear_to_send, data_set_ready) = match self.control.as_mut() {
+            Some(port) => (
+                port.read_clear_to_send().ok(),
+                port.read_data_set_ready().ok(),
+            ),
+            None => (None, None),
+        };
+        #[cfg(not(feature = "serial"))]
+        let (clear_to_send, data_set_ready) = (None, None);
+        Ok(Status {
+            raw,
+            clear_to_send,
+            data_set_ready,
+        })
+    }
+
+    /// Drive the DTR line to `level`. Needs a display opened by path.
+    #[cfg(feature = "serial")]
+    pub fn set_dtr(&mut self, level: bool) -> Result<(), VfdError> {
+        self.control_port()?.write_data_terminal_ready(level)?;
+        Ok(())
+    }
+
+    /// Drive the RTS line to `level`. Needs a display opened by path; with
+    /// hardware flow control the driver may take RTS back at any time.
+    #[cfg(feature = "serial")]
+    pub fn set_rts(&mut self, level: bool) -> Result<(), VfdError> {
+        self.control_port()?.write_request_to_send(level)?;
+        Ok(())
+    }
+
+    // The serial port behind the display, for its modem control lines
+    #[cfg(feature = "serial")]
+    fn control_port(&mut self) -> Result<&mut Box<dyn SerialPort>, VfdError> {
+        self.control.as_mut().ok_or_else(|| {
+            VfdError::NotSupported("Modem control lines on this transport".to_string())
+        })
+    }
+
+    // Like `query`, for requests older firmware does not answer
+    fn query_optional(&mut self, request: Option<Vec<u8>>) -> Result<Option<u8>, VfdError> {
+        match request.map(|request| self.query(&request)) {
+            Some(Ok(answer)) => Ok(Some(answer)),
+            None | Some(Err(VfdError::Timeout)) => Ok(None),
+            Some(Err(e)) => Err(e),
+        }
+    }
+
+    /// Run the display's built-in self-test, which leaves it in its power-on state.
+    pub fn self_test(&mut self) -> Result<(), VfdError> {
+        let cmd = self.command_set.self_test().ok_or_else(|| {
+            VfdError::NotSupported(format!("Self-test on a {:?} display", self.command_set))
+        })?;
+        self.send(&cmd)?;
+        self.brightness = BrightnessLevel::Percent100;
+        self.code_page = CodePage::default();
+        self.display_mode = DisplayMode::default();
+        self.glyphs.clear();
+        self.shown.clear();
+        (self.cursor_x, self.cursor_y) = (0, 0);
+        Ok(())
+    }
+
+    /// Block until everything written so far has been sent out of the port.
+    ///
+    /// While batching, the commands collected so far go out first, in a single write.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+    pub fn flush(&mut self) -> Result<(), VfdError> {
+        if let Some(batch) = self.batch.take() {
+            let known_cursor = self.known_cursor;
+            let result = self.transmit(&batch);
+            self.batch = Some(Vec::new());
+            result?;
+            self.known_cursor = known_cursor;
+        }
+        self.port.flush()?;
+        Ok(())
+    }
+
+    /// Collect commands instead of sending each one, until [`Vfd::flush`] or
+    /// [`Vfd::end_batch`] sends them in a single write.
+    ///
+    /// Cursor moves to where the display's cursor already is are left out of
+    /// a batch, so drawing several pieces of text next to each other costs
+    /// one positioning command instead of one each.
+    pub fn begin_batch(&mut self) {
+        self.batch.get_or_insert_with(Vec::new);
+    }
+
+    /// Send everything collected since [`Vfd::begin_batch`] and go back to sending commands as they come.
+    pub fn end_batch(&mut self) -> Result<(), VfdError> {
+        let result = self.flush();
+        self.batch = None;
+        result
+    }
+
+    pub fn is_batching(&self) -> bool {
+        self.batch.is_some()
+    }
+
+    /// Run `f` as one batch, see [`Vfd::begin_batch`].
+    ///
+    /// What `f` drew is sent even if it fails part way. Inside another batch,
+    /// `f` just adds to it.
+    ///
+    /// ```
+    /// use vfd_dsp_v9fb_over_serial::{Align, CommandSet, Simulator};
+    ///
+    /// let simulator = Simulator::new(CommandSet::Birch, 20, 2);
+    /// let mut vfd = simulator.vfd();
+    /// vfd.batch(|vfd| {
+    ///     vfd.write_line(0, "Coffee", Align::Left)?;
+    ///     vfd.write_line(1, "2.50", Align::Right)
+    /// })
+    /// .unwrap();
+    /// assert_eq!(simulator.row(1).trim(), "2.50");
+    /// ```
+    pub fn batch<T, F>(&mut self, f: F) -> Result<T, VfdError>
+    where
+        F: FnOnce(&mut Vfd) -> Result<T, VfdError>,
+    {
+        if self.is_batching() {
+            return f(self);
+        }
+        self.begin_batch();
+        let result = f(self);
+        let sent = self.end_batch();
+        let value = result?;
+        sent?;
+        Ok(value)
+    }
+
+    /// Hand the display to a writer thread so writes stop blocking the caller.
+    ///
+    /// At most `capacity` commands are queued before callers wait for the writer.
+    pub fn into_background(self, capacity: usize) -> BackgroundVfd {
+        BackgroundVfd::new(self, capacity)
+    }
+
+    /// What has been written since the last clear, as the display should show it
+    pub(crate) fn shown(&self) -> &FrameBuffer {
+        &self.shown
+    }
+
+    /// The text on every row, top to bottom, padded to the display width.
+    ///
+    /// This is what was written through this driver since the display was
+    /// last cleared; anything another program sent to the display is not seen.
+    ///
+    /// ```
+    /// use vfd_dsp_v9fb_over_serial::{Align, CommandSet, Simulator};
+    ///
+    /// let mut vfd = Simulator::new(CommandSet::Birch, 10, 2).vfd();
+    /// vfd.write_line(1, "Total", Align::Right).unwrap();
+    /// assert_eq!(vfd.dump(), ["          ", "     Total"]);
+    /// ```
+    pub fn dump(&self) -> Vec<String> {
+        (0..self.height).map(|y| self.shown.row(y)).collect()
+    }
+
+    /// Show or hide the cursor
+    pub fn set_cursor_visible(&mut self, visible: bool) -> Result<(), VfdError> {
+        let cmd = self.command_set.set_cursor_visible(visible);
+        self.send(&cmd)?;
+        Ok(())
+    }
+
+    /// Change how the cursor is drawn, where the command set supports the style
+    pub fn set_cursor_style(&mut self, style: CursorStyle) -> Result<(), VfdError> {
+        let cmd = self.command_set.set_cursor_style(style).ok_or_else(|| {
+            VfdError::NotSupported(format!(
+                "{:?} cursor on {:?} displays",
+                style, self.command_set
+            ))
+        })?;
+        self.send(&cmd)?;
+        Ok(())
+    }
+
+    /// Turn the display on or off without losing its contents
+    pub fn set_display_enabled(&mut self, enabled: bool) -> Result<(), VfdError> {
+        let cmd = self
+            .command_set
+            .set_display_enabled(enabled, self.brightness)
+            .ok_or_else(|| {
+                VfdError::NotSupported(format!(
+                    "Turning {:?} displays on and off",
+                    self.command_set
+                ))
+            })?;
+        self.send(&cmd)?;
+        Ok(())
+    }
+
+    /// Show `text` over whatever is on the display for `duration`, then put
+    /// the previous content back. See [`Vfd::show_alert_with`].
+    pub fn show_alert(&mut self, text: &str, duration: Duration) -> Result<(), VfdError> {
+        self.show_alert_with(&Alert::new(text, duration))
+    }
+
+    /// Show `alert` over whatever is on the display, blocking until it is over
+    /// and the previous content, cursor and settings are restored.
+    ///
+    /// The alert ends early when dismissed through [`Vfd::alert_dismisser`].
+    /// A display blinking through [`Vfd::blink`] holds still for it.
+    ///
+    /// The caller keeps the display to itself all along, so marquees, widgets
+    /// and animations drawing on it through an `Arc<Mutex<_>>` are held off
+    /// while this is called through that lock. [`Alert::show`] lets them go on.
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use vfd_dsp_v9fb_over_serial::{Alert, Align, CommandSet, Simulator};
+    ///
+    /// let simulator = Simulator::new(CommandSet::Birch, 20, 2);
+    /// let mut vfd = simulator.vfd();
+    /// vfd.write_line(1, "Total: 4.20", Align::Right).unwrap();
+    /// vfd.show_alert_with(&Alert::new("Card declined", Duration::from_millis(10))).unwrap();
+    /// assert_eq!(simulator.row(1), "         Total: 4.20");
+    /// ```
+    pub fn show_alert_with(&mut self, alert: &Alert) -> Result<(), VfdError> {
+        let rows = self.begin_alert(alert)?;
+        let dismisser = self.alert_dismisser.clone();
+        let shown = alert.flash(&dismisser, |visible| self.draw_alert(&rows, visible));
+        let restored = self.end_alert();
+        shown?;
+        restored
+    }
+
+    /// A handle that ends the alert on show from another thread, such as one
+    /// watching a button.
+    pub fn alert_dismisser(&self) -> AlertDismisser {
+        self.alert_dismisser.clone()
+    }
+
+    /// Whether an alert is showing, see [`Alert::show`].
+    pub fn is_showing_alert(&self) -> bool {
+        self.alert.is_some()
+    }
+
+    // Put the display under an alert: from here on until end_alert, output
+    // is held back. Returns the rows the alert is drawn with.
+    pub(crate) fn begin_alert(&mut self, alert: &Alert) -> Result<Vec<String>, VfdError> {
+        if self.alert.is_some() {
+            return Err(VfdError::InvalidInput(
+                "An alert is showing already".to_string(),
+            ));
+        }
+        self.flush()?;
+        let lines = alert.rows(&self.text_mapping(), self.width, self.height);
+        let rows = (0..self.height as usize)
+            .map(|y| self.lay_out(lines.get(y).map_or("", String::as_str), alert.align))
+            .collect();
+        // A blinking display would hide the alert half the time
+        if !self.blink_interval.is_zero()
+            && let Some(cmd) = self.command_set.blink(Duration::ZERO)
+        {
+            self.transmit(&cmd)?;
+        }
+        self.alert_dismisser.reset();
+        self.alert = Some(AlertOverlay::new(self.save_state()));
+        Ok(rows)
+    }
+
+    // Show the alert's rows, or blank ones for the dark phase of a blinking alert
+    pub(crate) fn draw_alert(&mut self, rows: &[String], visible: bool) -> Result<(), VfdError> {
+        let Some(overlay) = &self.alert else {
+            return Ok(());
+        };
+        let blank = " ".repeat(self.width as usize);
+        let frame = rows
+            .iter()
+            .enumerate()
+            .map(|(y, row)| (y as u8, if visible { row.as_str() } else { &blank }));
+        let bytes = self.frame_bytes(frame, overlay.under.code_page);
+        self.unheld(|vfd| vfd.transmit(&bytes))
+    }
+
+    // Take the alert down: put back what the display showed before it, then
+    // send what was held back meanwhile
+    pub(crate) fn end_alert(&mut self) -> Result<(), VfdError> {
+        let Some(AlertOverlay { under, held, .. }) = self.alert.take() else {
+            return Ok(());
+        };
+        // A cursor just past the end of a row cannot be moved to, but ends up
+        // there again when that row is written last
+        let Position { x, y } = under.cursor;
+        let past_end = x >= self.width;
+        let mut order: Vec<u8> = (0..self.height)
+            .filter(|&row| !past_end || row != y)
+            .collect();
+        if past_end && y < self.height {
+            order.push(y);
+        }
+        let rows: Vec<(u8, String)> = order
+            .into_iter()
+            .map(|row| (row, under.frame.row(row)))
+            .collect();
+        let frame = rows.iter().map(|(row, text)| (*row, text.as_str()));
+        let mut bytes = self.frame_bytes(frame, under.code_page);
+        if !past_end {
+            bytes.extend(self.command_set.set_cursor(x, y, self.width));
+        }
+        if !under.blink.is_zero()
+            && let Some(cmd) = self.command_set.blink(under.blink)
+        {
+            bytes.extend(cmd);
+        }
+        bytes.extend(held);
+        self.transmit(&bytes)
+    }
+
+    // Bytes drawing whole rows of text, by row number, in `code_page`
+    fn frame_bytes<'a>(
+        &self,
+        rows: impl Iterator<Item = (u8, &'a str)>,
+        code_page: CodePage,
+    ) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        for (y, text) in rows {
+            bytes.extend(self.command_set.set_cursor(0, y, self.width));
+            bytes.extend(encode_text(text, self.command_set.clone(), code_page));
+        }
+        bytes
+    }
+
+    // Run `f` with its output sent even while an alert holds output back
+    fn unheld<T, F>(&mut self, f: F) -> Result<T, VfdError>
+    where
+        F: FnOnce(&mut Vfd) -> Result<T, VfdError>,
+    {
+        let passing = self
+            .alert
+            .as_mut()
+            .map(|overlay| std::mem::replace(&mut overlay.passing, true));
+        let result = f(self);
+        if let (Some(overlay), Some(passing)) = (&mut self.alert, passing) {
+            overlay.passing = passing;
+        }
+        result
+    }
+
+    /// Blink the whole display every `interval`; `Duration::ZERO` stops blinking
+    pub fn blink(&mut self, interval: Duration) -> Result<(), VfdError> {
+        let cmd = self.command_set.blink(interval).ok_or_else(|| {
+            VfdError::NotSupported(format!("Blinking {:?} displays", self.command_set))
+        })?;
+        self.send(&cmd)?;
+        self.blink_interval = interval;
+        Ok(())
+    }
+
+    /// Light or darken one of the icons printed on the display, such as TOTAL.
+    ///
+    /// Fails with [`VfdError::NotSupported`] when the model has no such icon,
+    /// see [`CommandSet::annunciators`].
+    ///
+    /// ```
+    /// use vfd_dsp_v9fb_over_serial::{Annunciator, CommandSet, Simulator};
+    ///
+    /// let simulator = Simulator::new(CommandSet::EpsonDmD, 20, 2);
+    /// let mut vfd = simulator.vfd();
+    /// vfd.set_annunciator(Annunciator::Total, true).unwrap();
+    /// assert!(simulator.annunciator(Annunciator::Total));
+    /// ```
+    pub fn set_annunciator(&mut self, icon: Annunciator, on: bool) -> Result<(), VfdError> {
+        let cmd = self.command_set.set_annunciator(icon, on).ok_or_else(|| {
+            VfdError::NotSupported(format!(
+                "The {} annunciator on {:?} displays",
+                icon, self.command_set
+            ))
+        })?;
+        self.send(&cmd)?;
+        Ok(())
+    }
+
+    /// Change the display brightness
+    pub fn set_brightness(&mut self, level: BrightnessLevel) -> Result<(), VfdError> {
+        if !self.capabilities.supports_brightness {
+            return Err(VfdError::NotSupported(
+                "Changing the brightness".to_string(),
+            ));
+        }
+        let cmd = self.command_set.set_brightness(level);
+        self.send(&cmd)?;
+        self.brightness = level;
+        Ok(())
+    }
+
+    /// Last brightness level sent to the display
+    pub fn brightness(&self) -> BrightnessLevel {
+        self.brightness
+    }
+
+    /// Cursor position as (column, row), following writes the way the display does.
+    ///
+    /// Text that ends exactly at the end of a row leaves the column one past
+    /// the last; the display wraps or scrolls only once the next character arrives.
+    pub fn get_cursor(&self) -> (u8, u8) {
+        (self.cursor_x, self.cursor_y)
+    }
+
+    /// Display geometry as (columns, rows)
+    pub fn size(&self) -> (u8, u8) {
+        (self.width, self.height)
+    }
+
+    pub fn geometry(&self) -> Geometry {
+        Geometry::new(self.width, self.height)
+    }
+
+    /// What the display can do; by default everything its command set has commands for.
+    pub fn capabilities(&self) -> Capabilities {
+        self.capabilities
+    }
+
+    /// Narrow what the display is assumed to do, for models that ignore some
+    /// of their command set. Requests beyond them fail with
+    /// [`VfdError::NotSupported`] instead of being sent.
+    ///
+    /// Fails if `capabilities` is for a display of another size.
+    ///
+    /// ```
+    /// use vfd_dsp_v9fb_over_serial::{CommandSet, Simulator, VfdError};
+    ///
+    /// let simulator = Simulator::new(CommandSet::Birch, 20, 2);
+    /// let mut vfd = simulator.vfd();
+    /// let mut capabilities = vfd.capabilities();
+    /// capabilities.supports_cgram = false;
+    /// vfd.set_capabilities(capabilities).unwrap();
+    /// assert!(matches!(
+    ///     vfd.define_custom_char(0, &[0; 7]),
+    ///     Err(VfdError::NotSupported(_))
+    /// ));
+    /// ```
+    pub fn set_capabilities(&mut self, capabilities: Capabilities) -> Result<(), VfdError> {
+        if capabilities.geometry != self.geometry() {
+            return Err(VfdError::InvalidInput(format!(
+                "Capabilities are for a {} display, not {}",
+                capabilities.geometry,
+                self.geometry()
+            )));
+        }
+        self.capabilities = capabilities;
+        Ok(())
+    }
+
+    /// Select the character table used to render non-ASCII text
+    pub fn set_code_page(&mut self, page: CodePage) -> Result<(), VfdError> {
+        let cmd = self.command_set.set_code_page(page).ok_or_else(|| {
+            VfdError::NotSupported(format!(
+                "Switching code pages on {:?} displays",
+                self.command_set
+            ))
+        })?;
+        self.send(&cmd)?;
+        self.code_page = page;
+        Ok(())
+    }
+
+    /// Select how the display continues when text reaches the end of a line.
+    ///
+    /// In the scroll modes, text that does not fit from the cursor is sent as
+    /// one stream and the display scrolls it, instead of being rejected.
+    pub fn set_display_mode(&mut self, mode: DisplayMode) -> Result<(), VfdError> {
+        let cmd = self
+            .command_set
+            .set_display_mode(mode)
+            .filter(|_| self.capabilities.supports_display_mode(mode))
+            .ok_or_else(|| {
+                VfdError::NotSupported(format!(
+                    "{:?} mode on {:?} displays",
+                    mode, self.command_set
+                ))
+            })?;
+        self.send(&cmd)?;
+        self.display_mode = mode;
+        Ok(())
+    }
+
+    /// Display mode last selected
+    pub fn display_mode(&self) -> DisplayMode {
+        self.display_mode
+    }
+
+    /// Character table text is currently transcoded into
+    pub fn code_page(&self) -> CodePage {
+        self.code_page
+    }
+
+    /// Download a 5x7 bitmap into user-definable character `slot`.
+    ///
+    /// Reference it in text with [`custom_char`](crate::glyph::custom_char).
+    pub fn define_custom_char(&mut self, slot: u8, glyph: &Glyph) -> Result<(), VfdError> {
+        if !self.capabilities.supports_cgram {
+            return Err(VfdError::NotSupported("Custom characters".to_string()));
+        }
+        let code = self
+            .command_set
+            .custom_char_code(slot)
+            .filter(|_| self.capabilities.has_custom_char(slot))
+            .ok_or(VfdError::OutOfBounds {
+                what: "Custom character slot",
+                value: slot as usize,
+                max: (self.capabilities.max_custom_chars as usize).saturating_sub(1),
+            })?;
+        let cmd = self.command_set.define_custom_char(code, glyph);
+        self.send(&cmd)?;
+        self.glyphs.insert(slot, *glyph);
+        Ok(())
+    }
+
+    // Download a glyph unless the slot already holds it
+    fn ensure_custom_char(&mut self, slot: u8, glyph: &Glyph) -> Result<(), VfdError> {
+        if self.glyphs.get(&slot) == Some(glyph) {
+            return Ok(());
+        }
+        self.define_custom_char(slot, glyph)
+    }
+
+    /// Draw `value` in large two-row digits with its left edge at column `x`.
+    pub fn write_big_number(&mut self, value: impl fmt::Display, x: u8) -> Result<(), VfdError> {
+        self.write_big_text(&value.to_string(), x, &BigFont::default())
+    }
+
+    /// Draw `text` in `font` from the top row, with its left edge at column `x`.
+    pub fn write_big_text(&mut self, text: &str, x: u8, font: &BigFont) -> Result<(), VfdError> {
+        let rows = font.render(text)?;
+        if rows.len() > self.height as usize {
+            return Err(VfdError::OutOfBounds {
+                what: "Big font height",
+                value: rows.len(),
+                max: self.height as usize,
+            });
+        }
+        let space = (self.width as usize).saturating_sub(x as usize);
+        let fitting = font.fitting_chars(text, space);
+        if fitting < text.chars().count() {
+            // Counted in big characters, as the text was given
+            return Err(VfdError::TextTooLong {
+                max: fitting,
+                got: text.chars().count(),
+            });
+        }
+        for (slot, glyph) in font.glyphs() {
+            self.ensure_custom_char(*slot, glyph)?;
+        }
+        for (y, row) in rows.iter().enumerate() {
+            self.set_cursor(x, y as u8)?;
+            self.write(row)?;
+        }
+        Ok(())
+    }
+
+    /// Replace row `row` with a bar filled to `fraction` (0.0 to 1.0).
+    ///
+    /// [`ProgressStyle::Blocks`] downloads its glyphs on first use.
+    pub fn draw_progress_bar(
+        &mut self,
+        row: u8,
+        fraction: f32,
+        style: ProgressStyle,
+    ) -> Result<(), VfdError> {
+        for (slot, glyph) in style.glyphs() {
+            self.ensure_custom_char(slot, &glyph)?;
+        }
+        let bar = progress::progress_bar(self.width as usize, fraction, style);
+        self.write_line(row, &bar, Align::Left)
+    }
+
+    /// Replace characters missing from the code page with ASCII look-alikes,
+    /// or `?` where there is none (on by default). When off, writing such a
+    /// character fails with [`VfdError::Encoding`].
+    pub fn set_transliterate(&mut self, enabled: bool) {
+        self.transliterate = enabled;
+    }
+
+    /// Replacements for symbols missing from the code page, such as box
+    /// drawing and arrows ([`SymbolMap::default`] unless set)
+    pub fn set_symbols(&mut self, symbols: SymbolMap) {
+        self.symbols = symbols;
+    }
+
+    pub fn symbols(&self) -> &SymbolMap {
+        &self.symbols
+    }
+
+    /// How text is mapped onto the cells with the current code page,
+    /// transliteration and symbols.
+    pub fn text_mapping(&self) -> TextMapping {
+        TextMapping::new(self.code_page, self.transliterate, self.symbols.clone())
+    }
+
+    /// Show Hebrew and Arabic text right to left (off by default).
+    ///
+    /// Lines written with [`Vfd::write_line`], [`Vfd::write_item_price`] and
+    /// [`Vfd::write_text_with`] are put in the order they are shown in, see
+    /// [`text::visual_order`], and lines starting with a right-to-left
+    /// letter have left and right alignment swapped. The glyphs need a code
+    /// page that has them, such as [`CodePage::Pc862`].
+    ///
+    /// ```
+    /// use vfd_dsp_v9fb_over_serial::{Align, CodePage, CommandSet, Simulator};
+    ///
+    /// let simulator = Simulator::new(CommandSet::Birch, 12, 2);
+    /// let mut vfd = simulator.vfd();
+    /// vfd.set_code_page(CodePage::Pc862).unwrap();
+    /// vfd.set_bidi(true);
+    /// vfd.write_item_price("לחם", 420, 0).unwrap();
+    /// vfd.write_line(1, "תודה", Align::Left).unwrap();
+    /// assert_eq!(simulator.rows(), ["$4.20    םחל", "        הדות"]);
+    /// ```
+    pub fn set_bidi(&mut self, enabled: bool) {
+        self.bidi = enabled;
+    }
+
+    pub fn bidi(&self) -> bool {
+        self.bidi
+    }
+
+    /// How [`Vfd::write_text`] breaks text that runs past the end of a row in overwrite mode (by character by default)
+    pub fn set_wrap_policy(&mut self, policy: WrapPolicy) {
+        self.wrap_policy = policy;
+    }
+
+    pub fn wrap_policy(&self) -> WrapPolicy {
+        self.wrap_policy
+    }
+
+    /// Pace every write for displays that drop bytes at full line speed (off by default)
+    pub fn set_throttle(&mut self, throttle: Throttle) {
+        self.throttle = throttle;
+    }
+
+    pub fn throttle(&self) -> Throttle {
+        self.throttle
+    }
+
+    /// What to leave on the display when this value is dropped (default [`OnDrop::Leave`]).
+    ///
+    /// ```
+    /// use vfd_dsp_v9fb_over_serial::{Align, CommandSet, OnDrop, Simulator};
+    ///
+    /// let simulator = Simulator::new(CommandSet::Birch, 20, 2);
+    /// let mut vfd = simulator.vfd();
+    /// vfd.set_on_drop(OnDrop::Message("CLOSED".to_string()));
+    /// vfd.write_line(1, "Total: 4.20", Align::Right).unwrap();
+    /// drop(vfd);
+    /// assert_eq!(simulator.rows(), ["CLOSED              ", "                    "]);
+    /// ```
+    pub fn set_on_drop(&mut self, on_drop: OnDrop) {
+        self.on_drop = on_drop;
+    }
+
+    pub fn on_drop(&self) -> &OnDrop {
+        &self.on_drop
+    }
+
+    /// Character shown in the last cell of text cut by [`Overflow::Ellipsis`]
+    pub fn set_ellipsis(&mut self, indicator: char) {
+        self.ellipsis = Some(indicator);
+    }
+
+    /// Character marking cut text: the one set with [`Vfd::set_ellipsis`], or
+    /// `…` where the code page has it and `>` where it does not
+    pub fn ellipsis(&self) -> char {
+        self.ellipsis
+            .unwrap_or(match self.code_page.encode_char('…') {
+                Some(_) => '…',
+                None => '>',
+            })
+    }
+
+    /// Currency used by [`Vfd::write_item_price`] (US dollars by default)
+    pub fn set_currency(&mut self, currency: Currency) {
+        self.currency = currency;
+    }
+
+    /// How [`Vfd::write_item_value`] writes numbers (two decimals, no grouping by default)
+    pub fn set_number_format(&mut self, format: NumberFormat) {
+        self.number_format = format;
+    }
+
+    /// Templates available to [`Vfd::render_template`]
+    pub fn set_templates(&mut self, templates: Templates) {
+        self.templates = templates;
+    }
+
+    pub fn templates(&self) -> &Templates {
+        &self.templates
+    }
+
+    // Cut a line to `width` cells and put it in display order when bidi is on,
+    // swapping left and right alignment for a right-to-left line
+    fn reorder(&self, text: &str, width: usize, align: Option<Align>) -> (String, Option<Align>) {
+        if !self.bidi || !text.chars().any(text::is_rtl) {
+            return (text.to_string(), align);
+        }
+        let text = text::truncate_to_width(text, width);
+        let align = match align {
+            Some(Align::Left) if text::is_rtl_line(text) => Some(Align::Right),
+            Some(Align::Right) if text::is_rtl_line(text) => Some(Align::Left),
+            other => other,
+        };
+        (text::visual_order(text), align)
+    }
+
+    fn prepare(&self, text: &str) -> String {
+        prepare_text(text, self.code_page, self.transliterate, &self.symbols)
+    }
+
+    // Without transliteration, nothing stands in for characters the code page lacks
+    fn encode(&self, text: &str) -> Result<Vec<u8>, VfdError> {
+        if !self.transliterate
+            && let Some(c) = unencodable(text, self.code_page)
+        {
+            return Err(VfdError::Encoding(c.to_string()));
+        }
+        Ok(encode_text(text, self.command_set.clone(), self.code_page))
+    }
+
+    // Send text and follow the cursor as the display moves it along, wrapping
+    // or scrolling at the end of a row the way the display mode says
+    fn write(&mut self, text: &str) -> Result<(), VfdError> {
+        let bytes = self.encode(text)?;
+        let start = self.known_cursor;
+        self.send(&bytes)?;
+        for c in text.chars().filter(|c| text::char_width(*c) > 0) {
+            self.put_char(c);
+        }
+        // A cursor just past the end of a row cannot be moved to, so it never counts as known
+        if start.is_some() && self.cursor_x < self.width {
+            self.known_cursor = Some(self.position());
+        }
+        Ok(())
+    }
+
+    // Mirror the display showing `c` at the cursor
+    fn put_char(&mut self, c: char) {
+        if self.width == 0 || self.height == 0 {
+            return;
+        }
+        if self.cursor_x >= self.width {
+            match self.display_mode {
+                DisplayMode::HorizontalScroll => {
+                    let row = self.shown.row(self.cursor_y);
+                    let shifted: String = row.chars().skip(1).collect();
+                    self.shown.put_str(0, self.cursor_y, &shifted);
+                }
+                DisplayMode::VerticalScroll if self.cursor_y + 1 >= self.height => {
+                    self.shown.scroll_up();
+                }
+                _ => {}
+            }
+        }
+        (self.cursor_x, self.cursor_y) =
+            print_cell(self.get_cursor(), self.size(), self.display_mode);
+        self.shown.set(self.cursor_x, self.cursor_y, c);
+        self.cursor_x += 1;
+    }
+
+    /// Show `c` at column `x`, row `y`, sending nothing if the display
+    /// already shows it there. See [`Vfd::update_span`].
+    pub fn update_cell(&mut self, x: u8, y: u8, c: char) -> Result<(), VfdError> {
+        self.update_span(x, y, c.encode_utf8(&mut [0; 4]))
+    }
+
+    /// Show `text` from column `x` on row `y`, cut off at the end of the row,
+    /// sending only the cells that differ from what the display shows.
+    ///
+    /// Meant for values redrawn often, such as a reading updated every second.
+    /// What the display shows is what the driver wrote to it, so bytes sent
+    /// with [`Vfd::send_raw`] are not taken into account.
+    ///
+    /// ```
+    /// use vfd_dsp_v9fb_over_serial::{CommandSet, Simulator};
+    ///
+    /// let simulator = Simulator::new(CommandSet::Birch, 20, 2);
+    /// let mut vfd = simulator.vfd();
+    /// vfd.update_span(0, 0, "Temp 21.4 C").unwrap();
+    /// // Only the changed digit goes out
+    /// vfd.update_span(0, 0, "Temp 21.5 C").unwrap();
+    /// assert_eq!(simulator.row(0).trim_end(), "Temp 21.5 C");
+    /// ```
+    pub fn update_span(&mut self, x: u8, y: u8, text: &str) -> Result<(), VfdError> {
+        Position::new(x, y).check(self.size())?;
+        let text = self.prepare(text);
+        for span in self.shown.changes(x, y, &text) {
+            self.set_cursor(span.x, span.y)?;
+            self.write(&span.text)?;
+        }
+        Ok(())
+    }
+
+    /// Write a single line to the display
+    pub fn writeln(&mut self, text: &str) -> Result<(), VfdError> {
+        let text = self.prepare(text);
+        self.write(&text)
+    }
+
+    /// Write a single line to the display and truncate if necessary
+    pub fn writeln_truncate(&mut self, text: &str) -> Result<(), VfdError> {
+        let text = self.prepare(text);
+        self.write_truncated(&text)
+    }
+
+    fn write_truncated(&mut self, text: &str) -> Result<(), VfdError> {
+        let space_available = self.get_space_available_on_line();
+        let truncated_str = text::truncate_to_width(text, space_available);
+
+        self.write(truncated_str)
+    }
+
+    fn write_multi_line(&mut self, text: &str) -> Result<(), VfdError> {
+        let mut remaining = text;
+        while !remaining.is_empty() {
+            let (cursor_x, cursor_y) = self.get_cursor();
+            let space_available = (self.width - cursor_x) as usize;
+            let (chunk, rest) = text::split_at_width(remaining, space_available);
+            let chunk = chunk.trim().to_string();
+
+            self.write(&chunk)?;
+            remaining = rest;
+
+            if remaining.is_empty() {
+                break;
+            } else {
+                self.set_cursor(0, cursor_y + 1)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    // Write text broken into rows by the wrap policy, sending nothing if it needs more rows than are left
+    fn write_wrapped(&mut self, text: &str) -> Result<(), VfdError> {
+        let (cursor_x, cursor_y) = self.get_cursor();
+        let first = self.width.saturating_sub(cursor_x) as usize;
+        let rows = text::wrap(text, first, self.width as usize, self.wrap_policy);
+        let rows_left = (self.height - cursor_y) as usize;
+        if rows.len() > rows_left {
+            return Err(VfdError::TextTooLong {
+                max: first + (rows_left - 1) * self.width as usize,
+                got: text::display_width(text),
+            });
+        }
+        for (i, row) in rows.iter().enumerate() {
+            if i > 0 {
+                self.set_cursor(0, cursor_y + i as u8)?;
+            }
+            if !row.is_empty() {
+                self.write(row)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn get_space_available_on_line(&self) -> usize {
+        let (cursor_x, _) = self.get_cursor();
+        self.width.saturating_sub(cursor_x) as usize
+    }
+
+    /// A `width × height` part of the display starting at column `x`, row `y`, with its own cursor.
+    pub fn region(&self, x: u8, y: u8, width: u8, height: u8) -> Result<Region, VfdError> {
+        if x as usize + width as usize > self.width as usize {
+            return Err(VfdError::OutOfBounds {
+                what: "Region right edge",
+                value: x as usize + width as usize,
+                max: self.width as usize,
+            });
+        }
+        if y as usize + height as usize > self.height as usize {
+            return Err(VfdError::OutOfBounds {
+                what: "Region bottom edge",
+                value: y as usize + height as usize,
+                max: self.height as usize,
+            });
+        }
+        Ok(Region::new(x, y, width, height))
+    }
+
+    /// Create a marquee that scrolls `text` across `row` of this display.
+    ///
+    /// Nothing is drawn until the marquee is ticked or spawned.
+    pub fn marquee(
+        &self,
+        text: &str,
+        row: u8,
+        speed: Duration,
+        direction: ScrollDirection,
+    ) -> Marquee {
+        Marquee::new(&self.prepare(text), row, self.width, speed, direction)
+    }
+
+    pub fn write_text(&mut self, text: &str) -> Result<(), VfdError> {
+        self.write_text_handler(text, false)
+    }
+
+    /// Write text with explicit control over where it starts, how it is broken
+    /// across rows and what happens when it does not fit.
+    ///
+    /// Ignores the display mode and [`Vfd::wrap_policy`]; everything comes from `options`.
+    pub fn write_text_with(&mut self, text: &str, options: WriteOptions) -> Result<(), VfdError> {
+        // Checked first so that nothing is sent for a style the display lacks
+        let (style_on, style_off) = self.style_commands(options.style)?;
+        if let Some(start) = options.start {
+            self.set_position(start)?;
+        }
+        let text = match options.fit {
+            FitPolicy::Strict => self.prepare(text),
+            FitPolicy::Compact => self.prepare(&text::compact(text)),
+        };
+        let (cursor_x, cursor_y) = self.get_cursor();
+        let room = self.width.saturating_sub(cursor_x) as usize;
+        let indicator = self.ellipsis().to_string();
+        let layout = |line: &str, room: usize| match options.overflow {
+            Overflow::Truncate => vec![text::truncate_to_width(line, room).to_string()],
+            Overflow::Ellipsis => vec![layout::ellipsize(line, room, &indicator)],
+            Overflow::Error | Overflow::Scroll => {
+                text::wrap(line, room, self.width as usize, options.wrap)
+            }
+        };
+        // Compacted lines each start a new row
+        let mut rows: Vec<String> = match options.fit {
+            FitPolicy::Strict => layout(&text, room),
+            FitPolicy::Compact => text
+                .split('\n')
+                .enumerate()
+                .flat_map(|(i, line)| layout(line, if i == 0 { room } else { self.width as usize }))
+                .collect(),
+        };
+        let rows_left = (self.height - cursor_y) as usize;
+        // Text that is cut off is cut at the bottom row too, rather than scrolled
+        if matches!(options.overflow, Overflow::Truncate | Overflow::Ellipsis) {
+            rows.truncate(rows_left);
+        }
+        if options.overflow == Overflow::Error && rows.len() > rows_left {
+            return Err(VfdError::TextTooLong {
+                max: room + (rows_left - 1) * self.width as usize,
+                got: text::display_width(&text),
+            });
+        }
+
+        let mut y = cursor_y;
+        for (i, row) in rows.iter().enumerate() {
+            let (x, width) = if i == 0 {
+                (cursor_x, room)
+            } else if y + 1 < self.height {
+                y += 1;
+                (0, self.width as usize)
+            } else {
+                self.scroll_up()?;
+                (0, self.width as usize)
+            };
+            let row = match self.reorder(row, width, options.align) {
+                (row, Some(align)) => layout::align(&row, width, align),
+                (row, None) => row,
+            };
+            if row.is_empty() {
+                continue;
+            }
+            if i > 0 {
+                self.set_cursor(x, y)?;
+            }
+            self.write_styled(&row, &style_on, &style_off)?;
+            if options.style.blink {
+                if self.soft_blink.spans.is_empty() {
+                    self.soft_blink.last_toggle = Instant::now();
+                }
+                self.soft_blink.spans.push(BlinkSpan {
+                    x,
+                    y,
+                    text: row,
+                    style: TextStyle {
+                        blink: false,
+                        ..options.style
+                    },
+                    visible: true,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    // Bytes that turn the attributes of `style` on and back off, apart from
+    // blinking, which is done in software
+    fn style_commands(&self, style: TextStyle) -> Result<(Vec<u8>, Vec<u8>), VfdError> {
+        let command_set = &self.command_set;
+        let unsupported =
+            |what: &str| VfdError::NotSupported(format!("{} on {:?} displays", what, command_set));
+        let (mut on, mut off) = (Vec::new(), Vec::new());
+        if style.reverse {
+            let (Some(start), Some(end)) = (
+                command_set.set_reverse(true),
+                command_set.set_reverse(false),
+            ) else {
+                return Err(unsupported("Reverse text"));
+            };
+            on.extend(start);
+            off.extend(end);
+        }
+        if style.underline {
+            let (Some(start), Some(end)) = (
+                command_set.set_underline(true),
+                command_set.set_underline(false),
+            ) else {
+                return Err(unsupported("Underlined text"));
+            };
+            on.extend(start);
+            off.extend(end);
+        }
+        Ok((on, off))
+    }
+
+    // Send attribute commands, if there are any, around `text`
+    fn write_styled(&mut self, text: &str, on: &[u8], off: &[u8]) -> Result<(), VfdError> {
+        if !on.is_empty() {
+            self.send(on)?;
+        }
+        self.write(text)?;
+        if !off.is_empty() {
+            self.send(off)?;
+        }
+        Ok(())
+    }
+
+    /// Show or blank the text written with [`TextStyle::blink`], whichever is
+    /// due, once the blink interval has passed. Returns whether the display
+    /// was updated.
+    ///
+    /// Call this from your own loop, or use
+    /// [`spawn_blink`](crate::spawn_blink). Text written over
+    /// blinking text, or cleared, stops blinking.
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use vfd_dsp_v9fb_over_serial::{CommandSet, Simulator, TextStyle, WriteOptions};
+    ///
+    /// let simulator = Simulator::new(CommandSet::Birch, 20, 2);
+    /// let mut vfd = simulator.vfd();
+    /// vfd.set_text_blink_interval(Duration::ZERO);
+    /// vfd.write_text_with("ALARM", WriteOptions::default().style(TextStyle::default().blink()))
+    ///     .unwrap();
+    /// assert!(vfd.tick_blink().unwrap());
+    /// assert_eq!(simulator.row(0).trim(), "");
+    /// vfd.tick_blink().unwrap();
+    /// assert_eq!(simulator.row(0).trim(), "ALARM");
+    /// ```
+    pub fn tick_blink(&mut self) -> Result<bool, VfdError> {
+        self.forget_overwritten_blinks()?;
+        if self.soft_blink.spans.is_empty()
+            || self.soft_blink.last_toggle.elapsed() < self.soft_blink.interval
+        {
+            return Ok(false);
+        }
+        let visible = !self.soft_blink.visible;
+        self.draw_blinking(visible)?;
+        self.soft_blink.visible = visible;
+        self.soft_blink.last_toggle = Instant::now();
+        Ok(true)
+    }
+
+    /// Stop blinking text, leaving it shown.
+    pub fn stop_text_blink(&mut self) -> Result<(), VfdError> {
+        self.forget_overwritten_blinks()?;
+        self.draw_blinking(true)?;
+        self.soft_blink.spans.clear();
+        Ok(())
+    }
+
+    // Stop blinking text that other text was written over, showing again
+    // what is left of it when it was blanked
+    fn forget_overwritten_blinks(&mut self) -> Result<(), VfdError> {
+        let (spans, overwritten): (Vec<BlinkSpan>, Vec<BlinkSpan>) =
+            std::mem::take(&mut self.soft_blink.spans)
+                .into_iter()
+                .partition(|span| span.is_intact(&self.shown));
+        self.soft_blink.spans = spans;
+        let blanked: Vec<BlinkSpan> = overwritten
+            .into_iter()
+            .filter(|span| !span.visible)
+            .collect();
+        if blanked.is_empty() {
+            return Ok(());
+        }
+        let (cursor_x, cursor_y) = self.get_cursor();
+        for span in blanked {
+            let (on, off) = self.style_commands(span.style)?;
+            for (c, x) in span.text.chars().zip(span.x..) {
+                if self.shown.get(x, span.y) == Some(c) {
+                    self.set_cursor(x, span.y)?;
+                    self.write_styled(&c.to_string(), &on, &off)?;
+                }
+            }
+        }
+        self.restore_cursor(cursor_x, cursor_y)
+    }
+
+    // Show or blank every blinking span not in that state yet, putting the cursor back afterwards
+    fn draw_blinking(&mut self, visible: bool) -> Result<(), VfdError> {
+        let (cursor_x, cursor_y) = self.get_cursor();
+        let mut spans = std::mem::take(&mut self.soft_blink.spans);
+        let result: Result<(), VfdError> = spans
+            .iter_mut()
+            .filter(|span| span.visible != visible)
+            .try_for_each(|span| {
+                self.set_cursor(span.x, span.y)?;
+                if visible {
+                    let (on, off) = self.style_commands(span.style)?;
+                    self.write_styled(&span.text, &on, &off)?;
+                } else {
+                    // Only the display goes blank; the text stays what it shows
+                    self.write(&" ".repeat(span.text.chars().count()))?;
+                    self.shown.put_mapped(span.x, span.y, &span.text);
+                }
+                span.visible = visible;
+                Ok(())
+            });
+        self.soft_blink.spans = spans;
+        result?;
+        self.restore_cursor(cursor_x, cursor_y)
+    }
+
+    /// How long blinking text stays shown and then blank (500 ms by default).
+    pub fn set_text_blink_interval(&mut self, interval: Duration) {
+        self.soft_blink.interval = interval;
+    }
+
+    pub fn text_blink_interval(&self) -> Duration {
+        self.soft_blink.interval
+    }
+
+    // Move every row up one and blank the bottom one
+    fn scroll_up(&mut self) -> Result<(), VfdError> {
+        self.shown.scroll_up();
+        for y in 0..self.height {
+            let row = self.shown.row(y);
+            self.set_cursor(0, y)?;
+            self.write(&row)?;
+        }
+        Ok(())
+    }
+
+    /// Replace row `row` with `text`, aligned and padded to the full width.
+    ///
+    /// Text longer than the line is truncated.
+    pub fn write_line(&mut self, row: u8, text: &str, align: Align) -> Result<(), VfdError> {
+        if row >= self.height {
+            return Err(VfdError::OutOfBounds {
+                what: "Row",
+                value: row as usize,
+                max: self.height as usize - 1,
+            });
+        }
+        let text = self.lay_out(&self.prepare(text), align);
+        self.set_cursor(0, row)?;
+        self.write(&text)
+    }
+
+    // Prepared text as a whole row shows it
+    fn lay_out(&self, text: &str, align: Align) -> String {
+        let width = self.width as usize;
+        match self.reorder(text, width, Some(align)) {
+            (text, Some(align)) => layout::align(&text, width, align),
+            (text, None) => text,
+        }
+    }
+
+    /// Replace row `row` with formatted text, left-aligned.
+    ///
+    /// ```
+    /// use vfd_dsp_v9fb_over_serial::{CommandSet, Simulator};
+    ///
+    /// let simulator = Simulator::new(CommandSet::Birch, 20, 2);
+    /// let mut vfd = simulator.vfd();
+    /// vfd.write_fmt_line(0, format_args!("{} items", 3)).unwrap();
+    /// assert_eq!(simulator.row(0).trim_end(), "3 items");
+    /// ```
+    pub fn write_fmt_line(&mut self, row: u8, args: fmt::Arguments<'_>) -> Result<(), VfdError> {
+        self.write_line(row, &fmt::format(args), Align::Left)
+    }
+
+    /// A handle that `write!` and `writeln!` can format text into, starting at row `row`.
+    ///
+    /// See [`Line`] for how its text reaches the display.
+    pub fn line(&mut self, row: u8) -> Line<'_> {
+        Line::new(self, row)
+    }
+
+    /// Show `name` flush left and `price` (in minor units, e.g. cents) flush right on `row`.
+    ///
+    /// The name is cut short when both do not fit; the price is always shown whole.
+    pub fn write_item_price(&mut self, name: &str, price: i64, row: u8) -> Result<(), VfdError> {
+        let price = self.prepare(&self.currency.format(price));
+        let line = layout::justify(&self.prepare(name), &price, self.width as usize);
+        self.write_line(row, &line, Align::Left)
+    }
+
+    /// Show `label` flush left and `value` flush right on `row`, written in the
+    /// number format.
+    ///
+    /// The label is cut short when both do not fit; the value is always shown whole.
+    ///
+    /// ```
+    /// use vfd_dsp_v9fb_over_serial::{CommandSet, Locale, Simulator};
+    ///
+    /// let simulator = Simulator::new(CommandSet::Birch, 20, 2);
+    /// let mut vfd = simulator.vfd();
+    /// vfd.set_number_format(Locale::DeDe.number_format().decimals(1));
+    /// vfd.write_item_value("Gewicht kg", 1250.26, 0).unwrap();
+    /// assert_eq!(simulator.row(0), "Gewicht kg   1.250,3");
+    /// ```
+    pub fn write_item_value(&mut self, label: &str, value: f64, row: u8) -> Result<(), VfdError> {
+        let value = self.prepare(&self.number_format.format(value));
+        let line = layout::justify(&self.prepare(label), &value, self.width as usize);
+        self.write_line(row, &line, Align::Left)
+    }
+
+    /// Draw the template called `name`, filling its placeholders from `values`.
+    pub fn render_template(&mut self, name: &str, values: &[(&str, &str)]) -> Result<(), VfdError> {
+        let templates = std::mem::take(&mut self.templates);
+        let result = templates.render(self, name, values);
+        self.templates = templates;
+        result
+    }
+
+    pub fn write_text_truncate(&mut self, text: &str) -> Result<(), VfdError> {
+        self.write_text_handler(text, true)
+    }
+
+    fn write_text_handler(&mut self, text: &str, truncate: bool) -> Result<(), VfdError> {
+        let text = &self.prepare(text);
+        match self.get_text_fit(text, truncate) {
+            TextFit::OneLine => self.write(text),
+            TextFit::OneLineTruncated => self.write_truncated(text),
+            // Let the hardware wrap and scroll
+            TextFit::NeedsWrap | TextFit::TooLong
+                if self.display_mode != DisplayMode::Overwrite =>
+            {
+                self.write(text)
+            }
+            TextFit::NeedsWrap | TextFit::TooLong if self.wrap_policy != WrapPolicy::Character => {
+                self.write_wrapped(text)
+            }
+            TextFit::NeedsWrap => self.write_multi_line(text),
+            TextFit::TooLong => Err(too_long(text, self.size(), self.get_cursor())),
+        }
+    }
+
+    /// Determine if the text fits on the display and how to handle it
+    ///  based on the current cursor position, display size,
+    ///  and user preferences for wrapping and truncation.
+    pub fn get_text_fit(&self, text: &str, truncate: bool) -> TextFit {
+        text_fit(
+            &self.prepare(text),
+            self.size(),
+            self.get_cursor(),
+            truncate,
+        )
+    }
+}
+
+/// Cell the display prints the next character in with the cursor at `cursor`.
+///
+/// A cursor past the end of a row moves on first: to the start of the next
+/// row in overwrite mode (the top row after the bottom one), to the start of
+/// the next row or the bottom row scrolled up in vertical scroll mode, and
+/// onto the last cell of the row, scrolled left, in horizontal scroll mode.
+pub(crate) fn print_cell(cursor: (u8, u8), size: (u8, u8), mode: DisplayMode) -> (u8, u8) {
+    let ((x, y), (width, height)) = (cursor, size);
+    if x < width {
+        return (x, y);
+    }
+    match mode {
+        DisplayMode::Overwrite => (0, (y + 1) % height.max(1)),
+        DisplayMode::VerticalScroll => (0, (y + 1).min(height.saturating_sub(1))),
+        DisplayMode::HorizontalScroll => (width.saturating_sub(1), y),
+    }
+}
+
+// How already prepared text fits a `size` display with the cursor at `cursor`
+pub(crate) fn text_fit(text: &str, size: (u8, u8), cursor: (u8, u8), truncate: bool) -> TextFit {
+    // Work in usize: text can be longer than 255 cells and so can larger displays
+    let text_length = text::display_width(text);
+    let (width, height) = (size.0 as usize, size.1 as usize);
+    let (cursor_x, cursor_y) = (cursor.0 as usize, cursor.1 as usize);
+    let space_left_on_line = width.saturating_sub(cursor_x);
+    let lines_left = height.saturating_sub(cursor_y + 1);
+
+    if text_length <= space_left_on_line {
+        return TextFit::OneLine;
+    }
+
+    if cursor_x < width && truncate {
+        return TextFit::OneLineTruncated;
+    }
+
+    // Text is longer than one line, but still would fit if wrapped
+    if space_left_on_line + (lines_left * width) >= text_length {
+        TextFit::NeedsWrap
+    } else {
+        TextFit::TooLong
+    }
+}
+
+// The error for already prepared text found by `text_fit` not to fit
+pub(crate) fn too_long(text: &str, size: (u8, u8), cursor: (u8, u8)) -> VfdError {
+    let (width, height) = (size.0 as usize, size.1 as usize);
+    let (cursor_x, cursor_y) = (cursor.0 as usize, cursor.1 as usize);
+    VfdError::TextTooLong {
+        max: width.saturating_sub(cursor_x) + height.saturating_sub(cursor_y + 1) * width,
+        got: text::display_width(text),
+    }
+}
+
+// A failed read, telling write-only transports apart from broken ones
+fn read_error(e: io::Error) -> VfdError {
+    match e.kind() {
+        io::ErrorKind::Unsupported => {
+            VfdError::NotSupported("Reading from this transport".to_string())
+        }
+        _ => e.into(),
+    }
+}
+
+impl Drop for Vfd {
+    fn drop(&mut self) {
+        let on_drop = std::mem::take(&mut self.on_drop);
+        // Only when unwinding from the middle of an alert
+        let _ = self.end_alert();
+        // Don't hold up the exit waiting for a display that went away
+        #[cfg(feature = "serial")]
+        {
+            self.reconnecting = true;
+        }
+        // Whatever was still batched goes out first, so the policy acts on
+        // the screen the caller last drew
+        if self.flush().is_err() {
+            return;
+        }
+        let result = match on_drop {
+            OnDrop::Leave => return,
+            OnDrop::Clear => self.clear(),
+            OnDrop::Message(text) => self.clear().and_then(|()| self.write_text(&text)),
+            OnDrop::DisplayOff => self.set_display_enabled(false),
+        };
+        let _ = result.and_then(|()| self.flush());
+    }
+}
+
+impl VfdDisplay for Vfd {
+    fn size(&self) -> (u8, u8) {
+        Vfd::size(self)
+    }
+
+    fn clear(&mut self) -> Result<(), VfdError> {
+        Vfd::clear(self)
+    }
+
+    fn set_cursor(&mut self, x: u8, y: u8) -> Result<(), VfdError> {
+        Vfd::set_cursor(self, x, y)
+    }
+
+    fn get_cursor(&self) -> (u8, u8) {
+        Vfd::get_cursor(self)
+    }
+
+    fn text_mapping(&self) -> TextMapping {
+        Vfd::text_mapping(self)
+    }
+
+    fn write_text(&mut self, text: &str) -> Result<(), VfdError> {
+        Vfd::write_text(self, text)
+    }
+
+    fn write_line(&mut self, row: u8, text: &str, align: Align) -> Result<(), VfdError> {
+        Vfd::write_line(self, row, text, align)
+    }
+
+    fn update_span(&mut self, x: u8, y: u8, text: &str) -> Result<(), VfdError> {
+        Vfd::update_span(self, x, y, text)
+    }
+
+    fn set_brightness(&mut self, level: BrightnessLevel) -> Result<(), VfdError> {
+        Vfd::set_brightness(self, level)
+    }
+}
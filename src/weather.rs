@@ -0,0 +1,384 @@
+//! Current weather screen (`weather` feature).
+//!
+//! Fetches the temperature and conditions from [Open-Meteo](https://open-meteo.com)
+//! (no key needed) or [OpenWeatherMap](https://openweathermap.org) and takes
+//! turns with whatever else is on the display: every so often the weather is
+//! drawn for a few seconds, with an icon in a custom glyph, and then the
+//! previous content is put back.
+
+use crate::error::VfdError;
+use crate::glyph::{self, Glyph, custom_char};
+use crate::layout::{self, Align};
+use crate::logging;
+use crate::vfd::Vfd;
+use serde_json::Value;
+use std::fmt;
+use std::io;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const HTTP_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Custom glyph slot the icon is downloaded into.
+pub const ICON_SLOT: u8 = 0;
+
+/// Where the weather comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WeatherProvider {
+    /// Open-Meteo, free without an API key.
+    #[default]
+    OpenMeteo,
+    /// OpenWeatherMap, which needs [`WeatherConfig::api_key`].
+    OpenWeatherMap,
+}
+
+impl FromStr for WeatherProvider {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "open-meteo" | "openmeteo" => Ok(WeatherProvider::OpenMeteo),
+            "openweathermap" | "owm" => Ok(WeatherProvider::OpenWeatherMap),
+            _ => Err(format!(
+                "Unknown weather provider '{}'. Expected one of open-meteo, openweathermap.",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for WeatherProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WeatherProvider::OpenMeteo => write!(f, "open-meteo"),
+            WeatherProvider::OpenWeatherMap => write!(f, "openweathermap"),
+        }
+    }
+}
+
+/// Whether temperatures are in Celsius or Fahrenheit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Units {
+    #[default]
+    Metric,
+    Imperial,
+}
+
+impl Units {
+    /// `C` or `F`.
+    pub fn symbol(&self) -> char {
+        match self {
+            Units::Metric => 'C',
+            Units::Imperial => 'F',
+        }
+    }
+}
+
+impl FromStr for Units {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "metric" | "c" | "celsius" => Ok(Units::Metric),
+            "imperial" | "f" | "fahrenheit" => Ok(Units::Imperial),
+            _ => Err(format!(
+                "Unknown units '{}'. Expected one of metric, imperial.",
+                s
+            )),
+        }
+    }
+}
+
+/// Location, provider and how the weather takes turns with other content.
+#[derive(Debug, Clone)]
+pub struct WeatherConfig {
+    pub provider: WeatherProvider,
+    pub api_key: Option<String>,
+    pub latitude: f64,
+    pub longitude: f64,
+    /// Place name shown on the second row, if any.
+    pub location: Option<String>,
+    pub units: Units,
+    /// How often the weather is fetched again.
+    pub refresh_interval: Duration,
+    /// Time from one turn of the weather on the display to the next.
+    pub show_every: Duration,
+    /// How long the weather stays up each turn.
+    pub show_for: Duration,
+}
+
+impl Default for WeatherConfig {
+    fn default() -> Self {
+        WeatherConfig {
+            provider: WeatherProvider::default(),
+            api_key: None,
+            latitude: 0.0,
+            longitude: 0.0,
+            location: None,
+            units: Units::default(),
+            refresh_interval: Duration::from_secs(600),
+            show_every: Duration::from_secs(60),
+            show_for: Duration::from_secs(10),
+        }
+    }
+}
+
+/// Kind of weather, as far as a single icon can tell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Conditions {
+    Clear,
+    PartlyCloudy,
+    Cloudy,
+    Fog,
+    Drizzle,
+    Rain,
+    Snow,
+    Thunderstorm,
+}
+
+impl Conditions {
+    /// Conditions for a WMO weather interpretation code, as Open-Meteo reports them.
+    pub fn from_wmo_code(code: u64) -> Option<Self> {
+        match code {
+            0 => Some(Conditions::Clear),
+            1 | 2 => Some(Conditions::PartlyCloudy),
+            3 => Some(Conditions::Cloudy),
+            45 | 48 => Some(Conditions::Fog),
+            51..=57 => Some(Conditions::Drizzle),
+            61..=67 | 80..=82 => Some(Conditions::Rain),
+            71..=77 | 85 | 86 => Some(Conditions::Snow),
+            95..=99 => Some(Conditions::Thunderstorm),
+            _ => None,
+        }
+    }
+
+    /// Conditions for an OpenWeatherMap condition id.
+    pub fn from_openweathermap_id(id: u64) -> Option<Self> {
+        match id {
+            200..=299 => Some(Conditions::Thunderstorm),
+            300..=399 => Some(Conditions::Drizzle),
+            500..=599 => Some(Conditions::Rain),
+            600..=699 => Some(Conditions::Snow),
+            700..=799 => Some(Conditions::Fog),
+            800 => Some(Conditions::Clear),
+            801 | 802 => Some(Conditions::PartlyCloudy),
+            803 | 804 => Some(Conditions::Cloudy),
+            _ => None,
+        }
+    }
+
+    /// Icon for the conditions.
+    pub fn glyph(&self) -> Glyph {
+        match self {
+            Conditions::Clear => glyph::SUN,
+            Conditions::PartlyCloudy => glyph::PARTLY_CLOUDY,
+            Conditions::Cloudy => glyph::CLOUD,
+            Conditions::Fog => glyph::FOG,
+            Conditions::Drizzle => glyph::DRIZZLE,
+            Conditions::Rain => glyph::RAIN,
+            Conditions::Snow => glyph::SNOW,
+            Conditions::Thunderstorm => glyph::THUNDERSTORM,
+        }
+    }
+}
+
+impl fmt::Display for Conditions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Conditions::Clear => "Clear",
+            Conditions::PartlyCloudy => "Partly cloudy",
+            Conditions::Cloudy => "Cloudy",
+            Conditions::Fog => "Fog",
+            Conditions::Drizzle => "Drizzle",
+            Conditions::Rain => "Rain",
+            Conditions::Snow => "Snow",
+            Conditions::Thunderstorm => "Thunderstorm",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Weather right now.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Weather {
+    /// In the units it was fetched in.
+    pub temperature: f64,
+    pub conditions: Conditions,
+}
+
+/// Read the weather out of an Open-Meteo `/v1/forecast` response asking for
+/// `current=temperature_2m,weather_code`.
+pub fn parse_open_meteo(body: &str) -> Result<Weather, VfdError> {
+    let json = parse_json(body)?;
+    let current = &json["current"];
+    Ok(Weather {
+        temperature: number(&current["temperature_2m"], "current.temperature_2m")?,
+        conditions: current["weather_code"]
+            .as_u64()
+            .and_then(Conditions::from_wmo_code)
+            .ok_or_else(|| unexpected("current.weather_code"))?,
+    })
+}
+
+/// Read the weather out of an OpenWeatherMap `/data/2.5/weather` response.
+pub fn parse_openweathermap(body: &str) -> Result<Weather, VfdError> {
+    let json = parse_json(body)?;
+    Ok(Weather {
+        temperature: number(&json["main"]["temp"], "main.temp")?,
+        conditions: json["weather"][0]["id"]
+            .as_u64()
+            .and_then(Conditions::from_openweathermap_id)
+            .ok_or_else(|| unexpected("weather[0].id"))?,
+    })
+}
+
+fn parse_json(body: &str) -> Result<Value, VfdError> {
+    serde_json::from_str(body)
+        .map_err(|e| VfdError::InvalidInput(format!("Invalid weather response: {}", e)))
+}
+
+fn number(value: &Value, what: &str) -> Result<f64, VfdError> {
+    value.as_f64().ok_or_else(|| unexpected(what))
+}
+
+fn unexpected(what: &str) -> VfdError {
+    VfdError::InvalidInput(format!("Weather response has no usable {}", what))
+}
+
+/// Fetch the current weather from the configured provider.
+pub fn fetch(config: &WeatherConfig) -> Result<Weather, VfdError> {
+    match config.provider {
+        WeatherProvider::OpenMeteo => {
+            let unit = match config.units {
+                Units::Metric => "celsius",
+                Units::Imperial => "fahrenheit",
+            };
+            let url = format!(
+                "https://api.open-meteo.com/v1/forecast?latitude={}&longitude={}&current=temperature_2m,weather_code&temperature_unit={}",
+                config.latitude, config.longitude, unit
+            );
+            parse_open_meteo(&http_get(&url)?)
+        }
+        WeatherProvider::OpenWeatherMap => {
+            let key = config.api_key.as_deref().ok_or_else(|| {
+                VfdError::InvalidInput("OpenWeatherMap needs an API key".to_string())
+            })?;
+            let units = match config.units {
+                Units::Metric => "metric",
+                Units::Imperial => "imperial",
+            };
+            let url = format!(
+                "https://api.openweathermap.org/data/2.5/weather?lat={}&lon={}&units={}&appid={}",
+                config.latitude, config.longitude, units, key
+            );
+            parse_openweathermap(&http_get(&url)?)
+        }
+    }
+}
+
+fn http_get(url: &str) -> Result<String, VfdError> {
+    // The URL is left out of errors as it may hold the API key
+    ureq::get(url)
+        .timeout(HTTP_TIMEOUT)
+        .call()
+        .map_err(|e| io::Error::other(format!("Weather request failed: {}", e.kind())))?
+        .into_string()
+        .map_err(VfdError::Io)
+}
+
+/// Draw `weather` over the whole display.
+///
+/// The icon goes into custom glyph [`ICON_SLOT`], or is left out on displays
+/// without custom glyphs.
+///
+/// ```
+/// use vfd_dsp_v9fb_over_serial::weather::{self, Conditions, Weather, WeatherConfig};
+/// use vfd_dsp_v9fb_over_serial::{CommandSet, Simulator};
+///
+/// let simulator = Simulator::new(CommandSet::Birch, 20, 2);
+/// let mut vfd = simulator.vfd();
+/// let config = WeatherConfig {
+///     location: Some("Lisbon".to_string()),
+///     ..WeatherConfig::default()
+/// };
+/// let now = Weather { temperature: 21.4, conditions: Conditions::Rain };
+/// weather::show(&mut vfd, &now, &config).unwrap();
+/// assert_eq!(vfd.dump()[0], "\u{E000} Rain          21°C");
+/// assert_eq!(vfd.dump()[1], "       Lisbon       ");
+/// ```
+pub fn show(vfd: &mut Vfd, weather: &Weather, config: &WeatherConfig) -> Result<(), VfdError> {
+    let (width, height) = vfd.size();
+    let conditions = match vfd.define_custom_char(ICON_SLOT, &weather.conditions.glyph()) {
+        Ok(()) => format!("{} {}", custom_char(ICON_SLOT), weather.conditions),
+        Err(VfdError::NotSupported(_) | VfdError::OutOfBounds { .. }) => {
+            weather.conditions.to_string()
+        }
+        Err(e) => return Err(e),
+    };
+    let temperature = format!("{:.0}°{}", weather.temperature, config.units.symbol());
+    let rows = [
+        layout::justify(&conditions, &temperature, width as usize),
+        config.location.clone().unwrap_or_default(),
+    ];
+    vfd.batch(|vfd| {
+        for y in 0..height {
+            let row = rows.get(y as usize).map_or("", String::as_str);
+            vfd.write_line(y, row, Align::Center)?;
+        }
+        Ok(())
+    })
+}
+
+/// Fetch the weather and show it in turns with other content until the
+/// process exits.
+///
+/// Fetch errors are logged, and the last weather fetched keeps being shown.
+/// Each turn is a [`take_turn`].
+pub fn serve_weather(vfd: Arc<Mutex<Vfd>>, config: &WeatherConfig) {
+    let mut latest: Option<(Weather, Instant)> = None;
+    loop {
+        let started = Instant::now();
+        if latest.is_none_or(|(_, at)| at.elapsed() >= config.refresh_interval) {
+            match fetch(config) {
+                Ok(weather) => latest = Some((weather, Instant::now())),
+                Err(e) => logging::warning!("Could not fetch the weather: {}", e),
+            }
+        }
+        if let Some((weather, _)) = &latest
+            && let Err(e) = take_turn(&vfd, weather, config)
+        {
+            logging::warning!("Could not show the weather: {}", e);
+        }
+        thread::sleep(config.show_every.saturating_sub(started.elapsed()));
+    }
+}
+
+/// Show `weather` for `config.show_for`, then put back what was there.
+///
+/// The previous content is only put back if nothing else wrote to the display
+/// in the meantime. The glyph in [`ICON_SLOT`] is given back either way, unless
+/// something else redefined it.
+pub fn take_turn(
+    vfd: &Mutex<Vfd>,
+    weather: &Weather,
+    config: &WeatherConfig,
+) -> Result<(), VfdError> {
+    let (state, drawn) = {
+        let mut vfd = vfd.lock().unwrap_or_else(PoisonError::into_inner);
+        let state = vfd.save_state();
+        show(&mut vfd, weather, config)?;
+        (state, vfd.dump())
+    };
+    thread::sleep(config.show_for);
+    let mut vfd = vfd.lock().unwrap_or_else(PoisonError::into_inner);
+    if vfd.dump() == drawn {
+        vfd.restore_state(&state)?;
+    } else if let Some(glyph) = state.glyphs.get(&ICON_SLOT)
+        && vfd.save_state().glyphs.get(&ICON_SLOT) == Some(&weather.conditions.glyph())
+    {
+        vfd.define_custom_char(ICON_SLOT, glyph)?;
+    }
+    Ok(())
+}
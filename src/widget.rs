@@ -0,0 +1,74 @@
+use crate::display::VfdDisplay;
+use crate::error::VfdError;
+use std::io;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// Something drawn on part of the display that changes over time, such as a clock.
+pub trait Widget {
+    /// Redraw if the widget changed since it was last drawn. Returns whether the display was updated.
+    fn tick(&mut self, display: &mut dyn VfdDisplay) -> Result<bool, VfdError>;
+
+    /// Draw the widget unconditionally.
+    fn render(&mut self, display: &mut dyn VfdDisplay) -> Result<(), VfdError>;
+
+    /// How often [`Widget::tick`] should be called.
+    fn interval(&self) -> Duration;
+}
+
+/// Tick `widget` on a background thread until the returned handle is stopped.
+///
+/// To run several widgets from one thread, add them to a [`Scheduler`](crate::Scheduler).
+pub fn spawn<W, D>(mut widget: W, display: Arc<Mutex<D>>) -> WidgetHandle
+where
+    W: Widget + Send + 'static,
+    D: VfdDisplay + Send + 'static,
+{
+    let stop = Arc::new(AtomicBool::new(false));
+    let thread_stop = Arc::clone(&stop);
+    let thread = thread::spawn(move || {
+        let mut due = Instant::now();
+        while !thread_stop.load(Ordering::Relaxed) {
+            if Instant::now() >= due {
+                let mut display = display.lock().unwrap_or_else(PoisonError::into_inner);
+                widget.tick(&mut *display)?;
+                due = Instant::now() + widget.interval();
+            }
+            // In short steps, so that stopping does not wait for a slow widget's next tick
+            let wait = due.saturating_duration_since(Instant::now());
+            thread::sleep(wait.min(Duration::from_millis(50)));
+        }
+        Ok(())
+    });
+    WidgetHandle {
+        stop,
+        thread: Some(thread),
+    }
+}
+
+/// Control handle for a widget updating on a background thread.
+pub struct WidgetHandle {
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<Result<(), VfdError>>>,
+}
+
+impl WidgetHandle {
+    /// Stop updating and wait for the thread to finish, returning any write error it hit.
+    pub fn stop(mut self) -> Result<(), VfdError> {
+        self.stop.store(true, Ordering::Relaxed);
+        match self.thread.take() {
+            Some(thread) => thread
+                .join()
+                .unwrap_or_else(|_| Err(io::Error::other("Widget thread panicked").into())),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for WidgetHandle {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
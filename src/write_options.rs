@@ -0,0 +1,139 @@
+use crate::layout::Align;
+use crate::position::Position;
+use crate::text::WrapPolicy;
+use crate::text_style::TextStyle;
+use std::str::FromStr;
+
+/// What happens to text that does not fit on the rest of the row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Overflow {
+    /// Cut the text at the end of the row.
+    Truncate,
+    /// Cut the text at the end of the row and show [`Vfd::ellipsis`](crate::Vfd::ellipsis)
+    /// in its last cell, so readers can tell something is missing.
+    Ellipsis,
+    /// Continue on the following rows, failing before anything is sent if they run out.
+    #[default]
+    Error,
+    /// Continue on the following rows, scrolling the display up once the bottom row is used.
+    Scroll,
+}
+
+impl FromStr for Overflow {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "truncate" => Ok(Overflow::Truncate),
+            "ellipsis" => Ok(Overflow::Ellipsis),
+            "error" => Ok(Overflow::Error),
+            "scroll" => Ok(Overflow::Scroll),
+            other => Err(format!(
+                "Unknown overflow '{}'. Expected one of truncate, ellipsis, error, scroll.",
+                other
+            )),
+        }
+    }
+}
+
+/// How much of the whitespace in text [`Vfd::write_text_with`](crate::Vfd::write_text_with)
+/// lays out as written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FitPolicy {
+    /// All of it: text is laid out exactly as written.
+    #[default]
+    Strict,
+    /// Trim every line, squeeze runs of spaces into one and drop blank lines
+    /// (see [`text::compact`](crate::text::compact)). Each line left starts a
+    /// new row; with [`Overflow::Truncate`] and [`Overflow::Ellipsis`], lines
+    /// past the bottom row are left out.
+    Compact,
+}
+
+impl FromStr for FitPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "strict" => Ok(FitPolicy::Strict),
+            "compact" => Ok(FitPolicy::Compact),
+            other => Err(format!(
+                "Unknown fit policy '{}'. Expected one of strict, compact.",
+                other
+            )),
+        }
+    }
+}
+
+/// How [`Vfd::write_text_with`](crate::Vfd::write_text_with) places text.
+///
+/// ```
+/// use vfd_dsp_v9fb_over_serial::{Align, Overflow, Position, WrapPolicy, WriteOptions};
+///
+/// let options = WriteOptions::default()
+///     .start(Position::new(0, 1))
+///     .wrap(WrapPolicy::WORD)
+///     .overflow(Overflow::Scroll)
+///     .align(Align::Center);
+/// ```
+///
+/// Receipt text squeezed onto a two row display:
+///
+/// ```
+/// use vfd_dsp_v9fb_over_serial::{CommandSet, FitPolicy, Simulator, WrapPolicy, WriteOptions};
+///
+/// let simulator = Simulator::new(CommandSet::Birch, 10, 2);
+/// let mut vfd = simulator.vfd();
+/// let receipt = "  Tea  2.50\n\n  Total 2.50";
+/// let options = WriteOptions::default().wrap(WrapPolicy::WORD);
+/// assert!(vfd.write_text_with(receipt, options).is_err());
+/// vfd.write_text_with(receipt, options.fit(FitPolicy::Compact)).unwrap();
+/// assert_eq!(simulator.rows(), ["Tea 2.50  ", "Total 2.50"]);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct WriteOptions {
+    /// Where rows are broken when the text continues on the next one.
+    pub wrap: WrapPolicy,
+    pub overflow: Overflow,
+    /// Pad every row to the space left on it, placing the text this way.
+    /// `None` writes the text as is, leaving the rest of the row untouched.
+    pub align: Option<Align>,
+    /// Cell to start at instead of the cursor.
+    pub start: Option<Position>,
+    /// Character attributes for the text.
+    pub style: TextStyle,
+    /// Whitespace squeezed out before the text is laid out.
+    pub fit: FitPolicy,
+}
+
+impl WriteOptions {
+    pub fn wrap(mut self, wrap: WrapPolicy) -> Self {
+        self.wrap = wrap;
+        self
+    }
+
+    pub fn overflow(mut self, overflow: Overflow) -> Self {
+        self.overflow = overflow;
+        self
+    }
+
+    pub fn align(mut self, align: Align) -> Self {
+        self.align = Some(align);
+        self
+    }
+
+    pub fn start(mut self, start: Position) -> Self {
+        self.start = Some(start);
+        self
+    }
+
+    pub fn style(mut self, style: TextStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    pub fn fit(mut self, fit: FitPolicy) -> Self {
+        self.fit = fit;
+        self
+    }
+}
@@ -0,0 +1,139 @@
+#![cfg(feature = "std")]
+
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use vfd_dsp_v9fb_over_serial::{Alert, Align, CommandSet, Simulator, Vfd, VfdError};
+
+fn shared(simulator: &Simulator) -> Arc<Mutex<Vfd>> {
+    Arc::new(Mutex::new(simulator.vfd()))
+}
+
+#[test]
+fn shared_alerts_let_go_of_the_display_while_showing() {
+    let simulator = Simulator::new(CommandSet::Birch, 20, 2);
+    let vfd = shared(&simulator);
+    vfd.lock()
+        .unwrap()
+        .write_line(1, "Total: 4.20", Align::Right)
+        .unwrap();
+
+    let alert =
+        Alert::new("Card declined", Duration::from_millis(300)).blink(Duration::from_millis(50));
+    let showing = {
+        let vfd = Arc::clone(&vfd);
+        thread::spawn(move || alert.show(&vfd))
+    };
+    thread::sleep(Duration::from_millis(100));
+
+    // Another thread draws while the alert is up, without waiting it out
+    {
+        let mut vfd = vfd.lock().unwrap();
+        assert!(vfd.is_showing_alert());
+        vfd.write_line(1, "Total: 5.10", Align::Right).unwrap();
+        vfd.flush().unwrap();
+    }
+    assert!(!simulator.rows().concat().contains("5.10"));
+
+    showing.join().unwrap().unwrap();
+    assert!(!vfd.lock().unwrap().is_showing_alert());
+    assert_eq!(
+        simulator.rows(),
+        ["                    ", "         Total: 5.10"]
+    );
+}
+
+#[test]
+fn only_the_driver_state_sees_what_was_drawn_under_an_alert() {
+    let simulator = Simulator::new(CommandSet::Birch, 20, 2);
+    let vfd = shared(&simulator);
+    let showing = {
+        let vfd = Arc::clone(&vfd);
+        thread::spawn(move || Alert::new("Wait", Duration::from_millis(200)).show(&vfd))
+    };
+    thread::sleep(Duration::from_millis(50));
+    {
+        let mut vfd = vfd.lock().unwrap();
+        vfd.write_line(0, "Drawn meanwhile", Align::Left).unwrap();
+        assert_eq!(vfd.save_state().frame.row(0), "Drawn meanwhile     ");
+    }
+    assert_eq!(simulator.row(0).trim(), "Wait");
+
+    showing.join().unwrap().unwrap();
+    assert_eq!(simulator.row(0), "Drawn meanwhile     ");
+}
+
+#[test]
+fn a_blinking_display_holds_still_for_an_alert() {
+    let simulator = Simulator::new(CommandSet::Birch, 20, 2);
+    let vfd = shared(&simulator);
+    vfd.lock()
+        .unwrap()
+        .blink(Duration::from_millis(500))
+        .unwrap();
+    assert_eq!(simulator.blink(), Duration::from_millis(500));
+
+    let showing = {
+        let vfd = Arc::clone(&vfd);
+        thread::spawn(move || Alert::new("Steady", Duration::from_millis(150)).show(&vfd))
+    };
+    thread::sleep(Duration::from_millis(50));
+    assert_eq!(simulator.blink(), Duration::ZERO);
+
+    showing.join().unwrap().unwrap();
+    assert_eq!(simulator.blink(), Duration::from_millis(500));
+    assert_eq!(
+        vfd.lock().unwrap().save_state().blink,
+        Duration::from_millis(500)
+    );
+}
+
+#[test]
+fn blink_is_restored_with_the_rest_of_the_state() {
+    let simulator = Simulator::new(CommandSet::Birch, 20, 2);
+    let mut vfd = simulator.vfd();
+    let state = vfd.save_state();
+    vfd.blink(Duration::from_millis(250)).unwrap();
+
+    vfd.restore_state(&state).unwrap();
+    assert_eq!(simulator.blink(), Duration::ZERO);
+}
+
+#[test]
+fn alerts_put_back_a_cursor_past_the_end_of_a_row() {
+    let simulator = Simulator::new(CommandSet::Birch, 20, 2);
+    let mut vfd = simulator.vfd();
+    vfd.write_line(0, "Twenty characters!!!", Align::Left)
+        .unwrap();
+    let cursor = simulator.cursor();
+    assert_eq!(cursor, (20, 0));
+
+    vfd.show_alert_with(&Alert::new("Hi", Duration::from_millis(20)))
+        .unwrap();
+    assert_eq!(simulator.cursor(), cursor);
+    assert_eq!(simulator.row(0), "Twenty characters!!!");
+}
+
+#[test]
+fn a_second_alert_waits_for_the_first() {
+    let simulator = Simulator::new(CommandSet::Birch, 20, 2);
+    let vfd = shared(&simulator);
+    let first = {
+        let vfd = Arc::clone(&vfd);
+        thread::spawn(move || Alert::new("First", Duration::from_millis(150)).show(&vfd))
+    };
+    thread::sleep(Duration::from_millis(50));
+    // Called through the lock, it cannot wait without holding up the first one
+    let error = vfd
+        .lock()
+        .unwrap()
+        .show_alert("Second", Duration::from_millis(10))
+        .unwrap_err();
+    assert!(matches!(error, VfdError::InvalidInput(_)), "{error}");
+
+    Alert::new("Second", Duration::from_millis(50))
+        .show(&vfd)
+        .unwrap();
+    first.join().unwrap().unwrap();
+    assert_eq!(simulator.rows().concat().trim(), "");
+}
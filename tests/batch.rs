@@ -0,0 +1,60 @@
+#![cfg(feature = "std")]
+
+use vfd_dsp_v9fb_over_serial::{CommandSet, MockVfd};
+
+fn move_to(x: u8, y: u8) -> Vec<u8> {
+    CommandSet::Birch.set_cursor(x, y, 20)
+}
+
+#[test]
+fn moves_to_where_the_cursor_already_is_are_left_out_of_a_batch() {
+    let mut vfd = MockVfd::with_command_set(20, 2, CommandSet::Birch);
+    vfd.begin_batch();
+    vfd.set_cursor(0, 0).unwrap();
+    vfd.write_text("Tea").unwrap();
+    vfd.set_cursor(3, 0).unwrap();
+    vfd.write_text(" 2.50").unwrap();
+    vfd.set_cursor(8, 0).unwrap();
+    vfd.end_batch().unwrap();
+
+    let expected = [move_to(0, 0), b"Tea 2.50".to_vec()].concat();
+    assert_eq!(vfd.bytes(), expected);
+}
+
+#[test]
+fn moves_elsewhere_stay_in_a_batch() {
+    let mut vfd = MockVfd::with_command_set(20, 2, CommandSet::Birch);
+    vfd.begin_batch();
+    vfd.set_cursor(0, 0).unwrap();
+    vfd.write_text("Tea").unwrap();
+    vfd.set_cursor(0, 1).unwrap();
+    vfd.write_text("2.50").unwrap();
+    vfd.end_batch().unwrap();
+
+    let expected = [
+        move_to(0, 0),
+        b"Tea".to_vec(),
+        move_to(0, 1),
+        b"2.50".to_vec(),
+    ]
+    .concat();
+    assert_eq!(vfd.bytes(), expected);
+}
+
+#[test]
+fn every_move_is_sent_outside_a_batch() {
+    let mut vfd = MockVfd::with_command_set(20, 2, CommandSet::Birch);
+    vfd.set_cursor(0, 0).unwrap();
+    vfd.write_text("Tea").unwrap();
+    vfd.set_cursor(3, 0).unwrap();
+    vfd.write_text(" 2.50").unwrap();
+
+    let expected = [
+        move_to(0, 0),
+        b"Tea".to_vec(),
+        move_to(3, 0),
+        b" 2.50".to_vec(),
+    ]
+    .concat();
+    assert_eq!(vfd.bytes(), expected);
+}
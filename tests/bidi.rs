@@ -0,0 +1,28 @@
+use vfd_dsp_v9fb_over_serial::text::visual_order;
+use vfd_dsp_v9fb_over_serial::{Align, CodePage, CommandSet, Position, Simulator, WriteOptions};
+
+#[test]
+fn numbers_and_latin_words_keep_their_order() {
+    assert_eq!(visual_order("קפה (גדול) 12.50₪"), "12.50₪ (לודג) הפק");
+    assert_eq!(visual_order("שלום VFD עולם"), "םלוע VFD םולש");
+    assert_eq!(visual_order("Total שלום 3"), "Total 3 םולש");
+}
+
+#[test]
+fn long_lines_lose_their_logical_end() {
+    let simulator = Simulator::new(CommandSet::Birch, 6, 2);
+    let mut vfd = simulator.vfd();
+    vfd.set_code_page(CodePage::Pc862).unwrap();
+    vfd.set_bidi(true);
+    vfd.write_line(0, "אבגדהוזח", Align::Left).unwrap();
+    assert_eq!(simulator.row(0), "והדגבא");
+
+    vfd.write_text_with(
+        "שלום",
+        WriteOptions::default()
+            .start(Position::new(0, 1))
+            .align(Align::Right),
+    )
+    .unwrap();
+    assert_eq!(simulator.row(1), "םולש  ");
+}
@@ -0,0 +1,30 @@
+use vfd_dsp_v9fb_over_serial::{BigFont, CommandSet, Simulator, VfdError};
+
+#[test]
+fn big_text_too_wide_reports_how_many_characters_fit() {
+    let simulator = Simulator::new(CommandSet::Birch, 20, 2);
+    let mut vfd = simulator.vfd();
+    let font = BigFont::default();
+    assert_eq!(font.fitting_chars("12:34:56", 20), 6);
+
+    let error = vfd.write_big_text("12:34:56", 0, &font).unwrap_err();
+    assert!(matches!(error, VfdError::TextTooLong { max: 6, got: 8 }));
+    let error = vfd.write_big_text("12:34", 4, &font).unwrap_err();
+    assert!(matches!(error, VfdError::TextTooLong { max: 4, got: 5 }));
+    vfd.write_big_text("12:34", 3, &font).unwrap();
+}
+
+#[test]
+fn big_fonts_taller_than_the_display_are_refused() {
+    let simulator = Simulator::new(CommandSet::Birch, 20, 1);
+    let mut vfd = simulator.vfd();
+    let error = vfd.write_big_number(42, 0).unwrap_err();
+    assert!(matches!(
+        error,
+        VfdError::OutOfBounds {
+            value: 2,
+            max: 1,
+            ..
+        }
+    ));
+}
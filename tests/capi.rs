@@ -0,0 +1,54 @@
+#![cfg(feature = "capi")]
+
+use std::ffi::CStr;
+use std::ptr;
+use vfd_dsp_v9fb_over_serial::capi::{
+    VFD_ERROR, VfdAlign, vfd_close, vfd_last_error, vfd_open, vfd_write_line,
+};
+
+fn last_error() -> String {
+    let message = vfd_last_error();
+    assert!(!message.is_null());
+    // SAFETY: a non-NULL message stays valid until the next failing call
+    unsafe { CStr::from_ptr(message) }
+        .to_string_lossy()
+        .into_owned()
+}
+
+#[test]
+fn alignments_out_of_range_are_rejected() {
+    // SAFETY: NULL handles and valid strings are allowed
+    let status = unsafe { vfd_write_line(ptr::null_mut(), 0, c"Total".as_ptr(), 7) };
+    assert_eq!(status, VFD_ERROR);
+    assert!(
+        last_error().contains("Invalid alignment 7"),
+        "{}",
+        last_error()
+    );
+
+    // SAFETY: as above
+    let status = unsafe {
+        vfd_write_line(
+            ptr::null_mut(),
+            0,
+            c"Total".as_ptr(),
+            VfdAlign::Right as i32,
+        )
+    };
+    assert_eq!(status, VFD_ERROR);
+    assert!(last_error().contains("handle is NULL"), "{}", last_error());
+}
+
+#[test]
+fn failing_to_open_returns_null_with_a_reason() {
+    // SAFETY: NULL and valid strings are allowed
+    unsafe {
+        assert!(vfd_open(ptr::null(), 20, 2).is_null());
+        assert!(last_error().contains("device is NULL"));
+
+        assert!(vfd_open(c"/dev/no-such-display".as_ptr(), 20, 2).is_null());
+        assert!(!last_error().is_empty());
+
+        vfd_close(ptr::null_mut());
+    }
+}
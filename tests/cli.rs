@@ -0,0 +1,194 @@
+#![cfg(feature = "cli")]
+
+use std::path::PathBuf;
+use std::process::{Command, Output};
+#[cfg(unix)]
+use std::{
+    io::{BufRead, BufReader},
+    process::{Child, Stdio},
+};
+
+const VFD_CTL: &str = env!("CARGO_BIN_EXE_vfd-ctl");
+
+// An empty config file, so settings on the machine running the tests are not picked up
+fn empty_config() -> PathBuf {
+    let path = std::env::temp_dir().join(format!("vfd-cli-{}.toml", std::process::id()));
+    std::fs::write(&path, "").unwrap();
+    path
+}
+
+fn dry_run(args: &[&str]) -> Output {
+    let output = Command::new(VFD_CTL)
+        .arg("--config")
+        .arg(empty_config())
+        .arg("--dry-run")
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    output
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+// Read printed screens from `child` until `done` accepts the latest one, then stop it
+#[cfg(unix)]
+fn screens_until(mut child: Child, done: impl Fn(&str) -> bool) -> Vec<String> {
+    let mut lines = BufReader::new(child.stdout.take().unwrap()).lines();
+    let mut screens = Vec::new();
+    let mut screen = String::new();
+    while let Some(line) = lines.next().transpose().unwrap() {
+        if !line.starts_with(['+', '|']) {
+            continue;
+        }
+        screen.push_str(&line);
+        screen.push('\n');
+        // A screen ends with its second rule
+        if line.starts_with('+') && screen.lines().count() > 1 {
+            let finished = done(&screen);
+            screens.push(std::mem::take(&mut screen));
+            if finished {
+                break;
+            }
+        }
+    }
+    child.kill().unwrap();
+    child.wait().unwrap();
+    screens
+}
+
+#[test]
+fn dry_runs_print_the_screen_left_behind() {
+    let output = dry_run(&["write", "--row", "1", "Total 4.20"]);
+    assert_eq!(
+        stdout(&output),
+        "+--------------------+\n\
+         |                    |\n\
+         |Total 4.20          |\n\
+         +--------------------+\n"
+    );
+}
+
+#[test]
+fn dry_runs_answer_status_requests() {
+    let output = dry_run(&["status"]);
+    assert!(stdout(&output).starts_with("ready, status 0x12"));
+}
+
+#[test]
+fn dry_runs_answer_identify_requests() {
+    let output = dry_run(&["--geometry", "16x2", "info"]);
+    let text = stdout(&output);
+    assert!(text.contains("Size:        16x2"), "{text}");
+    assert!(text.contains("Model ID:    0x00"), "{text}");
+}
+
+#[test]
+fn dry_runs_print_every_frame_of_a_scrolling_text() {
+    let output = dry_run(&[
+        "scroll",
+        "Longer than the twenty columns of a row",
+        "--speed",
+        "100",
+        "--duration",
+        "1",
+    ]);
+    let frames = stdout(&output).matches("+\n|").count();
+    assert!(frames > 3, "{} frames", frames);
+}
+
+#[cfg(unix)]
+#[test]
+fn dry_runs_print_each_screen_of_commands_that_keep_running() {
+    let counter = std::env::temp_dir().join(format!("vfd-cli-watch-{}", std::process::id()));
+    let _ = std::fs::remove_file(&counter);
+    let command = format!(
+        "n=$(cat {0} 2>/dev/null || echo 0); echo $((n + 1)) > {0}; echo run $n",
+        counter.display()
+    );
+    let child = Command::new(VFD_CTL)
+        .arg("--config")
+        .arg(empty_config())
+        .args(["--dry-run", "watch", "-n", "1", "--", &command])
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let screens = screens_until(child, |screen| screen.contains("run 2"));
+    std::fs::remove_file(&counter).unwrap();
+    for run in ["run 0", "run 1", "run 2"] {
+        assert!(
+            screens.iter().any(|screen| screen.contains(run)),
+            "{run} missing from {screens:?}"
+        );
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn the_daemon_prints_what_clients_draw_on_a_dry_run() {
+    use std::io::{Read, Write};
+    use std::os::unix::net::UnixStream;
+    use std::thread::sleep;
+    use std::time::{Duration, Instant};
+
+    let socket = std::env::temp_dir().join(format!("vfd-cli-{}.sock", std::process::id()));
+    let child = Command::new(env!("CARGO_BIN_EXE_vfdd"))
+        .arg("--config")
+        .arg(empty_config())
+        .arg("--dry-run")
+        .arg("--socket")
+        .arg(&socket)
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let started = Instant::now();
+    let mut stream = loop {
+        match UnixStream::connect(&socket) {
+            Ok(stream) => break stream,
+            Err(_) if started.elapsed() < Duration::from_secs(10) => {
+                sleep(Duration::from_millis(20))
+            }
+            Err(e) => panic!("vfdd did not listen: {e}"),
+        }
+    };
+    stream.write_all(b"write Order 42\n").unwrap();
+    stream.shutdown(std::net::Shutdown::Write).unwrap();
+    let mut answer = String::new();
+    stream.read_to_string(&mut answer).unwrap();
+    assert_eq!(answer.trim(), "OK");
+
+    let screens = screens_until(child, |screen| screen.contains("Order 42"));
+    let _ = std::fs::remove_file(&socket);
+    assert!(screens.last().unwrap().contains("|Order 42            |"));
+}
+
+#[cfg(all(unix, feature = "weather"))]
+#[test]
+fn the_daemon_takes_the_openweathermap_key_from_the_environment() {
+    let socket = std::env::temp_dir().join(format!("vfd-cli-weather-{}.sock", std::process::id()));
+    let output = Command::new(env!("CARGO_BIN_EXE_vfdd"))
+        .arg("--config")
+        .arg(empty_config())
+        .args(["--dry-run", "--socket"])
+        .arg(&socket)
+        .args([
+            "--weather",
+            "52.52,13.40",
+            "--weather-provider",
+            "openweathermap",
+        ])
+        .env_remove("OPENWEATHERMAP_API_KEY")
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let errors = String::from_utf8_lossy(&output.stderr);
+    assert!(errors.contains("OPENWEATHERMAP_API_KEY"), "{errors}");
+}
@@ -0,0 +1,44 @@
+use vfd_dsp_v9fb_over_serial::{Command, CommandSet, Simulator, VfdError};
+
+#[test]
+fn cursor_moves_are_sent_one_based() {
+    let command = Command::MoveCursor { x: 0, y: 254 };
+    assert_eq!(command.to_bytes().unwrap(), [0x1F, b'$', 1, 255]);
+}
+
+#[test]
+fn cursor_moves_past_254_are_invalid() {
+    for command in [
+        Command::MoveCursor { x: 255, y: 0 },
+        Command::MoveCursor { x: 0, y: 255 },
+    ] {
+        assert!(matches!(command.to_bytes(), Err(VfdError::InvalidInput(_))));
+    }
+    let simulator = Simulator::new(CommandSet::Birch, 20, 2);
+    let mut vfd = simulator.vfd();
+    let error = vfd
+        .send_command(&Command::MoveCursor { x: 255, y: 255 })
+        .unwrap_err();
+    assert!(matches!(error, VfdError::InvalidInput(_)));
+}
+
+#[test]
+fn initializing_by_command_stops_all_blinking() {
+    use std::time::Duration;
+    use vfd_dsp_v9fb_over_serial::{TextStyle, WriteOptions};
+
+    let simulator = Simulator::new(CommandSet::Birch, 20, 2);
+    let mut vfd = simulator.vfd();
+    vfd.set_text_blink_interval(Duration::ZERO);
+    vfd.write_text_with(
+        "ALARM",
+        WriteOptions::default().style(TextStyle::default().blink()),
+    )
+    .unwrap();
+    vfd.blink(Duration::from_millis(500)).unwrap();
+
+    vfd.send_command(&Command::Initialize).unwrap();
+    assert_eq!(vfd.save_state().blink, Duration::ZERO);
+    assert!(!vfd.tick_blink().unwrap());
+    assert_eq!(simulator.row(0).trim(), "");
+}
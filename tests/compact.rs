@@ -0,0 +1,102 @@
+#![cfg(feature = "std")]
+
+use vfd_dsp_v9fb_over_serial::text::compact;
+use vfd_dsp_v9fb_over_serial::{
+    CommandSet, FitPolicy, Overflow, Position, Simulator, VfdError, WrapPolicy, WriteOptions,
+};
+
+fn compacted() -> WriteOptions {
+    WriteOptions::default()
+        .wrap(WrapPolicy::WORD)
+        .fit(FitPolicy::Compact)
+}
+
+#[test]
+fn compacting_keeps_line_breaks() {
+    assert_eq!(
+        compact("  Tea  2.50\n\n  Total 2.50  "),
+        "Tea 2.50\nTotal 2.50"
+    );
+    assert_eq!(compact("Tea\t\t2.50\r\n \r\nTotal"), "Tea 2.50\nTotal");
+    assert_eq!(compact(" \n\t\n "), "");
+}
+
+#[test]
+fn compacted_lines_start_new_rows() {
+    let simulator = Simulator::new(CommandSet::Birch, 20, 2);
+    let mut vfd = simulator.vfd();
+    vfd.write_text_with("  Tea  2.50\n\n  Total 2.50  ", compacted())
+        .unwrap();
+    assert_eq!(
+        simulator.rows(),
+        ["Tea 2.50            ", "Total 2.50          "]
+    );
+}
+
+#[test]
+fn the_first_compacted_line_continues_from_the_cursor() {
+    let simulator = Simulator::new(CommandSet::Birch, 20, 2);
+    let mut vfd = simulator.vfd();
+    vfd.write_text_with(
+        "  2 x   Tea\n  Total",
+        compacted().start(Position::new(12, 0)),
+    )
+    .unwrap();
+    assert_eq!(
+        simulator.rows(),
+        ["            2 x Tea ", "Total               "]
+    );
+}
+
+#[test]
+fn compacted_lines_are_cut_one_by_one() {
+    let simulator = Simulator::new(CommandSet::Birch, 10, 2);
+    let mut vfd = simulator.vfd();
+    vfd.write_text_with(
+        "Green   tea  2.50\n\nTotal  2.50",
+        compacted().overflow(Overflow::Truncate),
+    )
+    .unwrap();
+    assert_eq!(simulator.rows(), ["Green tea ", "Total 2.50"]);
+}
+
+#[test]
+fn too_many_lines_fail_before_anything_is_sent() {
+    let simulator = Simulator::new(CommandSet::Birch, 20, 2);
+    let mut vfd = simulator.vfd();
+    let result = vfd.write_text_with("Tea\n\nCake\n\nTotal", compacted());
+    assert!(
+        matches!(result, Err(VfdError::TextTooLong { .. })),
+        "{result:?}"
+    );
+    assert_eq!(simulator.rows(), [" ".repeat(20), " ".repeat(20)]);
+}
+
+#[test]
+fn strict_text_is_laid_out_as_written() {
+    let simulator = Simulator::new(CommandSet::Birch, 20, 2);
+    let mut vfd = simulator.vfd();
+    vfd.write_text_with("  Tea  2.50", WriteOptions::default())
+        .unwrap();
+    assert_eq!(simulator.row(0), "  Tea  2.50         ");
+}
+
+#[test]
+fn cut_off_lines_past_the_bottom_row_are_dropped() {
+    for overflow in [Overflow::Truncate, Overflow::Ellipsis] {
+        let simulator = Simulator::new(CommandSet::Birch, 10, 2);
+        let mut vfd = simulator.vfd();
+        vfd.write_text_with("Order 42", WriteOptions::default())
+            .unwrap();
+        vfd.write_text_with(
+            "Tea\n\nCake\n\nTotal",
+            compacted().overflow(overflow).start(Position::new(0, 1)),
+        )
+        .unwrap();
+        assert_eq!(
+            simulator.rows(),
+            ["Order 42  ", "Tea       "],
+            "{overflow:?}"
+        );
+    }
+}
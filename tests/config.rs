@@ -0,0 +1,35 @@
+#![cfg(feature = "config")]
+
+use vfd_dsp_v9fb_over_serial::config::Config;
+use vfd_dsp_v9fb_over_serial::{Template, TemplateField, Templates};
+
+fn templates(entries: &[(&str, &str)]) -> Templates {
+    let mut templates = Templates::new();
+    for (name, text) in entries {
+        templates.insert(name, Template::new().field(TemplateField::new(0, 0, text)));
+    }
+    templates
+}
+
+#[test]
+fn overriding_templates_are_combined_by_name() {
+    let base = Config {
+        templates: Some(templates(&[("sale", "{item}"), ("idle", "Welcome")])),
+        ..Config::default()
+    };
+    let overrides = Config {
+        templates: Some(templates(&[
+            ("sale", "{item} {price}"),
+            ("total", "{total}"),
+        ])),
+        ..Config::default()
+    };
+    let merged = base.merge(overrides).templates.unwrap();
+    assert_eq!(
+        merged.names().collect::<Vec<_>>(),
+        ["idle", "sale", "total"]
+    );
+    let sale = merged.get("sale").unwrap();
+    assert_eq!(sale.fields.len(), 1);
+    assert_eq!(sale.fields[0].text, "{item} {price}");
+}
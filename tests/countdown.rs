@@ -0,0 +1,45 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+use vfd_dsp_v9fb_over_serial::{Align, CommandSet, Countdown, CountdownFormat, Region, Simulator};
+
+#[test]
+fn formats_round_up_to_whole_seconds() {
+    let remaining = Duration::from_millis(3_723_400);
+    assert_eq!(CountdownFormat::Seconds.format(remaining), "3724");
+    assert_eq!(CountdownFormat::MinutesSeconds.format(remaining), "62:04");
+    assert_eq!(
+        CountdownFormat::HoursMinutesSeconds.format(remaining),
+        "1:02:04"
+    );
+}
+
+#[test]
+fn finishing_shows_the_finished_text_and_calls_back_once() {
+    let simulator = Simulator::new(CommandSet::Birch, 20, 2);
+    let mut vfd = simulator.vfd();
+    let completions = Arc::new(AtomicUsize::new(0));
+    let counter = Arc::clone(&completions);
+    let mut countdown = Countdown::new(Region::new(10, 0, 10, 1))
+        .align(Align::Right)
+        .format(CountdownFormat::Seconds)
+        .finished_text("Ready")
+        .on_complete(move || {
+            counter.fetch_add(1, Ordering::Relaxed);
+        });
+    let start = Instant::now();
+    assert!(!countdown.tick_at(&mut vfd, start).unwrap());
+
+    countdown.start_at(Duration::from_millis(50), start);
+    assert!(countdown.tick_at(&mut vfd, start).unwrap());
+    assert_eq!(simulator.row(0), "                   1");
+    let almost = start + Duration::from_millis(49);
+    assert!(!countdown.tick_at(&mut vfd, almost).unwrap());
+    assert_eq!(completions.load(Ordering::Relaxed), 0);
+
+    let done = start + Duration::from_millis(50);
+    assert!(countdown.tick_at(&mut vfd, done).unwrap());
+    assert!(!countdown.tick_at(&mut vfd, done).unwrap());
+    assert_eq!(simulator.row(0), "               Ready");
+    assert_eq!(completions.load(Ordering::Relaxed), 1);
+}
@@ -0,0 +1,83 @@
+#![cfg(feature = "std")]
+
+#[cfg(unix)]
+#[test]
+fn only_stale_sockets_are_replaced() {
+    use std::fs;
+    use vfd_dsp_v9fb_over_serial::daemon;
+
+    let dir = std::env::temp_dir().join(format!("vfd-daemon-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let socket = dir.join("vfd.sock");
+    drop(daemon::bind_unix(&socket).unwrap());
+    drop(daemon::bind_unix(&socket).unwrap());
+
+    let notes = dir.join("notes.txt");
+    fs::write(&notes, "keep me").unwrap();
+    assert!(daemon::bind_unix(&notes).is_err());
+    assert_eq!(fs::read_to_string(&notes).unwrap(), "keep me");
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn escape_sequences_ending_in_other_characters_are_shown_as_text() {
+    use vfd_dsp_v9fb_over_serial::daemon::TextStream;
+    use vfd_dsp_v9fb_over_serial::{CommandSet, Simulator};
+
+    let simulator = Simulator::new(CommandSet::Birch, 20, 2);
+    let mut vfd = simulator.vfd();
+    let mut stream = TextStream::new();
+    stream.feed(&mut vfd, "\x1b[é").unwrap();
+    stream.feed(&mut vfd, "\n\x1b[12").unwrap();
+    stream.feed(&mut vfd, "ü ok").unwrap();
+    assert_eq!(simulator.row(0).trim_end(), "[é");
+    assert_eq!(simulator.row(1).trim_end(), "[12ü ok");
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn other_clients_are_answered_while_a_json_alert_shows() {
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+    use std::time::{Duration, Instant};
+    use vfd_dsp_v9fb_over_serial::daemon::handle_shared_line;
+    use vfd_dsp_v9fb_over_serial::{CommandSet, Simulator};
+
+    let simulator = Simulator::new(CommandSet::Birch, 20, 2);
+    let vfd = Arc::new(Mutex::new(simulator.vfd()));
+    let alerting = {
+        let vfd = Arc::clone(&vfd);
+        thread::spawn(move || {
+            handle_shared_line(
+                &vfd,
+                r#"{"cmd": "alert", "text": "Back soon", "duration_ms": 2000}"#,
+            )
+        })
+    };
+    let started = Instant::now();
+    while !simulator.rows().concat().contains("Back soon") {
+        assert!(started.elapsed() < Duration::from_secs(2), "no alert shown");
+        thread::sleep(Duration::from_millis(5));
+    }
+
+    assert_eq!(
+        handle_shared_line(&vfd, r#"{"cmd": "ping"}"#),
+        r#"{"ok":true}"#
+    );
+    assert!(!alerting.is_finished());
+    assert_eq!(alerting.join().unwrap(), r#"{"ok":true}"#);
+}
+
+#[test]
+fn the_stream_column_follows_transliterated_text() {
+    use vfd_dsp_v9fb_over_serial::daemon::TextStream;
+    use vfd_dsp_v9fb_over_serial::{CodePage, CommandSet, Simulator};
+
+    let simulator = Simulator::new(CommandSet::Birch, 20, 2);
+    let mut vfd = simulator.vfd();
+    vfd.set_code_page(CodePage::Wpc1252).unwrap();
+    let mut stream = TextStream::new();
+    stream.feed(&mut vfd, "R1 47Ω").unwrap();
+    stream.feed(&mut vfd, " ok\x1b[K").unwrap();
+    assert_eq!(simulator.row(0), "R1 47Ohm ok         ");
+}
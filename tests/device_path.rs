@@ -0,0 +1,38 @@
+use std::cmp::Ordering;
+use vfd_dsp_v9fb_over_serial::device_path::{Platform, compare, display_name, normalize};
+
+#[test]
+fn unix_bare_names_get_dev_prefix() {
+    assert_eq!(normalize("ttyUSB0", Platform::Unix), "/dev/ttyUSB0");
+    assert_eq!(normalize(" /dev/ttyS1 ", Platform::Unix), "/dev/ttyS1");
+    assert_eq!(normalize("./pty0", Platform::Unix), "./pty0");
+}
+
+#[test]
+fn windows_com_names_are_canonical() {
+    assert_eq!(normalize("com3", Platform::Windows), "COM3");
+    assert_eq!(normalize("7", Platform::Windows), "COM7");
+    assert_eq!(normalize(r"\\.\com12", Platform::Windows), r"\\.\COM12");
+    assert_eq!(normalize("COM0", Platform::Windows), "COM0");
+    assert_eq!(normalize("COMX", Platform::Windows), "COMX");
+}
+
+#[test]
+fn windows_paths_are_not_given_dev_prefix() {
+    assert!(!normalize("COM3", Platform::Windows).starts_with("/dev"));
+    assert_eq!(display_name(r"\\.\COM12"), "COM12");
+}
+
+#[test]
+fn default_devices_follow_platform() {
+    assert_eq!(Platform::Unix.default_device(), "/dev/ttyUSB0");
+    assert_eq!(Platform::Windows.default_device(), "COM3");
+}
+
+#[test]
+fn ports_sort_by_number() {
+    assert_eq!(compare("COM9", "COM10"), Ordering::Less);
+    assert_eq!(compare(r"\\.\COM10", "COM9"), Ordering::Greater);
+    assert_eq!(compare("/dev/ttyUSB2", "/dev/ttyUSB10"), Ordering::Less);
+    assert_eq!(compare("/dev/ttyACM0", "/dev/ttyUSB0"), Ordering::Less);
+}
@@ -0,0 +1,48 @@
+use vfd_dsp_v9fb_over_serial::{
+    CodePage, CommandSet, DisplayGroup, GroupLayout, Simulator, VfdDisplay, VfdError,
+};
+
+// A group of `layout` over two transliterating 10x1 displays
+fn transliterating(layout: GroupLayout) -> (DisplayGroup, [Simulator; 2]) {
+    let simulators = [(); 2].map(|_| Simulator::new(CommandSet::Birch, 10, 1));
+    let mut group = DisplayGroup::new(layout);
+    for (name, simulator) in ["left", "right"].into_iter().zip(&simulators) {
+        let mut vfd = simulator.vfd();
+        vfd.set_code_page(CodePage::Wpc1252).unwrap();
+        group.add(name, vfd);
+    }
+    (group, simulators)
+}
+
+#[test]
+fn split_text_is_measured_as_each_member_shows_it() {
+    let (mut group, [left, right]) = transliterating(GroupLayout::SideBySide);
+    group.write_text("Resistor 47Ω").unwrap();
+    assert_eq!(group.get_cursor(), (14, 0));
+
+    group.write_text("!").unwrap();
+    assert_eq!(left.row(0), "Resistor 4");
+    assert_eq!(right.row(0), "7Ohm!     ");
+}
+
+#[test]
+fn split_text_that_only_overflows_once_mapped_is_refused() {
+    let (mut group, [left, right]) = transliterating(GroupLayout::SideBySide);
+    let result = group.write_text("Loads 4Ω, 8Ω, 16Ω");
+    assert!(
+        matches!(result, Err(VfdError::TextTooLong { max: 20, .. })),
+        "{result:?}"
+    );
+    assert_eq!(left.row(0).trim(), "");
+    assert_eq!(right.row(0).trim(), "");
+    assert_eq!(group.get_cursor(), (0, 0));
+}
+
+#[test]
+fn mirrored_cursors_follow_the_mapped_text() {
+    let (mut group, [left, right]) = transliterating(GroupLayout::Mirror);
+    group.write_text("5Ω").unwrap();
+    assert_eq!(group.get_cursor(), (4, 0));
+    assert_eq!(left.row(0), "5Ohm      ");
+    assert_eq!(right.row(0), "5Ohm      ");
+}
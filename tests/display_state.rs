@@ -0,0 +1,63 @@
+#![cfg(feature = "std")]
+
+use vfd_dsp_v9fb_over_serial::{
+    Align, BrightnessLevel, CodePage, CommandSet, DisplayMode, Simulator,
+};
+
+#[test]
+fn restoring_puts_back_text_cursor_and_settings() {
+    let simulator = Simulator::new(CommandSet::Birch, 20, 2);
+    let mut vfd = simulator.vfd();
+    vfd.write_line(0, "Coffee", Align::Left).unwrap();
+    vfd.write_line(1, "2.50", Align::Right).unwrap();
+    vfd.set_cursor(3, 1).unwrap();
+    let state = vfd.save_state();
+
+    vfd.set_brightness(BrightnessLevel::Percent20).unwrap();
+    vfd.set_display_mode(DisplayMode::VerticalScroll).unwrap();
+    vfd.set_code_page(CodePage::Wpc1252).unwrap();
+    vfd.clear().unwrap();
+    vfd.write_line(0, "Card declined", Align::Center).unwrap();
+    assert_eq!(simulator.row(0).trim(), "Card declined");
+
+    vfd.restore_state(&state).unwrap();
+    assert_eq!(
+        simulator.rows(),
+        ["Coffee              ", "                2.50"]
+    );
+    assert_eq!(simulator.cursor(), (3, 1));
+    assert_eq!(simulator.brightness(), state.brightness);
+    assert_eq!(simulator.display_mode(), state.display_mode);
+    assert_eq!(simulator.code_page(), state.code_page);
+    assert_eq!(vfd.save_state(), state);
+}
+
+#[test]
+fn the_snapshot_follows_what_the_display_shows() {
+    let simulator = Simulator::new(CommandSet::Birch, 20, 2);
+    let mut vfd = simulator.vfd();
+    vfd.set_brightness(BrightnessLevel::Percent60).unwrap();
+    vfd.write_line(1, "Saved", Align::Center).unwrap();
+
+    let state = vfd.save_state();
+    assert_eq!(state.frame, simulator.frame());
+    assert_eq!(state.brightness, simulator.brightness());
+    assert_eq!((state.cursor.x, state.cursor.y), simulator.cursor());
+}
+
+#[test]
+fn rows_left_unchanged_are_not_redrawn() {
+    let simulator = Simulator::new(CommandSet::Birch, 20, 2);
+    let mut vfd = simulator.vfd();
+    vfd.write_line(0, "Kept as it was", Align::Left).unwrap();
+    vfd.write_line(1, "Overwritten", Align::Left).unwrap();
+    let state = vfd.save_state();
+    vfd.write_line(1, "Alert!", Align::Center).unwrap();
+
+    // A stray byte on the unchanged row would survive a restore that skips it
+    simulator.feed(&CommandSet::Birch.set_cursor(0, 0, 20));
+    simulator.feed(b"*");
+    vfd.restore_state(&state).unwrap();
+    assert_eq!(simulator.row(1), "Overwritten         ");
+    assert!(simulator.row(0).starts_with('*'));
+}
@@ -0,0 +1,132 @@
+use vfd_dsp_v9fb_over_serial::{Align, Geometry, MockVfd, TextFit, VfdError, WrapPolicy};
+
+fn mock(geometry: Geometry) -> MockVfd {
+    MockVfd::new(geometry.width, geometry.height)
+}
+
+#[test]
+fn presets_round_trip_through_their_names() {
+    for geometry in Geometry::PRESETS {
+        assert_eq!(geometry.to_string().parse::<Geometry>(), Ok(geometry));
+    }
+    assert!("20".parse::<Geometry>().is_err());
+    assert!("0x2".parse::<Geometry>().is_err());
+}
+
+#[test]
+fn a_full_screen_of_text_fits_every_preset() {
+    for geometry in Geometry::PRESETS {
+        let mut vfd = mock(geometry);
+        let text = "x".repeat(geometry.cells());
+        vfd.write_text(&text).unwrap();
+        let row = "x".repeat(geometry.width as usize);
+        assert_eq!(
+            vfd.rows(),
+            vec![row; geometry.height as usize],
+            "{}",
+            geometry
+        );
+    }
+}
+
+#[test]
+fn one_cell_more_than_the_screen_is_rejected() {
+    for geometry in Geometry::PRESETS {
+        let mut vfd = mock(geometry);
+        let text = "x".repeat(geometry.cells() + 1);
+        match vfd.write_text(&text) {
+            Err(VfdError::TextTooLong { max, got }) => {
+                assert_eq!((max, got), (geometry.cells(), geometry.cells() + 1));
+            }
+            other => panic!("{}: expected TextTooLong, got {:?}", geometry, other),
+        }
+        assert!(
+            vfd.bytes().is_empty(),
+            "{}: nothing should be sent",
+            geometry
+        );
+    }
+}
+
+#[test]
+fn text_longer_than_255_cells_is_too_long() {
+    for geometry in Geometry::PRESETS {
+        let vfd = mock(geometry);
+        assert_eq!(vfd.get_text_fit(&"x".repeat(300), false), TextFit::TooLong);
+    }
+}
+
+#[test]
+fn fit_counts_from_the_cursor() {
+    for geometry in Geometry::PRESETS {
+        let (width, height) = geometry.into();
+        let mut vfd = mock(geometry);
+
+        vfd.set_cursor(width - 3, 0).unwrap();
+        assert_eq!(vfd.get_text_fit("abc", false), TextFit::OneLine);
+        assert_eq!(vfd.get_text_fit("abcde", false), TextFit::NeedsWrap);
+        assert_eq!(vfd.get_text_fit("abcde", true), TextFit::OneLineTruncated);
+
+        vfd.set_cursor(width - 1, height - 1).unwrap();
+        assert_eq!(vfd.get_text_fit("a", false), TextFit::OneLine);
+        assert_eq!(vfd.get_text_fit("ab", false), TextFit::TooLong);
+    }
+}
+
+#[test]
+fn every_row_can_be_written_and_no_more() {
+    for geometry in Geometry::PRESETS {
+        let (_, height) = geometry.into();
+        let mut vfd = mock(geometry);
+        for row in 0..height {
+            vfd.write_line(row, &format!("Row {}", row), Align::Right)
+                .unwrap();
+        }
+        let last = vfd.row(height - 1);
+        assert!(
+            last.ends_with(&format!("Row {}", height - 1)),
+            "{}",
+            geometry
+        );
+        assert_eq!(last.chars().count(), geometry.width as usize);
+        assert!(matches!(
+            vfd.write_line(height, "", Align::Left),
+            Err(VfdError::OutOfBounds { .. })
+        ));
+    }
+}
+
+#[test]
+fn cursor_moves_off_the_display_are_errors() {
+    for geometry in Geometry::PRESETS {
+        let (width, height) = geometry.into();
+        let mut vfd = mock(geometry);
+        vfd.set_cursor(width - 1, height - 1).unwrap();
+        assert!(matches!(
+            vfd.set_cursor(width, 0),
+            Err(VfdError::OutOfBounds { what: "Column", .. })
+        ));
+        assert!(matches!(
+            vfd.set_cursor(0, height),
+            Err(VfdError::OutOfBounds { what: "Row", .. })
+        ));
+        assert_eq!(vfd.get_cursor(), (width - 1, height - 1));
+    }
+}
+
+#[test]
+fn word_wrap_uses_every_row() {
+    let mut vfd = mock(Geometry::SIZE_20X4);
+    vfd.set_wrap_policy(WrapPolicy::WORD);
+    vfd.write_text("The quick brown fox jumps over the lazy dog and keeps running far away")
+        .unwrap();
+    assert_eq!(
+        vfd.rows(),
+        [
+            "The quick brown fox ",
+            "jumps over the lazy ",
+            "dog and keeps       ",
+            "running far away    ",
+        ]
+    );
+}
@@ -0,0 +1,215 @@
+#![cfg(feature = "home-assistant")]
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use vfd_dsp_v9fb_over_serial::home_assistant::{self, HomeAssistantConfig};
+use vfd_dsp_v9fb_over_serial::{Align, CommandSet, Simulator, Template, TemplateField};
+
+// Read one masked text frame from the client
+fn read_frame(stream: &mut TcpStream) -> String {
+    let mut header = [0u8; 2];
+    stream.read_exact(&mut header).unwrap();
+    assert_eq!(header[0], 0x81);
+    assert_ne!(header[1] & 0x80, 0, "client frames are masked");
+    let len = match header[1] & 0x7F {
+        126 => {
+            let mut len = [0u8; 2];
+            stream.read_exact(&mut len).unwrap();
+            u16::from_be_bytes(len) as usize
+        }
+        len => len as usize,
+    };
+    let mut mask = [0u8; 4];
+    stream.read_exact(&mut mask).unwrap();
+    let mut payload = vec![0u8; len];
+    stream.read_exact(&mut payload).unwrap();
+    for (i, b) in payload.iter_mut().enumerate() {
+        *b ^= mask[i % 4];
+    }
+    String::from_utf8(payload).unwrap()
+}
+
+fn write_frame(stream: &mut TcpStream, text: &str) {
+    let mut frame = vec![0x81];
+    if text.len() < 126 {
+        frame.push(text.len() as u8);
+    } else {
+        frame.push(126);
+        frame.extend((text.len() as u16).to_be_bytes());
+    }
+    frame.extend(text.as_bytes());
+    stream.write_all(&frame).unwrap();
+}
+
+// Play Home Assistant for one connection
+fn serve(listener: TcpListener, events: &'static [&'static str]) -> Vec<String> {
+    let (mut stream, _) = listener.accept().unwrap();
+    stream
+        .set_read_timeout(Some(Duration::from_secs(2)))
+        .unwrap();
+    let mut reader = BufReader::new(stream.try_clone().unwrap());
+    let mut request = String::new();
+    reader.read_line(&mut request).unwrap();
+    assert_eq!(request, "GET /api/websocket HTTP/1.1\r\n");
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        if line == "\r\n" {
+            break;
+        }
+    }
+    stream
+        .write_all(b"HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\n\r\n")
+        .unwrap();
+
+    let mut received = Vec::new();
+    write_frame(
+        &mut stream,
+        r#"{"type":"auth_required","ha_version":"2026.10.0"}"#,
+    );
+    received.push(read_frame(&mut stream));
+    write_frame(
+        &mut stream,
+        r#"{"type":"auth_ok","ha_version":"2026.10.0"}"#,
+    );
+    received.push(read_frame(&mut stream));
+    write_frame(
+        &mut stream,
+        r#"{"id":1,"type":"result","success":true,"result":null}"#,
+    );
+    for event in events {
+        write_frame(&mut stream, event);
+    }
+    // A close frame ends the session
+    stream.write_all(&[0x88, 0x00]).unwrap();
+    received
+}
+
+#[test]
+fn states_fill_the_template() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let server = thread::spawn(move || {
+        serve(
+            listener,
+            &[
+                r#"{"id":1,"type":"event","event":{"a":{"sensor.outside":{"s":"11.5","a":{"unit_of_measurement":"°C","friendly_name":"Outside"}},"lock.front_door":{"s":"locked","a":{}}}}}"#,
+                r#"{"id":1,"type":"event","event":{"c":{"sensor.outside":{"+":{"s":"12.0","lc":1760000000.0}}}}}"#,
+            ],
+        )
+    });
+
+    let simulator = Simulator::new(CommandSet::Birch, 20, 2);
+    let vfd = Arc::new(Mutex::new(simulator.vfd()));
+    let config = HomeAssistantConfig {
+        host: "127.0.0.1".to_string(),
+        port,
+        token: "secret".to_string(),
+        template: Template::new()
+            .field(TemplateField::new(
+                0,
+                0,
+                "{sensor.outside.name} {sensor.outside}{sensor.outside.unit}",
+            ))
+            .field(TemplateField::new(0, 1, "Door {lock.front_door}").align(Align::Right)),
+        ..HomeAssistantConfig::default()
+    };
+    assert!(home_assistant::follow(&vfd, &config).is_err());
+
+    let received = server.join().unwrap();
+    assert_eq!(received[0], r#"{"access_token":"secret","type":"auth"}"#);
+    assert_eq!(
+        received[1],
+        r#"{"entity_ids":["sensor.outside","lock.front_door"],"id":1,"type":"subscribe_entities"}"#
+    );
+    assert_eq!(
+        simulator.rows(),
+        ["Outside 12.0°C      ", "         Door locked"]
+    );
+}
+
+#[test]
+fn rejected_tokens_are_reported() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let server = thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        let mut line = String::new();
+        while line != "\r\n" {
+            line.clear();
+            reader.read_line(&mut line).unwrap();
+        }
+        stream
+            .write_all(b"HTTP/1.1 101 Switching Protocols\r\n\r\n")
+            .unwrap();
+        write_frame(&mut stream, r#"{"type":"auth_required"}"#);
+        read_frame(&mut stream);
+        write_frame(
+            &mut stream,
+            r#"{"type":"auth_invalid","message":"Invalid access token or password"}"#,
+        );
+    });
+
+    let simulator = Simulator::new(CommandSet::Birch, 20, 2);
+    let vfd = Arc::new(Mutex::new(simulator.vfd()));
+    let config = HomeAssistantConfig {
+        host: "127.0.0.1".to_string(),
+        port,
+        token: "expired".to_string(),
+        ..HomeAssistantConfig::default()
+    };
+    let error = home_assistant::follow(&vfd, &config).unwrap_err();
+    assert!(
+        error.to_string().contains("Invalid access token"),
+        "{}",
+        error
+    );
+    server.join().unwrap();
+}
+
+#[test]
+fn messages_split_into_many_frames_are_limited_as_a_whole() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let server = thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        let mut line = String::new();
+        while line != "\r\n" {
+            line.clear();
+            reader.read_line(&mut line).unwrap();
+        }
+        stream
+            .write_all(b"HTTP/1.1 101 Switching Protocols\r\n\r\n")
+            .unwrap();
+        // 17 MiB in 64 KiB frames, each well under the limit on its own; the
+        // client may hang up before all of them are sent
+        let chunk = [b'a'; 65536];
+        for i in 0..272 {
+            let opcode = if i == 0 { 0x01 } else { 0x00 };
+            let mut frame = vec![opcode, 127];
+            frame.extend((chunk.len() as u64).to_be_bytes());
+            frame.extend(chunk);
+            if stream.write_all(&frame).is_err() {
+                return;
+            }
+        }
+        let _ = stream.write_all(&[0x80, 0x00]);
+    });
+
+    let simulator = Simulator::new(CommandSet::Birch, 20, 2);
+    let vfd = Arc::new(Mutex::new(simulator.vfd()));
+    let config = HomeAssistantConfig {
+        host: "127.0.0.1".to_string(),
+        port,
+        token: "secret".to_string(),
+        ..HomeAssistantConfig::default()
+    };
+    let error = home_assistant::follow(&vfd, &config).unwrap_err();
+    assert!(error.to_string().contains("too large"), "{}", error);
+    server.join().unwrap();
+}
@@ -0,0 +1,190 @@
+#![cfg(feature = "http")]
+
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use vfd_dsp_v9fb_over_serial::http::serve_http;
+use vfd_dsp_v9fb_over_serial::protocol::{Reply, Request};
+use vfd_dsp_v9fb_over_serial::{Align, CommandSet, Simulator, daemon::DaemonCommand};
+
+// A server on a free local port, drawing on a fresh simulator
+fn serve() -> (u16, Simulator) {
+    let port = TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let simulator = Simulator::new(CommandSet::Birch, 20, 2);
+    let vfd = Arc::new(Mutex::new(simulator.vfd()));
+    thread::spawn(move || serve_http(vfd, &format!("127.0.0.1:{}", port)));
+    (port, simulator)
+}
+
+// Status code and body of the answer to one request
+fn send(port: u16, method: &str, path: &str, body: &str) -> (u16, String) {
+    let mut stream = loop {
+        match TcpStream::connect(("127.0.0.1", port)) {
+            Ok(stream) => break stream,
+            // Until the server thread is listening
+            Err(_) => thread::sleep(Duration::from_millis(10)),
+        }
+    };
+    write!(
+        stream,
+        "{method} {path} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\nContent-Length: {}\r\n\r\n{body}",
+        body.len()
+    )
+    .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    let (head, body) = response.split_once("\r\n\r\n").unwrap();
+    let status = head.split(' ').nth(1).unwrap().parse().unwrap();
+    (status, body.to_string())
+}
+
+fn reply(body: &str) -> Reply {
+    serde_json::from_str(body).unwrap()
+}
+
+#[test]
+fn requests_parse_into_daemon_commands() {
+    for (json, command) in [
+        (r#"{"cmd": "clear"}"#, DaemonCommand::Clear),
+        (
+            r#"{"cmd": "write", "text": "Hi"}"#,
+            DaemonCommand::Write {
+                text: "Hi".to_string(),
+            },
+        ),
+        (
+            r#"{"cmd": "write", "row": 1, "text": "2.50", "align": "right"}"#,
+            DaemonCommand::Line {
+                row: 1,
+                align: Align::Right,
+                text: "2.50".to_string(),
+            },
+        ),
+        (
+            r#"{"cmd": "blink", "interval_ms": 500}"#,
+            DaemonCommand::Blink(Duration::from_millis(500)),
+        ),
+    ] {
+        let request: Request = serde_json::from_str(json).unwrap();
+        assert_eq!(DaemonCommand::try_from(request), Ok(command), "{json}");
+    }
+}
+
+#[test]
+fn malformed_requests_are_rejected() {
+    for json in [
+        r#"{"cmd": "teleport"}"#,
+        r#"{"cmd": "cursor", "x": 0}"#,
+        r#"{"cmd": "cursor", "x": 0, "y": 1, "z": 2}"#,
+        r#"{"cmd": "cursor", "x": 300, "y": 0}"#,
+        r#"{"text": "no command"}"#,
+    ] {
+        assert!(serde_json::from_str::<Request>(json).is_err(), "{json}");
+    }
+    let request: Request = serde_json::from_str(r#"{"cmd": "brightness", "level": 55}"#).unwrap();
+    assert!(DaemonCommand::try_from(request).is_err());
+}
+
+#[test]
+fn commands_run_in_order_and_dump_answers_with_the_rows() {
+    let (port, simulator) = serve();
+    let (status, body) = send(
+        port,
+        "POST",
+        "/command",
+        r#"[{"cmd": "write", "row": 0, "text": "ORDER 42"},
+            {"cmd": "write", "row": 1, "text": "READY", "align": "right"},
+            {"cmd": "dump"}]"#,
+    );
+    assert_eq!(status, 200, "{body}");
+    assert_eq!(
+        reply(&body).rows.unwrap(),
+        ["ORDER 42            ", "               READY"]
+    );
+    assert_eq!(simulator.row(1), "               READY");
+
+    let (status, body) = send(port, "POST", "/command", r#"{"cmd": "ping"}"#);
+    assert_eq!((status, reply(&body)), (200, Reply::ok()));
+}
+
+#[test]
+fn bad_command_bodies_are_answered_with_400() {
+    let (port, simulator) = serve();
+    for body in [
+        "not json",
+        r#"{"cmd": "teleport"}"#,
+        r#"[{"cmd": "clear"}, {"cmd": "brightness", "level": 55}]"#,
+    ] {
+        let (status, answer) = send(port, "POST", "/command", body);
+        assert_eq!(status, 400, "{body}");
+        let answer = reply(&answer);
+        assert!(!answer.ok && answer.error.is_some(), "{body}");
+    }
+    // Nothing of a rejected array runs
+    simulator.feed(b"x");
+    let (status, _) = send(
+        port,
+        "POST",
+        "/command",
+        r#"[{"cmd": "clear"}, {"cmd": "nope"}]"#,
+    );
+    assert_eq!(status, 400);
+    assert_eq!(simulator.row(0).trim(), "x");
+}
+
+#[test]
+fn display_failures_are_answered_with_500_after_what_ran() {
+    let (port, simulator) = serve();
+    let (status, body) = send(
+        port,
+        "POST",
+        "/command",
+        r#"[{"cmd": "write", "row": 0, "text": "First"},
+            {"cmd": "cursor", "x": 0, "y": 5},
+            {"cmd": "write", "row": 1, "text": "Never"}]"#,
+    );
+    assert_eq!(status, 500);
+    assert!(reply(&body).error.unwrap().contains("Row"));
+    assert_eq!(
+        simulator.rows(),
+        ["First               ", "                    "]
+    );
+}
+
+#[test]
+fn display_route_writes_lines() {
+    let (port, simulator) = serve();
+    let (status, _) = send(
+        port,
+        "POST",
+        "/display",
+        r#"{"line1": "ORDER 42", "line2": "READY", "align": "center"}"#,
+    );
+    assert_eq!(status, 204);
+    assert_eq!(
+        simulator.rows(),
+        ["      ORDER 42      ", "       READY        "]
+    );
+
+    let (status, body) = send(port, "POST", "/display", r#"{"line1": 42}"#);
+    assert_eq!(status, 400, "{body}");
+}
+
+#[test]
+fn routes_answer_only_their_methods() {
+    let (port, _) = serve();
+    assert_eq!(send(port, "GET", "/command", "").0, 405);
+    assert_eq!(send(port, "PUT", "/display", "").0, 405);
+    assert_eq!(send(port, "POST", "/metrics", "").0, 405);
+    assert_eq!(send(port, "GET", "/nowhere", "").0, 404);
+
+    let (status, body) = send(port, "GET", "/metrics", "");
+    assert_eq!(status, 200);
+    assert!(!body.is_empty());
+}
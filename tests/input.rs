@@ -0,0 +1,132 @@
+#![cfg(all(feature = "input", target_os = "linux"))]
+
+use std::collections::VecDeque;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::os::fd::OwnedFd;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use vfd_dsp_v9fb_over_serial::input::{
+    self, ButtonAction, Buttons, EvdevButtons, GpioButtons, parse_bindings,
+};
+use vfd_dsp_v9fb_over_serial::{CommandSet, Paginator, ScreenCycle, Simulator};
+
+// A struct input_event as the kernel writes it on 64-bit Linux
+fn input_event(kind: u16, code: u16, value: i32) -> Vec<u8> {
+    let mut event = vec![0u8; 16];
+    event.extend(kind.to_ne_bytes());
+    event.extend(code.to_ne_bytes());
+    event.extend(value.to_ne_bytes());
+    event
+}
+
+#[test]
+fn evdev_key_presses_map_to_actions() {
+    let (reader, mut writer) = io::pipe().unwrap();
+    let keys = parse_bindings("106=next, 105=previous").unwrap();
+    let mut buttons = EvdevButtons::from_file(File::from(OwnedFd::from(reader)), &keys);
+    assert_eq!(buttons.wait(Duration::from_millis(10)).unwrap(), None);
+
+    // A press of an unbound key, then KEY_LEFT pressed, repeated and released
+    writer.write_all(&input_event(1, 28, 1)).unwrap();
+    writer.write_all(&input_event(1, 105, 1)).unwrap();
+    writer.write_all(&input_event(1, 105, 2)).unwrap();
+    writer.write_all(&input_event(1, 105, 0)).unwrap();
+    let timeout = Duration::from_millis(100);
+    assert_eq!(buttons.wait(timeout).unwrap(), Some(ButtonAction::Previous));
+    assert_eq!(buttons.wait(timeout).unwrap(), None);
+}
+
+#[test]
+fn gpio_buttons_fire_when_pressed() {
+    let root = std::env::temp_dir().join(format!("vfd-gpio-{}", std::process::id()));
+    fs::create_dir_all(root.join("gpio17")).unwrap();
+    fs::write(root.join("gpio17/value"), "1\n").unwrap();
+    let mut buttons = GpioButtons::open_in(&root, &[(17, ButtonAction::Acknowledge)]).unwrap();
+    assert_eq!(
+        fs::read_to_string(root.join("gpio17/direction")).unwrap(),
+        "in"
+    );
+    assert_eq!(buttons.wait(Duration::from_millis(20)).unwrap(), None);
+
+    fs::write(root.join("gpio17/value"), "0\n").unwrap();
+    assert_eq!(
+        buttons.wait(Duration::from_millis(100)).unwrap(),
+        Some(ButtonAction::Acknowledge)
+    );
+    assert_eq!(buttons.wait(Duration::from_millis(20)).unwrap(), None);
+    fs::remove_dir_all(root).unwrap();
+}
+
+// Presses after a pause each, then an error that ends serve_buttons
+struct Script(VecDeque<ButtonAction>);
+
+impl Buttons for Script {
+    fn wait(&mut self, _timeout: Duration) -> io::Result<Option<ButtonAction>> {
+        thread::sleep(Duration::from_millis(50));
+        self.0
+            .pop_front()
+            .map(Some)
+            .ok_or_else(|| io::ErrorKind::UnexpectedEof.into())
+    }
+}
+
+#[test]
+fn buttons_switch_screens() {
+    let simulator = Simulator::new(CommandSet::Birch, 20, 2);
+    let vfd = Arc::new(Mutex::new(simulator.vfd()));
+    let screens = ScreenCycle::new()
+        .screen("hours", Paginator::new("Mon-Fri 9-18"))
+        .screen("wifi", Paginator::new("WiFi: Guest"))
+        .screen("phone", Paginator::new("Call 555-0100"));
+    let presses = [ButtonAction::Next, ButtonAction::Previous];
+    let script = Script(presses.into_iter().collect());
+    assert!(input::serve_buttons(vfd, script, screens).is_err());
+    assert_eq!(simulator.row(0).trim_end(), "Call 555-0100");
+}
+
+// Presses sent from the test, after telling it the buttons are being read
+struct Remote {
+    presses: Receiver<ButtonAction>,
+    waiting: Sender<()>,
+}
+
+impl Buttons for Remote {
+    fn wait(&mut self, timeout: Duration) -> io::Result<Option<ButtonAction>> {
+        let _ = self.waiting.send(());
+        match self.presses.recv_timeout(timeout) {
+            Ok(action) => Ok(Some(action)),
+            Err(RecvTimeoutError::Timeout) => Ok(None),
+            Err(RecvTimeoutError::Disconnected) => Err(io::ErrorKind::UnexpectedEof.into()),
+        }
+    }
+}
+
+#[test]
+fn acknowledge_ends_the_alert() {
+    let simulator = Simulator::new(CommandSet::Birch, 20, 2);
+    let vfd = Arc::new(Mutex::new(simulator.vfd()));
+    let (press, presses) = mpsc::channel();
+    let (waiting, started) = mpsc::channel();
+    let buttons = {
+        let vfd = Arc::clone(&vfd);
+        let remote = Remote { presses, waiting };
+        thread::spawn(move || input::serve_buttons(vfd, remote, ScreenCycle::new()))
+    };
+    started.recv().unwrap();
+    let acknowledge = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(100));
+        press.send(ButtonAction::Acknowledge).unwrap();
+    });
+
+    let shown = Instant::now();
+    vfd.lock()
+        .unwrap()
+        .show_alert("Order ready", Duration::from_secs(5))
+        .unwrap();
+    assert!(shown.elapsed() < Duration::from_secs(2));
+    acknowledge.join().unwrap();
+    assert!(buttons.join().unwrap().is_err());
+}
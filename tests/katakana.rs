@@ -0,0 +1,13 @@
+use vfd_dsp_v9fb_over_serial::{Align, CodePage, CommandSet, Simulator};
+
+#[test]
+fn full_width_input_is_shown_half_width() {
+    let simulator = Simulator::new(CommandSet::Birch, 20, 2);
+    let mut vfd = simulator.vfd();
+    vfd.set_code_page(CodePage::Katakana).unwrap();
+    vfd.write_line(0, "コーヒー　３５０", Align::Left).unwrap();
+    vfd.write_line(1, "ありがとうございます", Align::Right)
+        .unwrap();
+    assert_eq!(simulator.row(0).trim_end(), "ｺｰﾋｰ 350");
+    assert_eq!(simulator.row(1), "       ｱﾘｶﾞﾄｳｺﾞｻﾞｲﾏｽ");
+}
@@ -0,0 +1,24 @@
+use std::fmt::Write;
+use vfd_dsp_v9fb_over_serial::{Align, CommandSet, Simulator, VfdError};
+
+#[test]
+fn each_newline_moves_to_the_next_row() {
+    let simulator = Simulator::new(CommandSet::Birch, 10, 2);
+    let mut vfd = simulator.vfd();
+    let mut line = vfd.line(0).align(Align::Right);
+    write!(line, "Sub {:.2}\nTax", 9.5).unwrap();
+    write!(line, " {:.2}", 0.76).unwrap();
+    line.finish().unwrap();
+    assert_eq!(simulator.rows(), ["  Sub 9.50", "  Tax 0.76"]);
+}
+
+#[test]
+fn errors_are_kept_for_the_caller() {
+    let simulator = Simulator::new(CommandSet::Birch, 10, 2);
+    let mut vfd = simulator.vfd();
+    let mut line = vfd.line(1);
+    assert!(writeln!(line, "one\ntwo").is_err());
+    assert!(matches!(line.error(), Some(VfdError::OutOfBounds { .. })));
+    drop(line);
+    assert_eq!(simulator.row(1), "one       ");
+}
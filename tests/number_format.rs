@@ -0,0 +1,28 @@
+use vfd_dsp_v9fb_over_serial::{CodePage, CommandSet, Currency, Locale, NumberFormat, Simulator};
+
+#[test]
+fn numbers_are_grouped_and_rounded() {
+    let format = Locale::FrFr.number_format();
+    assert_eq!(format.format(1234567.891), "1 234 567,89");
+    assert_eq!(format.format(-0.001), "0,00");
+    assert_eq!(format.format(999.999), "1 000,00");
+    assert_eq!(format.decimals(0).format(-1234.4), "-1 234");
+    assert_eq!(NumberFormat::default().format_minor(-5), "-0.05");
+}
+
+#[test]
+fn prices_use_the_locale() {
+    assert_eq!(Locale::JaJp.currency().format(1234567), "¥1,234,567");
+    assert_eq!(Locale::EnGb.currency().format(123456), "£1,234.56");
+    assert_eq!(Currency::eur().format(123456), "1234,56 €");
+    assert_eq!("de_DE".parse::<Locale>(), Ok(Locale::DeDe));
+    assert!("xx".parse::<Locale>().is_err());
+
+    let simulator = Simulator::new(CommandSet::Birch, 20, 2);
+    let mut vfd = simulator.vfd();
+    vfd.set_code_page(CodePage::Wpc1252).unwrap();
+    vfd.set_currency(Locale::DeDe.currency());
+    vfd.write_item_price("Kaffeemaschine Deluxe", 123456, 1)
+        .unwrap();
+    assert_eq!(simulator.row(1), "Kaffeemas 1.234,56 €");
+}
@@ -0,0 +1,79 @@
+use std::io::{self, Write};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use vfd_dsp_v9fb_over_serial::{CommandSet, Vfd, VfdError, VfdObserver};
+
+// A port that fails with `kind` once unplugged
+struct Unpluggable {
+    unplugged: Arc<AtomicBool>,
+    kind: io::ErrorKind,
+}
+
+impl Write for Unpluggable {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.unplugged.load(Ordering::Relaxed) {
+            return Err(self.kind.into());
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+struct Counts {
+    disconnected: AtomicUsize,
+    write_errors: AtomicUsize,
+}
+
+impl VfdObserver for Counts {
+    fn on_disconnected(&self, _error: &VfdError) {
+        self.disconnected.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn on_write_error(&self, _error: &VfdError) {
+        self.write_errors.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+fn observed(kind: io::ErrorKind) -> (Vfd, Arc<AtomicBool>, Arc<Counts>) {
+    let unplugged = Arc::new(AtomicBool::new(false));
+    let port = Unpluggable {
+        unplugged: Arc::clone(&unplugged),
+        kind,
+    };
+    let counts = Arc::new(Counts::default());
+    let mut vfd = Vfd::from_transport(port, CommandSet::Birch, 20, 2);
+    vfd.set_observer(Arc::clone(&counts));
+    (vfd, unplugged, counts)
+}
+
+#[test]
+fn disconnect_is_reported_once() {
+    let (mut vfd, unplugged, counts) = observed(io::ErrorKind::BrokenPipe);
+    vfd.write_text("Hello").unwrap();
+    assert_eq!(counts.write_errors.load(Ordering::Relaxed), 0);
+
+    unplugged.store(true, Ordering::Relaxed);
+    assert!(vfd.write_text("Hello").unwrap_err().is_disconnected());
+    assert!(vfd.clear().is_err());
+    assert_eq!(counts.write_errors.load(Ordering::Relaxed), 2);
+    assert_eq!(counts.disconnected.load(Ordering::Relaxed), 1);
+
+    let metrics = vfd.metrics();
+    assert_eq!((metrics.writes(), metrics.bytes_written()), (1, 5));
+    assert_eq!((metrics.write_errors(), metrics.disconnects()), (2, 1));
+    assert!(!metrics.is_connected());
+    assert!(metrics.prometheus().contains("vfd_connected 0\n"));
+}
+
+#[test]
+fn other_write_errors_are_not_disconnects() {
+    let (mut vfd, unplugged, counts) = observed(io::ErrorKind::TimedOut);
+    unplugged.store(true, Ordering::Relaxed);
+    assert!(vfd.write_text("Hello").is_err());
+    assert_eq!(counts.write_errors.load(Ordering::Relaxed), 1);
+    assert_eq!(counts.disconnected.load(Ordering::Relaxed), 0);
+}
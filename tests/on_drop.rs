@@ -0,0 +1,42 @@
+#![cfg(feature = "std")]
+
+use vfd_dsp_v9fb_over_serial::{Align, CommandSet, OnDrop, Simulator};
+
+#[test]
+fn leaving_the_display_sends_what_was_still_batched() {
+    let simulator = Simulator::new(CommandSet::Birch, 20, 2);
+    let mut vfd = simulator.vfd();
+    vfd.begin_batch();
+    vfd.write_line(0, "Last words", Align::Left).unwrap();
+    assert_eq!(simulator.row(0).trim(), "");
+
+    drop(vfd);
+    assert_eq!(simulator.row(0).trim(), "Last words");
+}
+
+#[test]
+fn batched_output_goes_out_before_the_display_is_turned_off() {
+    let simulator = Simulator::new(CommandSet::Birch, 20, 2);
+    let mut vfd = simulator.vfd();
+    vfd.set_on_drop(OnDrop::DisplayOff);
+    vfd.begin_batch();
+    vfd.write_line(1, "Goodnight", Align::Right).unwrap();
+
+    drop(vfd);
+    assert_eq!(simulator.row(1), "           Goodnight");
+}
+
+#[test]
+fn the_drop_message_replaces_what_was_batched() {
+    let simulator = Simulator::new(CommandSet::Birch, 20, 2);
+    let mut vfd = simulator.vfd();
+    vfd.set_on_drop(OnDrop::Message("CLOSED".to_string()));
+    vfd.begin_batch();
+    vfd.write_line(1, "Total: 4.20", Align::Right).unwrap();
+
+    drop(vfd);
+    assert_eq!(
+        simulator.rows(),
+        ["CLOSED              ", "                    "]
+    );
+}
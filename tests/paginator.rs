@@ -0,0 +1,41 @@
+use std::thread;
+use std::time::Duration;
+use vfd_dsp_v9fb_over_serial::widget::Widget;
+use vfd_dsp_v9fb_over_serial::{CommandSet, Paginator, Simulator};
+
+#[test]
+fn pages_turn_both_ways_and_wrap_around() {
+    let simulator = Simulator::new(CommandSet::Birch, 10, 2);
+    let mut vfd = simulator.vfd();
+    let mut pages = Paginator::from_lines(["One", "Two", "Three", "Four", "Five"]);
+    assert_eq!(pages.page_count((10, 2)), 3);
+    pages.prev_page(&mut vfd).unwrap();
+    assert_eq!(pages.page(), 2);
+    assert_eq!(simulator.rows(), ["Five      ", "       3/3"]);
+    pages.next_page(&mut vfd).unwrap();
+    assert_eq!(simulator.rows(), ["One       ", "Two    1/3"]);
+}
+
+#[test]
+fn a_single_page_has_no_indicator() {
+    let simulator = Simulator::new(CommandSet::Birch, 10, 2);
+    let mut vfd = simulator.vfd();
+    let mut pages = Paginator::new("Thank you\nCome again");
+    pages.show(&mut vfd).unwrap();
+    assert_eq!(simulator.rows(), ["Thank you ", "Come again"]);
+}
+
+#[test]
+fn auto_advance_turns_pages_when_ticked() {
+    let simulator = Simulator::new(CommandSet::Birch, 10, 1);
+    let mut vfd = simulator.vfd();
+    let mut pages = Paginator::from_lines(["One", "Two"])
+        .indicator(false)
+        .auto_advance(Duration::from_millis(20));
+    assert!(pages.tick(&mut vfd).unwrap());
+    assert!(!pages.tick(&mut vfd).unwrap());
+    assert_eq!(simulator.row(0), "One       ");
+    thread::sleep(Duration::from_millis(30));
+    assert!(pages.tick(&mut vfd).unwrap());
+    assert_eq!(simulator.row(0), "Two       ");
+}
@@ -0,0 +1,104 @@
+#![cfg(feature = "config")]
+
+use std::path::Path;
+use std::sync::Arc;
+use vfd_dsp_v9fb_over_serial::{
+    Align, BrightnessLevel, CodePage, CommandSet, ProtocolDefinition, Simulator, VfdError,
+};
+
+fn custom(toml: &str) -> CommandSet {
+    CommandSet::Custom(Arc::new(ProtocolDefinition::from_toml(toml).unwrap()))
+}
+
+#[test]
+fn bundled_definition_matches_builtin_command_set() {
+    let protocol = ProtocolDefinition::load(Path::new("protocols/epson-compatible.toml")).unwrap();
+    let custom = CommandSet::Custom(Arc::new(protocol));
+    let birch = CommandSet::Birch;
+    assert_eq!(custom.initialize(), birch.initialize());
+    assert_eq!(custom.clear(), birch.clear());
+    assert_eq!(custom.set_cursor(3, 1, 20), birch.set_cursor(3, 1, 20));
+    assert_eq!(custom.clear_line(), birch.clear_line());
+    for level in [BrightnessLevel::Percent20, BrightnessLevel::Percent100] {
+        assert_eq!(custom.set_brightness(level), birch.set_brightness(level));
+    }
+    assert_eq!(
+        custom.set_code_page(CodePage::Pc858),
+        birch.set_code_page(CodePage::Pc858)
+    );
+}
+
+#[test]
+fn linear_cursor_addressing() {
+    let command_set = custom(
+        r#"
+        name = "Linear"
+        clear = "0E"
+        cursor = "10 cell+1"
+        "#,
+    );
+    assert_eq!(command_set.set_cursor(2, 1, 20), [0x10, 23]);
+    // No init sequence given, so initializing clears
+    assert_eq!(command_set.initialize(), [0x0E]);
+}
+
+#[test]
+fn simulated_display_follows_the_definition() {
+    let command_set = custom(
+        r#"
+        name = "Acme"
+        init = "'R'"
+        clear = "0x0E"
+        cursor = "1B 'H' y x"
+
+        [brightness]
+        40 = "04 40"
+        "#,
+    );
+    let simulator = Simulator::new(command_set, 16, 2);
+    let mut vfd = simulator.vfd();
+    vfd.initialize().unwrap();
+    vfd.write_line(1, "Total 4.20", Align::Right).unwrap();
+    vfd.set_brightness(BrightnessLevel::Percent60).unwrap();
+    assert_eq!(simulator.row(1), "      Total 4.20");
+    assert_eq!(simulator.brightness(), BrightnessLevel::Percent40);
+    assert_eq!(simulator.unknown_bytes(), 0);
+}
+
+#[test]
+fn commands_missing_from_the_definition_are_not_supported() {
+    let command_set = custom(
+        r#"
+        name = "Minimal"
+        clear = "0C"
+        cursor = "1F 24 x y"
+        "#,
+    );
+    let mut vfd = Simulator::new(command_set, 20, 2).vfd();
+    assert!(!vfd.capabilities().supports_brightness);
+    assert!(matches!(
+        vfd.set_brightness(BrightnessLevel::Percent40),
+        Err(VfdError::NotSupported(_))
+    ));
+    assert!(matches!(
+        vfd.define_custom_char(0, &[0; 7]),
+        Err(VfdError::NotSupported(_))
+    ));
+    assert!(matches!(
+        vfd.set_code_page(CodePage::Pc850),
+        Err(VfdError::NotSupported(_))
+    ));
+}
+
+#[test]
+fn invalid_sequences_name_their_key() {
+    let error = ProtocolDefinition::from_toml(
+        r#"
+        name = "Broken"
+        clear = "0C"
+        cursor = "1F 24 z"
+        "#,
+    )
+    .unwrap_err();
+    assert!(error.to_string().starts_with("cursor: Invalid byte 'z'"));
+}
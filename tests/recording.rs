@@ -0,0 +1,29 @@
+use std::time::{Duration, SystemTime};
+use vfd_dsp_v9fb_over_serial::{Recording, hex_dump};
+
+// Chunks two seconds apart
+fn recording() -> Recording {
+    let start = SystemTime::UNIX_EPOCH + Duration::from_secs(1_760_486_400);
+    let dump = [0, 2, 4]
+        .iter()
+        .map(|secs| hex_dump(start + Duration::from_secs(*secs), b"Hi"))
+        .collect::<String>();
+    Recording::parse_hex_dump(&dump).unwrap()
+}
+
+#[test]
+fn replays_take_the_recording_time_over_the_speed() {
+    let recording = recording();
+    assert_eq!(recording.replay_duration(1.0), Duration::from_secs(4));
+    assert_eq!(recording.replay_duration(4.0), Duration::from_secs(1));
+    assert_eq!(recording.replay_duration(0.0), Duration::ZERO);
+    assert_eq!(recording.replay_duration(f64::NAN), Duration::ZERO);
+    assert_eq!(recording.replay_duration(f64::INFINITY), Duration::ZERO);
+}
+
+#[test]
+fn pauses_are_capped_at_slow_speeds() {
+    let recording = recording();
+    assert_eq!(recording.replay_duration(0.01), Duration::from_secs(120));
+    assert_eq!(recording.replay_duration(1e-300), Duration::from_secs(120));
+}
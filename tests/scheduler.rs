@@ -0,0 +1,47 @@
+use std::time::{Duration, Instant};
+use vfd_dsp_v9fb_over_serial::{
+    Align, CommandSet, Marquee, Scheduler, ScrollDirection, Simulator, Spinner,
+};
+
+#[test]
+fn frames_are_limited_to_the_frame_rate() {
+    let simulator = Simulator::new(CommandSet::Birch, 20, 2);
+    let mut vfd = simulator.vfd();
+    let mut scheduler = Scheduler::new(20, 2)
+        .widget(Spinner::new(0, 1).speed(Duration::from_millis(1)))
+        .widget(Spinner::new(19, 1).speed(Duration::from_millis(1)))
+        .max_frame_rate(20);
+
+    // A millisecond at a time through 200 ms
+    let start = Instant::now();
+    let frames = (0..200)
+        .map(|ms| start + Duration::from_millis(ms))
+        .filter(|&now| scheduler.tick_at(&mut vfd, now).unwrap())
+        .count();
+    // One frame straight away, then one every 50 ms
+    assert_eq!(frames, 4);
+}
+
+#[test]
+fn text_between_widgets_is_left_alone() {
+    let simulator = Simulator::new(CommandSet::Birch, 20, 2);
+    let mut vfd = simulator.vfd();
+    vfd.write_line(1, "Loading", Align::Center).unwrap();
+    let mut scheduler = Scheduler::for_display(&vfd)
+        .widget(Spinner::new(0, 1))
+        .widget(Spinner::new(19, 1))
+        .widget(Marquee::new(
+            "Now playing",
+            0,
+            20,
+            Duration::from_millis(100),
+            ScrollDirection::Left,
+        ))
+        .max_frame_rate(0);
+    assert!(scheduler.tick(&mut vfd).unwrap());
+    assert_eq!(simulator.row(0).trim(), "Now playing");
+    assert_eq!(simulator.row(1), "|     Loading      |");
+
+    // Nothing is due again until the spinners step
+    assert!(!scheduler.tick(&mut vfd).unwrap());
+}
@@ -0,0 +1,121 @@
+#![cfg(feature = "std")]
+
+use std::time::{Duration, Instant};
+use vfd_dsp_v9fb_over_serial::script::{Script, Step};
+use vfd_dsp_v9fb_over_serial::{Align, BrightnessLevel, CommandSet, Simulator};
+
+fn run(source: &str) -> Simulator {
+    let script: Script = source.parse().unwrap();
+    let simulator = Simulator::new(CommandSet::Birch, 20, 2);
+    let mut vfd = simulator.vfd();
+    script.run(&mut vfd).unwrap();
+    vfd.flush().unwrap();
+    simulator
+}
+
+#[test]
+fn scripts_draw_on_the_display() {
+    let simulator = run("# Till greeting
+        clear
+        brightness 40
+        line 0 center \"Welcome!\"
+        goto 2 1
+        write \"Open \\\"9-5\\\"\"");
+    assert_eq!(
+        simulator.rows(),
+        ["      Welcome!      ", "  Open \"9-5\"        "]
+    );
+    assert_eq!(simulator.brightness(), BrightnessLevel::Percent40);
+}
+
+#[test]
+fn alerts_leave_the_display_as_they_found_it() {
+    let simulator = run("line 1 right \"2.50\"\nalert \"Card declined\" for 50ms blink 20ms");
+    assert_eq!(
+        simulator.rows(),
+        ["                    ", "                2.50"]
+    );
+}
+
+#[test]
+fn sleeps_wait_and_marquees_scroll_for_their_duration() {
+    let started = Instant::now();
+    let simulator = run("sleep 30ms\nmarquee \"Fresh bread\" row 1 for 100ms speed 20ms");
+    assert!(started.elapsed() >= Duration::from_millis(130));
+    assert!(simulator.row(0).trim().is_empty());
+    assert!(!simulator.row(1).trim().is_empty());
+}
+
+#[test]
+fn commands_and_options_are_parsed() {
+    let script: Script = "LINE 1 \"Total\"\nmarquee \"News\" right speed 1.5s\nalert \"Hi\" for 2s"
+        .parse()
+        .unwrap();
+    assert_eq!(script.steps.len(), 3);
+    assert_eq!(
+        script.steps[0],
+        Step::Line {
+            row: 1,
+            align: Align::Left,
+            text: "Total".to_string(),
+        }
+    );
+    let Step::Marquee {
+        row,
+        duration,
+        speed,
+        ..
+    } = &script.steps[1]
+    else {
+        panic!("expected a marquee, got {:?}", script.steps[1]);
+    };
+    assert_eq!(
+        (*row, *duration, *speed),
+        (0, Duration::from_secs(5), Duration::from_millis(1500))
+    );
+    let Step::Alert(alert) = &script.steps[2] else {
+        panic!("expected an alert, got {:?}", script.steps[2]);
+    };
+    assert_eq!(
+        (alert.duration, alert.blink),
+        (Duration::from_secs(2), None)
+    );
+}
+
+#[test]
+fn errors_name_the_line() {
+    for (source, message) in [
+        ("clear\ngoto 1", "Line 2: Usage: goto <x> <y>"),
+        ("write \"open", "Line 1: Unterminated string"),
+        ("write \"a\\tb\"", "Line 1: Unknown escape '\\t'"),
+        ("\n\nblink", "Line 3: Unknown command 'blink'"),
+        ("goto 300 0", "Line 1: Invalid column '300'"),
+        (
+            "sleep 5m",
+            "Line 1: Invalid duration '5m'. Expected a number followed by ms or s.",
+        ),
+        ("alert \"Hi\" for", "Line 1: Missing value for 'for'"),
+    ] {
+        assert_eq!(
+            source.parse::<Script>(),
+            Err(message.to_string()),
+            "{source:?}"
+        );
+    }
+}
+
+#[test]
+fn loading_reports_the_file_with_the_error() {
+    let path = std::env::temp_dir().join(format!("vfd-script-{}.vfd", std::process::id()));
+    std::fs::write(&path, "clear\nbogus").unwrap();
+    let error = Script::load(&path).unwrap_err();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+    assert!(
+        error
+            .to_string()
+            .ends_with("Line 2: Unknown command 'bogus'"),
+        "{error}"
+    );
+}
@@ -0,0 +1,95 @@
+#![cfg(feature = "rhai")]
+
+use std::sync::{Arc, Mutex};
+use vfd_dsp_v9fb_over_serial::scripting::ScriptEngine;
+use vfd_dsp_v9fb_over_serial::{CommandSet, Simulator, VfdError};
+
+fn engine() -> (ScriptEngine, Simulator) {
+    let simulator = Simulator::new(CommandSet::Birch, 20, 2);
+    let engine = ScriptEngine::new(Arc::new(Mutex::new(simulator.vfd())));
+    (engine, simulator)
+}
+
+// Runs `source` from a file of its own
+fn run_file(engine: &ScriptEngine, name: &str, source: &str) -> Result<(), VfdError> {
+    let path = std::env::temp_dir().join(format!("vfd-{}-{}.rhai", name, std::process::id()));
+    std::fs::write(&path, source).unwrap();
+    let result = engine.run_file(&path);
+    std::fs::remove_file(&path).unwrap();
+    result
+}
+
+#[test]
+fn display_errors_come_out_unchanged() {
+    let (engine, _) = engine();
+    let error = run_file(&engine, "row", r#"write_line(5, "Off the display");"#).unwrap_err();
+    assert!(
+        matches!(
+            error,
+            VfdError::OutOfBounds {
+                what: "Row",
+                value: 5,
+                max: 1
+            }
+        ),
+        "{error:?}"
+    );
+
+    // Also from inside a function the script defines
+    let error = engine
+        .run("fn show() { cursor(30, 0); } show();")
+        .unwrap_err();
+    assert!(
+        matches!(error, VfdError::OutOfBounds { value: 30, .. }),
+        "{error:?}"
+    );
+}
+
+#[test]
+fn scripts_that_do_not_parse_are_invalid_input() {
+    let (engine, _) = engine();
+    let error = run_file(&engine, "parse", "write_line(0, ").unwrap_err();
+    let VfdError::InvalidInput(message) = error else {
+        panic!("expected invalid input, got {error:?}");
+    };
+    assert!(message.starts_with("Script error in "), "{message}");
+    assert!(message.contains("vfd-parse-"), "{message}");
+}
+
+#[test]
+fn failures_of_the_script_itself_are_invalid_input() {
+    let (engine, simulator) = engine();
+    for source in [
+        r#"write_line(300, "x");"#,
+        "no_such_function();",
+        r#"throw "Gave up";"#,
+    ] {
+        let error = run_file(&engine, "runtime", source).unwrap_err();
+        assert!(
+            matches!(error, VfdError::InvalidInput(_)),
+            "{source}: {error:?}"
+        );
+    }
+    assert_eq!(simulator.rows().concat().trim(), "");
+}
+
+#[test]
+fn unreadable_files_are_io_errors() {
+    let (engine, _) = engine();
+    let missing = std::env::temp_dir().join("vfd-no-such-script.rhai");
+    let error = engine.run_file(&missing).unwrap_err();
+    assert!(matches!(error, VfdError::Io(_)), "{error:?}");
+}
+
+#[test]
+fn caught_display_errors_read_as_their_message() {
+    let (engine, simulator) = engine();
+    engine
+        .run(r#"try { cursor(0, 9); } catch (e) { write_line(0, `${e}`); }"#)
+        .unwrap();
+    assert!(
+        simulator.row(0).starts_with("Row 9"),
+        "{}",
+        simulator.row(0)
+    );
+}
@@ -0,0 +1,45 @@
+#![cfg(feature = "serial")]
+
+use serialport::{DataBits, FlowControl, Parity, StopBits};
+use vfd_dsp_v9fb_over_serial::{CommandSet, SerialProfile};
+
+#[test]
+fn profiles_parse_back_from_their_display_form() {
+    let mut seven_odd_two = SerialProfile::new(4800, Parity::Odd);
+    seven_odd_two.data_bits = DataBits::Seven;
+    seven_odd_two.stop_bits = StopBits::Two;
+    let profiles = [
+        SerialProfile::default(),
+        SerialProfile::for_command_set(CommandSet::NoritakeCu),
+        SerialProfile::new(19200, Parity::Even).flow_control(FlowControl::Software),
+        seven_odd_two,
+    ];
+    for profile in profiles {
+        let text = profile.to_string();
+        assert_eq!(text.parse::<SerialProfile>(), Ok(profile), "{}", text);
+    }
+    assert_eq!(
+        SerialProfile::for_command_set(CommandSet::NoritakeCu).to_string(),
+        "19200 8N1 RTS/CTS"
+    );
+}
+
+#[test]
+fn profiles_parse_from_short_forms() {
+    let profile: SerialProfile = "19200-8e1".parse().unwrap();
+    assert_eq!(profile, SerialProfile::new(19200, Parity::Even));
+    let profile: SerialProfile = "9600,8N1,xon/xoff".parse().unwrap();
+    assert_eq!(profile.flow_control, FlowControl::Software);
+    for bad in [
+        "",
+        "fast",
+        "0",
+        "9600 9N1",
+        "9600 8X1",
+        "9600 8N3",
+        "9600 8N1 sometimes",
+        "9600 8N1 none 1",
+    ] {
+        assert!(bad.parse::<SerialProfile>().is_err(), "{}", bad);
+    }
+}
@@ -0,0 +1,224 @@
+use proptest::prelude::*;
+use vfd_dsp_v9fb_over_serial::{
+    Align, CommandSet, DisplayInfo, DisplayMode, Geometry, MockVfd, Overflow, Position, Simulator,
+    VfdError, WrapPolicy, WriteOptions,
+};
+
+fn geometry() -> impl Strategy<Value = Geometry> {
+    prop::sample::select(Geometry::PRESETS.to_vec())
+}
+
+fn command_set() -> impl Strategy<Value = CommandSet> {
+    prop::sample::select(vec![
+        CommandSet::Birch,
+        CommandSet::EpsonDmD,
+        CommandSet::NoritakeCu,
+        CommandSet::Futaba,
+    ])
+}
+
+fn align() -> impl Strategy<Value = Align> {
+    prop::sample::select(vec![Align::Left, Align::Center, Align::Right])
+}
+
+fn wrap_policy() -> impl Strategy<Value = WrapPolicy> {
+    prop_oneof![
+        Just(WrapPolicy::Character),
+        (any::<bool>(), any::<bool>()).prop_map(|(hyphenate, collapse_spaces)| {
+            WrapPolicy::Word {
+                hyphenate,
+                collapse_spaces,
+            }
+        }),
+    ]
+}
+
+fn display_mode() -> impl Strategy<Value = DisplayMode> {
+    prop::sample::select(vec![
+        DisplayMode::Overwrite,
+        DisplayMode::VerticalScroll,
+        DisplayMode::HorizontalScroll,
+    ])
+}
+
+fn overflow() -> impl Strategy<Value = Overflow> {
+    prop::sample::select(vec![
+        Overflow::Truncate,
+        Overflow::Ellipsis,
+        Overflow::Error,
+        Overflow::Scroll,
+    ])
+}
+
+// Printable ASCII plus a few accented letters every code page table has
+fn text() -> impl Strategy<Value = String> {
+    "[ -~äöüé]{0,100}"
+}
+
+// Replay everything the mock sent into a fresh simulator
+fn simulate(vfd: &MockVfd, command_set: CommandSet, geometry: Geometry) -> Simulator {
+    let simulator = Simulator::new(command_set, geometry.width, geometry.height);
+    simulator.feed(&vfd.bytes());
+    simulator
+}
+
+proptest! {
+    #[test]
+    fn write_text_draws_what_the_driver_tracks(
+        command_set in command_set(),
+        geometry in geometry(),
+        (x, y) in (0u8..16, 0u8..2),
+        text in text(),
+        wrap in wrap_policy(),
+    ) {
+        let mut vfd = MockVfd::with_command_set(geometry.width, geometry.height, command_set.clone());
+        vfd.set_wrap_policy(wrap);
+        vfd.set_cursor(x, y).unwrap();
+        match vfd.write_text(&text) {
+            Ok(()) | Err(VfdError::TextTooLong { .. }) => {}
+            Err(e) => return Err(TestCaseError::fail(e.to_string())),
+        }
+        let simulator = simulate(&vfd, command_set, geometry);
+        prop_assert_eq!(simulator.rows(), vfd.rows());
+        prop_assert_eq!(simulator.unknown_bytes(), 0);
+    }
+
+    #[test]
+    fn write_line_fills_exactly_one_row(
+        command_set in command_set(),
+        geometry in geometry(),
+        row in 0u8..4,
+        text in text(),
+        align in align(),
+    ) {
+        let mut vfd = MockVfd::with_command_set(geometry.width, geometry.height, command_set.clone());
+        let result = vfd.write_line(row, &text, align);
+        if row >= geometry.height {
+            prop_assert!(
+                matches!(result, Err(VfdError::OutOfBounds { .. })),
+                "expected OutOfBounds, got {:?}",
+                result
+            );
+            return Ok(());
+        }
+        result.unwrap();
+        let simulator = simulate(&vfd, command_set, geometry);
+        prop_assert_eq!(simulator.rows(), vfd.rows());
+        for other in (0..geometry.height).filter(|other| *other != row) {
+            prop_assert!(simulator.row(other).trim().is_empty());
+        }
+    }
+
+    #[test]
+    fn write_text_with_draws_what_the_driver_tracks(
+        command_set in command_set(),
+        geometry in geometry(),
+        (x, y) in (0u8..16, 0u8..2),
+        text in text(),
+        wrap in wrap_policy(),
+        overflow in overflow(),
+        align in prop::option::of(align()),
+    ) {
+        let mut vfd = MockVfd::with_command_set(geometry.width, geometry.height, command_set.clone());
+        let options = WriteOptions {
+            wrap,
+            overflow,
+            align,
+            start: Some(Position::new(x, y)),
+            ..WriteOptions::default()
+        };
+        match vfd.write_text_with(&text, options) {
+            Ok(()) => {}
+            Err(VfdError::TextTooLong { .. }) if overflow == Overflow::Error => {}
+            Err(e) => return Err(TestCaseError::fail(e.to_string())),
+        }
+        let simulator = simulate(&vfd, command_set, geometry);
+        prop_assert_eq!(simulator.rows(), vfd.rows());
+    }
+
+    #[test]
+    fn truncated_text_never_leaves_its_row(
+        geometry in geometry(),
+        (x, y) in (0u8..16, 0u8..2),
+        text in text(),
+    ) {
+        let mut vfd = MockVfd::new(geometry.width, geometry.height);
+        vfd.set_cursor(x, y).unwrap();
+        vfd.write_text_truncate(&text).unwrap();
+        let simulator = simulate(&vfd, CommandSet::Birch, geometry);
+        for other in (0..geometry.height).filter(|other| *other != y) {
+            prop_assert!(simulator.row(other).trim().is_empty());
+        }
+    }
+
+    #[test]
+    fn cursor_follows_the_display(
+        command_set in command_set(),
+        geometry in geometry(),
+        (x, y) in (0u8..16, 0u8..2),
+        mode in display_mode(),
+        texts in prop::collection::vec(text(), 1..4),
+    ) {
+        let simulator = Simulator::new(command_set, geometry.width, geometry.height);
+        let mut vfd = simulator.vfd();
+        match vfd.set_display_mode(mode) {
+            Ok(()) => {}
+            Err(VfdError::NotSupported(_)) => return Ok(()),
+            Err(e) => return Err(TestCaseError::fail(e.to_string())),
+        }
+        vfd.set_cursor(x, y).unwrap();
+        for text in &texts {
+            match vfd.write_text(text) {
+                Ok(()) | Err(VfdError::TextTooLong { .. }) => {}
+                Err(e) => return Err(TestCaseError::fail(e.to_string())),
+            }
+            prop_assert_eq!(vfd.get_cursor(), simulator.cursor());
+        }
+    }
+
+    #[test]
+    fn any_byte_stream_is_survivable(
+        command_set in command_set(),
+        geometry in geometry(),
+        chunks in prop::collection::vec(prop::collection::vec(any::<u8>(), 0..64), 0..8),
+    ) {
+        let simulator = Simulator::new(command_set, geometry.width, geometry.height);
+        for chunk in &chunks {
+            simulator.feed(chunk);
+        }
+        let (x, y) = simulator.cursor();
+        prop_assert!(x <= geometry.width && y < geometry.height);
+        prop_assert_eq!(simulator.rows().len(), geometry.height as usize);
+    }
+}
+
+#[test]
+fn identify_requests_are_answered_with_the_set_identity() {
+    let simulator = Simulator::new(CommandSet::Birch, 20, 2);
+    let identity = DisplayInfo {
+        model_id: 0x41,
+        type_id: None,
+        rom_version: Some(0x07),
+    };
+    simulator.set_identity(identity);
+    assert_eq!(simulator.vfd().identify().unwrap(), identity);
+}
+
+#[test]
+fn status_requests_are_answered_with_the_set_status() {
+    let simulator = Simulator::new(CommandSet::EpsonDmD, 20, 2);
+    let mut vfd = simulator.vfd();
+    assert!(!vfd.read_status().unwrap().busy());
+
+    simulator.set_status(0x1A);
+    assert_eq!(vfd.read_status().unwrap().raw, Some(0x1A));
+    assert!(vfd.read_status().unwrap().busy());
+}
+
+#[test]
+fn requests_a_command_set_lacks_are_not_answered() {
+    let simulator = Simulator::new(CommandSet::Futaba, 20, 2);
+    let mut vfd = simulator.vfd();
+    assert!(matches!(vfd.identify(), Err(VfdError::NotSupported(_))));
+    assert_eq!(vfd.read_status().unwrap().raw, None);
+}
@@ -0,0 +1,37 @@
+use vfd_dsp_v9fb_over_serial::widget::Widget;
+use vfd_dsp_v9fb_over_serial::{CommandSet, Simulator, StatusBoard, StatusField, VfdError};
+
+#[test]
+fn only_changed_fields_are_sent() {
+    let simulator = Simulator::new(CommandSet::Birch, 20, 2);
+    let mut vfd = simulator.vfd();
+    let metrics = vfd.metrics();
+    let mut board = StatusBoard::new()
+        .field("TEMP", StatusField::new(0, 0).width(10).label("TEMP "))
+        .field("HUM", StatusField::new(10, 0).label("HUM "));
+    board.set_value("TEMP", "23.4C").unwrap();
+    board.set_value("HUM", "41%").unwrap();
+    assert!(board.tick(&mut vfd).unwrap());
+    assert_eq!(simulator.row(0), "TEMP 23.4CHUM 41%   ");
+
+    // The same value again is not redrawn
+    board.set_value("HUM", "41%").unwrap();
+    let sent = metrics.bytes_written();
+    assert!(!board.tick(&mut vfd).unwrap());
+    assert_eq!(metrics.bytes_written(), sent);
+
+    board.set_value("HUM", "42%").unwrap();
+    assert!(board.tick(&mut vfd).unwrap());
+    assert_eq!(simulator.row(0), "TEMP 23.4CHUM 42%   ");
+    assert!(metrics.bytes_written() - sent < 6);
+}
+
+#[test]
+fn unknown_fields_are_rejected() {
+    let mut board = StatusBoard::new().field("TEMP", StatusField::new(0, 0));
+    assert!(matches!(
+        board.set_value("PRESSURE", "1013"),
+        Err(VfdError::InvalidInput(_))
+    ));
+    assert_eq!(board.value("TEMP"), Some(""));
+}
@@ -0,0 +1,82 @@
+use vfd_dsp_v9fb_over_serial::{
+    Align, CodePage, CommandSet, FrameBuffer, LogView, Region, Screen, Simulator, SymbolMap,
+    TextMapping, VfdDisplay, VfdError, custom_char,
+};
+
+#[test]
+fn symbols_fall_back_to_what_the_code_page_has() {
+    let simulator = Simulator::new(CommandSet::Birch, 20, 2);
+    let mut vfd = simulator.vfd();
+    vfd.write_line(0, "╭──╮ 21℃ ▲", Align::Left).unwrap();
+    assert_eq!(simulator.row(0).trim_end(), "┌──┐ 21°C ^");
+
+    vfd.set_code_page(CodePage::Wpc1252).unwrap();
+    vfd.write_line(1, "╭──╮ 21℃ ▲", Align::Left).unwrap();
+    assert_eq!(simulator.row(1).trim_end(), "+--+ 21°C ^");
+}
+
+#[test]
+fn symbols_can_point_at_custom_glyphs() {
+    let simulator = Simulator::new(CommandSet::Birch, 20, 2);
+    let mut vfd = simulator.vfd();
+    let mut symbols = SymbolMap::default();
+    symbols.insert('✓', [custom_char(0).to_string(), "v".to_string()]);
+    vfd.set_symbols(symbols);
+    vfd.set_transliterate(false);
+    vfd.write_line(0, "Paid ✓", Align::Left).unwrap();
+    assert_eq!(vfd.dump()[0].trim_end(), format!("Paid {}", custom_char(0)));
+}
+
+#[test]
+fn layouts_measure_symbols_after_expanding_them() {
+    let simulator = Simulator::new(CommandSet::Birch, 20, 2);
+    let mut vfd = simulator.vfd();
+    vfd.set_code_page(CodePage::Wpc1252).unwrap();
+    let mut screen = Screen::for_display(&vfd);
+    screen
+        .line(0, "R1 47Ω", Align::Right)
+        .line(1, "21℃", Align::Center);
+    screen.present(&mut vfd).unwrap();
+    assert_eq!(simulator.row(0), "            R1 47Ohm");
+    assert_eq!(simulator.row(1), "        21°C        ");
+
+    let mut region = Region::new(0, 1, 20, 1);
+    region
+        .view(&mut vfd)
+        .write_line(0, "5Ω", Align::Right)
+        .unwrap();
+    assert_eq!(simulator.row(1), "                5Ohm");
+}
+
+#[test]
+fn frame_buffers_hold_what_the_display_shows() {
+    let mut frame = FrameBuffer::new(8, 1);
+    let mapping = TextMapping::new(CodePage::Wpc1252, true, SymbolMap::default());
+    frame.set_text_mapping(mapping);
+    frame.put_str(0, 0, "Ω 21℃");
+    assert_eq!(frame.row(0), "Ohm 21°C");
+}
+
+#[test]
+fn characters_the_code_page_lacks_fail_without_transliteration() {
+    let simulator = Simulator::new(CommandSet::Birch, 20, 2);
+    let mut vfd = simulator.vfd();
+    vfd.set_transliterate(false);
+    let error = vfd.write_line(0, "Łódź ☃", Align::Left).unwrap_err();
+    assert!(matches!(error, VfdError::Encoding(ref c) if c == "Ł"));
+
+    vfd.set_transliterate(true);
+    vfd.write_line(0, "Łódź ☃", Align::Left).unwrap();
+    assert_eq!(simulator.row(0).trim_end(), "Lódz ?");
+}
+
+#[test]
+fn log_lines_are_cut_after_expanding_symbols() {
+    let simulator = Simulator::new(CommandSet::Birch, 10, 2);
+    let mut vfd = simulator.vfd();
+    vfd.set_code_page(CodePage::Wpc1252).unwrap();
+    let mut log = LogView::new();
+    log.push_line(&mut vfd, "R 470Ω ok").unwrap();
+    log.push_line(&mut vfd, "R 1kΩ ok").unwrap();
+    assert_eq!(simulator.rows(), ["R 470Ohm o", "R 1kOhm ok"]);
+}
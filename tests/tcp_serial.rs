@@ -0,0 +1,75 @@
+#![cfg(feature = "serial")]
+
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::time::Duration;
+use vfd_dsp_v9fb_over_serial::{BridgeMode, Duplex, SerialProfile, TcpSerial};
+
+const TIMEOUT: Duration = Duration::from_secs(2);
+
+// Connect a bridge to a local listener, returning both ends
+fn connect(mode: BridgeMode) -> (TcpSerial, TcpStream) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap().to_string();
+    let bridge = TcpSerial::connect(&address, mode, &SerialProfile::default(), TIMEOUT).unwrap();
+    let (server, _) = listener.accept().unwrap();
+    server.set_read_timeout(Some(TIMEOUT)).unwrap();
+    (bridge, server)
+}
+
+fn read_exact(server: &mut TcpStream, len: usize) -> Vec<u8> {
+    let mut buf = vec![0u8; len];
+    server.read_exact(&mut buf).unwrap();
+    buf
+}
+
+#[test]
+fn raw_mode_passes_bytes_through() {
+    let (mut bridge, mut server) = connect(BridgeMode::Raw);
+    bridge.write_all(&[0x1B, 0x40, 0xFF]).unwrap();
+    assert_eq!(read_exact(&mut server, 3), [0x1B, 0x40, 0xFF]);
+
+    server.write_all(&[0xFF, 0x01]).unwrap();
+    let mut buf = [0u8; 8];
+    assert_eq!(Duplex::read(&mut bridge, &mut buf, TIMEOUT).unwrap(), 2);
+    assert_eq!(&buf[..2], [0xFF, 0x01]);
+}
+
+#[test]
+fn rfc2217_sets_line_and_escapes_data() {
+    let (mut bridge, mut server) = connect(BridgeMode::Rfc2217);
+    // WILL/DO BINARY, WILL/DO SGA, WILL COM-PORT-OPTION, then 9600 baud
+    let negotiation = read_exact(&mut server, 15 + 10);
+    assert_eq!(&negotiation[12..15], [255, 251, 44]);
+    assert_eq!(
+        &negotiation[15..],
+        [255, 250, 44, 1, 0, 0, 0x25, 0x80, 255, 240]
+    );
+    // Data size, parity, stop size, flow control, DTR and RTS
+    read_exact(&mut server, 6 * 7);
+
+    bridge.write_all(&[0x41, 0xFF]).unwrap();
+    assert_eq!(read_exact(&mut server, 3), [0x41, 0xFF, 0xFF]);
+}
+
+#[test]
+fn rfc2217_strips_telnet_commands() {
+    let (mut bridge, mut server) = connect(BridgeMode::Rfc2217);
+    read_exact(&mut server, 15 + 10 + 6 * 7);
+
+    // A modem state notification, an escaped 0xFF and a request we refuse (DO ECHO)
+    server
+        .write_all(&[
+            255, 250, 44, 107, 0x30, 255, 240, 0x06, 255, 255, 255, 253, 1,
+        ])
+        .unwrap();
+    let mut buf = [0u8; 16];
+    assert_eq!(Duplex::read(&mut bridge, &mut buf, TIMEOUT).unwrap(), 2);
+    assert_eq!(&buf[..2], [0x06, 0xFF]);
+    assert_eq!(read_exact(&mut server, 3), [255, 252, 1]);
+
+    assert_eq!(
+        Duplex::read(&mut bridge, &mut buf, Duration::from_millis(50)).unwrap(),
+        0
+    );
+}
@@ -0,0 +1,95 @@
+#![cfg(feature = "std")]
+
+use std::time::Duration;
+use vfd_dsp_v9fb_over_serial::{
+    Align, CommandSet, Simulator, TextStyle, Vfd, VfdError, WriteOptions,
+};
+
+fn blinking(text: &str) -> (Vfd, Simulator) {
+    let simulator = Simulator::new(CommandSet::Birch, 20, 2);
+    let mut vfd = simulator.vfd();
+    vfd.set_text_blink_interval(Duration::ZERO);
+    vfd.write_text_with(
+        text,
+        WriteOptions::default().style(TextStyle::default().blink()),
+    )
+    .unwrap();
+    (vfd, simulator)
+}
+
+#[test]
+fn the_blank_phase_is_not_part_of_what_the_display_shows() {
+    let (mut vfd, simulator) = blinking("ALARM");
+    assert!(vfd.tick_blink().unwrap());
+    assert_eq!(simulator.row(0).trim(), "");
+
+    assert_eq!(vfd.dump()[0].trim(), "ALARM");
+    assert_eq!(vfd.save_state().frame.row(0).trim(), "ALARM");
+}
+
+#[test]
+fn restoring_a_state_saved_while_blanked_shows_the_text() {
+    let (mut vfd, simulator) = blinking("ALARM");
+    vfd.tick_blink().unwrap();
+    let state = vfd.save_state();
+    vfd.stop_text_blink().unwrap();
+    vfd.clear().unwrap();
+
+    vfd.restore_state(&state).unwrap();
+    assert_eq!(simulator.row(0).trim(), "ALARM");
+}
+
+#[test]
+fn blinking_text_comes_back_with_each_tick() {
+    let (mut vfd, simulator) = blinking("ALARM");
+    vfd.tick_blink().unwrap();
+    vfd.tick_blink().unwrap();
+    assert_eq!(simulator.row(0).trim(), "ALARM");
+
+    vfd.tick_blink().unwrap();
+    vfd.stop_text_blink().unwrap();
+    assert_eq!(simulator.row(0).trim(), "ALARM");
+}
+
+#[test]
+fn what_is_left_of_blanked_text_shows_again_once_written_over() {
+    let (mut vfd, simulator) = blinking("ALARM");
+    vfd.tick_blink().unwrap();
+    vfd.set_cursor(0, 0).unwrap();
+    vfd.write_text("OK").unwrap();
+
+    assert!(!vfd.tick_blink().unwrap());
+    assert_eq!(simulator.row(0).trim(), "OKARM");
+    assert_eq!(vfd.dump()[0].trim(), "OKARM");
+}
+
+#[test]
+fn reverse_text_is_refused_where_the_protocol_lacks_it() {
+    for command_set in [CommandSet::Birch, CommandSet::EpsonDmD] {
+        let simulator = Simulator::new(command_set.clone(), 20, 2);
+        let mut vfd = simulator.vfd();
+        let result = vfd.write_text_with(
+            "Total",
+            WriteOptions::default().style(TextStyle::default().reverse()),
+        );
+        assert!(
+            matches!(result, Err(VfdError::NotSupported(_))),
+            "{command_set:?}: {result:?}"
+        );
+        assert_eq!(simulator.row(0).trim(), "");
+    }
+}
+
+#[test]
+fn reverse_text_is_written_on_noritake_displays() {
+    let simulator = Simulator::new(CommandSet::NoritakeCu, 20, 2);
+    let mut vfd = simulator.vfd();
+    vfd.write_text_with(
+        "Total",
+        WriteOptions::default()
+            .style(TextStyle::default().reverse())
+            .align(Align::Right),
+    )
+    .unwrap();
+    assert_eq!(simulator.row(0), "               Total");
+}
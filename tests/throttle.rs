@@ -0,0 +1,116 @@
+#![cfg(feature = "std")]
+
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use vfd_dsp_v9fb_over_serial::{Align, CommandSet, Simulator, Throttle, Vfd};
+
+// Most a write call accepts, so every chunk has to be sent in several pieces
+const ACCEPTED_PER_WRITE: usize = 3;
+
+#[derive(Default)]
+struct Log {
+    bytes: Vec<u8>,
+    // Bytes written since the previous flush, and when each flush happened
+    pending: usize,
+    flushes: Vec<(usize, Instant)>,
+}
+
+// Takes only a few bytes per call, like a port with a small output buffer
+struct Sluggish {
+    simulator: Simulator,
+    log: Arc<Mutex<Log>>,
+}
+
+impl Write for Sluggish {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let taken = &buf[..buf.len().min(ACCEPTED_PER_WRITE)];
+        let mut log = self.log.lock().unwrap();
+        log.bytes.extend_from_slice(taken);
+        log.pending += taken.len();
+        self.simulator.write(taken)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        let mut log = self.log.lock().unwrap();
+        let pending = std::mem::take(&mut log.pending);
+        log.flushes.push((pending, Instant::now()));
+        Ok(())
+    }
+}
+
+fn throttled(throttle: Throttle) -> (Vfd, Simulator, Arc<Mutex<Log>>) {
+    let simulator = Simulator::new(CommandSet::Birch, 20, 2);
+    let log = Arc::new(Mutex::new(Log::default()));
+    let transport = Sluggish {
+        simulator: simulator.clone(),
+        log: Arc::clone(&log),
+    };
+    let mut vfd = Vfd::from_transport(transport, CommandSet::Birch, 20, 2);
+    vfd.set_throttle(throttle);
+    (vfd, simulator, log)
+}
+
+#[test]
+fn chunked_writes_arrive_whole_in_paced_pieces() {
+    let delay = Duration::from_millis(20);
+    let (mut vfd, simulator, log) = throttled(Throttle::Chunked { bytes: 8, delay });
+    vfd.write_line(0, "Paced in small bites", Align::Left)
+        .unwrap();
+    vfd.write_line(1, "and all of it lands", Align::Right)
+        .unwrap();
+
+    assert_eq!(simulator.row(0), "Paced in small bites");
+    assert_eq!(simulator.row(1), " and all of it lands");
+
+    let log = log.lock().unwrap();
+    let sent = log.bytes.len();
+    let sizes: Vec<usize> = log.flushes.iter().map(|&(size, _)| size).collect();
+    assert!(sizes.len() > 2, "expected several chunks, got {sizes:?}");
+    assert!(sizes.iter().all(|&size| size > 0 && size <= 8), "{sizes:?}");
+    assert_eq!(sizes.iter().sum::<usize>(), sent);
+
+    // Sleeps never end early, so each gap is at least the configured delay
+    for pair in log.flushes.windows(2) {
+        let gap = pair[1].1.duration_since(pair[0].1);
+        assert!(gap >= delay, "chunks only {gap:?} apart");
+    }
+}
+
+#[test]
+fn chunked_writes_send_the_same_bytes_as_unpaced_ones() {
+    let draw = |vfd: &mut Vfd| {
+        vfd.write_line(0, "Same bytes", Align::Center).unwrap();
+        vfd.send_raw(b"0123456789abcdefghij").unwrap();
+    };
+    let (mut unpaced, _, unpaced_log) = throttled(Throttle::Off);
+    draw(&mut unpaced);
+    let (mut paced, _, paced_log) = throttled(Throttle::Chunked {
+        bytes: 5,
+        delay: Duration::from_millis(1),
+    });
+    draw(&mut paced);
+
+    assert_eq!(
+        paced_log.lock().unwrap().bytes,
+        unpaced_log.lock().unwrap().bytes
+    );
+    // Every chunk is flushed on its own, the unpaced writes never are
+    assert!(unpaced_log.lock().unwrap().flushes.is_empty());
+    assert!(paced_log.lock().unwrap().flushes.len() >= 4);
+}
+
+#[test]
+fn byte_rates_become_chunks_of_a_hundredth_of_a_second() {
+    let (mut vfd, _, log) = throttled(Throttle::BytesPerSecond(400));
+    vfd.send_raw(&[b'x'; 12]).unwrap();
+
+    let sizes: Vec<usize> = log
+        .lock()
+        .unwrap()
+        .flushes
+        .iter()
+        .map(|&(size, _)| size)
+        .collect();
+    assert_eq!(sizes, [4, 4, 4]);
+}
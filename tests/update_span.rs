@@ -0,0 +1,99 @@
+use std::io::{self, Write};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use vfd_dsp_v9fb_over_serial::{Align, CommandSet, Simulator, Vfd};
+
+// Feeds a simulator while counting the bytes that pass through
+struct Counting {
+    simulator: Simulator,
+    sent: Arc<AtomicUsize>,
+}
+
+impl Write for Counting {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.sent.fetch_add(buf.len(), Ordering::Relaxed);
+        self.simulator.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn counted(command_set: CommandSet) -> (Vfd, Simulator, Arc<AtomicUsize>) {
+    let simulator = Simulator::new(command_set.clone(), 20, 2);
+    let sent = Arc::new(AtomicUsize::new(0));
+    let transport = Counting {
+        simulator: simulator.clone(),
+        sent: Arc::clone(&sent),
+    };
+    (
+        Vfd::from_transport(transport, command_set, 20, 2),
+        simulator,
+        sent,
+    )
+}
+
+// One reading a second for a minute, as a dashboard would show it
+fn readings() -> Vec<String> {
+    (0..60)
+        .map(|second| format!("CPU {:>3}% 12:00:{:02}", 40 + second % 7, second))
+        .collect()
+}
+
+// Bytes sent to show every reading, with each drawn by `draw`
+fn bytes_for(command_set: CommandSet, draw: impl Fn(&mut Vfd, &str)) -> usize {
+    let (mut vfd, simulator, sent) = counted(command_set);
+    for reading in readings() {
+        draw(&mut vfd, &reading);
+        assert_eq!(simulator.row(0).trim_end(), reading);
+    }
+    sent.load(Ordering::Relaxed)
+}
+
+#[test]
+fn span_updates_send_less_than_half_of_full_redraws() {
+    for command_set in [
+        CommandSet::Birch,
+        CommandSet::NoritakeCu,
+        CommandSet::Futaba,
+    ] {
+        let full = bytes_for(command_set.clone(), |vfd, text| {
+            vfd.write_line(0, text, Align::Left).unwrap()
+        });
+        let diffed = bytes_for(command_set.clone(), |vfd, text| {
+            vfd.update_span(0, 0, text).unwrap()
+        });
+        assert!(
+            diffed * 2 < full,
+            "{:?}: {} bytes diffed, {} redrawn",
+            command_set,
+            diffed,
+            full
+        );
+    }
+}
+
+#[test]
+fn unchanged_cells_send_nothing() {
+    let (mut vfd, simulator, sent) = counted(CommandSet::Birch);
+    vfd.update_span(2, 1, "42").unwrap();
+    let before = sent.load(Ordering::Relaxed);
+    vfd.update_span(2, 1, "42").unwrap();
+    vfd.update_cell(3, 1, '2').unwrap();
+    assert_eq!(sent.load(Ordering::Relaxed), before);
+
+    vfd.update_cell(3, 1, '3').unwrap();
+    // US $ x y, then the digit
+    assert_eq!(sent.load(Ordering::Relaxed), before + 5);
+    assert_eq!(simulator.row(1).trim(), "43");
+}
+
+#[test]
+fn spans_are_cut_at_the_end_of_the_row() {
+    let (mut vfd, simulator, _) = counted(CommandSet::Birch);
+    vfd.update_span(15, 0, "overflowing").unwrap();
+    assert_eq!(simulator.row(0), "               overf");
+    assert_eq!(simulator.row(1).trim(), "");
+    assert!(vfd.update_span(20, 0, "x").is_err());
+}
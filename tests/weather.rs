@@ -0,0 +1,109 @@
+#![cfg(feature = "weather")]
+
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use vfd_dsp_v9fb_over_serial::weather::{
+    self, Conditions, Units, Weather, WeatherConfig, parse_open_meteo, parse_openweathermap,
+};
+use vfd_dsp_v9fb_over_serial::{Align, CommandSet, MockVfd, Simulator, glyph};
+
+#[test]
+fn responses_from_both_providers() {
+    let open_meteo = r#"{"latitude":38.72,"current":{"time":"2026-10-15T12:00","temperature_2m":18.6,"weather_code":3}}"#;
+    assert_eq!(
+        parse_open_meteo(open_meteo).unwrap(),
+        Weather {
+            temperature: 18.6,
+            conditions: Conditions::Cloudy
+        }
+    );
+
+    let openweathermap =
+        r#"{"weather":[{"id":611,"main":"Snow"}],"main":{"temp":29.1},"name":"Denver"}"#;
+    assert_eq!(
+        parse_openweathermap(openweathermap).unwrap(),
+        Weather {
+            temperature: 29.1,
+            conditions: Conditions::Snow
+        }
+    );
+
+    assert!(parse_open_meteo(r#"{"current":{"temperature_2m":18.6}}"#).is_err());
+    assert!(parse_openweathermap("Invalid API key").is_err());
+}
+
+#[test]
+fn icon_goes_into_a_custom_glyph() {
+    let simulator = Simulator::new(CommandSet::Birch, 20, 2);
+    let mut vfd = simulator.vfd();
+    let config = WeatherConfig {
+        units: Units::Imperial,
+        ..WeatherConfig::default()
+    };
+    let now = Weather {
+        temperature: 71.5,
+        conditions: Conditions::Clear,
+    };
+    weather::show(&mut vfd, &now, &config).unwrap();
+    assert_eq!(
+        vfd.save_state().glyphs.get(&weather::ICON_SLOT),
+        Some(&glyph::SUN)
+    );
+    assert_eq!(vfd.dump()[0].trim_end(), "\u{E000} Clear         72°F");
+    assert_eq!(vfd.dump()[1].trim_end(), "");
+}
+
+#[test]
+fn previous_content_can_be_put_back() {
+    let mut vfd = MockVfd::new(20, 2);
+    vfd.write_line(0, "Open until 9pm", Align::Left).unwrap();
+    let state = vfd.save_state();
+    let now = Weather {
+        temperature: -3.2,
+        conditions: Conditions::Fog,
+    };
+    weather::show(&mut vfd, &now, &WeatherConfig::default()).unwrap();
+    assert!(vfd.row(0).ends_with("-3°C"));
+    vfd.restore_state(&state).unwrap();
+    assert_eq!(vfd.row(0).trim_end(), "Open until 9pm");
+}
+
+#[test]
+fn the_icon_slot_is_given_back_when_other_content_took_over() {
+    let simulator = Simulator::new(CommandSet::Birch, 20, 2);
+    let mut vfd = simulator.vfd();
+    vfd.define_custom_char(weather::ICON_SLOT, &glyph::BATTERY)
+        .unwrap();
+    let vfd = Arc::new(Mutex::new(vfd));
+    let config = WeatherConfig {
+        show_for: Duration::from_millis(300),
+        ..WeatherConfig::default()
+    };
+    let turn = thread::spawn({
+        let vfd = Arc::clone(&vfd);
+        move || {
+            let now = Weather {
+                temperature: 12.0,
+                conditions: Conditions::Fog,
+            };
+            weather::take_turn(&vfd, &now, &config)
+        }
+    });
+    while !simulator.row(0).contains("Fog") {
+        thread::sleep(Duration::from_millis(5));
+    }
+    let mut display = vfd.lock().unwrap();
+    display
+        .write_line(1, "Order 42 ready", Align::Left)
+        .unwrap();
+    drop(display);
+    turn.join().unwrap().unwrap();
+
+    let vfd = vfd.lock().unwrap();
+    assert_eq!(simulator.row(1).trim_end(), "Order 42 ready");
+    assert_eq!(
+        vfd.save_state().glyphs.get(&weather::ICON_SLOT),
+        Some(&glyph::BATTERY)
+    );
+}
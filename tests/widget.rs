@@ -0,0 +1,36 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use vfd_dsp_v9fb_over_serial::widget;
+use vfd_dsp_v9fb_over_serial::{CommandSet, Simulator, VfdDisplay, VfdError, Widget};
+
+// Counts its ticks, asking for one every 300 ms
+struct Slow(Arc<AtomicUsize>);
+
+impl Widget for Slow {
+    fn tick(&mut self, _display: &mut dyn VfdDisplay) -> Result<bool, VfdError> {
+        self.0.fetch_add(1, Ordering::Relaxed);
+        Ok(false)
+    }
+
+    fn render(&mut self, _display: &mut dyn VfdDisplay) -> Result<(), VfdError> {
+        Ok(())
+    }
+
+    fn interval(&self) -> Duration {
+        Duration::from_millis(300)
+    }
+}
+
+#[test]
+fn spawned_widgets_tick_at_their_interval() {
+    let simulator = Simulator::new(CommandSet::Birch, 20, 2);
+    let display = Arc::new(Mutex::new(simulator.vfd()));
+    let ticks = Arc::new(AtomicUsize::new(0));
+    let handle = widget::spawn(Slow(Arc::clone(&ticks)), display);
+    thread::sleep(Duration::from_millis(400));
+    handle.stop().unwrap();
+    // At the start and 300 ms in; a slow machine only ticks fewer times
+    assert!((1..=2).contains(&ticks.load(Ordering::Relaxed)));
+}